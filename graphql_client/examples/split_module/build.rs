@@ -0,0 +1,84 @@
+//! Generates the code for the query with `generate_to_directory`, which splits it across several files.
+
+extern crate graphql_client_codegen;
+extern crate syn;
+
+use graphql_client_codegen::{
+    generate_to_directory, EnumUnknowns, FloatType, GraphQLClientDeriveOptions, SensitiveFields,
+};
+use std::path::{Path, PathBuf};
+
+fn main() {
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let options = GraphQLClientDeriveOptions {
+        operation_name: Some("RepoIssues".to_string()),
+        struct_name: Some("RepoIssues".to_string()),
+        module_name: Some("repo_issues".to_string()),
+        additional_derives: Some("Debug".to_string()),
+        deprecation_strategy: None,
+        module_visibility: syn::parse_str("pub").unwrap(),
+        variant_accessors: false,
+        shared_variables: false,
+        root_field_accessor: false,
+        list_iterators: false,
+        rename_all_camel_case: false,
+        input_patches: false,
+        comment_descriptions: false,
+        with_variables: false,
+        test_defaults: false,
+        enum_all_variants: false,
+        optimistic_responses: false,
+        field_accessors: false,
+        variables_try_from: false,
+        interface_flatten: false,
+        enum_unknowns: EnumUnknowns::default(),
+        rkyv: false,
+        field_json: false,
+        strip_typename: false,
+        visibility: None,
+        transform: None,
+        float_type: FloatType::default(),
+        qualified_paths: false,
+        scalar_paths: None,
+        variables_schema: false,
+        skip_external_fields: false,
+        variables_builder: false,
+        compact_str: false,
+        bevy_reflect: false,
+        variables_as_pairs: false,
+        include_tag: None,
+        scalar_collectors: false,
+        add_typename: false,
+        sqlx_from_row: false,
+        empty_lists: false,
+        unknown_fields: false,
+        serde_crate: None,
+        estimated_size: false,
+        enum_value_methods: None,
+        response_wrapper: None,
+        top_level_derives_only: false,
+        persisted_query_manifest: None,
+        test_only_derives: None,
+        conditional_fields_report: false,
+        field_types: None,
+        response_trait: false,
+        max_identifier_length: None,
+        case_insensitive_enums: false,
+        module_attributes: None,
+        result_aliases: false,
+        sensitive_fields: SensitiveFields::default(),
+        variables_constructor: false,
+        raw_response: false,
+        cache_keys: false,
+    };
+
+    generate_to_directory(
+        PathBuf::from("../github/src/schema.graphql"),
+        PathBuf::from("src/query.graphql"),
+        options,
+        &Path::new(&out_dir).join("repo_issues"),
+    )
+    .unwrap();
+
+    println!("cargo:rerun-if-changed=src/query.graphql");
+}
@@ -0,0 +1,12 @@
+//! The code for the query is generated by the build script, split across several files.
+
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate graphql_client;
+
+type URI = String;
+
+pub struct RepoIssues;
+
+include!(concat!(env!("OUT_DIR"), "/repo_issues/mod.rs"));
@@ -0,0 +1,43 @@
+extern crate graphql_client;
+extern crate serde_json;
+extern crate split_module;
+
+use graphql_client::GraphQLQuery;
+use split_module::{repo_issues, RepoIssues};
+
+const RESPONSE: &str = r#"{
+    "repository": {
+        "homepageUrl": "https://example.com",
+        "issues": { "nodes": [{ "title": "Split the generated code", "state": "OPEN" }] }
+    }
+}"#;
+
+#[test]
+fn split_module_types_are_reexported() {
+    let response: repo_issues::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+    let issue = response
+        .repository
+        .unwrap()
+        .issues
+        .nodes
+        .unwrap()
+        .remove(0)
+        .unwrap();
+
+    assert_eq!(issue.title, "Split the generated code");
+    match issue.state {
+        repo_issues::IssueState::OPEN => (),
+        other => panic!("unexpected state: {:?}", other),
+    }
+}
+
+#[test]
+fn split_module_implements_graphql_query() {
+    let body = RepoIssues::build_query(repo_issues::Variables {
+        owner: "graphql-rust".to_string(),
+        name: "graphql-client".to_string(),
+    });
+
+    assert_eq!(body.operation_name, "RepoIssues");
+    assert_eq!(body.query, repo_issues::QUERY);
+}
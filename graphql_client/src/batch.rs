@@ -0,0 +1,139 @@
+//! Batched requests, for servers accepting a JSON array of query bodies and answering with an array of responses, in the same order.
+//!
+//! A batch of the same operation with many sets of variables is type-safe:
+//!
+//! ```ignore
+//! let body = graphql_client::batch::batch_query::<MyQuery, _>(vec![first_variables, second_variables]);
+//! // Send `body` as JSON, then:
+//! let responses: Vec<Response<my_query::ResponseData>> =
+//!     graphql_client::batch::decode_batch(&response_body)?;
+//! ```
+//!
+//! Batches of different operations are built with the [`batch_operations!`](../macro.batch_operations.html) macro, which serializes the variables of each query body to JSON values, and decoded with [`decode_batch_operations!`](../macro.decode_batch_operations.html), which returns a tuple of responses:
+//!
+//! ```ignore
+//! let body = batch_operations!(
+//!     HeroQuery::build_query(hero_variables),
+//!     ShipQuery::build_query(ship_variables),
+//! )?;
+//! let (hero, ship) = decode_batch_operations!(&response_body, hero_query::ResponseData, ship_query::ResponseData)?;
+//! ```
+
+use serde::de::{self, DeserializeOwned};
+use serde::Serialize;
+use serde_json::{Error, Value};
+
+use {GraphQLQuery, QueryBody, Response};
+
+/// The body of a batched request: the query bodies, serialized as a JSON array.
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct BatchBody<Variables: Serialize> {
+    /// The query bodies, in the order of the responses.
+    pub bodies: Vec<QueryBody<Variables>>,
+}
+
+/// A batch of query bodies with the same type of variables.
+pub fn batch<Variables: Serialize>(bodies: Vec<QueryBody<Variables>>) -> BatchBody<Variables> {
+    BatchBody { bodies }
+}
+
+/// A batch running the operation `Q` once for each set of variables.
+pub fn batch_query<Q, I>(variables: I) -> BatchBody<Q::Variables>
+where
+    Q: GraphQLQuery,
+    I: IntoIterator<Item = Q::Variables>,
+{
+    batch(variables.into_iter().map(Q::build_query).collect())
+}
+
+/// Serializes the variables of a query body to a JSON value, so the query bodies of different operations fit in one batch.
+pub fn erase_variables<Variables: Serialize>(
+    body: QueryBody<Variables>,
+) -> Result<QueryBody<Value>, Error> {
+    Ok(QueryBody {
+        variables: ::serde_json::to_value(body.variables)?,
+        query: body.query,
+        operation_name: body.operation_name,
+    })
+}
+
+/// Deserializes the array of responses to a batch of the same operation.
+pub fn decode_batch<Data: DeserializeOwned>(body: &str) -> Result<Vec<Response<Data>>, Error> {
+    ::serde_json::from_str(body)
+}
+
+/// The responses to a batch of different operations, as JSON values, checking there is one for each of the `expected` operations.
+pub fn split_batch(body: &str, expected: usize) -> Result<Vec<Value>, Error> {
+    let responses: Vec<Value> = ::serde_json::from_str(body)?;
+
+    if responses.len() != expected {
+        return Err(de::Error::custom(format!(
+            "expected {} responses in the batch, got {}",
+            expected,
+            responses.len()
+        )));
+    }
+
+    Ok(responses)
+}
+
+/// Builds a [`BatchBody`](batch/struct.BatchBody.html) from query bodies of different operations, serializing their variables to JSON values. Evaluates to a `Result`, since the serialization of the variables can fail.
+#[macro_export]
+macro_rules! batch_operations {
+    ($($body:expr),+ $(,)*) => {
+        (|| -> ::std::result::Result<
+            $crate::batch::BatchBody<$crate::serde_json::Value>,
+            $crate::serde_json::Error,
+        > {
+            Ok($crate::batch::batch(vec![$($crate::batch::erase_variables($body)?),+]))
+        })()
+    };
+}
+
+/// Deserializes the responses to a batch built with [`batch_operations!`](macro.batch_operations.html), given the response data type of each operation in order, to a tuple of `Response`s.
+#[macro_export]
+macro_rules! decode_batch_operations {
+    ($body:expr, $($data:ty),+ $(,)*) => {
+        (|| -> ::std::result::Result<_, $crate::serde_json::Error> {
+            let expected = [$(stringify!($data)),+].len();
+            let mut responses = $crate::batch::split_batch($body, expected)?.into_iter();
+
+            Ok(($(
+                $crate::serde_json::from_value::<$crate::Response<$data>>(
+                    responses.next().expect("the batch has a response for each operation"),
+                )?,
+            )+))
+        })()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bodies_are_serialized_as_an_array() {
+        let body = batch(vec![QueryBody {
+            variables: json!({"id": 1}),
+            query: "query Hero($id: Int) { hero(id: $id) { name } }",
+            operation_name: "Hero",
+        }]);
+
+        assert_eq!(
+            ::serde_json::to_value(&body).unwrap(),
+            json!([{
+                "variables": {"id": 1},
+                "query": "query Hero($id: Int) { hero(id: $id) { name } }",
+                "operationName": "Hero",
+            }])
+        );
+    }
+
+    #[test]
+    fn batches_must_have_a_response_for_each_operation() {
+        let err = split_batch(r#"[{"data": null}]"#, 2).unwrap_err();
+
+        assert_eq!(err.to_string(), "expected 2 responses in the batch, got 1");
+    }
+}
@@ -0,0 +1,53 @@
+//! Typestate builders for the generated `Variables` structs, with the `variables_builder` derive option.
+//!
+//! Each required variable is a type parameter of the builder, [`Unset`] until its setter is called. `build` is only implemented once every required variable is set, so forgetting one is a compile error:
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate graphql_client;
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde;
+//! #[derive(GraphQLQuery)]
+//! #[graphql(
+//!     query_path = "tests/variables_builder/query.graphql",
+//!     schema_path = "tests/variables_builder/schema.graphql",
+//!     variables_builder = "true"
+//! )]
+//! pub struct ReviewsQuery;
+//!
+//! # fn main() {
+//! let variables = reviews_query::Variables::builder()
+//!     .product_id("42".to_string())
+//!     .min_rating(4)
+//!     .build();
+//! # assert_eq!(variables.first, 10);
+//! # }
+//! ```
+//!
+//! ```compile_fail
+//! # #[macro_use]
+//! # extern crate graphql_client;
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde;
+//! # #[derive(GraphQLQuery)]
+//! # #[graphql(
+//! #     query_path = "tests/variables_builder/query.graphql",
+//! #     schema_path = "tests/variables_builder/schema.graphql",
+//! #     variables_builder = "true"
+//! # )]
+//! # pub struct ReviewsQuery;
+//! # fn main() {
+//! // `minRating` is required: there is no `build` method yet.
+//! let variables = reviews_query::Variables::builder()
+//!     .product_id("42".to_string())
+//!     .build();
+//! # }
+//! ```
+//!
+//! Nullable variables start as `None`, and variables with a default value start with it.
+
+/// The state of a required variable that has not been set on a builder yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unset;
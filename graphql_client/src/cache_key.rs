@@ -0,0 +1,75 @@
+//! Cache keys for client-side caches keyed by operation and variables, for the `cache_keys` derive option.
+//!
+//! The variables are serialized as canonical JSON, with the keys of every object sorted and no whitespace, so equal variables give the same key whatever order their fields are serialized in.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// The cache key of an operation: its name, the hash of its query and its canonical variables, separated by colons. Variables that cannot be serialized are an error, since they have no key of their own.
+pub fn cache_key<V: Serialize>(
+    operation_name: &str,
+    query_hash: &str,
+    variables: &V,
+) -> Result<String, ::serde_json::Error> {
+    let variables = ::serde_json::to_value(variables)?;
+    let mut key = format!("{}:{}:", operation_name, query_hash);
+    write_canonical(&variables, &mut key);
+    Ok(key)
+}
+
+/// Writes the value as JSON, with the keys of the objects sorted.
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(fields) => {
+            let mut fields: Vec<(&String, &Value)> = fields.iter().collect();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (index, (name, value)) in fields.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(name.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_keys_are_sorted_at_every_level() {
+        let variables = json!({
+            "where": { "name": "Luke", "age": [19, { "z": true, "a": null }] },
+            "first": 10,
+        });
+
+        assert_eq!(
+            cache_key("Heroes", "abc", &variables).unwrap(),
+            r#"Heroes:abc:{"first":10,"where":{"age":[19,{"a":null,"z":true}],"name":"Luke"}}"#
+        );
+    }
+
+    #[test]
+    fn variables_that_cannot_be_serialized_are_an_error() {
+        let mut variables = ::std::collections::BTreeMap::new();
+        variables.insert(vec![1], "a map with non-string keys");
+
+        assert!(cache_key("Heroes", "abc", &variables).is_err());
+    }
+}
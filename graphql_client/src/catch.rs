@@ -0,0 +1,124 @@
+//! Field-level error boundaries, for the `@catch` client directive.
+//!
+//! Codegen generates the fields annotated with `@catch` as `Result<T, FieldError>`. Servers report field errors in the top-level `errors` array and set the field to `null`, so the errors have to be moved into the data before deserializing. [`embed_field_errors`] does this, given the `CAUGHT_FIELDS` constant of the generated module:
+//!
+//! ```ignore
+//! let mut body: serde_json::Value = response.json()?;
+//! graphql_client::catch::embed_field_errors(&mut body, my_query::CAUGHT_FIELDS);
+//! let body: Response<my_query::ResponseData> = serde_json::from_value(body)?;
+//! ```
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::fmt::{self, Display};
+
+use itertools::Itertools;
+
+use {Error, PathFragment};
+
+/// The key under which [`embed_field_errors`] stores the errors of a caught field in the response data.
+const FIELD_ERRORS_KEY: &str = "__graphql_client_field_errors";
+
+/// The errors that made a caught field `null`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldError {
+    /// The errors of the top-level `errors` array whose path leads to the field.
+    pub errors: Vec<Error>,
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.errors.iter().format("; "))
+    }
+}
+
+/// Moves the errors of the caught fields from the top-level `errors` array of a response body into its `data`, where the generated types expect them.
+///
+/// `caught_fields` are the paths of the caught fields, made of response keys. An error is attached to a caught field when its path goes through the field, and the field is `null` in the data. Other errors are left alone, and they stay in the `errors` array either way.
+pub fn embed_field_errors(response: &mut Value, caught_fields: &[&[&str]]) {
+    let errors: Vec<Error> = match response.get("errors") {
+        Some(errors) => match Vec::<Error>::deserialize(errors) {
+            Ok(errors) => errors,
+            Err(_) => return,
+        },
+        None => return,
+    };
+    let data = match response.get_mut("data") {
+        Some(data) => data,
+        None => return,
+    };
+
+    for error in errors {
+        let path = match &error.path {
+            Some(path) => path.clone(),
+            None => continue,
+        };
+
+        for caught in caught_fields {
+            if let Some(field) = caught_field_value(data, &path, caught) {
+                if field.is_null() {
+                    let mut marker = ::serde_json::Map::new();
+                    marker.insert(FIELD_ERRORS_KEY.to_string(), Value::Array(Vec::new()));
+                    *field = Value::Object(marker);
+                }
+                if let Some(Value::Array(errors)) = field.get_mut(FIELD_ERRORS_KEY) {
+                    errors.push(::serde_json::to_value(&error).expect("errors are serializable"));
+                }
+            }
+        }
+    }
+}
+
+/// The value of the caught field the error path goes through, if the path goes through it and it is in the data.
+fn caught_field_value<'a>(
+    data: &'a mut Value,
+    path: &[PathFragment],
+    caught: &[&str],
+) -> Option<&'a mut Value> {
+    let mut value = data;
+    let mut remaining_keys = caught;
+
+    for fragment in path {
+        if remaining_keys.is_empty() {
+            break;
+        }
+        value = match fragment {
+            PathFragment::Key(key) => {
+                if key != remaining_keys[0] {
+                    return None;
+                }
+                remaining_keys = &remaining_keys[1..];
+                value.get_mut(key.as_str())?
+            }
+            PathFragment::Index(idx) => value.get_mut(*idx as usize)?,
+        };
+    }
+
+    if remaining_keys.is_empty() {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Caught<T> {
+    Error {
+        #[serde(rename = "__graphql_client_field_errors")]
+        errors: Vec<Error>,
+    },
+    Value(T),
+}
+
+/// Deserializes a caught field. Codegen points the `deserialize_with` attribute of the fields annotated with `@catch` at this function.
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Result<T, FieldError>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(match Caught::deserialize(deserializer)? {
+        Caught::Error { errors } => Err(FieldError { errors }),
+        Caught::Value(value) => Ok(value),
+    })
+}
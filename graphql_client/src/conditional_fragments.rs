@@ -0,0 +1,72 @@
+//! Deserialization of the fragment spreads behind `@include` or `@skip`, generated as an `Option` of the fragment struct.
+//!
+//! The server leaves all the fields of such a fragment out, or sends all of them. serde's own handling of a flattened `Option` would give `Some` with every field `None` for an absent fragment with nullable fields, and `None` for a present fragment with a mistyped field. Codegen points the `deserialize_with` attribute of these fields at [`deserialize`] instead, which tells them apart from the response keys of the fragment.
+
+use serde::de::{DeserializeOwned, Deserializer, Error};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// The response keys a fragment struct reads, implemented by codegen for every fragment.
+pub trait ConditionalFragment {
+    /// The response keys of the fields the fragment selects, including through the fragments it spreads. `__typename` is left out, unless it is the only one, since any selection may ask for it.
+    const RESPONSE_KEYS: &'static [&'static str];
+}
+
+/// Deserializes a flattened fragment as `None` when none of its response keys are in the object, and as the fragment otherwise. Errors in the fields of a present fragment are returned.
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ConditionalFragment + DeserializeOwned,
+{
+    let fields = Map::<String, Value>::deserialize(deserializer)?;
+    if !T::RESPONSE_KEYS.iter().any(|key| fields.contains_key(*key)) {
+        return Ok(None);
+    }
+
+    T::deserialize(Value::Object(fields))
+        .map(Some)
+        .map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct NickFields {
+        nick: Option<String>,
+    }
+
+    impl ConditionalFragment for NickFields {
+        const RESPONSE_KEYS: &'static [&'static str] = &["nick"];
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Hero {
+        name: String,
+        #[serde(flatten, deserialize_with = "deserialize")]
+        nick_fields: Option<NickFields>,
+    }
+
+    #[test]
+    fn absent_fragments_are_none() {
+        let hero: Hero = ::serde_json::from_value(json!({"name": "a"})).unwrap();
+
+        assert_eq!(hero.name, "a");
+        assert_eq!(hero.nick_fields, None);
+    }
+
+    #[test]
+    fn present_fragments_are_some_even_with_null_fields() {
+        let hero: Hero = ::serde_json::from_value(json!({"name": "a", "nick": null})).unwrap();
+
+        assert_eq!(hero.nick_fields, Some(NickFields { nick: None }));
+    }
+
+    #[test]
+    fn errors_in_present_fragments_are_returned() {
+        let result = ::serde_json::from_value::<Hero>(json!({"name": "a", "nick": 1}));
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,44 @@
+//! Deserialization of nullable lists as empty lists, for the `empty_lists` derive option.
+//!
+//! Codegen generates the nullable list fields as `Vec<T>` with `#[serde(default)]`, and points their `deserialize_with` attribute at [`deserialize`], so a `null` list and a missing one are both empty.
+
+use serde::{Deserialize, Deserializer};
+
+/// Deserializes a list, or `null` as an empty list.
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<Vec<T>>::deserialize(deserializer).map(Option::unwrap_or_default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Post {
+        #[serde(default, deserialize_with = "deserialize")]
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn null_is_an_empty_list() {
+        let post: Post = ::serde_json::from_str(r#"{"tags": null}"#).unwrap();
+
+        assert_eq!(post, Post { tags: Vec::new() });
+    }
+
+    #[test]
+    fn lists_are_deserialized_as_is() {
+        let post: Post = ::serde_json::from_str(r#"{"tags": ["rust"]}"#).unwrap();
+
+        assert_eq!(
+            post,
+            Post {
+                tags: vec!["rust".to_string()]
+            }
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Rough estimates of the memory held by response data, for the `estimated_size` derive option.
+//!
+//! The estimates add up the inline size of the values, the capacity of their strings and vectors, and an approximation of the hash maps and JSON values. Allocator overhead and padding of the heap blocks are not counted, so they are only meant to tune buffers and caches, not to account for memory exactly.
+
+use scalars::Long;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::mem::size_of;
+use {Error, Location, PathFragment};
+
+/// The estimated size of a value, implemented for the response types generated with the `estimated_size` option. Custom scalar types must implement it too.
+pub trait EstimatedSize {
+    /// The bytes owned by the value outside of its inline size, like the buffer of a `String`.
+    fn heap_size(&self) -> usize;
+
+    /// The inline size of the value plus [`heap_size`](EstimatedSize::heap_size).
+    fn estimated_size(&self) -> usize
+    where
+        Self: Sized,
+    {
+        size_of::<Self>() + self.heap_size()
+    }
+}
+
+macro_rules! inline_only {
+    ($($ty:ty),*) => {
+        $(
+            impl EstimatedSize for $ty {
+                fn heap_size(&self) -> usize {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+inline_only!(bool, char, i8, i16, i32, i64, u8, u16, u32, u64, isize, usize, f32, f64, Long);
+
+impl EstimatedSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: EstimatedSize> EstimatedSize for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map(EstimatedSize::heap_size).unwrap_or(0)
+    }
+}
+
+impl<T: EstimatedSize> EstimatedSize for Box<T> {
+    fn heap_size(&self) -> usize {
+        size_of::<T>() + (**self).heap_size()
+    }
+}
+
+impl<T: EstimatedSize> EstimatedSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(EstimatedSize::heap_size).sum::<usize>()
+    }
+}
+
+impl<T: EstimatedSize, E: EstimatedSize> EstimatedSize for Result<T, E> {
+    fn heap_size(&self) -> usize {
+        match self {
+            Ok(value) => value.heap_size(),
+            Err(err) => err.heap_size(),
+        }
+    }
+}
+
+/// The buckets of the table, without its control bytes.
+impl<K: EstimatedSize, V: EstimatedSize, S> EstimatedSize for HashMap<K, V, S> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * (size_of::<K>() + size_of::<V>())
+            + self
+                .iter()
+                .map(|(key, value)| key.heap_size() + value.heap_size())
+                .sum::<usize>()
+    }
+}
+
+impl EstimatedSize for Value {
+    fn heap_size(&self) -> usize {
+        match self {
+            Value::Null | Value::Bool(_) | Value::Number(_) => 0,
+            Value::String(s) => s.heap_size(),
+            Value::Array(items) => items.heap_size(),
+            // The map type depends on the features of serde_json, count the entries as if they were in a vector.
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, value)| {
+                    size_of::<String>() + size_of::<Value>() + key.heap_size() + value.heap_size()
+                })
+                .sum(),
+        }
+    }
+}
+
+inline_only!(Location);
+
+impl EstimatedSize for PathFragment {
+    fn heap_size(&self) -> usize {
+        match self {
+            PathFragment::Key(key) => key.heap_size(),
+            PathFragment::Index(_) => 0,
+        }
+    }
+}
+
+impl EstimatedSize for Error {
+    fn heap_size(&self) -> usize {
+        self.message.heap_size()
+            + self.locations.heap_size()
+            + self.path.heap_size()
+            + self.extensions.heap_size()
+    }
+}
+
+impl EstimatedSize for ::catch::FieldError {
+    fn heap_size(&self) -> usize {
+        self.errors.heap_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strings_and_vectors_count_their_capacity() {
+        let mut names = Vec::with_capacity(4);
+        names.push(String::with_capacity(10));
+
+        assert_eq!(names.heap_size(), 4 * size_of::<String>() + 10);
+        assert_eq!(
+            Some(names).estimated_size(),
+            size_of::<Option<Vec<String>>>() + 4 * size_of::<String>() + 10
+        );
+    }
+}
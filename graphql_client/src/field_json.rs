@@ -0,0 +1,50 @@
+//! Extraction of a single field of a response as JSON, by path. The generated response data has a `field_json` method calling [`extract`] with the `field_json = "true"` option:
+//!
+//! ```ignore
+//! log::debug!("{:?}", response_data.field_json("viewer.repositories.0.name"));
+//! ```
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Serializes `value` and returns the subtree at the dotted `path` of object keys and list indices. The empty path selects the whole value.
+///
+/// Returns `None` if a key is missing, an index is out of bounds, or the value cannot be serialized.
+pub fn extract<T: Serialize>(value: &T, path: &str) -> Option<Value> {
+    let mut value = ::serde_json::to_value(value).ok()?;
+
+    if path.is_empty() {
+        return Some(value);
+    }
+
+    for segment in path.split('.') {
+        value = match value {
+            Value::Object(mut object) => object.remove(segment)?,
+            Value::Array(mut items) => {
+                let index: usize = segment.parse().ok()?;
+                if index >= items.len() {
+                    return None;
+                }
+                items.swap_remove(index)
+            }
+            _ => return None,
+        };
+    }
+
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_follows_keys_and_indices() {
+        let value = json!({"hero": {"friends": [{"name": "Han"}, {"name": "Leia"}]}});
+
+        assert_eq!(extract(&value, "hero.friends.1.name"), Some(json!("Leia")));
+        assert_eq!(extract(&value, "hero.friends.2.name"), None);
+        assert_eq!(extract(&value, "hero.enemies"), None);
+        assert_eq!(extract(&value, ""), Some(value.clone()));
+    }
+}
@@ -5,20 +5,51 @@
 #![deny(warnings)]
 #![deny(missing_docs)]
 
+#[cfg(feature = "postgres")]
+extern crate bytes;
+#[cfg(feature = "subscription_stream")]
+#[macro_use]
+extern crate futures;
 extern crate itertools;
+#[cfg(feature = "postgres")]
+#[macro_use]
+extern crate postgres_types;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 pub extern crate graphql_query_derive;
 
+#[doc(hidden)]
 #[cfg_attr(test, macro_use)]
-extern crate serde_json;
+pub extern crate serde_json;
 
 #[doc(hidden)]
 pub use graphql_query_derive::*;
 
+pub mod batch;
+pub mod builder;
+pub mod cache_key;
+pub mod catch;
+pub mod conditional_fragments;
+pub mod empty_lists;
+pub mod estimated_size;
+pub mod field_json;
+pub mod raw;
+pub mod scalars;
+pub mod sensitive;
+pub mod streaming;
+#[cfg(feature = "subscription_stream")]
+pub mod subscription_stream;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod transforms;
+mod tristate;
+
+pub use tristate::Tristate;
+
 use std::collections::HashMap;
 use std::fmt::{self, Display};
+use std::marker::PhantomData;
 
 use itertools::Itertools;
 
@@ -75,8 +106,45 @@ pub trait GraphQLQuery {
     /// The top-level shape of the response data (the `data` field in the GraphQL response). In practice this should be generated, since it is hard to write by hand without error.
     type ResponseData: for<'de> serde::Deserialize<'de>;
 
+    /// The HTTP method to send the operation with, chosen with the `@httpMethod` directive on the operation. Transports send GET requests with the query, variables and operation name as query parameters.
+    const HTTP_METHOD: HttpMethod = HttpMethod::Post;
+
+    /// Whether the operation is marked with the `@idempotent` directive, and can be retried safely with the same idempotency key. See [`GraphQLQuery::build_idempotent_query`].
+    const IDEMPOTENT: bool = false;
+
     /// Produce a GraphQL query struct that can be JSON serialized and sent to a GraphQL API.
     fn build_query(variables: Self::Variables) -> QueryBody<Self::Variables>;
+
+    /// Like [`GraphQLQuery::build_query`], with the idempotency key a transport should send along, typically in an `Idempotency-Key` header. It is meant for operations where [`GraphQLQuery::IDEMPOTENT`] is true.
+    fn build_idempotent_query(
+        variables: Self::Variables,
+        idempotency_key: String,
+    ) -> IdempotentQueryBody<Self::Variables> {
+        IdempotentQueryBody {
+            body: Self::build_query(variables),
+            idempotency_key,
+        }
+    }
+}
+
+/// The response data of an operation behind a trait object, so the responses of different operations can be stored together, e.g. as `Box<dyn GraphQLResponse>`. The `ResponseData` types generated with the `response_trait = "true"` option implement it.
+///
+/// The methods have no generic parameters, to keep the trait object-safe.
+pub trait GraphQLResponse {
+    /// The name of the operation the response data is for.
+    fn operation_name(&self) -> &'static str;
+
+    /// The response data serialized to JSON.
+    fn to_json(&self) -> Result<serde_json::Value, serde_json::Error>;
+}
+
+/// The HTTP method of an operation. See [`GraphQLQuery::HTTP_METHOD`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HttpMethod {
+    /// For cacheable queries, typically persisted ones.
+    Get,
+    /// The default.
+    Post,
 }
 
 /// The form in which queries are sent over HTTP in most implementations. This will be built using the [`GraphQLQuery`] trait normally.
@@ -94,6 +162,46 @@ where
     pub operation_name: &'static str,
 }
 
+/// A [`QueryBody`] with an idempotency key, built with [`GraphQLQuery::build_idempotent_query`]. The key is not part of the serialized body: transports send it separately, e.g. in a header.
+#[derive(Debug, Serialize)]
+pub struct IdempotentQueryBody<Variables>
+where
+    Variables: serde::Serialize,
+{
+    /// The request body.
+    #[serde(flatten)]
+    pub body: QueryBody<Variables>,
+    /// The key identifying the request, so the server can deduplicate retries.
+    #[serde(skip)]
+    pub idempotency_key: String,
+}
+
+/// An operation with its variables set, ready to be built into a [`QueryBody`]. This is returned by the `with_variables` method generated with the `with_variables = "true"` option.
+pub struct WithVariables<Q: GraphQLQuery> {
+    variables: Q::Variables,
+    query: PhantomData<Q>,
+}
+
+impl<Q: GraphQLQuery> WithVariables<Q> {
+    /// Wraps the variables of the `Q` operation.
+    pub fn new(variables: Q::Variables) -> Self {
+        WithVariables {
+            variables,
+            query: PhantomData,
+        }
+    }
+
+    /// Replaces the variables.
+    pub fn with_variables(self, variables: Q::Variables) -> Self {
+        WithVariables::new(variables)
+    }
+
+    /// Builds the request body, like [`GraphQLQuery::build_query`].
+    pub fn build(self) -> QueryBody<Q::Variables> {
+        Q::build_query(self.variables)
+    }
+}
+
 /// Represents a location inside a query string. Used in errors. See [`Error`].
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
 pub struct Location {
@@ -202,6 +310,27 @@ pub struct Error {
     pub extensions: Option<HashMap<String, serde_json::Value>>,
 }
 
+impl Error {
+    /// The path of the error rendered as a dotted string, with array indices in brackets, e.g. `users[0].email`. This is `None` when the error has no path.
+    pub fn dotted_path(&self) -> Option<String> {
+        self.path.as_ref().map(|fragments| {
+            let mut rendered = String::new();
+            for fragment in fragments {
+                match fragment {
+                    PathFragment::Key(key) => {
+                        if !rendered.is_empty() {
+                            rendered.push('.');
+                        }
+                        rendered.push_str(key);
+                    }
+                    PathFragment::Index(idx) => rendered.push_str(&format!("[{}]", idx)),
+                }
+            }
+            rendered
+        })
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // Use `/` as a separator like JSON Pointer.
@@ -285,6 +414,33 @@ pub struct Response<Data> {
     pub errors: Option<Vec<Error>>,
 }
 
+impl<Data> Response<Data> {
+    /// The data if the server returned no errors, the errors otherwise.
+    ///
+    /// Partial data, sent next to errors, is discarded: read the `data` and `errors` fields directly to keep it. A response with neither data nor errors, which servers should not send, is turned into a single error.
+    ///
+    /// `Result<Data, Vec<Error>>` also implements `From<Response<Data>>`, so `let data: Data = Result::from(response)?` works in functions returning errors convertible from `Vec<Error>`.
+    pub fn into_result(self) -> Result<Data, Vec<Error>> {
+        match (self.data, self.errors) {
+            (_, Some(errors)) if !errors.is_empty() => Err(errors),
+            (Some(data), _) => Ok(data),
+            (None, _) => Err(vec![Error {
+                message: "The response has neither data nor errors.".to_string(),
+                locations: None,
+                path: None,
+                extensions: None,
+            }]),
+        }
+    }
+}
+
+impl<Data> From<Response<Data>> for Result<Data, Vec<Error>> {
+    /// See [`Response::into_result`].
+    fn from(response: Response<Data>) -> Self {
+        response.into_result()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -386,4 +542,99 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn mixed_error_paths_are_rendered_as_dotted_strings() {
+        let err = json!({
+            "message": "Not authorized",
+            "path": ["users", 0, "friends", 12, 3, "email"]
+        });
+
+        let deserialized_error: Error = serde_json::from_value(err).unwrap();
+
+        assert_eq!(
+            deserialized_error.path,
+            Some(vec![
+                PathFragment::Key("users".to_owned()),
+                PathFragment::Index(0),
+                PathFragment::Key("friends".to_owned()),
+                PathFragment::Index(12),
+                PathFragment::Index(3),
+                PathFragment::Key("email".to_owned()),
+            ])
+        );
+        assert_eq!(
+            deserialized_error.dotted_path(),
+            Some("users[0].friends[12][3].email".to_string())
+        );
+    }
+
+    #[test]
+    fn errors_without_a_path_have_no_dotted_path() {
+        let err: Error = serde_json::from_value(json!({ "message": "Oops" })).unwrap();
+
+        assert_eq!(err.dotted_path(), None);
+    }
+
+    #[test]
+    fn paths_starting_with_an_index_are_rendered() {
+        let err: Error = serde_json::from_value(json!({
+            "message": "Oops",
+            "path": [1, "name"]
+        }))
+        .unwrap();
+
+        assert_eq!(err.dotted_path(), Some("[1].name".to_string()));
+    }
+
+    #[test]
+    fn responses_with_data_convert_to_ok() {
+        let response: Response<String> =
+            serde_json::from_value(json!({ "data": "Leia", "errors": [] })).unwrap();
+
+        assert_eq!(Result::from(response), Ok("Leia".to_string()));
+    }
+
+    #[test]
+    fn responses_with_only_errors_convert_to_err() {
+        let response: Response<String> =
+            serde_json::from_value(json!({ "errors": [{ "message": "Not found" }] })).unwrap();
+        let result: Result<String, Vec<Error>> = response.into();
+
+        assert_eq!(
+            result
+                .unwrap_err()
+                .iter()
+                .map(|err| err.message.as_str())
+                .collect::<Vec<_>>(),
+            ["Not found"]
+        );
+    }
+
+    #[test]
+    fn partial_data_is_discarded_in_favor_of_the_errors() {
+        let response: Response<String> = serde_json::from_value(json!({
+            "data": "Leia",
+            "errors": [{ "message": "Partial failure" }]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            response.into_result().unwrap_err()[0].message,
+            "Partial failure"
+        );
+    }
+
+    #[test]
+    fn responses_without_data_or_errors_convert_to_one_error() {
+        let response: Response<String> = Response {
+            data: None,
+            errors: None,
+        };
+
+        assert_eq!(
+            response.into_result().unwrap_err()[0].message,
+            "The response has neither data nor errors."
+        );
+    }
 }
@@ -0,0 +1,82 @@
+//! Response data kept along with the raw JSON it was deserialized from, for the `raw_response` derive option.
+//!
+//! With the option, the `ResponseData` of the `GraphQLQuery` implementation is a [`WithRaw`] wrapping the generated `ResponseData` struct. The fields the typed view leaves out, like the fields added to the schema after the code was generated, can be read from [`WithRaw::raw`].
+//!
+//! The response is held twice, once as the typed structs and once as a `serde_json::Value`, which takes more memory than either of them. Keep the option for debugging, or for the operations where the server sending more than the query selects matters.
+
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::{Deref, DerefMut};
+
+/// The typed response data, with the JSON value it was deserialized from. It dereferences to the typed data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithRaw<T> {
+    /// The typed view of the response data.
+    pub data: T,
+    /// The response data as the server sent it.
+    pub raw: serde_json::Value,
+}
+
+impl<T> Deref for WithRaw<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T> DerefMut for WithRaw<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for WithRaw<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = serde_json::Value::deserialize(deserializer)?;
+        let data = T::deserialize(&raw).map_err(D::Error::custom)?;
+
+        Ok(WithRaw { data, raw })
+    }
+}
+
+/// Serializes the raw value, so the fields the typed view leaves out are kept.
+impl<T> Serialize for WithRaw<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.raw.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Viewer {
+        login: String,
+    }
+
+    #[test]
+    fn the_raw_value_keeps_the_fields_the_typed_view_leaves_out() {
+        let json = json!({"login": "tomhoule", "location": "Berlin"});
+
+        let viewer: WithRaw<Viewer> = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(viewer.login, "tomhoule");
+        assert_eq!(viewer.raw, json);
+        assert_eq!(serde_json::to_value(&viewer).unwrap(), json);
+    }
+
+    #[test]
+    fn typed_errors_are_reported() {
+        let result: Result<WithRaw<Viewer>, _> = serde_json::from_value(json!({"login": 2}));
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Types for common custom scalars. With the `integer_scalars` feature, the generated modules use them without the scalars being declared.
+//!
+//! With the `postgres` feature, they implement `postgres_types::ToSql` and `FromSql`, to store single values in Postgres. [`Long`] maps to `INT8` (`bigint`).
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::ops::Deref;
+
+/// A 64-bit integer, for the `Long` scalar.
+///
+/// It is serialized as a JSON number, and deserialized from a number or from a string: servers often send `Long` values as strings, because JavaScript numbers cannot represent every 64-bit integer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Long(pub i64);
+
+impl From<i64> for Long {
+    fn from(value: i64) -> Self {
+        Long(value)
+    }
+}
+
+impl From<Long> for i64 {
+    fn from(value: Long) -> Self {
+        value.0
+    }
+}
+
+impl Deref for Long {
+    type Target = i64;
+
+    fn deref(&self) -> &i64 {
+        &self.0
+    }
+}
+
+impl fmt::Display for Long {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for Long {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Long {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(LongVisitor)
+    }
+}
+
+struct LongVisitor;
+
+impl<'de> Visitor<'de> for LongVisitor {
+    type Value = Long;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a 64-bit integer, as a number or a string")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Long, E> {
+        Ok(Long(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Long, E> {
+        if value > i64::MAX as u64 {
+            return Err(E::invalid_value(de::Unexpected::Unsigned(value), &self));
+        }
+        Ok(Long(value as i64))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Long, E> {
+        value
+            .parse()
+            .map(Long)
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use super::Long;
+    use bytes::BytesMut;
+    use postgres_types::{FromSql, IsNull, ToSql, Type};
+    use std::error::Error;
+
+    impl ToSql for Long {
+        fn to_sql(
+            &self,
+            ty: &Type,
+            out: &mut BytesMut,
+        ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+            self.0.to_sql(ty, out)
+        }
+
+        accepts!(INT8);
+
+        to_sql_checked!();
+    }
+
+    impl<'a> FromSql<'a> for Long {
+        fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Long, Box<dyn Error + Sync + Send>> {
+            i64::from_sql(ty, raw).map(Long)
+        }
+
+        accepts!(INT8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_is_deserialized_from_a_number_or_a_string() {
+        let longs: Vec<Long> =
+            ::serde_json::from_str(r#"[42, "-9007199254740993", "9223372036854775807"]"#).unwrap();
+
+        assert_eq!(
+            longs,
+            vec![Long(42), Long(-9_007_199_254_740_993), Long(i64::MAX)]
+        );
+    }
+
+    #[test]
+    fn long_is_serialized_as_a_number() {
+        assert_eq!(
+            ::serde_json::to_string(&Long(9_007_199_254_740_993)).unwrap(),
+            "9007199254740993"
+        );
+    }
+
+    #[test]
+    fn long_rejects_strings_that_are_not_integers() {
+        let err = ::serde_json::from_str::<Long>(r#""12.5""#).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "invalid value: string \"12.5\", expected a 64-bit integer, as a number or a string at line 1 column 6"
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! Serialization of the fields with the `@sensitive` client directive, so serialized responses can be logged safely.
+//!
+//! With the default `sensitive_fields = "redact"` derive option, codegen points the `serialize_with` attribute of the annotated fields at [`serialize_redacted`]. With `sensitive_fields = "skip"`, the fields are left out of the serialized output instead. Deserialization is unchanged.
+
+use serde::Serializer;
+
+/// The string a redacted field is serialized as.
+pub const REDACTED: &str = "[redacted]";
+
+/// Serializes any value as [`REDACTED`].
+pub fn serialize_redacted<T, S>(_value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(REDACTED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Account {
+        login: String,
+        #[serde(serialize_with = "serialize_redacted")]
+        token: Option<String>,
+    }
+
+    #[test]
+    fn redacted_values_are_serialized_as_a_placeholder() {
+        let account = Account {
+            login: "tomhoule".to_string(),
+            token: Some("hunter2".to_string()),
+        };
+
+        assert_eq!(
+            ::serde_json::to_string(&account).unwrap(),
+            r#"{"login":"tomhoule","token":"[redacted]"}"#
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Deserialization of responses from buffers holding more than one JSON value, for streaming, chunked or multiplexed transports like newline-delimited JSON.
+//!
+//! The response type is given as a type parameter, usually the `ResponseData` of a generated module:
+//!
+//! ```ignore
+//! let (response, rest) =
+//!     graphql_client::streaming::from_str_tolerant::<my_query::ResponseData>(&buffer)?;
+//! ```
+
+use serde::de::{self, DeserializeOwned};
+use serde_json::{Deserializer, Error};
+
+use Response;
+
+/// Deserializes the first response in `input`, and returns it with the rest of the input, which may start with whitespace and hold more responses.
+pub fn from_str_tolerant<Data: DeserializeOwned>(
+    input: &str,
+) -> Result<(Response<Data>, &str), Error> {
+    let (response, offset) = first_response(input.as_bytes())?;
+    Ok((response, &input[offset..]))
+}
+
+/// Like [`from_str_tolerant`], for bytes, e.g. the buffer of a reader.
+pub fn from_slice_tolerant<Data: DeserializeOwned>(
+    input: &[u8],
+) -> Result<(Response<Data>, &[u8]), Error> {
+    let (response, offset) = first_response(input)?;
+    Ok((response, &input[offset..]))
+}
+
+/// The first response in the input, and the offset of the byte following it.
+fn first_response<Data: DeserializeOwned>(input: &[u8]) -> Result<(Response<Data>, usize), Error> {
+    let mut responses = Deserializer::from_slice(input).into_iter::<Response<Data>>();
+
+    match responses.next() {
+        Some(response) => Ok((response?, responses.byte_offset())),
+        None => Err(de::Error::custom("the input does not contain a response")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct ResponseData {
+        name: String,
+    }
+
+    #[test]
+    fn one_response_is_read_from_a_buffer_containing_two() {
+        let buffer = "{\"data\": {\"name\": \"Leia\"}}\n{\"data\": {\"name\": \"Han\"}}\n";
+
+        let (response, rest) = from_str_tolerant::<ResponseData>(buffer).unwrap();
+        assert_eq!(response.data.unwrap().name, "Leia");
+        assert_eq!(rest, "\n{\"data\": {\"name\": \"Han\"}}\n");
+
+        let (response, rest) = from_slice_tolerant::<ResponseData>(rest.as_bytes()).unwrap();
+        assert_eq!(response.data.unwrap().name, "Han");
+        assert_eq!(rest, b"\n");
+    }
+
+    #[test]
+    fn an_input_without_a_response_is_an_error() {
+        let err = from_str_tolerant::<ResponseData>(" \n").unwrap_err();
+
+        assert_eq!(err.to_string(), "the input does not contain a response");
+    }
+}
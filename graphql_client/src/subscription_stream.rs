@@ -0,0 +1,60 @@
+//! Decoding the payloads of a subscription into responses. Enabled by the `subscription_stream` feature.
+//!
+//! The transport provides a `futures::Stream` of raw JSON payloads, one per event, and [`decode_responses`] turns it into a stream of [`Response`]s for the subscription:
+//!
+//! ```ignore
+//! let responses = graphql_client::subscription_stream::decode_responses::<OnMessage, _>(payloads);
+//! ```
+
+use futures::{Async, Poll, Stream};
+use serde_json;
+use std::marker::PhantomData;
+
+use {GraphQLQuery, Response};
+
+/// Decodes each payload of `payloads` as a response of the `Q` operation. See [`ResponseStream`].
+pub fn decode_responses<Q, S>(payloads: S) -> ResponseStream<Q, S>
+where
+    Q: GraphQLQuery,
+    S: Stream,
+    S::Item: AsRef<[u8]>,
+{
+    ResponseStream {
+        payloads,
+        query: PhantomData,
+    }
+}
+
+/// A stream of the responses decoded from a stream of JSON payloads, returned by [`decode_responses`].
+///
+/// A payload that cannot be deserialized is an `Err` item, and the stream goes on with the next payload. Errors of the payload stream itself are passed through.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct ResponseStream<Q, S> {
+    payloads: S,
+    query: PhantomData<fn() -> Q>,
+}
+
+impl<Q, S> ResponseStream<Q, S> {
+    /// The stream of payloads.
+    pub fn into_inner(self) -> S {
+        self.payloads
+    }
+}
+
+impl<Q, S> Stream for ResponseStream<Q, S>
+where
+    Q: GraphQLQuery,
+    S: Stream,
+    S::Item: AsRef<[u8]>,
+{
+    type Item = Result<Response<Q::ResponseData>, serde_json::Error>;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, S::Error> {
+        let payload = try_ready!(self.payloads.poll());
+        Ok(Async::Ready(
+            payload.map(|payload| serde_json::from_slice(payload.as_ref())),
+        ))
+    }
+}
@@ -0,0 +1,127 @@
+//! Helpers for snapshot-style tests of generated response types. Enabled by the `testing` feature.
+//!
+//! With the feature enabled, the `ResponseData` structs that derive `Serialize` get an `assert_matches_json` method calling [`assert_matches_json`]:
+//!
+//! ```ignore
+//! let response_data: my_query::ResponseData = serde_json::from_str(body)?;
+//! response_data.assert_matches_json(r#"{"viewer": {"login": "octocat"}}"#);
+//! ```
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Compares the JSON serialization of `actual` with the `expected` JSON, semantically: whitespace and the order of object keys do not matter.
+///
+/// Returns `None` if they match, and a description of every difference otherwise, one per line, each starting with the path of the value that differs.
+///
+/// # Panics
+///
+/// If `expected` is not valid JSON, or `actual` cannot be serialized.
+pub fn json_mismatch<T: Serialize>(actual: &T, expected: &str) -> Option<String> {
+    let expected: Value = ::serde_json::from_str(expected).expect("the expected JSON is valid");
+    let actual = ::serde_json::to_value(actual).expect("the response data can be serialized");
+
+    let mut differences = Vec::new();
+    diff_values("$", &expected, &actual, &mut differences);
+
+    if differences.is_empty() {
+        None
+    } else {
+        Some(differences.join("\n"))
+    }
+}
+
+/// Panics with a readable diff if the JSON serialization of `actual` does not match the `expected` JSON. See [`json_mismatch`].
+pub fn assert_matches_json<T: Serialize>(actual: &T, expected: &str) {
+    if let Some(differences) = json_mismatch(actual, expected) {
+        panic!(
+            "the response data does not match the expected JSON:\n{}",
+            differences
+        );
+    }
+}
+
+fn diff_values(path: &str, expected: &Value, actual: &Value, differences: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, expected_value) in expected {
+                let path = format!("{}.{}", path, key);
+                match actual.get(key) {
+                    Some(actual_value) => {
+                        diff_values(&path, expected_value, actual_value, differences)
+                    }
+                    None => {
+                        differences.push(format!("{}: missing, expected {}", path, expected_value))
+                    }
+                }
+            }
+            for (key, actual_value) in actual {
+                if !expected.contains_key(key) {
+                    differences.push(format!("{}.{}: unexpected {}", path, key, actual_value));
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            if expected.len() != actual.len() {
+                differences.push(format!(
+                    "{}: expected {} items, got {}",
+                    path,
+                    expected.len(),
+                    actual.len()
+                ));
+            }
+            for (index, (expected, actual)) in expected.iter().zip(actual.iter()).enumerate() {
+                diff_values(
+                    &format!("{}[{}]", path, index),
+                    expected,
+                    actual,
+                    differences,
+                );
+            }
+        }
+        (expected, actual) => {
+            if expected != actual {
+                differences.push(format!("{}: expected {}, got {}", path, expected, actual));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_json_ignores_formatting_and_key_order() {
+        let actual = json!({"name": "Leia", "friends": [{"name": "Han"}]});
+
+        assert_eq!(
+            json_mismatch(
+                &actual,
+                r#"{ "friends": [ { "name": "Han" } ], "name": "Leia" }"#
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn differences_are_listed_with_their_path() {
+        let actual =
+            json!({"name": "Leia", "friends": [{"name": "Han"}, {"name": "Chewie"}], "age": 19});
+
+        assert_eq!(
+            json_mismatch(
+                &actual,
+                r#"{"name": "Luke", "friends": [{"name": "Han"}], "planet": "Tatooine"}"#
+            )
+            .unwrap(),
+            [
+                "$.friends: expected 1 items, got 2",
+                "$.name: expected \"Luke\", got \"Leia\"",
+                "$.planet: missing, expected \"Tatooine\"",
+                "$.age: unexpected 19",
+            ]
+            .join("\n")
+        );
+    }
+}
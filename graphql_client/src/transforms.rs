@@ -0,0 +1,56 @@
+//! Deserialization helpers for the transform client directives (`@trim`, `@lowercase` and `@uppercase`).
+//!
+//! Codegen points the `deserialize_with` attribute of the annotated fields at these functions. They work on `String` fields as well as on nullable and list fields wrapping strings.
+
+use serde::{Deserialize, Deserializer};
+
+/// Values whose strings can be transformed after deserialization.
+pub trait Transformable: Sized {
+    /// Apply `f` to every string contained in the value.
+    fn map_strings<F: Fn(String) -> String + Copy>(self, f: F) -> Self;
+}
+
+impl Transformable for String {
+    fn map_strings<F: Fn(String) -> String + Copy>(self, f: F) -> Self {
+        f(self)
+    }
+}
+
+impl<T: Transformable> Transformable for Option<T> {
+    fn map_strings<F: Fn(String) -> String + Copy>(self, f: F) -> Self {
+        self.map(|inner| inner.map_strings(f))
+    }
+}
+
+impl<T: Transformable> Transformable for Vec<T> {
+    fn map_strings<F: Fn(String) -> String + Copy>(self, f: F) -> Self {
+        self.into_iter().map(|inner| inner.map_strings(f)).collect()
+    }
+}
+
+/// Removes leading and trailing whitespace. Used for `@trim`.
+pub fn trim<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Transformable,
+{
+    T::deserialize(deserializer).map(|value| value.map_strings(|s| s.trim().to_owned()))
+}
+
+/// Converts to lowercase. Used for `@lowercase`.
+pub fn lowercase<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Transformable,
+{
+    T::deserialize(deserializer).map(|value| value.map_strings(|s| s.to_lowercase()))
+}
+
+/// Converts to uppercase. Used for `@uppercase`.
+pub fn uppercase<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Transformable,
+{
+    T::deserialize(deserializer).map(|value| value.map_strings(|s| s.to_uppercase()))
+}
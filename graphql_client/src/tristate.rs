@@ -0,0 +1,47 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A value that can be absent, explicitly null, or present. GraphQL treats an omitted input field differently from one set to `null`, which `Option` cannot express.
+///
+/// Absent fields are left out of the serialized input with `#[serde(skip_serializing_if = "Tristate::is_absent")]`, which the generated patch input types do for you.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Tristate<T> {
+    /// The field is not sent.
+    #[default]
+    Absent,
+    /// The field is sent as `null`.
+    Null,
+    /// The field is sent with this value.
+    Value(T),
+}
+
+impl<T> Tristate<T> {
+    /// Whether the value is `Absent`.
+    pub fn is_absent(&self) -> bool {
+        matches!(self, Tristate::Absent)
+    }
+}
+
+impl<T> From<Option<T>> for Tristate<T> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => Tristate::Value(value),
+            None => Tristate::Null,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for Tristate<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            // Absent values are normally skipped before we get here.
+            Tristate::Absent | Tristate::Null => serializer.serialize_none(),
+            Tristate::Value(value) => serializer.serialize_some(value),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Tristate<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::deserialize(deserializer).map(Tristate::from)
+    }
+}
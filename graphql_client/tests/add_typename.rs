@@ -0,0 +1,47 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/add_typename/query.graphql",
+    schema_path = "tests/interfaces/interface_schema.graphql",
+    response_derives = "Debug, PartialEq",
+    add_typename = "true"
+)]
+pub struct MyQuery;
+
+#[test]
+fn typename_is_added_to_the_query() {
+    assert!(my_query::QUERY.contains("everything {\n    __typename\n"));
+}
+
+#[test]
+fn type_refining_fragment_spreads_are_told_apart_with_the_added_typename() {
+    use my_query::*;
+
+    const RESPONSE: &str = include_str!("interfaces/interface_response.json");
+
+    let response_data: ResponseData = serde_json::from_str(RESPONSE).unwrap();
+    let everything = response_data.everything.unwrap();
+
+    assert_eq!(
+        everything[0].on,
+        MyQueryEverythingOn::Person(MyQueryEverythingOnPerson {
+            birthday: Some("1934-02-18".to_string()),
+        })
+    );
+    assert_eq!(
+        everything[1].on,
+        MyQueryEverythingOn::Dog(MyQueryEverythingOnDog { is_good_dog: true })
+    );
+    assert_eq!(
+        everything[2].on,
+        MyQueryEverythingOn::Organization(MyQueryEverythingOnOrganization {
+            industry: Industry::OTHER,
+        })
+    );
+}
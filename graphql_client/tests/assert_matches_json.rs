@@ -0,0 +1,52 @@
+// Run with `cargo test --features testing`.
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/field_accessors/query.graphql",
+    schema_path = "tests/field_accessors/schema.graphql",
+    response_derives = "Debug, Serialize"
+)]
+pub struct AccountQuery;
+
+const RESPONSE: &str = r#"{
+    "account": {
+        "id": "acc-1",
+        "balance": 12.5,
+        "years": 3,
+        "verified": true,
+        "status": "ACTIVE",
+        "tags": ["savings"],
+        "owner": {"name": "Ilse"}
+    }
+}"#;
+
+fn response_data() -> account_query::ResponseData {
+    serde_json::from_str(RESPONSE).unwrap()
+}
+
+#[test]
+fn matching_json_passes() {
+    response_data().assert_matches_json(
+        r#"{"account": {"tags": ["savings"], "owner": {"name": "Ilse"}, "status": "ACTIVE",
+            "verified": true, "years": 3, "balance": 12.5, "id": "acc-1"}}"#,
+    );
+}
+
+#[test]
+#[should_panic(expected = "the response data does not match the expected JSON:
+$.account.balance: expected 10.0, got 12.5
+$.account.tags: expected 2 items, got 1")]
+fn mismatching_json_fails_with_a_diff() {
+    response_data().assert_matches_json(
+        r#"{"account": {"tags": ["savings", "checking"], "owner": {"name": "Ilse"}, "status": "ACTIVE",
+            "verified": true, "years": 3, "balance": 10.0, "id": "acc-1"}}"#,
+    );
+}
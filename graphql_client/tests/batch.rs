@@ -0,0 +1,100 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::batch;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "../graphql_client_codegen/src/tests/star_wars_query.graphql",
+    schema_path = "../graphql_client_codegen/src/tests/star_wars_schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct StarWarsQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "../graphql_client_codegen/src/tests/star_wars_float_query.graphql",
+    schema_path = "../graphql_client_codegen/src/tests/star_wars_schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct HeightQuery;
+
+const HERO_RESPONSES: &str = r#"[
+    {"data": {"hero": {"__typename": "Droid", "name": "R2-D2"}}},
+    {"data": {"hero": {"__typename": "Human", "name": "Luke Skywalker"}}}
+]"#;
+
+#[test]
+fn a_batch_of_the_same_operation_is_an_array_of_bodies() {
+    let body = batch::batch_query::<StarWarsQuery, _>(vec![
+        star_wars_query::Variables {
+            episode_for_hero: star_wars_query::Episode::NEWHOPE,
+        },
+        star_wars_query::Variables {
+            episode_for_hero: star_wars_query::Episode::EMPIRE,
+        },
+    ]);
+
+    let body = serde_json::to_value(&body).unwrap();
+    assert_eq!(body[0]["variables"], json!({"episodeForHero": "NEWHOPE"}));
+    assert_eq!(body[1]["variables"], json!({"episodeForHero": "EMPIRE"}));
+    assert_eq!(body[1]["query"], star_wars_query::QUERY);
+}
+
+#[test]
+fn responses_to_a_batch_of_the_same_operation_are_decoded_in_order() {
+    let responses = batch::decode_batch::<star_wars_query::ResponseData>(HERO_RESPONSES).unwrap();
+
+    let names: Vec<String> = responses
+        .into_iter()
+        .map(|response| response.data.unwrap().hero.unwrap().name)
+        .collect();
+    assert_eq!(names, vec!["R2-D2", "Luke Skywalker"]);
+}
+
+#[test]
+fn a_batch_of_different_operations_is_decoded_to_a_tuple() {
+    use graphql_client::GraphQLQuery;
+
+    let body = batch_operations!(
+        StarWarsQuery::build_query(star_wars_query::Variables {
+            episode_for_hero: star_wars_query::Episode::JEDI,
+        }),
+        HeightQuery::build_query(height_query::Variables),
+    )
+    .unwrap();
+    let body = serde_json::to_value(&body).unwrap();
+    assert_eq!(body[0]["operationName"], "StarWarsQuery");
+    assert_eq!(body[1]["operationName"], "HeightQuery");
+
+    let responses = r#"[
+        {"data": {"hero": {"__typename": "Droid", "name": "R2-D2"}}},
+        {"data": {"human": {"name": "Luke Skywalker", "height": 1.72}}}
+    ]"#;
+    let (hero, height) = decode_batch_operations!(
+        responses,
+        star_wars_query::ResponseData,
+        height_query::ResponseData,
+    )
+    .unwrap();
+
+    assert_eq!(hero.data.unwrap().hero.unwrap().name, "R2-D2");
+    assert_eq!(height.data.unwrap().human.unwrap().height, Some(1.72));
+}
+
+#[test]
+fn a_batch_with_a_missing_response_is_an_error() {
+    let err = decode_batch_operations!(
+        r#"[{"data": null}]"#,
+        star_wars_query::ResponseData,
+        height_query::ResponseData
+    )
+    .unwrap_err();
+
+    assert_eq!(err.to_string(), "expected 2 responses in the batch, got 1");
+}
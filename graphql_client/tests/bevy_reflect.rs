@@ -0,0 +1,80 @@
+// Run with `cargo test --features bevy`.
+#![cfg(feature = "bevy")]
+
+extern crate bevy_reflect;
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use bevy_reflect::{Reflect, ReflectRef, Struct, TypeInfo, TypeRegistry};
+use std::any::TypeId;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/bevy_reflect/query.graphql",
+    schema_path = "tests/bevy_reflect/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    bevy_reflect = "true"
+)]
+pub struct HeroQuery;
+
+#[test]
+fn response_types_can_be_registered() {
+    let mut registry = TypeRegistry::default();
+    registry.register::<hero_query::ResponseData>();
+    registry.register::<hero_query::HeroQueryHeroOn>();
+
+    let response = registry
+        .get(TypeId::of::<hero_query::ResponseData>())
+        .unwrap();
+    match response.type_info() {
+        TypeInfo::Struct(info) => assert!(info.field("hero").is_some()),
+        _ => panic!("expected the response data to be reflected as a struct"),
+    }
+    match registry
+        .get(TypeId::of::<hero_query::HeroQueryHeroOn>())
+        .unwrap()
+        .type_info()
+    {
+        TypeInfo::Enum(info) => {
+            assert!(info.contains_variant("Human"));
+            assert!(info.contains_variant("Droid"));
+        }
+        _ => panic!("expected the interface variants to be reflected as an enum"),
+    }
+}
+
+#[test]
+fn responses_are_reflected() {
+    let response: hero_query::ResponseData = serde_json::from_value(json!({
+        "hero": {
+            "__typename": "Human",
+            "id": "1000",
+            "name": "Luke Skywalker",
+            "height": 1.72,
+            "appearsIn": ["NEWHOPE", "PHANTOM_MENACE"],
+        },
+    }))
+    .unwrap();
+
+    let hero = match response.field("hero").unwrap().reflect_ref() {
+        ReflectRef::Enum(hero) => hero.field_at(0).unwrap(),
+        _ => panic!("expected the hero to be reflected as an Option"),
+    };
+    let hero = match hero.reflect_ref() {
+        ReflectRef::Struct(hero) => hero,
+        _ => panic!("expected the hero to be reflected as a struct"),
+    };
+    assert_eq!(
+        hero.field("name").unwrap().downcast_ref::<String>(),
+        Some(&"Luke Skywalker".to_string())
+    );
+
+    let mut patched = response.clone_value();
+    patched.apply(&response);
+    assert!(patched.reflect_partial_eq(&response).unwrap());
+}
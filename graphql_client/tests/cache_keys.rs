@@ -0,0 +1,55 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/variables_builder/query.graphql",
+    schema_path = "tests/variables_builder/schema.graphql",
+    cache_keys = "true"
+)]
+pub struct ReviewsQuery;
+
+fn variables(product_id: &str, min_rating: i64) -> reviews_query::Variables {
+    reviews_query::Variables {
+        product_id: product_id.to_string(),
+        min_rating,
+        order: None,
+        first: 10,
+    }
+}
+
+#[test]
+fn identical_variables_give_identical_keys() {
+    assert_eq!(
+        ReviewsQuery::cache_key(&variables("42", 4)).unwrap(),
+        ReviewsQuery::cache_key(&variables("42", 4)).unwrap()
+    );
+}
+
+#[test]
+fn different_variables_give_different_keys() {
+    assert_ne!(
+        ReviewsQuery::cache_key(&variables("42", 4)).unwrap(),
+        ReviewsQuery::cache_key(&variables("42", 5)).unwrap()
+    );
+    assert_ne!(
+        ReviewsQuery::cache_key(&variables("42", 4)).unwrap(),
+        ReviewsQuery::cache_key(&variables("43", 4)).unwrap()
+    );
+}
+
+#[test]
+fn the_key_has_the_operation_name_the_query_hash_and_the_sorted_variables() {
+    assert_eq!(
+        ReviewsQuery::cache_key(&variables("42", 4)).unwrap(),
+        format!(
+            r#"ReviewsQuery:{}:{{"first":10,"minRating":4,"order":null,"productId":"42"}}"#,
+            reviews_query::QUERY_HASH
+        )
+    );
+}
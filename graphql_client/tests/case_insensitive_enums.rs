@@ -0,0 +1,85 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/case_insensitive_enums/query.graphql",
+    schema_path = "tests/case_insensitive_enums/schema.graphql",
+    case_insensitive_enums = "true",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct AccountsQuery;
+
+pub mod with_default {
+    use graphql_client::GraphQLQuery;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/case_insensitive_enums/query.graphql",
+        schema_path = "tests/case_insensitive_enums/schema.graphql",
+        case_insensitive_enums = "true",
+        enum_unknowns = "default",
+        response_derives = "Debug, PartialEq"
+    )]
+    pub struct AccountsQuery;
+}
+
+fn statuses(values: &[&str]) -> Vec<accounts_query::Status> {
+    let accounts: Vec<serde_json::Value> = values
+        .iter()
+        .map(|status| json!({"name": "leia", "status": status}))
+        .collect();
+    let response: accounts_query::ResponseData =
+        serde_json::from_value(json!({ "accounts": accounts })).unwrap();
+
+    response
+        .accounts
+        .into_iter()
+        .map(|account| account.status)
+        .collect()
+}
+
+#[test]
+fn enum_values_are_matched_in_any_case() {
+    use accounts_query::Status;
+
+    assert_eq!(
+        statuses(&["ACTIVE", "active", "Suspended", "pending_Review"]),
+        vec![
+            Status::ACTIVE,
+            Status::ACTIVE,
+            Status::SUSPENDED,
+            Status::PENDING_REVIEW,
+        ]
+    );
+}
+
+#[test]
+fn unknown_values_keep_their_spelling() {
+    assert_eq!(
+        statuses(&["Banned"]),
+        vec![accounts_query::Status::Other("Banned".to_string())]
+    );
+}
+
+#[test]
+fn unknown_values_go_to_the_default_variant() {
+    use self::with_default::accounts_query::{ResponseData, Status};
+
+    let response: ResponseData = serde_json::from_value(json!({
+        "accounts": [
+            {"name": "han", "status": "suspended"},
+            {"name": "leia", "status": "banned"},
+        ]
+    }))
+    .unwrap();
+
+    assert_eq!(response.accounts[0].status, Status::SUSPENDED);
+    assert_eq!(response.accounts[1].status, Status::ACTIVE);
+}
@@ -0,0 +1,97 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::catch::{embed_field_errors, FieldError};
+use graphql_client::{Error, PathFragment, Response};
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/catch/query.graphql",
+    schema_path = "tests/catch/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct UserQuery;
+
+fn field_error(message: &str, path: Vec<PathFragment>) -> Error {
+    Error {
+        message: message.to_string(),
+        locations: None,
+        path: Some(path),
+        extensions: None,
+    }
+}
+
+#[test]
+fn catch_directive_is_stripped_from_the_query() {
+    assert!(!user_query::QUERY.contains("@catch"));
+    assert_eq!(
+        user_query::CAUGHT_FIELDS,
+        &[&["user", "email"][..], &["user", "friends", "contact"][..]]
+    );
+}
+
+#[test]
+fn caught_field_errors_end_up_in_the_err_arm() {
+    let mut body = json!({
+        "data": {
+            "user": {
+                "name": "Rosa",
+                "email": null,
+                "friends": [
+                    { "name": "Karl", "contact": "karl@example.com" },
+                    { "name": "Clara", "contact": null },
+                ],
+            }
+        },
+        "errors": [
+            { "message": "Not authorized", "path": ["user", "email"] },
+            { "message": "Mail server down", "path": ["user", "friends", 1, "contact"] },
+        ],
+    });
+
+    embed_field_errors(&mut body, user_query::CAUGHT_FIELDS);
+    let response: Response<user_query::ResponseData> = serde_json::from_value(body).unwrap();
+    let user = response.data.unwrap().user.unwrap();
+
+    assert_eq!(user.name, "Rosa");
+    assert_eq!(
+        user.email,
+        Err(FieldError {
+            errors: vec![field_error(
+                "Not authorized",
+                vec![
+                    PathFragment::Key("user".to_string()),
+                    PathFragment::Key("email".to_string()),
+                ]
+            )],
+        })
+    );
+    assert_eq!(
+        user.friends[0].contact,
+        Ok(Some("karl@example.com".to_string()))
+    );
+    assert_eq!(
+        user.friends[1].contact.as_ref().unwrap_err().to_string(),
+        "user/friends/1/contact:0:0: Mail server down"
+    );
+    // The errors are still reported at the top level.
+    assert_eq!(response.errors.unwrap().len(), 2);
+}
+
+#[test]
+fn null_without_error_is_ok() {
+    let body = json!({
+        "data": {
+            "user": { "name": "Rosa", "email": null, "friends": [] }
+        }
+    });
+
+    let response: Response<user_query::ResponseData> = serde_json::from_value(body).unwrap();
+
+    assert_eq!(response.data.unwrap().user.unwrap().email, Ok(None));
+}
@@ -0,0 +1,41 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+// There is no schema_path, the definitions of the document are the schema.
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/combined_document/document.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct BooksQuery;
+
+#[test]
+fn the_schema_comes_from_the_query_document() {
+    let response: books_query::ResponseData = serde_json::from_str(
+        r#"{"books": [{"title": "Dune", "genre": "FICTION"}, {"title": "SPQR", "genre": null}]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(response.books.len(), 2);
+    assert_eq!(response.books[0].title, "Dune");
+    assert_eq!(response.books[0].genre, Some(books_query::Genre::FICTION));
+    assert_eq!(response.books[1].genre, None);
+}
+
+#[test]
+fn only_the_operations_are_sent() {
+    let body = BooksQuery::build_query(books_query::Variables {
+        author: "Frank Herbert".to_string(),
+    });
+
+    assert!(body
+        .query
+        .starts_with("query BooksQuery($author: String!) {"));
+    assert!(!body.query.contains("type Book"));
+    assert!(!body.query.contains("schema"));
+}
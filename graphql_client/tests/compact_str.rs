@@ -0,0 +1,63 @@
+// Run with `cargo test --features compact_str`.
+#![cfg(feature = "compact_str")]
+
+extern crate compact_str;
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use compact_str::CompactString;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/compact_str/query.graphql",
+    schema_path = "tests/compact_str/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    compact_str = "true"
+)]
+pub struct UsersQuery;
+
+#[test]
+fn string_fields_are_deserialized_to_compact_strings() {
+    let response: users_query::ResponseData = serde_json::from_value(json!({
+        "users": [{
+            "id": "1",
+            "name": "Ada",
+            "nickname": null,
+            "emails": ["ada@example.com", "a-rather-long-address-kept-on-the-heap@example.com"],
+        }],
+    }))
+    .unwrap();
+
+    let user = &response.users[0];
+    let name: &CompactString = &user.name;
+    assert_eq!(name.as_str(), "Ada");
+    assert!(!name.is_heap_allocated());
+    let nickname: &Option<CompactString> = &user.nickname;
+    assert_eq!(*nickname, None);
+    let emails: &Vec<CompactString> = &user.emails;
+    assert!(!emails[0].is_heap_allocated());
+    assert!(emails[1].is_heap_allocated());
+    // `ID` stays a `String`.
+    let id: &String = &user.id;
+    assert_eq!(id, "1");
+}
+
+#[test]
+fn string_variables_are_compact_strings() {
+    let variables = users_query::Variables {
+        filter: Some(users_query::UserFilter {
+            name_starts_with: Some(CompactString::new("A")),
+        }),
+        team: CompactString::new("core"),
+    };
+
+    assert_eq!(
+        serde_json::to_value(variables).unwrap(),
+        json!({"filter": {"nameStartsWith": "A"}, "team": "core"})
+    );
+}
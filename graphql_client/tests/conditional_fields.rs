@@ -0,0 +1,49 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/conditional_fields/query.graphql",
+    schema_path = "tests/conditional_fields/schema.graphql"
+)]
+pub struct ConditionalFields;
+
+#[test]
+fn fields_with_include_and_skip_are_optional() {
+    let included = json!({
+        "name": "Leia",
+        "homePlanet": "Alderaan",
+        "friends": ["Han"],
+    });
+
+    let included = serde_json::from_value::<conditional_fields::ResponseData>(included).unwrap();
+    assert_eq!(included.name.unwrap(), "Leia");
+    assert_eq!(included.home_planet.unwrap(), "Alderaan");
+    assert_eq!(included.friends, vec!["Han".to_string()]);
+
+    let skipped = json!({
+        "friends": [],
+    });
+
+    let skipped = serde_json::from_value::<conditional_fields::ResponseData>(skipped).unwrap();
+    assert!(skipped.name.is_none());
+    assert!(skipped.home_planet.is_none());
+}
+
+#[test]
+fn condition_variables_are_declared() {
+    let variables = conditional_fields::Variables {
+        with_name: true,
+        without_friends: false,
+    };
+
+    assert_eq!(
+        serde_json::to_value(variables).unwrap(),
+        json!({"withName": true, "withoutFriends": false})
+    );
+}
@@ -0,0 +1,49 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/conditional_fields/report_query.graphql",
+    schema_path = "tests/conditional_fields/schema.graphql",
+    conditional_fields_report = "true"
+)]
+pub struct ConditionalFieldsReport;
+
+#[test]
+fn omitted_conditional_fields_are_reported_by_response_key() {
+    let response = serde_json::from_value::<conditional_fields_report::ResponseData>(json!({
+        "buddies": ["Han"],
+    }))
+    .unwrap();
+
+    // homePlanet is nullable in the schema, so its absence says nothing about the condition.
+    assert_eq!(response.omitted_conditional_fields(), vec!["name"]);
+
+    let response = serde_json::from_value::<conditional_fields_report::ResponseData>(json!({
+        "planet": null,
+    }))
+    .unwrap();
+
+    assert_eq!(
+        response.omitted_conditional_fields(),
+        vec!["name", "buddies"]
+    );
+}
+
+#[test]
+fn nothing_is_reported_when_the_fields_are_included() {
+    let response = serde_json::from_value::<conditional_fields_report::ResponseData>(json!({
+        "name": "Leia",
+        "planet": "Alderaan",
+        "buddies": [],
+    }))
+    .unwrap();
+
+    assert!(response.omitted_conditional_fields().is_empty());
+}
@@ -40,3 +40,29 @@ fn custom_scalars() {
         serde_json::from_value::<custom_scalars_query::ResponseData>(invalid_response).is_err()
     );
 }
+
+pub mod network {
+    pub type Address = ::std::net::Ipv4Addr;
+}
+
+pub mod nested {
+    // NetworkAddress is not in scope here, the scalar is mapped by its path from the crate root.
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/custom_scalars/query.graphql",
+        schema_path = "tests/custom_scalars/schema.graphql",
+        scalar_paths = "NetworkAddress = crate::network::Address"
+    )]
+    pub struct CustomScalarsQuery;
+}
+
+#[test]
+fn custom_scalars_can_be_mapped_to_crate_paths() {
+    let response_data = serde_json::from_value::<nested::custom_scalars_query::ResponseData>(
+        json!({"address": "10.0.0.1"}),
+    )
+    .unwrap();
+
+    let address: network::Address = response_data.address.unwrap();
+    assert_eq!(address, "10.0.0.1".parse::<Ipv4Addr>().unwrap());
+}
@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/empty_lists/query.graphql",
+    schema_path = "tests/empty_lists/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    empty_lists = "true"
+)]
+pub struct PostQuery;
+
+#[test]
+fn null_and_absent_lists_are_empty() {
+    let response: post_query::ResponseData = serde_json::from_value(json!({
+        "post": { "title": "Hello", "tags": null }
+    }))
+    .unwrap();
+
+    let post = response.post.unwrap();
+    assert_eq!(post.tags, Vec::<String>::new());
+    assert_eq!(post.comments, Vec::new());
+}
+
+#[test]
+fn lists_keep_their_nullable_items() {
+    let response: post_query::ResponseData = serde_json::from_value(json!({
+        "post": {
+            "title": "Hello",
+            "tags": ["rust"],
+            "comments": [{ "body": "Nice" }, null]
+        }
+    }))
+    .unwrap();
+
+    let post = response.post.unwrap();
+    assert_eq!(post.tags, vec!["rust".to_string()]);
+    assert_eq!(
+        post.comments,
+        vec![
+            Some(post_query::PostQueryPostComments {
+                body: "Nice".to_string()
+            }),
+            None
+        ]
+    );
+}
@@ -0,0 +1,35 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/enum_all_variants/query.graphql",
+    schema_path = "tests/enum_all_variants/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    enum_all_variants = "true"
+)]
+pub struct ShirtsQuery;
+
+use shirts_query::Size;
+
+#[test]
+fn all_variants_lists_the_schema_values_in_order() {
+    let names: Vec<String> = Size::all_variants()
+        .iter()
+        .map(|size| size.to_string())
+        .collect();
+
+    assert_eq!(names, vec!["SMALL", "MEDIUM", "LARGE"]);
+}
+
+#[test]
+fn all_variants_excludes_the_fallback() {
+    for size in Size::all_variants() {
+        if let Size::Other(value) = size {
+            panic!("unexpected fallback value: {}", value);
+        }
+    }
+}
@@ -0,0 +1,43 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/enum_codes/query.graphql",
+    schema_path = "tests/enum_codes/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct EnumCodesQuery;
+
+const RESPONSE: &str = r#"{"accounts": [
+    {"name": "Oona", "status": "ACTIVE"},
+    {"name": "Eero", "status": "DELETED"},
+    {"name": "Aino", "status": "ARCHIVED"}
+]}"#;
+
+#[test]
+fn enum_values_expose_their_code() {
+    use enum_codes_query::Status;
+
+    assert_eq!(Status::ACTIVE.code(), Some(1));
+    assert_eq!(Status::SUSPENDED.code(), Some(2));
+    assert_eq!(Status::DELETED.code(), Some(3));
+    assert_eq!(Status::Other("ARCHIVED".to_string()).code(), None);
+}
+
+#[test]
+fn deserialized_enum_values_expose_their_code() {
+    let response: enum_codes_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    let codes: Vec<Option<i32>> = response
+        .accounts
+        .iter()
+        .map(|account| account.status.code())
+        .collect();
+
+    assert_eq!(codes, vec![Some(1), Some(3), None]);
+}
@@ -0,0 +1,51 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/enum_strings/query.graphql",
+    schema_path = "tests/enum_strings/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct ReviewsQuery;
+
+use reviews_query::ReviewState;
+
+const GRAPHQL_VALUES: &[&str] = &["APPROVED", "in_review", "changesRequested", "REJECTED"];
+
+#[test]
+fn display_and_from_str_use_the_graphql_spelling() {
+    let parsed: Vec<ReviewState> = GRAPHQL_VALUES
+        .iter()
+        .map(|value| value.parse().unwrap())
+        .collect();
+
+    assert_eq!(
+        parsed,
+        vec![
+            ReviewState::APPROVED,
+            ReviewState::in_review,
+            ReviewState::changesRequested,
+            ReviewState::Other("REJECTED".to_string()),
+        ]
+    );
+
+    let displayed: Vec<String> = parsed.iter().map(|state| state.to_string()).collect();
+    assert_eq!(displayed, GRAPHQL_VALUES);
+}
+
+#[test]
+fn serde_and_string_conversions_agree() {
+    for value in GRAPHQL_VALUES {
+        let json = format!("\"{}\"", value);
+
+        let deserialized: ReviewState = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, value.parse().unwrap());
+        assert_eq!(deserialized.to_string(), *value);
+        assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+    }
+}
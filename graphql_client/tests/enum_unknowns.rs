@@ -0,0 +1,45 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/enum_unknowns/query.graphql",
+    schema_path = "tests/enum_unknowns/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    enum_unknowns = "default"
+)]
+pub struct EnumUnknownsQuery;
+
+const RESPONSE: &str = r#"{"accounts": [
+    {"name": "Oona", "status": "ACTIVE", "role": "ADMIN"},
+    {"name": "Eero", "status": "ARCHIVED", "role": "OWNER"}
+]}"#;
+
+#[test]
+fn unknown_values_are_deserialized_to_the_default_variant() {
+    use enum_unknowns_query::{Role, Status};
+
+    let response: enum_unknowns_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    assert_eq!(response.accounts[0].status, Status::ACTIVE);
+    assert_eq!(response.accounts[0].role, Role::ADMIN);
+    // The value marked with @unknownDefault.
+    assert_eq!(response.accounts[1].status, Status::UNKNOWN);
+    // Without the directive, the first value.
+    assert_eq!(response.accounts[1].role, Role::MEMBER);
+}
+
+#[test]
+fn known_values_are_serialized_as_before() {
+    use enum_unknowns_query::Status;
+
+    assert_eq!(
+        serde_json::to_string(&Status::SUSPENDED).unwrap(),
+        r#""SUSPENDED""#
+    );
+    assert_eq!("UNKNOWN".parse::<Status>().unwrap(), Status::UNKNOWN);
+}
@@ -0,0 +1,63 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/enum_value_methods/query.graphql",
+    schema_path = "tests/enum_value_methods/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    enum_value_methods = "weight(n), label(text), billable(yes)"
+)]
+pub struct EnumValueMethodsQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/enum_value_methods/query.graphql",
+    schema_path = "tests/enum_value_methods/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    enum_value_methods = "weight(n)",
+    enum_unknowns = "default"
+)]
+pub struct DefaultEnumValueMethodsQuery;
+
+const RESPONSE: &str = r#"{"accounts": [
+    {"name": "Oona", "plan": "TEAM", "status": "ACTIVE"},
+    {"name": "Eero", "plan": "FREE", "status": "DELETED"},
+    {"name": "Aino", "plan": "PREMIUM", "status": "ACTIVE"}
+]}"#;
+
+#[test]
+fn enum_values_expose_their_directive_arguments() {
+    use enum_value_methods_query::Plan;
+
+    assert_eq!(Plan::TEAM.weight(), Some(5));
+    assert_eq!(Plan::ENTERPRISE.label(), Some("Enterprise"));
+    assert_eq!(Plan::FREE.billable(), Some(false));
+    assert_eq!(Plan::Other("PREMIUM".to_string()).weight(), None);
+}
+
+#[test]
+fn deserialized_enum_values_expose_their_directive_arguments() {
+    let response: enum_value_methods_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    let weights: Vec<Option<i32>> = response
+        .accounts
+        .iter()
+        .map(|account| account.plan.weight())
+        .collect();
+
+    assert_eq!(weights, vec![Some(5), Some(0), None]);
+}
+
+#[test]
+fn methods_are_not_optional_without_other_variant() {
+    use default_enum_value_methods_query::Plan;
+
+    let weight: i32 = Plan::ENTERPRISE.weight();
+
+    assert_eq!(weight, 20);
+}
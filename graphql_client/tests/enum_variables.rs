@@ -0,0 +1,31 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/enum_variables/query.graphql",
+    schema_path = "tests/enum_variables/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct ProjectsQuery;
+
+#[test]
+fn enum_variable_defaults_are_enum_values() {
+    let variables = projects_query::Variables {
+        status: projects_query::Variables::default_status(),
+        excluded: projects_query::Variables::default_excluded(),
+    };
+
+    assert_eq!(variables.status, Some(projects_query::Status::ACTIVE));
+
+    let out = serde_json::to_string(&variables).unwrap();
+
+    assert_eq!(
+        out,
+        r#"{"status":"ACTIVE","excluded":["ARCHIVED","DRAFT"]}"#
+    );
+}
@@ -0,0 +1,72 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::estimated_size::EstimatedSize;
+
+type DateTime = String;
+
+// The query of the `scalar_collectors` test has fragments, unions and interfaces.
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/scalar_collectors/query.graphql",
+    schema_path = "tests/scalar_collectors/schema.graphql",
+    estimated_size = "true"
+)]
+pub struct ScalarCollectorsQuery;
+
+fn response(friends: usize, title: &str) -> scalar_collectors_query::ResponseData {
+    let friends: Vec<_> = (0..friends)
+        .map(|i| json!({ "id": format!("u{}", i), "name": "Leia" }))
+        .collect();
+
+    serde_json::from_value(json!({
+        "me": {
+            "id": "u1",
+            "createdAt": "2019-01-01T00:00:00Z",
+            "friends": friends
+        },
+        "search": [
+            { "__typename": "Post", "id": "p1", "title": title },
+            { "__typename": "User", "id": "u2" }
+        ],
+        "node": { "__typename": "Post", "id": "p2", "publishedAt": null }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn the_estimate_grows_with_more_items() {
+    assert!(response(3, "GraphQL").estimated_size() > response(1, "GraphQL").estimated_size());
+}
+
+#[test]
+fn the_estimate_grows_with_longer_strings() {
+    let long_title = "GraphQL".repeat(100);
+
+    assert!(
+        response(1, &long_title).estimated_size()
+            >= response(1, "GraphQL").estimated_size() + long_title.len() - "GraphQL".len()
+    );
+}
+
+#[test]
+fn the_estimate_covers_the_inline_size_and_the_strings() {
+    let response = response(0, "GraphQL");
+
+    // At least the inline size and the bytes of the strings: ids, the date and the title.
+    assert!(
+        response.estimated_size()
+            >= ::std::mem::size_of::<scalar_collectors_query::ResponseData>()
+                + 2
+                + 20
+                + 2
+                + 7
+                + 2
+                + 2
+    );
+}
@@ -0,0 +1,34 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/field_accessors/query.graphql",
+    schema_path = "tests/field_accessors/schema.graphql",
+    response_derives = "Debug, Serialize"
+)]
+pub struct AccountQuery;
+
+#[test]
+fn none_is_serialized_as_null_instead_of_omitting_the_key() {
+    let json = json!({
+        "account": {
+            "id": "acc-1",
+            "balance": 12.5,
+            "years": null,
+            "verified": true,
+            "status": "ACTIVE",
+            "tags": [],
+            "owner": null,
+        }
+    });
+
+    let response: account_query::ResponseData = serde_json::from_value(json.clone()).unwrap();
+
+    assert_eq!(serde_json::to_value(&response).unwrap(), json);
+}
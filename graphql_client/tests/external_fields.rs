@@ -0,0 +1,53 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+pub mod generated {
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/external_fields/query.graphql",
+        schema_path = "tests/external_fields/schema.graphql"
+    )]
+    pub struct ReviewsQuery;
+}
+
+pub mod skipped {
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/external_fields/query.graphql",
+        schema_path = "tests/external_fields/schema.graphql",
+        skip_external_fields = "true"
+    )]
+    pub struct ReviewsQuery;
+}
+
+fn response() -> serde_json::Value {
+    json!({
+        "reviews": [{
+            "body": "Great!",
+            "author": { "id": "1", "name": "Ada" },
+        }],
+    })
+}
+
+#[test]
+fn external_fields_are_generated_by_default() {
+    let response: generated::reviews_query::ResponseData =
+        serde_json::from_value(response()).unwrap();
+
+    assert_eq!(response.reviews[0].author.name, "Ada");
+}
+
+#[test]
+fn external_fields_can_be_skipped() {
+    let response: skipped::reviews_query::ResponseData =
+        serde_json::from_value(response()).unwrap();
+
+    // The struct literal is exhaustive: `name` is not a field anymore.
+    let skipped::reviews_query::ReviewsQueryReviewsAuthor { id } = &response.reviews[0].author;
+    assert_eq!(id, "1");
+}
@@ -0,0 +1,59 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/field_accessors/query.graphql",
+    schema_path = "tests/field_accessors/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    field_accessors = "true"
+)]
+pub struct AccountQuery;
+
+use account_query::*;
+
+const RESPONSE: &str = r#"{
+    "account": {
+        "id": "acc-1",
+        "balance": 12.5,
+        "years": 3,
+        "verified": true,
+        "status": "ACTIVE",
+        "tags": ["savings"],
+        "owner": {"name": "Ilse"}
+    }
+}"#;
+
+#[test]
+fn copy_fields_are_returned_by_value() {
+    let response: ResponseData = serde_json::from_str(RESPONSE).unwrap();
+    let account = response.account();
+
+    let balance: f64 = account.balance();
+    let years: Option<i64> = account.years();
+    let verified: bool = account.verified();
+
+    assert_eq!(balance, 12.5);
+    assert_eq!(years, Some(3));
+    assert!(verified);
+}
+
+#[test]
+fn other_fields_are_returned_by_reference() {
+    let response: ResponseData = serde_json::from_str(RESPONSE).unwrap();
+    let account: &AccountQueryAccount = response.account();
+
+    let id: &String = account.id();
+    let status: &Status = account.status();
+    let tags: &Vec<String> = account.tags();
+    let owner: &Option<AccountQueryAccountOwner> = account.owner();
+
+    assert_eq!(id, "acc-1");
+    assert_eq!(status, &Status::ACTIVE);
+    assert_eq!(tags, &vec!["savings".to_string()]);
+    assert_eq!(owner.as_ref().unwrap().name(), "Ilse");
+}
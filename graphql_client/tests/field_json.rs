@@ -0,0 +1,46 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/field_accessors/query.graphql",
+    schema_path = "tests/field_accessors/schema.graphql",
+    response_derives = "Debug, Serialize",
+    field_json = "true"
+)]
+pub struct AccountQuery;
+
+const RESPONSE: &str = r#"{
+    "account": {
+        "id": "acc-1",
+        "balance": 12.5,
+        "years": 31,
+        "verified": true,
+        "status": "ACTIVE",
+        "tags": ["vip", "early"],
+        "owner": {"name": "Oona"}
+    }
+}"#;
+
+#[test]
+fn field_json_extracts_a_nested_field_by_path() {
+    let response: account_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    assert_eq!(
+        response.field_json("account.owner.name"),
+        Some(json!("Oona"))
+    );
+    assert_eq!(response.field_json("account.tags.1"), Some(json!("early")));
+    // Paths are made of response keys, aliases included.
+    assert_eq!(response.field_json("account.years"), Some(json!(31)));
+    assert_eq!(response.field_json("account.age"), None);
+    assert_eq!(
+        response.field_json("account.owner"),
+        Some(json!({"name": "Oona"}))
+    );
+}
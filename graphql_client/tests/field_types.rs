@@ -0,0 +1,74 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+type Json = serde_json::Value;
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Settings {
+    theme: String,
+    font_size: u8,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Layout {
+    columns: Vec<String>,
+}
+
+// `extra` has no known shape, it stays a `Json` value.
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/field_types/query.graphql",
+    schema_path = "tests/field_types/schema.graphql",
+    field_types = "User.settings = crate::Settings, User.layouts = crate::Layout",
+    response_derives = "Debug"
+)]
+pub struct ViewerQuery;
+
+#[test]
+fn mapped_json_fields_are_deserialized_to_their_type() {
+    let response: viewer_query::ResponseData = serde_json::from_value(json!({
+        "viewer": {
+            "name": "Leia",
+            "settings": {"theme": "dark", "fontSize": 14},
+            "layouts": [{"columns": ["name", "date"]}],
+            "extra": {"plugin": [1, 2]},
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        response.viewer.settings,
+        Settings {
+            theme: "dark".to_string(),
+            font_size: 14,
+        }
+    );
+    assert_eq!(
+        response.viewer.layouts,
+        Some(vec![Layout {
+            columns: vec!["name".to_string(), "date".to_string()],
+        }])
+    );
+    assert_eq!(response.viewer.extra, Some(json!({"plugin": [1, 2]})));
+}
+
+#[test]
+fn values_of_another_shape_are_rejected() {
+    let result = serde_json::from_value::<viewer_query::ResponseData>(json!({
+        "viewer": {
+            "name": "Leia",
+            "settings": {"theme": "dark"},
+            "layouts": null,
+            "extra": null,
+        }
+    }));
+
+    assert!(result.is_err());
+}
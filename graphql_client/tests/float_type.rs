@@ -0,0 +1,47 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/float_type/query.graphql",
+    schema_path = "tests/float_type/schema.graphql",
+    float_type = "f32"
+)]
+pub struct MeasurementsQuery;
+
+#[test]
+fn floats_are_f32_in_responses_lists_and_options() {
+    let response = json!({
+        "measurements": {
+            "average": 1.5,
+            "maximum": null,
+            "samples": [1.0, 2.0],
+            "gaps": [null, 0.25],
+        }
+    });
+
+    let data: measurements_query::ResponseData = serde_json::from_value(response).unwrap();
+    let measurements = data.measurements;
+
+    let average: f32 = measurements.average;
+    let maximum: Option<f32> = measurements.maximum;
+    let samples: Vec<f32> = measurements.samples;
+    let gaps: Option<Vec<Option<f32>>> = measurements.gaps;
+
+    assert_eq!(average, 1.5);
+    assert_eq!(maximum, None);
+    assert_eq!(samples, vec![1.0, 2.0]);
+    assert_eq!(gaps, Some(vec![None, Some(0.25)]));
+}
+
+#[test]
+fn float_variables_are_f32() {
+    let threshold: Option<f32> = measurements_query::Variables::default_threshold();
+
+    assert_eq!(threshold, Some(0.5));
+}
@@ -30,3 +30,90 @@ fn fragment_reference() {
         "value"
     );
 }
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/fragments/conditional_query.graphql",
+    schema_path = "tests/fragments/schema.graphql"
+)]
+pub struct ConditionalFragment;
+
+#[test]
+fn conditional_fragment_is_optional_as_a_whole() {
+    let included = json!({
+        "inFragment": "value",
+        "name": "Sam",
+        "extra": null,
+    });
+
+    let included =
+        serde_json::from_value::<conditional_fragment::ResponseData>(included).unwrap();
+    let details = included.details.unwrap();
+    assert_eq!(details.name, "Sam");
+    assert_eq!(details.extra, None);
+
+    let skipped = json!({
+        "inFragment": "value",
+    });
+
+    let skipped = serde_json::from_value::<conditional_fragment::ResponseData>(skipped).unwrap();
+    assert!(skipped.details.is_none());
+    assert_eq!(skipped.in_fragment.unwrap(), "value");
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/fragments/forward_reference_query.graphql",
+    schema_path = "tests/fragments/schema.graphql"
+)]
+pub struct ForwardReference;
+
+#[test]
+fn fragments_can_be_spread_before_their_definition() {
+    let response = json!({
+        "name": "Sam",
+        "inFragment": "value",
+    });
+
+    let response = serde_json::from_value::<forward_reference::ResponseData>(response).unwrap();
+
+    assert_eq!(response.outer.name, "Sam");
+    assert_eq!(response.outer.inner.in_fragment.unwrap(), "value");
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/fragments/conditional_hero_query.graphql",
+    schema_path = "tests/fragments/schema.graphql"
+)]
+pub struct ConditionalHero;
+
+#[test]
+fn conditional_fragment_with_nullable_fields_is_none_when_absent() {
+    let response = json!({
+        "hero": {"name": "a"},
+    });
+
+    let response = serde_json::from_value::<conditional_hero::ResponseData>(response).unwrap();
+    let hero = response.hero.unwrap();
+    assert_eq!(hero.name, "a");
+    assert!(hero.nick_fields.is_none());
+    assert!(hero.age_fields.is_none());
+
+    let response = json!({
+        "hero": {"name": "a", "nick": null},
+    });
+
+    let response = serde_json::from_value::<conditional_hero::ResponseData>(response).unwrap();
+    let nick_fields = response.hero.unwrap().nick_fields.unwrap();
+    assert_eq!(nick_fields.nick, None);
+}
+
+#[test]
+fn conditional_fragment_with_a_mistyped_field_is_an_error() {
+    let response = json!({
+        "hero": {"name": "a", "age": "notanint"},
+    });
+
+    assert!(serde_json::from_value::<conditional_hero::ResponseData>(response).is_err());
+}
@@ -0,0 +1,23 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/graphql_config/query.graphql",
+    config_path = "tests/graphql_config/graphql.config.yml",
+    project = "app",
+    response_derives = "Debug"
+)]
+pub struct GreetingQuery;
+
+#[test]
+fn schema_path_is_read_from_the_graphql_config_file() {
+    let response: greeting_query::ResponseData =
+        serde_json::from_str(r#"{"greeting": "Hello, Rosa!"}"#).unwrap();
+
+    assert_eq!(response.greeting, "Hello, Rosa!");
+}
@@ -0,0 +1,40 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+use graphql_client::{GraphQLQuery, HttpMethod};
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/http_method/query.graphql",
+    schema_path = "tests/http_method/schema.graphql"
+)]
+pub struct ViewerName;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/http_method/query.graphql",
+    schema_path = "tests/http_method/schema.graphql"
+)]
+pub struct RenameViewer;
+
+#[test]
+fn get_annotated_queries_expose_their_http_method() {
+    assert_eq!(viewer_name::HTTP_METHOD, HttpMethod::Get);
+    assert_eq!(<ViewerName as GraphQLQuery>::HTTP_METHOD, HttpMethod::Get);
+}
+
+#[test]
+fn operations_are_posted_by_default() {
+    assert_eq!(
+        <RenameViewer as GraphQLQuery>::HTTP_METHOD,
+        HttpMethod::Post
+    );
+}
+
+#[test]
+fn http_method_directive_is_stripped_from_the_query() {
+    assert!(!viewer_name::QUERY.contains("@httpMethod"));
+    assert!(viewer_name::QUERY.contains("query ViewerName {"));
+}
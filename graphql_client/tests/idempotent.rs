@@ -0,0 +1,57 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/idempotent/query.graphql",
+    schema_path = "tests/idempotent/schema.graphql"
+)]
+pub struct CreatePayment;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/idempotent/query.graphql",
+    schema_path = "tests/idempotent/schema.graphql"
+)]
+pub struct Balance;
+
+#[test]
+fn idempotent_operations_are_marked() {
+    let marked = (
+        create_payment::IDEMPOTENT,
+        <CreatePayment as GraphQLQuery>::IDEMPOTENT,
+        <Balance as GraphQLQuery>::IDEMPOTENT,
+    );
+
+    assert_eq!(marked, (true, true, false));
+}
+
+#[test]
+fn the_idempotency_key_is_kept_out_of_the_body() {
+    let request = CreatePayment::build_idempotent_query(
+        create_payment::Variables { amount: 100 },
+        "payment-42".to_string(),
+    );
+
+    assert_eq!(request.idempotency_key, "payment-42");
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        json!({
+            "variables": { "amount": 100 },
+            "query": create_payment::QUERY,
+            "operationName": "CreatePayment",
+        })
+    );
+}
+
+#[test]
+fn idempotent_directive_is_stripped_from_the_query() {
+    assert!(!create_payment::QUERY.contains("@idempotent"));
+    assert!(create_payment::QUERY.contains("mutation CreatePayment($amount: Int!) {"));
+}
@@ -0,0 +1,58 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::Tristate;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/input_patches/query.graphql",
+    schema_path = "tests/input_patches/schema.graphql",
+    input_patches = "true"
+)]
+pub struct UpdateProfile;
+
+#[test]
+fn patch_only_serializes_the_fields_that_are_not_absent() {
+    let variables = update_profile::Variables {
+        input: update_profile::ProfileInputPatch {
+            display_name: Tristate::Value("Rosa".to_string()),
+            website: Tristate::Null,
+            ..Default::default()
+        },
+    };
+
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        json!({
+            "input": {
+                "displayName": "Rosa",
+                "website": null,
+            }
+        })
+    );
+}
+
+#[test]
+fn full_input_object_is_still_generated() {
+    let input = update_profile::ProfileInput {
+        display_name: "Rosa".to_string(),
+        bio: None,
+        website: None,
+        tags: Some(vec!["spartacist".to_string()]),
+    };
+
+    assert_eq!(
+        serde_json::to_value(&input).unwrap(),
+        json!({
+            "displayName": "Rosa",
+            "bio": null,
+            "website": null,
+            "tags": ["spartacist"],
+        })
+    );
+}
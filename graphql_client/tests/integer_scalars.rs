@@ -0,0 +1,66 @@
+// Run with `cargo test --features integer_scalars`.
+#![cfg(feature = "integer_scalars")]
+
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::scalars::Long;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/integer_scalars/query.graphql",
+    schema_path = "tests/integer_scalars/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct MeasurementsQuery;
+
+#[test]
+fn integer_scalars_are_deserialized_to_rust_integers() {
+    let response = json!({
+        "measurements": [
+            {"id": 1, "timestamp": "1546300800000", "temperature": -12, "level": 7},
+            {"id": "9007199254740993", "timestamp": 1546300860000i64, "temperature": 300, "level": null},
+        ],
+    });
+
+    let response: measurements_query::ResponseData = serde_json::from_value(response).unwrap();
+
+    let first = &response.measurements[0];
+    assert_eq!(first.id, Long(1));
+    assert_eq!(*first.timestamp, 1_546_300_800_000);
+    let temperature: i16 = first.temperature;
+    assert_eq!(temperature, -12);
+    let level: Option<i8> = first.level;
+    assert_eq!(level, Some(7));
+
+    let second = &response.measurements[1];
+    assert_eq!(i64::from(second.id), 9_007_199_254_740_993);
+    assert_eq!(second.timestamp, Long(1_546_300_860_000));
+    assert_eq!(second.level, None);
+}
+
+#[test]
+fn long_variables_are_serialized_as_numbers() {
+    let variables = measurements_query::Variables {
+        since: Long(1_546_300_800_000),
+    };
+
+    assert_eq!(
+        serde_json::to_value(variables).unwrap(),
+        json!({"since": 1_546_300_800_000i64})
+    );
+}
+
+#[test]
+fn bytes_out_of_range_are_rejected() {
+    let response = json!({
+        "measurements": [{"id": 1, "timestamp": 1, "temperature": 1, "level": 300}],
+    });
+
+    assert!(serde_json::from_value::<measurements_query::ResponseData>(response).is_err());
+}
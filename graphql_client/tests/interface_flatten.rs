@@ -0,0 +1,39 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/interface_flatten/query.graphql",
+    schema_path = "tests/interface_flatten/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    interface_flatten = "true"
+)]
+pub struct UserQuery;
+
+use user_query::*;
+
+#[test]
+fn inline_fragments_on_interfaces_are_flattened_into_the_object() {
+    let response_data: ResponseData = serde_json::from_str(
+        r#"{"user": {"email": "ada@example.com", "id": "1", "name": "Ada", "nickname": null}}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        response_data.user,
+        Some(UserQueryUser {
+            email: "ada@example.com".to_string(),
+            node_fields: UserQueryUserNodeFields {
+                id: "1".to_string(),
+            },
+            named_fields: UserQueryUserNamedFields {
+                name: "Ada".to_string(),
+                nickname: None,
+            },
+        })
+    );
+}
@@ -78,6 +78,21 @@ fn interface_not_on_everything_deserialization() {
     assert_eq!(response_data.everything.map(|names| names.len()), Some(4));
 }
 
+#[test]
+fn interface_typename_is_read_from_the_variant() {
+    let response_data: interface_not_on_everything_query::ResponseData =
+        serde_json::from_str(RESPONSE_NOT_ON_EVERYTHING).unwrap();
+
+    let typenames: Vec<&str> = response_data
+        .everything
+        .unwrap()
+        .iter()
+        .map(|everything| everything.typename())
+        .collect();
+
+    assert_eq!(typenames, ["Person", "Dog", "Organization", "Dog"]);
+}
+
 #[derive(GraphQLQuery)]
 #[graphql(
     query_path = "tests/interfaces/interface_with_fragment_query.graphql",
@@ -135,3 +150,133 @@ fn fragment_in_interface() {
         }
     )
 }
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/interfaces/interface_merged_fragments_query.graphql",
+    schema_path = "tests/interfaces/interface_schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct InterfaceMergedFragmentsQuery;
+
+#[test]
+fn fragments_on_the_same_implementor_are_merged() {
+    use interface_merged_fragments_query::*;
+
+    let response_data: ResponseData = serde_json::from_str(
+        r#"{"everything": [
+            {"__typename": "Person", "name": "Audrey Lorde", "birthday": "1934-02-18"},
+            {"__typename": "Dog", "name": "Laïka", "isGoodDog": true}
+        ]}"#,
+    )
+    .unwrap();
+
+    let expected = ResponseData {
+        everything: Some(vec![
+            InterfaceMergedFragmentsQueryEverything {
+                name: "Audrey Lorde".to_string(),
+                on: InterfaceMergedFragmentsQueryEverythingOn::Person(
+                    InterfaceMergedFragmentsQueryEverythingOnPerson {
+                        birthday: Some("1934-02-18".to_string()),
+                    },
+                ),
+            },
+            InterfaceMergedFragmentsQueryEverything {
+                name: "Laïka".to_string(),
+                on: InterfaceMergedFragmentsQueryEverythingOn::Dog(
+                    InterfaceMergedFragmentsQueryEverythingOnDog { is_good_dog: true },
+                ),
+            },
+        ]),
+    };
+
+    assert_eq!(response_data, expected);
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/interfaces/interface_aliased_typename_query.graphql",
+    schema_path = "tests/interfaces/interface_schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct InterfaceAliasedTypenameQuery;
+
+#[test]
+fn aliased_typename_discriminates_the_implementors() {
+    use interface_aliased_typename_query::*;
+
+    let response_data: ResponseData = serde_json::from_str(
+        r#"{"everything": [
+            {"kind": "Dog", "name": "Laïka", "isGoodDog": true},
+            {"kind": "Organization", "name": "Mozilla"}
+        ]}"#,
+    )
+    .unwrap();
+
+    let expected = ResponseData {
+        everything: Some(vec![
+            InterfaceAliasedTypenameQueryEverything {
+                name: "Laïka".to_string(),
+                on: InterfaceAliasedTypenameQueryEverythingOn::Dog(
+                    InterfaceAliasedTypenameQueryEverythingOnDog { is_good_dog: true },
+                ),
+            },
+            InterfaceAliasedTypenameQueryEverything {
+                name: "Mozilla".to_string(),
+                on: InterfaceAliasedTypenameQueryEverythingOn::Organization,
+            },
+        ]),
+    };
+
+    assert_eq!(response_data, expected);
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/interfaces/interface_common_fields_query.graphql",
+    schema_path = "tests/interfaces/interface_common_fields_schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct InterfaceCommonFieldsQuery;
+
+#[test]
+fn fields_selected_on_every_implementor_have_an_accessor() {
+    use interface_common_fields_query::*;
+
+    let response_data: ResponseData = serde_json::from_str(
+        r#"{"nodes": [
+            {"__typename": "User", "id": "u1", "login": "audre"},
+            {"__typename": "Repository", "id": "r1", "name": "poems", "owner": {"id": "u1"}}
+        ]}"#,
+    )
+    .unwrap();
+
+    let ids: Vec<&String> = response_data.nodes.iter().map(|node| node.id()).collect();
+    assert_eq!(ids, vec!["u1", "r1"]);
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/interfaces/interface_without_implementors_query.graphql",
+    schema_path = "tests/interfaces/interface_without_implementors_schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct InterfaceWithoutImplementorsQuery;
+
+#[test]
+fn typename_is_a_field_without_implementors() {
+    use interface_without_implementors_query::*;
+
+    let response_data: ResponseData =
+        serde_json::from_str(r#"{"entities": [{"kind": "Ship", "id": "s1"}]}"#).unwrap();
+
+    let expected = ResponseData {
+        entities: vec![InterfaceWithoutImplementorsQueryEntities {
+            id: "s1".to_string(),
+            typename: "Ship".to_string(),
+        }],
+    };
+
+    assert_eq!(response_data, expected);
+    assert_eq!(response_data.entities[0].typename(), "Ship");
+}
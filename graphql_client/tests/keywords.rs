@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/keywords/query.graphql",
+    schema_path = "tests/keywords/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct TokenQuery;
+
+#[test]
+fn keyword_fields_are_deserialized_from_their_graphql_name() {
+    let response = json!({
+        "token": { "type": "bearer", "ref": "main", "move": 3 },
+    });
+
+    let response: token_query::ResponseData = serde_json::from_value(response).unwrap();
+
+    assert_eq!(
+        response.token,
+        Some(token_query::TokenQueryToken {
+            type_: "bearer".to_string(),
+            ref_: Some("main".to_string()),
+            move_: Some(3),
+        })
+    );
+}
+
+#[test]
+fn keyword_variables_and_input_fields_are_serialized_with_their_graphql_name() {
+    let variables = token_query::Variables {
+        type_: "bearer".to_string(),
+        filter: Some(token_query::TokenFilter {
+            ref_: Some("main".to_string()),
+            type_: None,
+        }),
+    };
+
+    assert_eq!(
+        serde_json::to_value(variables).unwrap(),
+        json!({
+            "type": "bearer",
+            "filter": { "ref": "main", "Type": null },
+        })
+    );
+}
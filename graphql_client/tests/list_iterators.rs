@@ -0,0 +1,49 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/list_iterators/query.graphql",
+    schema_path = "tests/list_iterators/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    list_iterators = "true"
+)]
+pub struct RepositoriesQuery;
+
+const RESPONSE: &str = r#"{"repositories": {"nodes": [{"name": "graphql-client"}, {"name": "serde"}]}}"#;
+
+#[test]
+fn list_wrapper_can_be_iterated() {
+    let response: repositories_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    let by_ref: Vec<&str> = (&response.repositories)
+        .into_iter()
+        .map(|repository| repository.name.as_str())
+        .collect();
+    assert_eq!(by_ref, vec!["graphql-client", "serde"]);
+
+    let by_value: Vec<String> = response
+        .repositories
+        .into_iter()
+        .map(|repository| repository.name)
+        .collect();
+    assert_eq!(by_value, vec!["graphql-client", "serde"]);
+}
+
+#[test]
+fn list_wrapper_can_be_collected() {
+    use repositories_query::*;
+
+    let collected: RepositoriesQueryRepositories = vec!["a", "b"]
+        .into_iter()
+        .map(|name| RepositoriesQueryRepositoriesNodes {
+            name: name.to_string(),
+        })
+        .collect();
+
+    assert_eq!(collected.nodes.len(), 2);
+}
@@ -0,0 +1,60 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/max_identifier_length/query.graphql",
+    schema_path = "tests/max_identifier_length/schema.graphql",
+    max_identifier_length = "32"
+)]
+pub struct DeepQuery;
+
+/// The name of the type of the value, without its module path.
+fn type_name_of<T>(_: &T) -> &'static str {
+    let name = ::std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap()
+}
+
+#[test]
+fn deep_type_names_are_bounded_and_unique() {
+    let response: deep_query::ResponseData = serde_json::from_value(json!({
+        "root": {
+            "subcategories": [{
+                "subcategories": [{
+                    "subcategoriesOfTheFirstKind": [{"name": "Tea", "parent": {"name": "Drinks"}}],
+                    "subcategoriesOfTheSecondKind": [{"name": "Coffee"}],
+                }],
+            }],
+        }
+    }))
+    .unwrap();
+
+    let nested = &response.root.subcategories[0].subcategories[0];
+    let first = &nested.subcategories_of_the_first_kind[0];
+    let second = &nested.subcategories_of_the_second_kind[0];
+    let parent = first.parent.as_ref().unwrap();
+    assert_eq!(parent.name, "Drinks");
+    assert_eq!(second.name, "Coffee");
+
+    let names = [
+        type_name_of(&response.root),
+        type_name_of(nested),
+        type_name_of(first),
+        type_name_of(second),
+        type_name_of(parent),
+    ];
+    // The short names are kept as they are.
+    assert_eq!(names[0], "DeepQueryRoot");
+    for name in &names {
+        assert!(name.len() <= 32, "{} is longer than 32", name);
+    }
+    for (i, name) in names.iter().enumerate() {
+        assert!(!names[i + 1..].contains(name), "{} is not unique", name);
+    }
+}
@@ -0,0 +1,26 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/meta/query.graphql",
+    schema_path = "tests/meta/schema.graphql"
+)]
+pub struct UserQuery;
+
+#[test]
+fn meta_directives_are_collected_in_order() {
+    assert_eq!(
+        user_query::META,
+        &[("team", "accounts"), ("cache", "private")]
+    );
+}
+
+#[test]
+fn meta_directives_are_stripped_from_the_query() {
+    assert!(!user_query::QUERY.contains("@meta"));
+    assert!(user_query::QUERY.contains("query UserQuery {"));
+}
@@ -0,0 +1,22 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+use graphql_client::GraphQLQuery;
+
+// The generated code must build under the lints added to its module.
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/response_trait/viewer_query.graphql",
+    schema_path = "tests/response_trait/schema.graphql",
+    module_attributes = "#![allow(clippy::all)] #![deny(unused_imports)]"
+)]
+pub struct ViewerQuery;
+
+#[test]
+fn module_attributes_are_applied() {
+    let body = ViewerQuery::build_query(viewer_query::Variables);
+
+    assert_eq!(body.operation_name, "ViewerQuery");
+}
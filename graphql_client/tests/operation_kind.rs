@@ -0,0 +1,33 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/operation_kind/operations.graphql",
+    schema_path = "tests/operation_kind/schema.graphql"
+)]
+pub struct GetCounter;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/operation_kind/operations.graphql",
+    schema_path = "tests/operation_kind/schema.graphql"
+)]
+pub struct IncrementCounter;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/operation_kind/operations.graphql",
+    schema_path = "tests/operation_kind/schema.graphql"
+)]
+pub struct WatchCounter;
+
+#[test]
+fn operation_kind_is_the_keyword_of_the_operation_type() {
+    assert_eq!(get_counter::OPERATION_KIND, "query");
+    assert_eq!(increment_counter::OPERATION_KIND, "mutation");
+    assert_eq!(watch_counter::OPERATION_KIND, "subscription");
+}
@@ -0,0 +1,46 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/optimistic_responses/query.graphql",
+    schema_path = "tests/optimistic_responses/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    optimistic_responses = "true"
+)]
+pub struct AddTodo;
+
+use add_todo::*;
+
+#[test]
+fn optimistic_response_can_be_partially_built() {
+    let predicted = ResponseDataOptimistic {
+        add_todo: Some(AddTodoAddTodoOptimistic {
+            todo_fields: Some(TodoFieldsOptimistic {
+                title: Some("Buy milk".to_string()),
+                done: Some(false),
+            }),
+            author: Some(AddTodoAddTodoAuthorOptimistic {
+                name: Some("Mallory".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+    };
+
+    let add_todo = predicted.add_todo.unwrap();
+    assert_eq!(add_todo.id, None);
+    assert_eq!(add_todo.todo_fields.unwrap().title.unwrap(), "Buy milk");
+    assert_eq!(add_todo.author.unwrap().id, None);
+}
+
+#[test]
+fn optimistic_response_defaults_to_nothing_known() {
+    assert_eq!(
+        ResponseDataOptimistic::default(),
+        ResponseDataOptimistic { add_todo: None }
+    );
+}
@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/ord_derives/query.graphql",
+    schema_path = "tests/ord_derives/schema.graphql",
+    response_derives = "Debug, PartialEq, Eq, PartialOrd, Ord, Hash"
+)]
+pub struct AnimalsQuery;
+
+#[test]
+fn responses_without_floats_can_be_sorted() {
+    let response: animals_query::ResponseData = serde_json::from_value(json!({
+        "animals": [
+            { "__typename": "Cat", "name": "Rex", "lives": 9 },
+            { "__typename": "Dog", "name": "Rex", "goodBoy": true },
+            { "__typename": "Cat", "name": "Felix", "lives": 7 },
+        ]
+    }))
+    .unwrap();
+
+    let mut animals = response.animals;
+    animals.sort();
+
+    let names: Vec<&str> = animals.iter().map(|animal| animal.name.as_str()).collect();
+    assert_eq!(names, vec!["Felix", "Rex", "Rex"]);
+    // With the same name, the variants of the `On` enum are ordered as they are declared.
+    match (&animals[1].on, &animals[2].on) {
+        (
+            animals_query::AnimalsQueryAnimalsOn::Dog(_),
+            animals_query::AnimalsQueryAnimalsOn::Cat(_),
+        ) => (),
+        other => panic!("unexpected order: {:?}", other),
+    }
+}
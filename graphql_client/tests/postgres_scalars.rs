@@ -0,0 +1,30 @@
+// Run with `cargo test --features postgres`.
+#![cfg(feature = "postgres")]
+
+extern crate bytes;
+extern crate graphql_client;
+extern crate postgres_types;
+
+use bytes::BytesMut;
+use graphql_client::scalars::Long;
+use postgres_types::{FromSql, ToSql, Type};
+
+#[test]
+fn long_round_trips_through_int8() {
+    let mut buffer = BytesMut::new();
+    Long(9_007_199_254_740_993)
+        .to_sql_checked(&Type::INT8, &mut buffer)
+        .unwrap();
+
+    assert_eq!(
+        Long::from_sql(&Type::INT8, &buffer).unwrap(),
+        Long(9_007_199_254_740_993)
+    );
+}
+
+#[test]
+fn long_only_maps_to_int8() {
+    assert!(<Long as ToSql>::accepts(&Type::INT8));
+    assert!(!<Long as ToSql>::accepts(&Type::TEXT));
+    assert!(!<Long as FromSql>::accepts(&Type::INT4));
+}
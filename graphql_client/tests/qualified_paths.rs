@@ -0,0 +1,51 @@
+// No `serde_derive` here: with `qualified_paths`, the generated code derives the serde traits by path.
+#[macro_use]
+extern crate graphql_client;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+pub mod conflicting {
+    /// A local type with the name of the serde derive.
+    #[allow(dead_code)]
+    pub struct Serialize;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/qualified_paths/query.graphql",
+        schema_path = "tests/qualified_paths/schema.graphql",
+        response_derives = "Debug, PartialEq",
+        qualified_paths = "true"
+    )]
+    pub struct ReviewQuery;
+}
+
+use conflicting::review_query;
+
+#[test]
+fn variables_serialize_with_qualified_derives() {
+    let variables = review_query::Variables {
+        filter: review_query::Serialize { min_stars: 4 },
+    };
+
+    assert_eq!(
+        serde_json::to_value(variables).unwrap(),
+        json!({"filter": {"minStars": 4}})
+    );
+}
+
+#[test]
+fn response_deserializes_with_qualified_derives() {
+    let response_data: review_query::ResponseData = serde_json::from_value(json!({
+        "review": {"stars": 5, "sentiment": "POSITIVE"}
+    }))
+    .unwrap();
+
+    assert_eq!(
+        response_data.review,
+        Some(review_query::ReviewQueryReview {
+            stars: 5,
+            sentiment: review_query::Deserialize::POSITIVE,
+        })
+    );
+}
@@ -0,0 +1,82 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+pub mod compact {
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/query_hash/compact_query.graphql",
+        schema_path = "tests/query_hash/schema.graphql",
+        selected_operation = "UserQuery"
+    )]
+    pub struct UserQuery;
+}
+
+pub mod pretty {
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/query_hash/pretty_query.graphql",
+        schema_path = "tests/query_hash/schema.graphql",
+        selected_operation = "UserQuery"
+    )]
+    pub struct UserQuery;
+}
+
+pub mod registered {
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/query_hash/pretty_query.graphql",
+        schema_path = "tests/query_hash/schema.graphql",
+        selected_operation = "UserQuery",
+        persisted_query_manifest = "tests/query_hash/persisted_queries.json"
+    )]
+    pub struct UserQuery;
+}
+
+#[test]
+fn query_hash_is_the_sha256_of_the_query() {
+    assert_eq!(compact::user_query::QUERY_HASH.len(), 64);
+    assert_ne!(
+        compact::user_query::QUERY_HASH,
+        pretty::user_query::QUERY_HASH
+    );
+}
+
+#[test]
+fn document_sha256_is_the_decoded_query_hash() {
+    let hash = compact::user_query::QUERY_HASH;
+    let decoded: Vec<u8> = (0..hash.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hash[i..i + 2], 16).unwrap())
+        .collect();
+
+    assert_eq!(&compact::user_query::DOCUMENT_SHA256[..], &decoded[..]);
+}
+
+#[test]
+fn normalized_query_hash_ignores_formatting() {
+    assert_eq!(
+        compact::user_query::NORMALIZED_QUERY_HASH,
+        pretty::user_query::NORMALIZED_QUERY_HASH
+    );
+}
+
+#[test]
+fn schema_hash_only_depends_on_the_schema() {
+    assert_eq!(compact::user_query::SCHEMA_HASH.len(), 64);
+    assert_eq!(
+        compact::user_query::SCHEMA_HASH,
+        pretty::user_query::SCHEMA_HASH
+    );
+}
+
+#[test]
+fn operations_registered_with_the_normalized_hash_get_it_as_persisted_hash() {
+    assert_eq!(
+        registered::user_query::PERSISTED_QUERY_HASH,
+        pretty::user_query::NORMALIZED_QUERY_HASH
+    );
+}
@@ -0,0 +1,52 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path_glob = "tests/query_path_glob/queries/*.graphql",
+    schema_path = "tests/query_path_glob/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct UserQueries;
+
+#[test]
+fn every_matched_operation_gets_a_query_struct() {
+    let body = user_queries::GetUser::build_query(user_queries::GetUserVariables {
+        id: "1".to_string(),
+    });
+    assert_eq!(body.operation_name, "GetUser");
+    assert_eq!(body.operation_name, user_queries::GET_USER_OPERATION_NAME);
+
+    let body = user_queries::ListUsers::build_query(user_queries::ListUsersVariables);
+    assert_eq!(body.operation_name, "ListUsers");
+}
+
+#[test]
+fn the_documents_are_merged_into_one_query() {
+    assert!(user_queries::QUERY.contains("fragment UserFields on User"));
+    assert!(user_queries::QUERY.contains("query GetUser"));
+    assert!(user_queries::QUERY.contains("query ListUsers"));
+}
+
+#[test]
+fn fragments_can_be_spread_across_files() {
+    let response: user_queries::ListUsersResponseData =
+        serde_json::from_str(r#"{"users": [{"id": "1", "name": "Ada"}]}"#).unwrap();
+
+    assert_eq!(
+        response,
+        user_queries::ListUsersResponseData {
+            users: vec![user_queries::ListUsersUsers {
+                user_fields: user_queries::UserFields {
+                    id: "1".to_string(),
+                    name: "Ada".to_string(),
+                },
+            }],
+        }
+    );
+}
@@ -0,0 +1,40 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/response_trait/viewer_query.graphql",
+    schema_path = "tests/response_trait/schema.graphql",
+    raw_response = "true"
+)]
+pub struct ViewerQuery;
+
+const BODY: &str = r#"{"data": {"viewer": {"login": "tomhoule", "location": "Berlin"}}}"#;
+
+#[test]
+fn the_raw_response_data_round_trips() {
+    let response = ViewerQuery::from_response_str(BODY).unwrap();
+    let data = response.data.unwrap();
+
+    assert_eq!(data.viewer.login, "tomhoule");
+    assert_eq!(
+        data.raw,
+        json!({"viewer": {"login": "tomhoule", "location": "Berlin"}})
+    );
+    assert_eq!(serde_json::to_value(&data).unwrap(), data.raw);
+}
+
+#[test]
+fn the_query_response_data_is_wrapped() {
+    let data: <ViewerQuery as GraphQLQuery>::ResponseData =
+        serde_json::from_str(r#"{"viewer": {"login": "tomhoule"}}"#).unwrap();
+    let typed: &viewer_query::ResponseData = &data;
+
+    assert_eq!(typed.viewer.login, "tomhoule");
+}
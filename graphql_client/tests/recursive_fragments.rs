@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/recursive_fragments/query.graphql",
+    schema_path = "tests/recursive_fragments/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct CommentQuery;
+
+#[test]
+fn self_referential_objects_are_selected_to_a_fixed_depth_through_fragments() {
+    let response = json!({
+        "comment": {
+            "body": "Agreed",
+            "parent": {
+                "body": "Ship it",
+                "parent": {"body": "Proposal"},
+            },
+            "author": {
+                "name": "Eero",
+                "pinned": {"body": "Welcome", "parent": null},
+            },
+            "replies": [
+                {"body": "Thanks", "replies": [{"body": "You're welcome"}]},
+            ],
+        }
+    });
+
+    let response: comment_query::ResponseData = serde_json::from_value(response).unwrap();
+    let comment = response.comment.unwrap().comment_fields;
+
+    let parent = &comment.parent.as_ref().unwrap().parent_fields;
+    assert_eq!(parent.body, "Ship it");
+    assert_eq!(parent.parent.as_ref().unwrap().body, "Proposal");
+
+    let author = &comment.author.author_fields;
+    assert_eq!(author.name, "Eero");
+    let pinned = &author.pinned.as_ref().unwrap().parent_fields;
+    assert_eq!(pinned.body, "Welcome");
+    assert!(pinned.parent.is_none());
+
+    assert_eq!(comment.replies[0].replies[0].body, "You're welcome");
+}
@@ -0,0 +1,63 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/rename_all/query.graphql",
+    schema_path = "tests/rename_all/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    rename_all_camel_case = "true"
+)]
+pub struct UsersQuery;
+
+const RESPONSE: &str = r#"{
+    "users": [
+        {
+            "__typename": "User",
+            "userID": "1",
+            "displayName": "Rosa",
+            "avatarUrl": null,
+            "lastLoginAt": "2018-10-01"
+        }
+    ]
+}"#;
+
+#[test]
+fn camel_case_fields_are_deserialized() {
+    let response: users_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    assert_eq!(
+        response.users,
+        vec![users_query::UsersQueryUsers {
+            typename: "User".to_string(),
+            user_id: "1".to_string(),
+            display_name: "Rosa".to_string(),
+            avatar_url: None,
+            last_login_at: Some("2018-10-01".to_string()),
+        }]
+    );
+}
+
+#[test]
+fn camel_case_variables_are_serialized() {
+    let variables = users_query::Variables {
+        filter: Some(users_query::UserFilter {
+            display_name: Some("Rosa".to_string()),
+            created_after: None,
+        }),
+        first_count: Some(10),
+    };
+
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        json!({
+            "filter": { "displayName": "Rosa", "createdAfter": null },
+            "firstCount": 10,
+        })
+    );
+}
@@ -0,0 +1,47 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "../graphql_client_codegen/src/tests/star_wars_query.graphql",
+    schema_path = "../graphql_client_codegen/src/tests/star_wars_schema.graphql"
+)]
+pub struct StarWarsQuery;
+
+#[test]
+fn to_request_body_serializes_the_whole_query_body() {
+    let body = StarWarsQuery::to_request_body(star_wars_query::Variables {
+        episode_for_hero: star_wars_query::Episode::NEWHOPE,
+    })
+    .unwrap();
+
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        body,
+        json!({
+            "operationName": "StarWarsQuery",
+            "query": star_wars_query::QUERY,
+            "variables": { "episodeForHero": "NEWHOPE" },
+        })
+    );
+}
+
+#[test]
+fn from_response_str_deserializes_the_response_body() {
+    let body = r#"{"data": {"hero": {"__typename": "Droid", "name": "R2-D2"}}, "errors": [{"message": "Slow resolver"}]}"#;
+
+    let response = StarWarsQuery::from_response_str(body).unwrap();
+
+    assert_eq!(response.data.unwrap().hero.unwrap().name, "R2-D2");
+    assert_eq!(response.errors.unwrap()[0].message, "Slow resolver");
+}
+
+#[test]
+fn from_response_str_rejects_invalid_bodies() {
+    assert!(StarWarsQuery::from_response_str(r#"{"data": {"hero": 3}}"#).is_err());
+}
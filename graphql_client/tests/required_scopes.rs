@@ -0,0 +1,23 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/required_scopes/query.graphql",
+    schema_path = "tests/required_scopes/schema.graphql"
+)]
+pub struct ViewerEmail;
+
+#[test]
+fn required_scopes_are_collected() {
+    assert_eq!(viewer_email::REQUIRED_SCOPES, &["read:user", "user:email"]);
+}
+
+#[test]
+fn required_scopes_directive_is_stripped_from_the_query() {
+    assert!(!viewer_email::QUERY.contains("@requiresScopes"));
+    assert!(viewer_email::QUERY.contains("query ViewerEmail {"));
+}
@@ -0,0 +1,52 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::{GraphQLQuery, GraphQLResponse};
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/response_trait/viewer_query.graphql",
+    schema_path = "tests/response_trait/schema.graphql",
+    response_derives = "Serialize",
+    response_trait = "true"
+)]
+pub struct ViewerQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/response_trait/count_query.graphql",
+    schema_path = "tests/response_trait/schema.graphql",
+    response_derives = "Serialize",
+    response_trait = "true"
+)]
+pub struct CountQuery;
+
+fn responses() -> Vec<Box<dyn GraphQLResponse>> {
+    let viewer: viewer_query::ResponseData =
+        serde_json::from_value(json!({"viewer": {"login": "leia"}})).unwrap();
+    let count: count_query::ResponseData =
+        serde_json::from_value(json!({"repositoryCount": 3})).unwrap();
+
+    vec![Box::new(viewer), Box::new(count)]
+}
+
+#[test]
+fn responses_of_different_operations_are_stored_together() {
+    let responses = responses();
+
+    let names: Vec<&str> = responses.iter().map(|r| r.operation_name()).collect();
+    assert_eq!(names, vec!["ViewerQuery", "CountQuery"]);
+
+    let json: Vec<serde_json::Value> = responses.iter().map(|r| r.to_json().unwrap()).collect();
+    assert_eq!(
+        json,
+        vec![
+            json!({"viewer": {"login": "leia"}}),
+            json!({"repositoryCount": 3})
+        ]
+    );
+}
@@ -0,0 +1,34 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+/// An app-specific envelope around the GraphQL data.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Envelope<T> {
+    pub request_id: String,
+    pub data: Option<T>,
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "../graphql_client_codegen/src/tests/star_wars_query.graphql",
+    schema_path = "../graphql_client_codegen/src/tests/star_wars_schema.graphql",
+    response_wrapper = "crate::Envelope"
+)]
+pub struct StarWarsQuery;
+
+#[test]
+fn responses_are_deserialized_to_the_wrapper() {
+    let body =
+        r#"{"requestId": "a1b2", "data": {"hero": {"__typename": "Droid", "name": "R2-D2"}}}"#;
+
+    let envelope: Envelope<star_wars_query::ResponseData> =
+        StarWarsQuery::from_response_str(body).unwrap();
+
+    assert_eq!(envelope.request_id, "a1b2");
+    assert_eq!(envelope.data.unwrap().hero.unwrap().name, "R2-D2");
+}
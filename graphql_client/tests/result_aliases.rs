@@ -0,0 +1,50 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/response_trait/viewer_query.graphql",
+    schema_path = "tests/response_trait/schema.graphql",
+    result_aliases = "true"
+)]
+pub struct ViewerQuery;
+
+pub mod wrapped {
+    use graphql_client::GraphQLQuery;
+
+    /// An app-specific envelope around the GraphQL data.
+    #[derive(Debug, Deserialize)]
+    pub struct Envelope<T> {
+        pub data: Option<T>,
+    }
+
+    // The wrapper path is relative, and resolved from this module.
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/response_trait/count_query.graphql",
+        schema_path = "tests/response_trait/schema.graphql",
+        response_wrapper = "Envelope",
+        result_aliases = "true"
+    )]
+    pub struct CountQuery;
+}
+
+#[test]
+fn the_result_alias_names_the_response() {
+    let result: viewer_query::ViewerQueryResult =
+        ViewerQuery::from_response_str(r#"{"data": {"viewer": {"login": "tomhoule"}}}"#).unwrap();
+
+    assert_eq!(result.data.unwrap().viewer.login, "tomhoule");
+}
+
+#[test]
+fn the_result_alias_follows_the_response_wrapper() {
+    let result: wrapped::count_query::CountQueryResult =
+        wrapped::CountQuery::from_response_str(r#"{"data": {"repositoryCount": 3}}"#).unwrap();
+
+    assert_eq!(result.data.unwrap().repository_count, 3);
+}
@@ -0,0 +1,53 @@
+// Run with `cargo test --features rkyv`.
+#![cfg(feature = "rkyv")]
+
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate rkyv;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use rkyv::Deserialize;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/rkyv/query.graphql",
+    schema_path = "tests/rkyv/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    rkyv = "true"
+)]
+pub struct HeroQuery;
+
+#[test]
+fn responses_round_trip_through_an_archive() {
+    let response: hero_query::ResponseData = serde_json::from_value(json!({
+        "hero": {
+            "__typename": "Human",
+            "id": "1000",
+            "name": "Luke Skywalker",
+            "height": 1.72,
+            "appearsIn": ["NEWHOPE", "EMPIRE", "PHANTOM_MENACE"],
+        },
+    }))
+    .unwrap();
+
+    let bytes = rkyv::to_bytes::<_, 256>(&response).unwrap();
+    let archived = unsafe { rkyv::archived_root::<hero_query::ResponseData>(&bytes[..]) };
+
+    let hero = archived.hero.as_ref().unwrap();
+    assert_eq!(hero.name, "Luke Skywalker");
+    match &hero.on {
+        hero_query::ArchivedHeroQueryHeroOn::Human(human) => {
+            assert_eq!(human.height.as_ref(), Some(&1.72));
+            assert_eq!(human.appears_in.len(), 3);
+        }
+        _ => panic!("expected a Human"),
+    }
+
+    let deserialized: hero_query::ResponseData =
+        archived.deserialize(&mut rkyv::Infallible).unwrap();
+    assert_eq!(deserialized, response);
+}
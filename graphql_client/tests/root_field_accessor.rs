@@ -0,0 +1,37 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/root_field_accessor/query.graphql",
+    schema_path = "tests/root_field_accessor/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    root_field_accessor = "true"
+)]
+pub struct ViewerQuery;
+
+const RESPONSE: &str = r#"{"me": {"id": "1", "name": "Aino"}}"#;
+
+#[test]
+fn root_field_returns_the_single_root_field() {
+    let response: viewer_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    assert_eq!(response.root_field().name, Some("Aino".to_string()));
+}
+
+#[test]
+fn into_root_field_consumes_the_response_data() {
+    let response: viewer_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    assert_eq!(
+        response.into_root_field(),
+        viewer_query::ViewerQueryMe {
+            id: "1".to_string(),
+            name: Some("Aino".to_string()),
+        }
+    );
+}
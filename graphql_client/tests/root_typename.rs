@@ -0,0 +1,51 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/root_typename/query.graphql",
+    schema_path = "tests/response_trait/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    root_field_accessor = "true"
+)]
+pub struct TypenameQuery;
+
+#[test]
+fn the_root_typename_is_the_only_field() {
+    let response: typename_query::ResponseData =
+        serde_json::from_str(r#"{"__typename": "Query"}"#).unwrap();
+
+    assert_eq!(
+        response,
+        typename_query::ResponseData {
+            typename: "Query".to_string(),
+        }
+    );
+    assert_eq!(response.root_field(), "Query");
+    assert_eq!(response.into_root_field(), "Query");
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/root_typename/anonymous_query.graphql",
+    schema_path = "tests/response_trait/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct AnonymousTypenameQuery;
+
+#[test]
+fn the_root_typename_of_an_anonymous_operation() {
+    let response: anonymous_typename_query::ResponseData =
+        serde_json::from_str(r#"{"__typename": "Query"}"#).unwrap();
+
+    assert_eq!(response.typename, "Query");
+    assert_eq!(
+        anonymous_typename_query::QUERY,
+        "query AnonymousTypenameQuery {\n  __typename\n}\n"
+    );
+}
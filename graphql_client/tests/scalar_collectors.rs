@@ -0,0 +1,54 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+type DateTime = String;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/scalar_collectors/query.graphql",
+    schema_path = "tests/scalar_collectors/schema.graphql",
+    scalar_collectors = "true"
+)]
+pub struct ScalarCollectorsQuery;
+
+fn response() -> scalar_collectors_query::ResponseData {
+    serde_json::from_value(json!({
+        "me": {
+            "id": "u1",
+            "createdAt": "2019-01-01T00:00:00Z",
+            "friends": [
+                { "id": "u2", "name": "Leia" },
+                { "id": "u3", "name": null }
+            ]
+        },
+        "search": [
+            { "__typename": "Post", "id": "p1", "title": "GraphQL in Rust" },
+            { "__typename": "User", "id": "u4" }
+        ],
+        "node": { "__typename": "Post", "id": "p2", "publishedAt": "2019-02-01T00:00:00Z" }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn all_id_values_are_collected() {
+    let response = response();
+
+    assert_eq!(response.all_id(), vec!["u1", "u2", "u3", "p1", "u4", "p2"]);
+}
+
+#[test]
+fn values_are_collected_per_scalar_type() {
+    let response = response();
+
+    assert_eq!(
+        response.all_date_time(),
+        vec!["2019-01-01T00:00:00Z", "2019-02-01T00:00:00Z"]
+    );
+    assert_eq!(response.all_string(), vec!["Leia", "GraphQL in Rust"]);
+}
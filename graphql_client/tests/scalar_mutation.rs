@@ -0,0 +1,33 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/scalar_mutation/mutation.graphql",
+    schema_path = "tests/scalar_mutation/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct DeleteUser;
+
+#[test]
+fn scalar_root_fields_are_deserialized_as_the_scalar_type() {
+    let response_data: delete_user::ResponseData =
+        serde_json::from_str(r#"{"deleteUser": true, "purged": 3}"#).unwrap();
+
+    assert_eq!(
+        response_data,
+        delete_user::ResponseData {
+            delete_user: Some(true),
+            purged: 3,
+        }
+    );
+
+    let response_data: delete_user::ResponseData =
+        serde_json::from_str(r#"{"deleteUser": null, "purged": 0}"#).unwrap();
+
+    assert_eq!(response_data.delete_user, None);
+}
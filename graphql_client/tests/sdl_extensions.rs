@@ -0,0 +1,39 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/sdl_extensions/query.graphql",
+    schema_path = "tests/sdl_extensions/schema.graphqls"
+)]
+pub struct GreetingQuery;
+
+pub mod sdl {
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/sdl_extensions/query.graphql",
+        schema_path = "tests/sdl_extensions/schema.sdl"
+    )]
+    pub struct GreetingQuery;
+}
+
+#[test]
+fn graphqls_schemas_are_read_as_sdl() {
+    let data: greeting_query::ResponseData =
+        serde_json::from_value(json!({"greeting": "hello"})).unwrap();
+
+    assert_eq!(data.greeting, "hello");
+}
+
+#[test]
+fn sdl_schemas_are_read_as_sdl() {
+    let data: sdl::greeting_query::ResponseData =
+        serde_json::from_value(json!({"greeting": "hello"})).unwrap();
+
+    assert_eq!(data.greeting, "hello");
+}
@@ -0,0 +1,60 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+const RESPONSE: &str =
+    r#"{"viewer": {"login": "tomhoule", "email": "tom@example.com", "apiToken": "hunter2"}}"#;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/sensitive_fields/query.graphql",
+    schema_path = "tests/sensitive_fields/schema.graphql",
+    response_derives = "Serialize"
+)]
+pub struct ViewerQuery;
+
+pub mod skipped {
+    use graphql_client::GraphQLQuery;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/sensitive_fields/query.graphql",
+        schema_path = "tests/sensitive_fields/schema.graphql",
+        response_derives = "Serialize",
+        sensitive_fields = "skip"
+    )]
+    pub struct ViewerQuery;
+}
+
+#[test]
+fn sensitive_fields_are_redacted_when_serialized() {
+    let response: viewer_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    assert_eq!(response.viewer.api_token, "hunter2");
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        serde_json::json!({
+            "viewer": {"login": "tomhoule", "email": "[redacted]", "apiToken": "[redacted]"}
+        })
+    );
+}
+
+#[test]
+fn sensitive_fields_can_be_skipped_when_serialized() {
+    let response: skipped::viewer_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    assert_eq!(response.viewer.email.as_ref().unwrap(), "tom@example.com");
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        serde_json::json!({"viewer": {"login": "tomhoule"}})
+    );
+}
+
+#[test]
+fn the_sensitive_directive_is_not_sent_to_the_server() {
+    assert!(!viewer_query::QUERY.contains("@sensitive"));
+}
@@ -0,0 +1,50 @@
+// No `serde` or `serde_derive` here: serde is only available under the name `my_serde`. Cargo does not allow a second name for the same dependency, so it is renamed here.
+#[macro_use]
+extern crate graphql_client;
+extern crate serde as my_serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/serde_crate/query.graphql",
+    schema_path = "tests/serde_crate/schema.graphql",
+    response_derives = "Debug, PartialEq, Serialize",
+    serde_crate = "my_serde"
+)]
+pub struct SearchQuery;
+
+#[test]
+fn variables_serialize_with_the_renamed_crate() {
+    let variables = search_query::Variables {
+        filter: search_query::SearchFilter {
+            text: "rust".to_string(),
+            genre: Some(search_query::Genre::POETRY),
+        },
+    };
+
+    assert_eq!(
+        serde_json::to_value(variables).unwrap(),
+        json!({"filter": {"text": "rust", "genre": "POETRY"}})
+    );
+}
+
+#[test]
+fn response_round_trips_with_the_renamed_crate() {
+    let response = json!({
+        "search": [
+            {"__typename": "Book", "title": "Ariel", "genre": "POETRY"},
+            {"__typename": "Author", "name": "Sylvia Plath"}
+        ]
+    });
+    let response_data: search_query::ResponseData =
+        serde_json::from_value(response.clone()).unwrap();
+
+    assert_eq!(
+        response_data.search[1],
+        search_query::SearchQuerySearch::Author(search_query::SearchQuerySearchOnAuthor {
+            name: "Sylvia Plath".to_string(),
+        })
+    );
+    assert_eq!(serde_json::to_value(&response_data).unwrap(), response);
+}
@@ -0,0 +1,44 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/shorthand_query/shorthand_query.graphql",
+    schema_path = "tests/shorthand_query/schema.graphql"
+)]
+pub struct MeQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/shorthand_query/anonymous_query.graphql",
+    schema_path = "tests/shorthand_query/schema.graphql"
+)]
+pub struct UserQuery;
+
+#[test]
+fn shorthand_query_is_named_after_the_struct() {
+    let body = MeQuery::build_query(me_query::Variables);
+
+    assert_eq!(body.operation_name, "MeQuery");
+    assert!(body.query.starts_with("query MeQuery {"));
+
+    let response: me_query::ResponseData =
+        serde_json::from_value(json!({"me": {"id": "1"}})).unwrap();
+    assert_eq!(response.me.id, "1");
+}
+
+#[test]
+fn anonymous_query_is_named_after_the_struct() {
+    let body = UserQuery::build_query(user_query::Variables {
+        id: "1".to_string(),
+    });
+
+    assert_eq!(body.operation_name, "UserQuery");
+    assert!(body.query.starts_with("query UserQuery($id: ID!) {"));
+}
@@ -0,0 +1,28 @@
+// Run with `cargo test --features sqlx`.
+#![cfg(feature = "sqlx")]
+
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate sqlx;
+
+use sqlx::postgres::PgRow;
+use sqlx::FromRow;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/sqlx_from_row/query.graphql",
+    schema_path = "tests/sqlx_from_row/schema.graphql",
+    response_derives = "Debug",
+    sqlx_from_row = "true"
+)]
+pub struct UsersQuery;
+
+fn assert_from_postgres_rows<T: for<'r> FromRow<'r, PgRow>>() {}
+
+#[test]
+fn flat_response_structs_can_be_read_from_rows() {
+    assert_from_postgres_rows::<users_query::UsersQueryUsers>();
+}
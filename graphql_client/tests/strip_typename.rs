@@ -0,0 +1,36 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/strip_typename/query.graphql",
+    schema_path = "tests/strip_typename/schema.graphql",
+    response_derives = "Debug, Serialize",
+    strip_typename = "true"
+)]
+pub struct StripTypenameQuery;
+
+#[test]
+fn typename_is_skipped_when_serializing_objects() {
+    let response = json!({
+        "viewer": {"__typename": "User", "name": "Oona"},
+        "node": {"__typename": "Repository", "id": "repo-1", "stars": 12},
+    });
+
+    let response: strip_typename_query::ResponseData = serde_json::from_value(response).unwrap();
+    assert_eq!(response.viewer.typename, "User");
+
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        json!({
+            "viewer": {"name": "Oona"},
+            // The tag of the interface enum cannot be stripped.
+            "node": {"__typename": "Repository", "id": "repo-1", "stars": 12},
+        })
+    );
+}
@@ -0,0 +1,70 @@
+// Run with `cargo test --features subscription_stream`.
+#![cfg(feature = "subscription_stream")]
+
+extern crate futures;
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+use futures::{stream, Stream};
+use graphql_client::subscription_stream::decode_responses;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/operation_kind/operations.graphql",
+    schema_path = "tests/operation_kind/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct WatchCounter;
+
+#[test]
+fn payloads_are_decoded_into_responses() {
+    let payloads = stream::iter_ok::<_, ()>(vec![
+        r#"{"data": {"counterChanged": 1}}"#,
+        r#"{"data": {"counterChanged": "two"}}"#,
+        r#"{"data": {"counterChanged": 3}}"#,
+    ]);
+
+    let responses: Vec<_> = decode_responses::<WatchCounter, _>(payloads)
+        .wait()
+        .map(|item| item.unwrap())
+        .collect();
+
+    assert_eq!(responses.len(), 3);
+    assert_eq!(
+        responses[0]
+            .as_ref()
+            .unwrap()
+            .data
+            .as_ref()
+            .unwrap()
+            .counter_changed,
+        1
+    );
+    assert!(responses[1].is_err());
+    assert_eq!(
+        responses[2]
+            .as_ref()
+            .unwrap()
+            .data
+            .as_ref()
+            .unwrap()
+            .counter_changed,
+        3
+    );
+}
+
+#[test]
+fn errors_of_the_payload_stream_are_passed_through() {
+    let payloads = stream::iter_result(vec![
+        Ok(r#"{"data": {"counterChanged": 1}}"#),
+        Err("closed"),
+    ]);
+    let mut responses = decode_responses::<WatchCounter, _>(payloads).wait();
+
+    assert!(responses.next().unwrap().unwrap().is_ok());
+    assert_eq!(responses.next().unwrap().unwrap_err(), "closed");
+    assert!(responses.next().is_none());
+}
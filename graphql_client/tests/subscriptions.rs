@@ -7,7 +7,7 @@ extern crate serde_json;
 
 const RESPONSE: &str = include_str!("subscription/subscription_query_response.json");
 
-// If you uncomment this, it will not compile because the query is not valid. We need to investigate how we can make this a real test.
+// If you uncomment this, it will not compile because the query is not valid. The error itself is tested in graphql_client_codegen.
 //
 // #[derive(GraphQLQuery)]
 // #[graphql(
@@ -0,0 +1,30 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/tag_filter/query.graphql",
+    schema_path = "tests/tag_filter/schema.graphql",
+    include_tag = "public"
+)]
+pub struct ShopQuery;
+
+#[test]
+fn fields_tagged_for_the_audience_are_generated() {
+    let response: shop_query::ResponseData = serde_json::from_value(json!({
+        "shop": {
+            "name": "Corner Shop",
+            "owner": { "name": "Ada", "email": null }
+        }
+    }))
+    .unwrap();
+
+    let shop = response.shop.unwrap();
+    assert_eq!(shop.name, "Corner Shop");
+    assert_eq!(shop.owner.unwrap().name, "Ada");
+}
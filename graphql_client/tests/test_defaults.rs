@@ -0,0 +1,53 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/test_defaults/query.graphql",
+    schema_path = "tests/test_defaults/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    test_defaults = "true"
+)]
+pub struct DefaultsQuery;
+
+use defaults_query::*;
+
+#[test]
+fn response_data_can_be_built_from_defaults() {
+    let response = ResponseData {
+        viewer: DefaultsQueryViewer {
+            name: "Aino".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    assert_eq!(response.viewer.name, "Aino");
+    assert_eq!(response.viewer.age, 0);
+    assert_eq!(response.viewer.score, None);
+    assert!(!response.viewer.verified);
+    assert_eq!(response.viewer.status, Status::ACTIVE);
+    assert!(response.viewer.tags.is_empty());
+    assert_eq!(response.node, None);
+    assert!(response.search.is_empty());
+}
+
+#[test]
+fn interface_and_union_enums_default_to_their_first_variant() {
+    let node = DefaultsQueryNode::default();
+    assert_eq!(node.id, "");
+    assert_eq!(
+        node.on,
+        DefaultsQueryNodeOn::Bot(DefaultsQueryNodeOnBot { owner: None })
+    );
+
+    assert_eq!(
+        DefaultsQuerySearch::default(),
+        DefaultsQuerySearch::User(DefaultsQuerySearchOnUser {
+            name: String::new()
+        })
+    );
+}
@@ -0,0 +1,33 @@
+#[macro_use]
+extern crate graphql_client;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+
+// `Debug` and `PartialEq` are derived when the crate is compiled for tests only.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/more_derives/schema.graphql",
+    query_path = "tests/more_derives/query.graphql",
+    test_only_derives = "Debug, PartialEq"
+)]
+pub struct MoreDerives;
+
+#[test]
+fn test_only_derives_are_available_in_tests() {
+    let response_data = more_derives::ResponseData {
+        current_user: Some(more_derives::TestCurrentUser {
+            id: Some("abcd".to_owned()),
+            name: Some("Angela Merkel".to_owned()),
+        }),
+    };
+
+    assert_eq!(
+        format!("{:?}", response_data),
+        "ResponseData { current_user: Some(TestCurrentUser { name: Some(\"Angela Merkel\"), id: Some(\"abcd\") }) }"
+    );
+    assert_ne!(
+        response_data,
+        more_derives::ResponseData { current_user: None }
+    );
+}
@@ -0,0 +1,53 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/transforms/query.graphql",
+    schema_path = "tests/transforms/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct TransformsQuery;
+
+const RESPONSE: &str = r#"{"people": [
+    {"name": "  Oona ", "nickname": "\tOo\n", "tags": ["Admin", "BETA"], "email": " oona@example.com "},
+    {"name": "Eero", "nickname": null, "tags": [], "email": null}
+]}"#;
+
+#[test]
+fn trim_directive_trims_string_fields() {
+    let response: transforms_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    assert_eq!(response.people[0].name, "Oona");
+    assert_eq!(response.people[0].nickname, Some("Oo".to_string()));
+    assert_eq!(response.people[1].name, "Eero");
+    assert_eq!(response.people[1].nickname, None);
+}
+
+#[test]
+fn lowercase_directive_applies_to_list_items() {
+    let response: transforms_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    assert_eq!(response.people[0].tags, vec!["admin", "beta"]);
+}
+
+#[test]
+fn fields_without_directives_are_untouched() {
+    let response: transforms_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    assert_eq!(
+        response.people[0].email,
+        Some(" oona@example.com ".to_string())
+    );
+}
+
+#[test]
+fn transform_directives_are_not_sent_to_the_server() {
+    assert!(!transforms_query::QUERY.contains("@trim"));
+    assert!(!transforms_query::QUERY.contains("@lowercase"));
+    assert!(transforms_query::QUERY.contains("nickname"));
+}
@@ -0,0 +1,57 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/type_introspection/query.graphql",
+    schema_path = "tests/type_introspection/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct TypeFields;
+
+const RESPONSE: &str = r#"
+{
+  "__type": {
+    "name": "User",
+    "kind": "OBJECT",
+    "fields": [
+      { "name": "id", "type": { "kind": "NON_NULL", "name": null, "ofType": { "kind": "SCALAR", "name": "ID" } } },
+      { "name": "name", "type": { "kind": "SCALAR", "name": "String", "ofType": null } }
+    ]
+  }
+}
+"#;
+
+#[test]
+fn type_meta_field_responses_are_typed() {
+    let response: type_fields::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+    let user_type = response.type_.unwrap();
+
+    assert_eq!(user_type.name, Some("User".to_string()));
+    assert_eq!(user_type.kind, type_fields::__TypeKind::OBJECT);
+
+    let fields = user_type.fields.unwrap();
+    assert_eq!(fields[0].name, "id");
+    assert_eq!(fields[0].type_.kind, type_fields::__TypeKind::NON_NULL);
+    assert_eq!(
+        fields[0].type_.of_type.as_ref().unwrap().kind,
+        type_fields::__TypeKind::SCALAR
+    );
+    assert_eq!(fields[1].type_.of_type, None);
+}
+
+#[test]
+fn type_meta_field_takes_the_name_variable() {
+    let variables = type_fields::Variables {
+        name: "User".to_string(),
+    };
+
+    assert_eq!(
+        serde_json::to_value(variables).unwrap(),
+        serde_json::json!({ "name": "User" })
+    );
+}
@@ -41,3 +41,129 @@ fn union_query_deserialization() {
 
     assert_eq!(response_data.names.map(|names| names.len()), Some(4));
 }
+
+#[test]
+fn union_typename_is_read_from_the_variant() {
+    let response_data: union_query::ResponseData = serde_json::from_str(RESPONSE).unwrap();
+
+    let typenames: Vec<&str> = response_data
+        .names
+        .unwrap()
+        .iter()
+        .map(|name| name.typename())
+        .collect();
+
+    assert_eq!(typenames, ["Person", "Dog", "Organization", "Dog"]);
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/unions/merged_fragments_query.graphql",
+    schema_path = "tests/unions/union_schema.graphql",
+    response_derives = "PartialEq, Debug"
+)]
+pub struct MergedFragmentsQuery;
+
+#[test]
+fn fragments_on_the_same_union_member_are_merged() {
+    use merged_fragments_query::*;
+
+    let response_data: ResponseData = serde_json::from_str(
+        r#"{"names": [
+            {"__typename": "Person", "firstName": "Audrey", "lastName": "Lorde", "birthday": "1934-02-18"},
+            {"__typename": "Dog", "name": "Laïka"},
+            {"__typename": "Organization"}
+        ]}"#,
+    )
+    .unwrap();
+
+    let expected = ResponseData {
+        names: Some(vec![
+            MergedFragmentsQueryNames::Person(MergedFragmentsQueryNamesOnPerson {
+                first_name: "Audrey".to_string(),
+                last_name: Some("Lorde".to_string()),
+                birthday: Some("1934-02-18".to_string()),
+            }),
+            MergedFragmentsQueryNames::Dog(MergedFragmentsQueryNamesOnDog {
+                name: "Laïka".to_string(),
+            }),
+            MergedFragmentsQueryNames::Organization,
+        ]),
+    };
+
+    assert_eq!(response_data, expected);
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/unions/adjacently_tagged_query.graphql",
+    schema_path = "tests/unions/union_schema.graphql",
+    response_derives = "PartialEq, Debug"
+)]
+pub struct AdjacentlyTaggedQuery;
+
+#[test]
+fn adjacently_tagged_union_deserialization() {
+    use adjacently_tagged_query::*;
+
+    let response_data: ResponseData = serde_json::from_str(
+        r#"{"names": [
+            {"type": "Person", "data": {"firstName": "Audrey"}},
+            {"type": "Dog", "data": {"name": "Laïka"}},
+            {"type": "Organization"}
+        ]}"#,
+    )
+    .unwrap();
+
+    let expected = ResponseData {
+        names: Some(vec![
+            AdjacentlyTaggedQueryNames::Person(AdjacentlyTaggedQueryNamesOnPerson {
+                first_name: "Audrey".to_string(),
+            }),
+            AdjacentlyTaggedQueryNames::Dog(AdjacentlyTaggedQueryNamesOnDog {
+                name: "Laïka".to_string(),
+            }),
+            AdjacentlyTaggedQueryNames::Organization,
+        ]),
+    };
+
+    assert_eq!(response_data, expected);
+    assert!(!adjacently_tagged_query::QUERY.contains("@adjacentlyTagged"));
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/unions/interface_member_query.graphql",
+    schema_path = "tests/unions/interface_member_schema.graphql",
+    response_derives = "PartialEq, Debug"
+)]
+pub struct SearchQuery;
+
+#[test]
+fn interface_members_of_unions_are_expanded_into_their_implementations() {
+    use search_query::*;
+
+    let response_data: ResponseData = serde_json::from_str(
+        r#"{"search": [
+            {"__typename": "User", "name": "Audre", "login": "alorde"},
+            {"__typename": "Organization", "name": "Mozilla"},
+            {"__typename": "Bot"}
+        ]}"#,
+    )
+    .unwrap();
+
+    let expected = ResponseData {
+        search: vec![
+            SearchQuerySearch::User(SearchQuerySearchOnUser {
+                name: "Audre".to_string(),
+                login: "alorde".to_string(),
+            }),
+            SearchQuerySearch::Organization(SearchQuerySearchOnOrganization {
+                name: "Mozilla".to_string(),
+            }),
+            SearchQuerySearch::Bot,
+        ],
+    };
+
+    assert_eq!(response_data, expected);
+}
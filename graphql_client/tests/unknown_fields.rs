@@ -0,0 +1,71 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/unknown_fields/query.graphql",
+    schema_path = "tests/unknown_fields/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    unknown_fields = "true"
+)]
+pub struct PostQuery;
+
+#[test]
+fn unknown_fields_land_in_the_extra_map() {
+    let response: post_query::ResponseData = serde_json::from_value(json!({
+        "post": {
+            "title": "Hello",
+            "views": 12,
+            "author": { "name": "Tom", "avatar": { "url": "tom.png" } }
+        },
+        "node": null,
+        "viewer": "anonymous"
+    }))
+    .unwrap();
+
+    assert_eq!(response.extra["viewer"], json!("anonymous"));
+    let post = response.post.unwrap();
+    assert_eq!(post.title, "Hello");
+    assert_eq!(post.extra.len(), 1);
+    assert_eq!(post.extra["views"], json!(12));
+    assert_eq!(post.author.extra["avatar"], json!({ "url": "tom.png" }));
+}
+
+#[test]
+fn the_extra_map_is_empty_without_unknown_fields() {
+    let response: post_query::ResponseData = serde_json::from_value(json!({
+        "post": { "title": "Hello", "author": { "name": "Tom" } },
+        "node": null
+    }))
+    .unwrap();
+
+    assert!(response.extra.is_empty());
+    let post = response.post.unwrap();
+    assert!(post.extra.is_empty());
+    assert!(post.author.extra.is_empty());
+}
+
+#[test]
+fn interface_variants_collect_the_unknown_fields() {
+    let response: post_query::ResponseData = serde_json::from_value(json!({
+        "post": null,
+        "node": { "__typename": "Comment", "id": "1", "body": "Nice", "likes": 3 }
+    }))
+    .unwrap();
+
+    let node = response.node.unwrap();
+    assert_eq!(node.id, "1");
+    match node.on {
+        post_query::PostQueryNodeOn::Comment(comment) => {
+            assert_eq!(comment.body, "Nice");
+            assert_eq!(comment.extra.len(), 1);
+            assert_eq!(comment.extra["likes"], json!(3));
+        }
+        other => panic!("unexpected variant: {:?}", other),
+    }
+}
@@ -0,0 +1,53 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/variables_as_pairs/query.graphql",
+    schema_path = "tests/variables_as_pairs/schema.graphql",
+    variables_as_pairs = "true"
+)]
+pub struct SearchQuery;
+
+#[test]
+fn each_variable_is_serialized_on_its_own() {
+    let variables = search_query::Variables {
+        text: "graphql".to_string(),
+        limit: Some(10),
+        filter: Some(search_query::Filter {
+            language: Some("rust".to_string()),
+            min_stars: None,
+        }),
+    };
+
+    assert_eq!(
+        variables.variables_as_pairs(),
+        vec![
+            ("text".to_string(), json!("graphql")),
+            ("limit".to_string(), json!(10)),
+            (
+                "filter".to_string(),
+                json!({"language": "rust", "minStars": null})
+            ),
+        ]
+    );
+}
+
+#[test]
+fn unset_optional_variables_are_left_out() {
+    let variables = search_query::Variables {
+        text: "graphql".to_string(),
+        limit: None,
+        filter: None,
+    };
+
+    assert_eq!(
+        variables.variables_as_pairs(),
+        vec![("text".to_string(), json!("graphql"))]
+    );
+}
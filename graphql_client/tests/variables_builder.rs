@@ -0,0 +1,48 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/variables_builder/query.graphql",
+    schema_path = "tests/variables_builder/schema.graphql",
+    variables_builder = "true"
+)]
+pub struct ReviewsQuery;
+
+#[test]
+fn variables_are_built_once_the_required_ones_are_set() {
+    let variables = reviews_query::Variables::builder()
+        .min_rating(4)
+        .order(Some(reviews_query::Order::DESC))
+        .product_id("42".to_string())
+        .build();
+
+    assert_eq!(variables.product_id, "42");
+    assert_eq!(variables.min_rating, 4);
+    match variables.order {
+        Some(reviews_query::Order::DESC) => (),
+        _ => panic!("the order is not set"),
+    }
+}
+
+#[test]
+fn optional_variables_start_unset_or_with_their_default() {
+    let variables = reviews_query::Variables::builder()
+        .product_id("42".to_string())
+        .min_rating(4)
+        .build();
+
+    assert!(variables.order.is_none());
+    assert_eq!(variables.first, 10);
+
+    let variables = reviews_query::Variables::builder()
+        .product_id("42".to_string())
+        .first(3)
+        .min_rating(4)
+        .build();
+
+    assert_eq!(variables.first, 3);
+}
@@ -0,0 +1,35 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/variables_builder/query.graphql",
+    schema_path = "tests/variables_builder/schema.graphql",
+    variables_constructor = "true"
+)]
+pub struct ReviewsQuery;
+
+#[test]
+fn the_constructor_takes_the_required_variables_in_order() {
+    let variables = reviews_query::Variables::new("42".to_string(), 4);
+
+    assert_eq!(variables.product_id, "42");
+    assert_eq!(variables.min_rating, 4);
+    assert!(variables.order.is_none());
+    assert_eq!(variables.first, 10);
+}
+
+#[test]
+fn constructed_variables_serialize_with_their_names() {
+    let body = ReviewsQuery::build_query(reviews_query::Variables::new("42".to_string(), 4));
+
+    assert_eq!(
+        serde_json::to_value(&body.variables).unwrap(),
+        serde_json::json!({"productId": "42", "minRating": 4, "order": null, "first": 10})
+    );
+}
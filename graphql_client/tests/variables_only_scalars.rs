@@ -0,0 +1,37 @@
+#[macro_use]
+extern crate graphql_client;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate serde_json;
+
+pub mod network {
+    pub type Address = ::std::net::Ipv4Addr;
+    pub type Subnet = String;
+}
+
+// NetworkAddress and Subnet are only used by the variables, never in the response.
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/variables_only_scalars/query.graphql",
+    schema_path = "tests/variables_only_scalars/schema.graphql",
+    scalar_paths = "NetworkAddress = crate::network::Address, Subnet = crate::network::Subnet"
+)]
+pub struct HostnameQuery;
+
+#[test]
+fn scalars_used_only_by_variables_are_mapped() {
+    let address: network::Address = "127.0.1.2".parse().unwrap();
+    let variables = hostname_query::Variables {
+        address,
+        filter: hostname_query::HostFilter {
+            subnet: "10.0.0.0/8".to_string(),
+        },
+    };
+
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        json!({"address": "127.0.1.2", "filter": {"subnet": "10.0.0.0/8"}})
+    );
+}
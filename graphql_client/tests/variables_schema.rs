@@ -0,0 +1,34 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/variables_schema/query.graphql",
+    schema_path = "tests/variables_schema/schema.graphql",
+    variables_schema = "true"
+)]
+pub struct Users;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/variables_schema/query.graphql",
+    schema_path = "tests/variables_schema/schema.graphql",
+    variables_schema = "true"
+)]
+pub struct Count;
+
+#[test]
+fn variables_schema_matches_the_declared_variables() {
+    assert_eq!(
+        users::VARIABLES_SCHEMA,
+        r#"($ids: [ID!]!, $limit: Int = 10, $order: Order = DESC, $nameLike: String = "a%")"#
+    );
+}
+
+#[test]
+fn variables_schema_is_empty_without_variables() {
+    assert_eq!(count::VARIABLES_SCHEMA, "");
+}
@@ -0,0 +1,56 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use std::convert::TryFrom;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/variables_try_from/query.graphql",
+    schema_path = "tests/variables_try_from/schema.graphql",
+    response_derives = "Debug",
+    variables_try_from = "true"
+)]
+pub struct PostsQuery;
+
+use posts_query::*;
+
+#[test]
+fn variables_are_converted_from_a_json_value() {
+    let variables = Variables::try_from(json!({
+        "author": "ursula",
+        "order": "DESC",
+        "page": {"first": 10},
+    }))
+    .unwrap();
+
+    assert_eq!(variables.author, "ursula");
+    match variables.order {
+        Some(Order::DESC) => (),
+        other => panic!("unexpected order: {:?}", other),
+    }
+    let page = variables.page.unwrap();
+    assert_eq!(page.first, 10);
+    assert_eq!(page.after, None);
+}
+
+#[test]
+fn a_missing_required_variable_is_an_error() {
+    let err = Variables::try_from(json!({"order": "ASC"})).unwrap_err();
+
+    assert_eq!(err.to_string(), "missing field `author`");
+}
+
+#[test]
+fn a_mistyped_variable_is_an_error() {
+    let err =
+        Variables::try_from(json!({"author": "ursula", "page": {"first": "ten"}})).unwrap_err();
+
+    assert!(err
+        .to_string()
+        .starts_with("invalid type: string \"ten\", expected i64"));
+}
@@ -0,0 +1,103 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+const UNION_RESPONSE: &str = include_str!("unions/union_query_response.json");
+const INTERFACE_RESPONSE: &str = include_str!("interfaces/interface_response.json");
+const INTERFACE_NOT_ON_EVERYTHING_RESPONSE: &str =
+    include_str!("interfaces/interface_response_not_on_everything.json");
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/unions/union_query.graphql",
+    schema_path = "tests/unions/union_schema.graphql",
+    response_derives = "PartialEq, Debug",
+    variant_accessors = "true"
+)]
+pub struct UnionQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/interfaces/interface_query.graphql",
+    schema_path = "tests/interfaces/interface_schema.graphql",
+    response_derives = "PartialEq, Debug",
+    variant_accessors = "true"
+)]
+pub struct InterfaceQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/interfaces/interface_not_on_everything_query.graphql",
+    schema_path = "tests/interfaces/interface_schema.graphql",
+    response_derives = "PartialEq, Debug",
+    variant_accessors = "true"
+)]
+pub struct InterfaceNotOnEverythingQuery;
+
+#[test]
+fn union_variant_accessors() {
+    let response_data: union_query::ResponseData = serde_json::from_str(UNION_RESPONSE).unwrap();
+    let names = response_data.names.unwrap();
+
+    assert!(names[0].is_person());
+    assert!(!names[0].is_dog());
+    assert_eq!(
+        names[0].as_person().map(|person| person.first_name.as_str()),
+        Some("Audrey")
+    );
+    assert_eq!(names[0].as_dog(), None);
+
+    assert!(names[1].is_dog());
+    assert_eq!(
+        names[1].as_dog().map(|dog| dog.name.as_str()),
+        Some("Laïka")
+    );
+
+    assert!(names[2].is_organization());
+    assert!(names[2].as_person().is_none());
+
+    let dogs = names.iter().filter(|name| name.is_dog()).count();
+    assert_eq!(dogs, 2);
+}
+
+#[test]
+fn interface_variant_accessors() {
+    let response_data: interface_query::ResponseData =
+        serde_json::from_str(INTERFACE_RESPONSE).unwrap();
+    let everything = response_data.everything.unwrap();
+
+    assert!(everything[0].on.is_person());
+    assert_eq!(
+        everything[0]
+            .on
+            .as_person()
+            .and_then(|person| person.birthday.as_ref())
+            .map(|birthday| birthday.as_str()),
+        Some("1934-02-18")
+    );
+    assert!(everything[0].on.as_dog().is_none());
+
+    assert!(everything[1].on.is_dog());
+    assert_eq!(
+        everything[1].on.as_dog().map(|dog| dog.is_good_dog),
+        Some(true)
+    );
+
+    assert!(everything[2].on.is_organization());
+    assert!(!everything[2].on.is_dog());
+}
+
+#[test]
+fn interface_variant_accessors_on_variants_without_selection() {
+    let response_data: interface_not_on_everything_query::ResponseData =
+        serde_json::from_str(INTERFACE_NOT_ON_EVERYTHING_RESPONSE).unwrap();
+    let everything = response_data.everything.unwrap();
+
+    // Dog has no selection in this query, so there is only `is_dog`.
+    assert!(everything[1].on.is_dog());
+    assert!(!everything[1].on.is_person());
+    assert!(everything[0].on.as_person().is_some());
+}
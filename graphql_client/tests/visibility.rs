@@ -0,0 +1,34 @@
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_json;
+
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/visibility/query.graphql",
+    schema_path = "tests/visibility/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    visibility = "pub(crate)"
+)]
+pub(crate) struct MemberQuery;
+
+#[test]
+fn crate_visible_types_are_usable_within_the_crate() {
+    let body = MemberQuery::build_query(member_query::Variables {
+        id: "1".to_string(),
+    });
+    assert_eq!(body.variables.id, "1");
+
+    let response: member_query::ResponseData =
+        serde_json::from_str(r#"{"member": {"name": "Elena", "role": "ADMIN"}}"#).unwrap();
+    assert_eq!(
+        response.member,
+        Some(member_query::MemberQueryMember {
+            name: "Elena".to_string(),
+            role: member_query::Role::ADMIN,
+        })
+    );
+}
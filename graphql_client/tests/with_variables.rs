@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate graphql_client;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "../graphql_client_codegen/src/tests/star_wars_query.graphql",
+    schema_path = "../graphql_client_codegen/src/tests/star_wars_schema.graphql",
+    with_variables = "true"
+)]
+pub struct StarWarsQuery;
+
+#[test]
+fn with_variables_builds_the_query_body_in_a_chain() {
+    let body = StarWarsQuery::new()
+        .with_variables(star_wars_query::Variables {
+            episode_for_hero: star_wars_query::Episode::NEWHOPE,
+        })
+        .build();
+
+    assert_eq!(
+        serde_json::to_value(body).unwrap(),
+        json!({
+            "operationName": "StarWarsQuery",
+            "query": star_wars_query::QUERY,
+            "variables": { "episodeForHero": "NEWHOPE" },
+        })
+    );
+}
+
+#[test]
+fn with_variables_can_replace_the_variables() {
+    let body = StarWarsQuery::new()
+        .with_variables(star_wars_query::Variables {
+            episode_for_hero: star_wars_query::Episode::NEWHOPE,
+        })
+        .with_variables(star_wars_query::Variables {
+            episode_for_hero: star_wars_query::Episode::JEDI,
+        })
+        .build();
+
+    assert_eq!(
+        serde_json::to_value(body.variables).unwrap(),
+        json!({ "episodeForHero": "JEDI" })
+    );
+}
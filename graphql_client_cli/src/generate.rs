@@ -1,8 +1,8 @@
 use failure;
 use graphql_client_codegen::*;
 use std::fs::File;
-use std::io::Write as IoWrite;
-use std::path::PathBuf;
+use std::io::{Read, Write as IoWrite};
+use std::path::{Path, PathBuf};
 use syn;
 
 #[allow(clippy::too_many_arguments)]
@@ -15,6 +15,8 @@ pub fn generate_code(
     deprecation_strategy: &Option<String>,
     no_formatting: bool,
     module_visibility: &Option<String>,
+    shared_variables: bool,
+    emit_type_map: Option<PathBuf>,
     output: &PathBuf,
 ) -> Result<(), failure::Error> {
     let deprecation_strategy = deprecation_strategy.as_ref().map(|s| s.as_str());
@@ -45,9 +47,95 @@ pub fn generate_code(
         additional_derives,
         deprecation_strategy,
         module_visibility,
+        variant_accessors: false,
+        shared_variables,
+        root_field_accessor: false,
+        list_iterators: false,
+        rename_all_camel_case: false,
+        input_patches: false,
+        comment_descriptions: false,
+        with_variables: false,
+        test_defaults: false,
+        enum_all_variants: false,
+        optimistic_responses: false,
+        field_accessors: false,
+        variables_try_from: false,
+        interface_flatten: false,
+        enum_unknowns: EnumUnknowns::default(),
+        rkyv: false,
+        field_json: false,
+        strip_typename: false,
+        visibility: None,
+        transform: None,
+        float_type: FloatType::default(),
+        qualified_paths: false,
+        scalar_paths: None,
+        variables_schema: false,
+        skip_external_fields: false,
+        variables_builder: false,
+        compact_str: false,
+        bevy_reflect: false,
+        variables_as_pairs: false,
+        include_tag: None,
+        scalar_collectors: false,
+        add_typename: false,
+        sqlx_from_row: false,
+        empty_lists: false,
+        unknown_fields: false,
+        serde_crate: None,
+        estimated_size: false,
+        enum_value_methods: None,
+        response_wrapper: None,
+        top_level_derives_only: false,
+        persisted_query_manifest: None,
+        test_only_derives: None,
+        conditional_fields_report: false,
+        field_types: None,
+        response_trait: false,
+        max_identifier_length: None,
+        case_insensitive_enums: false,
+        module_attributes: None,
+        result_aliases: false,
+        sensitive_fields: SensitiveFields::default(),
+        variables_constructor: false,
+        raw_response: false,
+        cache_keys: false,
     };
 
-    let gen = generate_module_token_stream(query_path, schema_path, Some(options))?;
+    // `-` means the schema is piped through stdin.
+    let stdin_schema = if schema_path == Path::new("-") {
+        if query_path == Path::new("-") {
+            Err(format_err!(
+                "The query cannot be read from stdin, only the schema can. Pass the path of the query file."
+            ))?
+        }
+        let mut schema = String::new();
+        ::std::io::stdin().read_to_string(&mut schema)?;
+        Some(schema::Schema::from_json_or_sdl(&schema)?)
+    } else {
+        None
+    };
+    let gen = match &stdin_schema {
+        Some(schema) => generate_module_token_stream_with_schema(
+            query_path.clone(),
+            schema.clone(),
+            Some(options.clone()),
+        )?,
+        None => generate_module_token_stream(
+            query_path.clone(),
+            schema_path.clone(),
+            Some(options.clone()),
+        )?,
+    };
+
+    if let Some(type_map_path) = emit_type_map {
+        let type_map = match stdin_schema {
+            Some(schema) => generate_type_map_with_schema(query_path, schema, options)?,
+            None => generate_type_map(query_path, schema_path, options)?,
+        };
+        let file = File::create(type_map_path)?;
+        ::serde_json::to_writer_pretty(file, &type_map)?;
+    }
 
     let mut file = File::create(output.clone())?;
 
@@ -63,6 +151,90 @@ pub fn generate_code(
     Ok(())
 }
 
+/// Prints the resolved selection tree of the operations in the query file, without generating any code.
+pub fn explain(
+    query_path: PathBuf,
+    schema_path: PathBuf,
+    selected_operation: Option<String>,
+) -> Result<(), failure::Error> {
+    if query_path == Path::new("-") {
+        Err(format_err!(
+            "The query cannot be read from stdin, only the schema can. Pass the path of the query file."
+        ))?
+    }
+
+    // `-` means the schema is piped through stdin.
+    let mut schema = String::new();
+    if schema_path == Path::new("-") {
+        ::std::io::stdin().read_to_string(&mut schema)?;
+    } else {
+        schema = read_file(&schema_path)?;
+    }
+    let schema = schema::Schema::from_json_or_sdl(&schema)?;
+
+    let query = read_file(&query_path)?;
+
+    let explanation = describe::explain_operations(&schema, &query, selected_operation.as_deref())?;
+    print!("{}", explanation);
+
+    Ok(())
+}
+
+/// Generates one module per query document of a graphql-config project, named after the document.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_code_from_config(
+    config: Option<PathBuf>,
+    project: Option<String>,
+    additional_derives: Option<String>,
+    deprecation_strategy: &Option<String>,
+    no_formatting: bool,
+    module_visibility: &Option<String>,
+    shared_variables: bool,
+    output_directory: &Path,
+) -> Result<(), failure::Error> {
+    let config = match config {
+        Some(config) => config,
+        None => graphql_config::find_config(&::std::env::current_dir()?).ok_or_else(|| {
+            format_err!(
+                "Could not find a graphql-config file in the current directory. Looked for: {}.",
+                graphql_config::CONFIG_FILE_NAMES.join(", ")
+            )
+        })?,
+    };
+    let project = graphql_config::load_project(&config, project.as_deref())?;
+
+    let documents = project.document_paths()?;
+    if documents.is_empty() {
+        Err(format_err!(
+            "No query documents match the documents of the graphql-config project."
+        ))?
+    }
+
+    for document in documents {
+        let module_name = document
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .ok_or_else(|| format_err!("Invalid query document path: {}", document.display()))?;
+        let output = output_directory.join(format!("{}.rs", module_name));
+
+        generate_code(
+            document,
+            project.schema.clone(),
+            module_name,
+            None,
+            additional_derives.clone(),
+            deprecation_strategy,
+            no_formatting,
+            module_visibility,
+            shared_variables,
+            None,
+            &output,
+        )?;
+    }
+
+    Ok(())
+}
+
 #[allow(unused_variables)]
 fn format(codes: &str) -> String {
     #[cfg(feature = "rustfmt")]
@@ -3,6 +3,7 @@ extern crate log;
 use env_logger::fmt::{Color, Style, StyledValue};
 use log::Level;
 
+#[macro_use]
 extern crate failure;
 extern crate reqwest;
 extern crate structopt;
@@ -44,7 +45,7 @@ enum Cli {
         /// Path to graphql query file.
         #[structopt(parse(from_os_str))]
         query_path: PathBuf,
-        /// Path to graphql schema file.
+        /// Path to graphql schema file. Use - to read the schema (JSON or SDL) from stdin.
         #[structopt(parse(from_os_str))]
         schema_path: PathBuf,
         /// Name of module.
@@ -69,9 +70,54 @@ enum Cli {
         /// Default value is pub.
         #[structopt(short = "m", long = "module_visibility")]
         module_visibility: Option<String>,
+        /// Operations with identical variables share one Variables type, the others become type aliases.
+        /// Only relevant when generating all the operations in the query file.
+        #[structopt(long = "shared-variables")]
+        shared_variables: bool,
+        /// Print the resolved selection tree of the operations (fields, types, expanded fragments and variants) instead of generating code.
+        /// The output path is not written to.
+        #[structopt(long = "explain")]
+        explain: bool,
+        /// Also write a JSON description of the generated response structs to this path: their names, fields, Rust types, nullability and GraphQL types.
+        #[structopt(parse(from_os_str))]
+        #[structopt(long = "emit-type-map")]
+        emit_type_map: Option<PathBuf>,
         #[structopt(parse(from_os_str))]
         output: PathBuf,
     },
+    #[structopt(name = "generate-from-config")]
+    GenerateFromConfig {
+        /// Path to the graphql-config file.
+        /// By default, we look for .graphqlrc, graphql.config.yml and their variants in the current directory.
+        #[structopt(parse(from_os_str))]
+        #[structopt(long = "config")]
+        config: Option<PathBuf>,
+        /// Name of the project to generate code for. Required when the config file defines several projects.
+        #[structopt(long = "project")]
+        project: Option<String>,
+        /// Additional derives that will be added to the generated structs and enums for the response and the variables.
+        /// --additional-derives='Serialize,PartialEq'
+        #[structopt(short = "a", long = "additional-derives")]
+        additional_derives: Option<String>,
+        /// You can choose deprecation strategy from allow, deny, or warn.
+        /// Default value is warn.
+        #[structopt(short = "d", long = "deprecation-strategy")]
+        deprecation_strategy: Option<String>,
+        /// If you don't want to execute rustfmt to generated code, set this option.
+        /// Default value is false.
+        #[structopt(long = "no-formatting")]
+        no_formatting: bool,
+        /// You can choose module and target struct visibility from pub and private.
+        /// Default value is pub.
+        #[structopt(short = "m", long = "module_visibility")]
+        module_visibility: Option<String>,
+        /// Operations with identical variables share one Variables type, the others become type aliases.
+        #[structopt(long = "shared-variables")]
+        shared_variables: bool,
+        /// Directory to write the generated code to, one module per query document.
+        #[structopt(parse(from_os_str))]
+        output_directory: PathBuf,
+    },
 }
 
 fn main() -> Result<(), failure::Error> {
@@ -84,6 +130,13 @@ fn main() -> Result<(), failure::Error> {
             output,
             authorization,
         } => introspect_schema::introspect_schema(&schema_location, output, authorization),
+        Cli::Generate {
+            query_path,
+            schema_path,
+            selected_operation,
+            explain: true,
+            ..
+        } => generate::explain(query_path, schema_path, selected_operation),
         Cli::Generate {
             query_path,
             schema_path,
@@ -93,6 +146,9 @@ fn main() -> Result<(), failure::Error> {
             deprecation_strategy,
             no_formatting,
             module_visibility,
+            shared_variables,
+            explain: false,
+            emit_type_map,
             output,
         } => generate::generate_code(
             query_path,
@@ -103,8 +159,29 @@ fn main() -> Result<(), failure::Error> {
             &deprecation_strategy,
             no_formatting,
             &module_visibility,
+            shared_variables,
+            emit_type_map,
             &output,
         ),
+        Cli::GenerateFromConfig {
+            config,
+            project,
+            additional_derives,
+            deprecation_strategy,
+            no_formatting,
+            module_visibility,
+            shared_variables,
+            output_directory,
+        } => generate::generate_code_from_config(
+            config,
+            project,
+            additional_derives,
+            &deprecation_strategy,
+            no_formatting,
+            &module_visibility,
+            shared_variables,
+            &output_directory,
+        ),
     }
 }
 
@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+const CLI: &str = env!("CARGO_BIN_EXE_graphql-client");
+
+fn fixture(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(path)
+}
+
+#[test]
+fn explain_prints_the_selection_tree_without_generating_code() {
+    let output = ::std::env::temp_dir().join("graphql_client_cli_explain.rs");
+
+    let result = Command::new(CLI)
+        .arg("generate")
+        .arg("--explain")
+        .arg(fixture("graphql_client_cli/tests/explain/query.graphql"))
+        .arg(fixture(
+            "graphql_client_codegen/src/tests/star_wars_schema.graphql",
+        ))
+        .arg("query")
+        .arg(&output)
+        .output()
+        .unwrap();
+
+    assert!(result.status.success(), "{:?}", result);
+    assert_eq!(
+        String::from_utf8(result.stdout).unwrap(),
+        [
+            "HeroQuery: Query",
+            "  hero: Character",
+            "    __typename: String!",
+            "    id: ID! [from fragment CharacterFields]",
+            "    name: String! [from fragment CharacterFields]",
+            "    on Human:",
+            "      homePlanet: String",
+            "",
+        ]
+        .join("\n")
+    );
+    assert!(!output.exists());
+}
+
+#[test]
+fn explain_rejects_fragments_spreading_themselves() {
+    let output = ::std::env::temp_dir().join("graphql_client_cli_explain_cyclic.rs");
+
+    let result = Command::new(CLI)
+        .arg("generate")
+        .arg("--explain")
+        .arg(fixture(
+            "graphql_client_cli/tests/explain/cyclic_query.graphql",
+        ))
+        .arg(fixture(
+            "graphql_client_codegen/src/tests/star_wars_schema.graphql",
+        ))
+        .arg("query")
+        .arg(&output)
+        .output()
+        .unwrap();
+
+    assert!(!result.status.success());
+    let stderr = String::from_utf8(result.stderr).unwrap();
+    assert!(
+        stderr.contains("The HeroFields fragment spreads itself through HeroFields -> HeroFields."),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn explain_reads_query_files_in_utf_16() {
+    let query =
+        ::std::fs::read_to_string(fixture("graphql_client_cli/tests/explain/query.graphql"))
+            .unwrap();
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in query.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_le_bytes());
+    }
+    let query_path = ::std::env::temp_dir().join("graphql_client_cli_explain_utf16.graphql");
+    ::std::fs::write(&query_path, bytes).unwrap();
+    let output = ::std::env::temp_dir().join("graphql_client_cli_explain_utf16.rs");
+
+    let result = Command::new(CLI)
+        .arg("generate")
+        .arg("--explain")
+        .arg(&query_path)
+        .arg(fixture(
+            "graphql_client_codegen/src/tests/star_wars_schema.graphql",
+        ))
+        .arg("query")
+        .arg(&output)
+        .output()
+        .unwrap();
+
+    assert!(result.status.success(), "{:?}", result);
+    assert!(String::from_utf8(result.stdout)
+        .unwrap()
+        .starts_with("HeroQuery: Query\n"));
+}
@@ -0,0 +1,72 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+const CLI: &str = env!("CARGO_BIN_EXE_graphql-client");
+
+fn fixture(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(path)
+}
+
+/// Runs `graphql-client generate` with the schema piped through stdin.
+fn generate_with_stdin_schema(query_path: &str, schema: &str, output: &PathBuf) -> Output {
+    let mut child = Command::new(CLI)
+        .arg("generate")
+        .arg(query_path)
+        .arg("-")
+        .arg("query")
+        .arg(output)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // The CLI may exit before reading stdin, so a broken pipe is not an error here.
+    let _ = child.stdin.take().unwrap().write_all(schema.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn sdl_schema_is_read_from_stdin() {
+    let output = ::std::env::temp_dir().join("graphql_client_cli_stdin_sdl.rs");
+    let schema = fs::read_to_string(fixture(
+        "graphql_client_codegen/src/tests/star_wars_schema.graphql",
+    ))
+    .unwrap();
+    let query = fixture("graphql_client_codegen/src/tests/star_wars_query.graphql");
+
+    let result = generate_with_stdin_schema(query.to_str().unwrap(), &schema, &output);
+
+    assert!(result.status.success(), "{:?}", result);
+    let generated = fs::read_to_string(&output).unwrap();
+    assert!(generated.contains("pub struct ResponseData"));
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn json_schema_is_read_from_stdin() {
+    let output = ::std::env::temp_dir().join("graphql_client_cli_stdin_json.rs");
+    let schema =
+        fs::read_to_string(fixture("graphql_client/tests/json_schema/schema_1.json")).unwrap();
+    let query = fixture("graphql_client/tests/json_schema/query.graphql");
+
+    let result = generate_with_stdin_schema(query.to_str().unwrap(), &schema, &output);
+
+    assert!(result.status.success(), "{:?}", result);
+    let generated = fs::read_to_string(&output).unwrap();
+    assert!(generated.contains("pub struct ResponseData"));
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn query_cannot_also_be_read_from_stdin() {
+    let output = ::std::env::temp_dir().join("graphql_client_cli_stdin_both.rs");
+
+    let result = generate_with_stdin_schema("-", "type Query { a: String }", &output);
+
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("The query cannot be read from stdin"));
+}
@@ -0,0 +1,71 @@
+extern crate serde_json;
+
+use serde_json::Value;
+use std::fs::File;
+use std::path::PathBuf;
+use std::process::Command;
+
+const CLI: &str = env!("CARGO_BIN_EXE_graphql-client");
+
+fn fixture(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(path)
+}
+
+#[test]
+fn emit_type_map_describes_the_generated_structs() {
+    let output = ::std::env::temp_dir().join("graphql_client_cli_type_map.rs");
+    let type_map_path = ::std::env::temp_dir().join("graphql_client_cli_type_map.json");
+
+    let result = Command::new(CLI)
+        .arg("generate")
+        .arg("--emit-type-map")
+        .arg(&type_map_path)
+        .arg(fixture("graphql_client_cli/tests/explain/query.graphql"))
+        .arg(fixture(
+            "graphql_client_codegen/src/tests/star_wars_schema.graphql",
+        ))
+        .arg("query")
+        .arg(&output)
+        .output()
+        .unwrap();
+    assert!(result.status.success(), "{:?}", result);
+    assert!(output.exists());
+
+    let type_map: Value = serde_json::from_reader(File::open(&type_map_path).unwrap()).unwrap();
+    let names: Vec<&str> = type_map["types"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|ty| ty["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "CharacterFields",
+            "HeroQueryHero",
+            "HeroQueryHeroOnHuman",
+            "ResponseData"
+        ]
+    );
+
+    let response_data = &type_map["types"][3];
+    assert_eq!(response_data["graphqlType"], "Query");
+    assert_eq!(
+        response_data["fields"][0],
+        serde_json::json!({
+            "name": "hero",
+            "responseKey": "hero",
+            "rustType": "Option<HeroQueryHero>",
+            "graphqlType": "Character",
+            "nullable": true
+        })
+    );
+
+    let hero_fields = &type_map["types"][1]["fields"];
+    assert_eq!(hero_fields[0]["rustType"], "CharacterFields");
+    assert_eq!(hero_fields[0]["responseKey"], Value::Null);
+    assert_eq!(hero_fields[1]["name"], "on");
+    assert_eq!(hero_fields[1]["rustType"], "HeroQueryHeroOn");
+}
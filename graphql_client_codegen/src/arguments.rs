@@ -0,0 +1,216 @@
+use error::CodegenError;
+use field_type::FieldType;
+use graphql_parser::query::Value;
+use objects::GqlObjectField;
+use query::QueryContext;
+use selection::SelectionField;
+
+/// Checks the literal arguments passed to a selected field against the argument types in the schema. Variables are not checked here, their type is declared on the operation.
+pub(crate) fn validate_arguments(
+    selected: &SelectionField,
+    schema_field: &GqlObjectField,
+    type_name: &str,
+    context: &QueryContext,
+) -> Result<(), CodegenError> {
+    for (name, value) in &selected.arguments {
+        let ty = schema_field
+            .arguments
+            .iter()
+            .find(|(argument, _)| argument == name)
+            .map(|(_, ty)| ty)
+            .ok_or_else(|| {
+                CodegenError::Validation(format!(
+                    "Unknown argument {} on the {} field of {}.",
+                    name, selected.name, type_name
+                ))
+            })?;
+
+        if !literal_matches(value, ty, context) {
+            if let Some((enum_name, invalid)) = invalid_enum_value(value, ty, context) {
+                Err(CodegenError::Validation(format!(
+                    "The {} argument on the {} field of {} expects a value of the {} enum, but {} is not one of its values. {}",
+                    name,
+                    selected.name,
+                    type_name,
+                    enum_name,
+                    invalid,
+                    enum_value_hint(enum_name, invalid, context)
+                )))?
+            }
+            Err(CodegenError::Validation(format!(
+                "The {} argument on the {} field of {} expects a value of type {}, but got {}.",
+                name,
+                selected.name,
+                type_name,
+                graphql_type(ty),
+                value
+            )))?
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the literal can be coerced to the input type, following the input coercion rules of the spec.
+fn literal_matches(value: &Value, ty: &FieldType, context: &QueryContext) -> bool {
+    match (value, ty) {
+        // The type of variables is checked by the server against the declared variable types.
+        (Value::Variable(_), _) => true,
+        (Value::Null, FieldType::Optional(_)) => true,
+        (Value::Null, _) => false,
+        (value, FieldType::Optional(inner)) => literal_matches(value, inner, context),
+        (Value::List(items), FieldType::Vector(inner)) => items
+            .iter()
+            .all(|item| literal_matches(item, inner, context)),
+        // A single value is coerced to a list of one item.
+        (value, FieldType::Vector(inner)) => literal_matches(value, inner, context),
+        (value, FieldType::Named(name)) => named_literal_matches(value, name, context),
+    }
+}
+
+fn named_literal_matches(value: &Value, type_name: &str, context: &QueryContext) -> bool {
+    match (type_name, value) {
+        ("Int", Value::Int(_)) => true,
+        ("Float", Value::Int(_)) | ("Float", Value::Float(_)) => true,
+        ("String", Value::String(_)) => true,
+        ("Boolean", Value::Boolean(_)) => true,
+        ("ID", Value::String(_)) | ("ID", Value::Int(_)) => true,
+        ("Int", _) | ("Float", _) | ("String", _) | ("Boolean", _) | ("ID", _) => false,
+        (type_name, value) => {
+            if let Some(enm) = context.schema.enums.get(type_name) {
+                match value {
+                    Value::Enum(variant) => enm.variants.iter().any(|v| &v.name == variant),
+                    _ => false,
+                }
+            } else if let Some(input) = context.schema.inputs.get(type_name) {
+                match value {
+                    Value::Object(fields) => fields.iter().all(|(name, value)| {
+                        input
+                            .fields
+                            .get(name)
+                            .map(|field| literal_matches(value, &field.type_, context))
+                            .unwrap_or(false)
+                    }),
+                    _ => false,
+                }
+            } else {
+                // Custom scalars can have any representation.
+                true
+            }
+        }
+    }
+}
+
+/// The first enum value in the literal that its enum does not have, with the name of the enum, looking into lists and input objects.
+fn invalid_enum_value<'a>(
+    value: &'a Value,
+    ty: &'a FieldType,
+    context: &'a QueryContext,
+) -> Option<(&'a str, &'a str)> {
+    match (value, ty) {
+        (value, FieldType::Optional(inner)) => invalid_enum_value(value, inner, context),
+        (Value::List(items), FieldType::Vector(inner)) => items
+            .iter()
+            .filter_map(|item| invalid_enum_value(item, inner, context))
+            .next(),
+        (value, FieldType::Vector(inner)) => invalid_enum_value(value, inner, context),
+        (Value::Enum(variant), FieldType::Named(name)) => {
+            let enm = context.schema.enums.get(name)?;
+            if enm.variants.iter().any(|v| &v.name == variant) {
+                None
+            } else {
+                Some((name, variant))
+            }
+        }
+        (Value::Object(fields), FieldType::Named(name)) => {
+            let input = context.schema.inputs.get(name)?;
+            fields
+                .iter()
+                .filter_map(|(field_name, value)| {
+                    let field = input.fields.get(field_name)?;
+                    invalid_enum_value(value, &field.type_, context)
+                })
+                .next()
+        }
+        _ => None,
+    }
+}
+
+/// Suggests the value of the enum closest to the invalid one, or lists the values if none is close.
+fn enum_value_hint(enum_name: &str, invalid: &str, context: &QueryContext) -> String {
+    let values: Vec<&str> = context.schema.enums[enum_name]
+        .variants
+        .iter()
+        .map(|variant| variant.name.as_str())
+        .collect();
+
+    // Typos are usually a few characters off, and the case of the values does not count.
+    let max_distance = ::std::cmp::max(1, invalid.len() / 3);
+    let closest = values
+        .iter()
+        .map(|value| {
+            let distance = levenshtein(&invalid.to_lowercase(), &value.to_lowercase());
+            (distance, *value)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min();
+
+    match closest {
+        Some((_, value)) => format!("Did you mean {}?", value),
+        None => format!("The values are {}.", values.join(", ")),
+    }
+}
+
+/// The number of single-character insertions, deletions and substitutions turning `a` into `b`.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            let insertion = current[j] + 1;
+            let deletion = previous[j + 1] + 1;
+            current.push(substitution.min(insertion).min(deletion));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// The type in GraphQL syntax, e.g. `[String!]`.
+pub(crate) fn graphql_type(ty: &FieldType) -> String {
+    match ty {
+        FieldType::Optional(inner) => match &**inner {
+            FieldType::Named(name) => name.clone(),
+            FieldType::Vector(item) => format!("[{}]", graphql_type(item)),
+            FieldType::Optional(inner) => graphql_type(inner),
+        },
+        FieldType::Named(name) => format!("{}!", name),
+        FieldType::Vector(item) => format!("[{}]!", graphql_type(item)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graphql_type_renders_nullability() {
+        let ty = FieldType::Optional(Box::new(FieldType::Vector(Box::new(FieldType::Named(
+            "String".to_string(),
+        )))));
+
+        assert_eq!(graphql_type(&ty), "[String!]");
+    }
+
+    #[test]
+    fn levenshtein_counts_the_edits() {
+        assert_eq!(levenshtein("ADMN", "ADMIN"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "USER"), 4);
+        assert_eq!(levenshtein("USER", "USER"), 0);
+    }
+}
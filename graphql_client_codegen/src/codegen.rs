@@ -1,12 +1,17 @@
-use deprecation;
-use failure;
+use deprecation::{DeprecationStatus, DeprecationStrategy};
+use error::CodegenError;
 use fragments::GqlFragment;
 use graphql_parser::query;
+use heck::{CamelCase, ShoutySnakeCase};
+use objects::GqlObject;
 use operations::Operation;
 use proc_macro2::{Ident, Span, TokenStream};
-use query::QueryContext;
+use query::{QueryContext, MIN_IDENTIFIER_LENGTH};
 use schema;
-use selection::Selection;
+use selection::{Selection, SelectionItem};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use type_map::TypeDescription;
+use GraphQLClientDeriveOptions;
 
 /// Selects the first operation matching `struct_name` or the first one. Returns `None` when the query document defines no operation.
 pub(crate) fn select_operation(query: &query::Document, struct_name: &str) -> Option<Operation> {
@@ -19,6 +24,7 @@ pub(crate) fn select_operation(query: &query::Document, struct_name: &str) -> Op
         .or_else(|| operations.iter().next().map(|i| i.to_owned()))
 }
 
+/// The operations of the document. Its anonymous operation must have been named with [name_anonymous_operation].
 pub(crate) fn all_operations(query: &query::Document) -> Vec<Operation> {
     let mut operations: Vec<Operation> = Vec::new();
 
@@ -30,41 +36,338 @@ pub(crate) fn all_operations(query: &query::Document) -> Vec<Operation> {
     operations
 }
 
+/// Names the anonymous operation of the document, written `{ ... }` or `query { ... }`, since the generated code and the `operationName` of the requests need a name. The query sent to the server has to be rewritten with it. Returns whether there was an anonymous operation.
+///
+/// Per the spec, an anonymous operation must be the only operation of its document.
+pub(crate) fn name_anonymous_operation(
+    query: &mut query::Document,
+    name: &str,
+) -> Result<bool, CodegenError> {
+    let operation_count = query
+        .definitions
+        .iter()
+        .filter(|definition| match definition {
+            query::Definition::Operation(_) => true,
+            query::Definition::Fragment(_) => false,
+        })
+        .count();
+    let mut named = false;
+
+    for definition in &mut query.definitions {
+        let operation = match definition {
+            query::Definition::Operation(operation) => operation,
+            query::Definition::Fragment(_) => continue,
+        };
+        let anonymous = match operation {
+            query::OperationDefinition::SelectionSet(_) => true,
+            query::OperationDefinition::Query(q) => q.name.is_none(),
+            query::OperationDefinition::Mutation(m) => m.name.is_none(),
+            query::OperationDefinition::Subscription(s) => s.name.is_none(),
+        };
+        if !anonymous {
+            continue;
+        }
+
+        if operation_count > 1 {
+            Err(CodegenError::Validation(
+                "The query document defines an anonymous operation next to other operations. An anonymous operation must be the only one in its document, give it a name.".to_string(),
+            ))?
+        }
+
+        let name = Some(name.to_string());
+        *operation = match operation.clone() {
+            // The shorthand is a query.
+            query::OperationDefinition::SelectionSet(selection_set) => {
+                query::OperationDefinition::Query(query::Query {
+                    position: selection_set.span.0,
+                    name,
+                    variable_definitions: Vec::new(),
+                    directives: Vec::new(),
+                    selection_set,
+                })
+            }
+            query::OperationDefinition::Query(q) => {
+                query::OperationDefinition::Query(query::Query { name, ..q })
+            }
+            query::OperationDefinition::Mutation(m) => {
+                query::OperationDefinition::Mutation(query::Mutation { name, ..m })
+            }
+            query::OperationDefinition::Subscription(s) => {
+                query::OperationDefinition::Subscription(query::Subscription { name, ..s })
+            }
+        };
+        named = true;
+    }
+
+    Ok(named)
+}
+
+/// Registers the fragments defined in the query document on the context, and rejects the spreads forming cycles.
+pub(crate) fn ingest_fragments(
+    context: &mut QueryContext,
+    query: &query::Document,
+) -> Result<(), CodegenError> {
+    for definition in &query.definitions {
+        if let query::Definition::Fragment(fragment) = definition {
+            let query::TypeCondition::On(on) = &fragment.type_condition;
+            context.fragments.insert(
+                fragment.name.clone(),
+                GqlFragment {
+                    name: fragment.name.clone(),
+                    selection: Selection::from(&fragment.selection_set),
+                    on: on.clone(),
+                    is_required: false.into(),
+                },
+            );
+        }
+    }
+
+    ::fragments::validate_spread_cycles(context)
+}
+
+/// The scalar types in the responses of all the operations of the document, for the `scalar_collectors` option.
+fn document_scalars(query: &query::Document, context: &QueryContext) -> BTreeSet<String> {
+    all_operations(query)
+        .iter()
+        .filter_map(|operation| {
+            let root_name = operation.root_name(&context.schema)?;
+            Some(::collectors::response_scalars(
+                &root_name,
+                &operation.selection,
+                context,
+            ))
+        })
+        .flatten()
+        .collect()
+}
+
+/// Rejects the `Eq`, `Ord` and `Hash` response derives when the response or the variables of the operation hold a `Float`, instead of letting the generated code fail to compile. The response derives are also added to the variables and input types.
+fn validate_float_derives(
+    context: &QueryContext,
+    operation: &Operation,
+    root_name: &str,
+) -> Result<(), CodegenError> {
+    let derives = context.float_incompatible_derives();
+    if derives.is_empty() {
+        return Ok(());
+    }
+
+    let float_holder = if ::collectors::response_scalars(root_name, &operation.selection, context)
+        .contains("Float")
+    {
+        Some(format!("the response of {}", operation.name))
+    } else {
+        operation
+            .variables
+            .iter()
+            .find(|variable| {
+                input_holds_float(
+                    &variable.ty.inner_name_string(),
+                    context,
+                    &mut BTreeSet::new(),
+                )
+            })
+            .map(|variable| format!("the {} variable of {}", variable.name, operation.name))
+    };
+
+    if let Some(float_holder) = float_holder {
+        Err(CodegenError::Validation(format!(
+            "The {} response derives cannot be used because {} holds a Float. Floats only implement PartialEq and PartialOrd.",
+            derives.join(", "),
+            float_holder
+        )))?
+    }
+
+    Ok(())
+}
+
+/// Whether a variable or input field of this type holds a `Float`, directly or in the fields of input objects.
+fn input_holds_float(
+    type_name: &str,
+    context: &QueryContext,
+    visited: &mut BTreeSet<String>,
+) -> bool {
+    if type_name == "Float" {
+        return true;
+    }
+    if !visited.insert(type_name.to_string()) {
+        return false;
+    }
+
+    context
+        .schema
+        .inputs
+        .get(type_name)
+        .map(|input| {
+            input
+                .fields
+                .values()
+                .any(|field| input_holds_float(&field.type_.inner_name_string(), context, visited))
+        })
+        .unwrap_or(false)
+}
+
+/// Generates the fragments required by the operation. Generating a fragment requires the fragments it spreads, whatever their name or their position in the document, so we loop until no new fragment is required.
+fn fragment_definitions(context: &QueryContext) -> Result<Vec<TokenStream>, CodegenError> {
+    let mut definitions: BTreeMap<&str, TokenStream> = BTreeMap::new();
+
+    loop {
+        let pending: Vec<&GqlFragment> = context
+            .fragments
+            .values()
+            .filter(|fragment| {
+                fragment.is_required.get() && !definitions.contains_key(fragment.name.as_str())
+            })
+            .collect();
+
+        if pending.is_empty() {
+            return Ok(definitions.into_values().collect());
+        }
+
+        for fragment in pending {
+            definitions.insert(&fragment.name, fragment.to_rust(context)?);
+        }
+    }
+}
+
+/// The code generated for one operation. The prelude (type aliases and custom scalars) must stay at the root of the generated module, the groups of definitions can be moved to submodules by `generate_to_directory`.
+pub(crate) struct OperationCode {
+    pub prelude: Vec<TokenStream>,
+    pub groups: Vec<(&'static str, Vec<TokenStream>)>,
+    /// The response structs of the operation, by name, for the type map.
+    pub types: BTreeMap<String, TypeDescription>,
+}
+
+impl OperationCode {
+    /// Removes the definitions that were already generated for another operation of the same module, like the fragments and enums both operations use.
+    pub fn remove_duplicates(&mut self, generated: &mut HashSet<String>) {
+        self.prelude
+            .retain(|definition| generated.insert(definition.to_string()));
+        for (_, definitions) in &mut self.groups {
+            definitions.retain(|definition| generated.insert(definition.to_string()));
+        }
+    }
+}
+
 /// The main code generation function.
-pub fn response_for_query(
+pub(crate) fn response_for_query(
     schema: schema::Schema,
     query: query::Document,
     operation: &Operation,
-    additional_derives: Option<String>,
-    deprecation_strategy: deprecation::DeprecationStrategy,
+    options: &GraphQLClientDeriveOptions,
     multiple_operation: bool,
-) -> Result<TokenStream, failure::Error> {
+    shared_variables_with: Option<&str>,
+) -> Result<OperationCode, CodegenError> {
+    // The user can determine what to do about deprecations.
+    let deprecation_strategy = options.deprecation_strategy.clone().unwrap_or_default();
+    let mut schema = schema;
+    let field_type_paths = match &options.field_types {
+        Some(option) => ::field_types::apply_field_types(option, &mut schema)?,
+        None => BTreeMap::new(),
+    };
     let mut context = QueryContext::new(schema, deprecation_strategy);
+    context.variant_accessors = options.variant_accessors;
+    context.list_iterators = options.list_iterators;
+    context.rename_all_camel_case = options.rename_all_camel_case;
+    context.input_patches = options.input_patches;
+    context.test_defaults = options.test_defaults;
+    context.enum_all_variants = options.enum_all_variants;
+    context.optimistic_responses = options.optimistic_responses;
+    context.field_accessors = options.field_accessors;
+    context.conditional_fields_report = options.conditional_fields_report;
+    context.case_insensitive_enums = options.case_insensitive_enums;
+    context.sensitive_fields = options.sensitive_fields.clone();
+    if let Some(max_length) = options.max_identifier_length {
+        if max_length < MIN_IDENTIFIER_LENGTH {
+            Err(CodegenError::Validation(format!(
+                "The max_identifier_length option must be at least {}, to leave room for the hash ending the shortened names.",
+                MIN_IDENTIFIER_LENGTH
+            )))?
+        }
+    }
+    context.max_identifier_length = options.max_identifier_length;
+    context.variables_try_from = options.variables_try_from;
+    context.interface_flatten = options.interface_flatten;
+    context.enum_unknowns = options.enum_unknowns.clone();
+    context.rkyv = options.rkyv;
+    context.strip_typename = options.strip_typename;
+    if options.rkyv && !cfg!(feature = "rkyv") {
+        Err(CodegenError::Validation(
+            "The rkyv option requires the rkyv feature of graphql_client.".to_string(),
+        ))?
+    }
+    context.visibility = options.visibility.clone();
+    context.transform = options.transform.clone();
+    context.qualified_paths = options.qualified_paths;
+    context.skip_external_fields = options.skip_external_fields;
+    context.variables_builder = options.variables_builder;
+    context.variables_constructor = options.variables_constructor;
+    context.compact_str = options.compact_str;
+    if options.compact_str && !cfg!(feature = "compact_str") {
+        Err(CodegenError::Validation(
+            "The compact_str option requires the compact_str feature of graphql_client."
+                .to_string(),
+        ))?
+    }
+    context.variables_as_pairs = options.variables_as_pairs;
+    context.bevy_reflect = options.bevy_reflect;
+    if options.bevy_reflect && !cfg!(feature = "bevy") {
+        Err(CodegenError::Validation(
+            "The bevy_reflect option requires the bevy feature of graphql_client.".to_string(),
+        ))?
+    }
+    context.sqlx_from_row = options.sqlx_from_row;
+    context.empty_lists = options.empty_lists;
+    if options.sqlx_from_row && !cfg!(feature = "sqlx") {
+        Err(CodegenError::Validation(
+            "The sqlx_from_row option requires the sqlx feature of graphql_client.".to_string(),
+        ))?
+    }
+    context.unknown_fields = options.unknown_fields;
+    context.estimated_size = options.estimated_size;
+    context.top_level_derives_only = options.top_level_derives_only;
+    if let Some(methods) = &options.enum_value_methods {
+        context.enum_value_methods = ::enums::parse_enum_value_methods(methods)?;
+    }
+    if options.estimated_size && options.compact_str {
+        Err(CodegenError::Validation(
+            "The estimated_size option cannot be combined with compact_str, graphql_client does not depend on compact_str to estimate its strings.".to_string(),
+        ))?
+    }
+    if let Some(serde_crate) = &options.serde_crate {
+        context.serde_crate = Some(absolute_serde_crate(serde_crate)?);
+    }
+    if options.unknown_fields && (options.sqlx_from_row || options.rkyv || options.bevy_reflect) {
+        Err(CodegenError::Validation(
+            "The unknown_fields option cannot be combined with sqlx_from_row, rkyv or bevy_reflect, the extra map of JSON values cannot be derived for them.".to_string(),
+        ))?
+    }
 
-    if let Some(derives) = additional_derives {
-        context.ingest_additional_derives(&derives).unwrap();
+    if let Some(derives) = &options.additional_derives {
+        context.ingest_additional_derives(derives).unwrap();
+    }
+    if let Some(derives) = &options.test_only_derives {
+        context.ingest_test_only_derives(derives)?;
     }
 
     let mut definitions = Vec::new();
 
-    for definition in query.definitions {
-        match definition {
-            query::Definition::Operation(_op) => (),
-            query::Definition::Fragment(fragment) => {
-                let query::TypeCondition::On(on) = fragment.type_condition;
-                context.fragments.insert(
-                    fragment.name.clone(),
-                    GqlFragment {
-                        name: fragment.name,
-                        selection: Selection::from(&fragment.selection_set),
-                        on,
-                        is_required: false.into(),
-                    },
-                );
-            }
-        }
+    ingest_fragments(&mut context, &query)?;
+
+    if options.scalar_collectors {
+        context.collected_scalars = document_scalars(&query, &context);
     }
 
+    let mut root_field_accessor = None;
+    let mut caught_fields = quote!();
+    let mut meta_const = quote!();
+    let mut required_scopes_const = quote!();
+    let mut http_method_const = quote!();
+    let mut idempotent_const = quote!();
+    let mut operation_kind_const = quote!();
+    let mut variables_schema_const = quote!();
+    let mut response_from_row_derive = quote!();
+
     let response_data_fields = {
         let opt_root_name = operation.root_name(&context.schema);
         let root_name: String = if let Some(root_name) = opt_root_name {
@@ -83,44 +386,179 @@ pub fn response_for_query(
         let prefix = &operation.name;
         let selection = &operation.selection;
 
-        if operation.is_subscription() && selection.0.len() > 1 {
-            Err(format_err!(
-                "{}",
-                ::constants::MULTIPLE_SUBSCRIPTION_FIELDS_ERROR
-            ))?
+        operation.validate_selection_not_empty()?;
+        operation.validate_condition_variables(&context)?;
+        operation.validate_argument_variables(&context)?;
+        operation.validate_list_item_variables(&context)?;
+        validate_float_derives(&context, operation, &root_name)?;
+
+        if operation.is_subscription() {
+            operation.validate_subscription_root(&context)?;
+        }
+
+        let caught_field_paths = ::transforms::caught_field_paths(selection, &context);
+        if !caught_field_paths.is_empty() {
+            let const_name = if multiple_operation {
+                format!("{}_CAUGHT_FIELDS", operation.name.to_shouty_snake_case())
+            } else {
+                "CAUGHT_FIELDS".to_string()
+            };
+            let const_name = Ident::new(&const_name, Span::call_site());
+            let paths = caught_field_paths.iter().map(|path| quote!(&[#(#path),*]));
+            caught_fields = quote! {
+                /// The paths of the fields with the `@catch` directive, for `graphql_client::catch::embed_field_errors`.
+                pub const #const_name: &[&[&str]] = &[#(#paths),*];
+            };
+        }
+
+        let meta = ::transforms::operation_meta(operation)?;
+        if !meta.is_empty() {
+            let const_name = if multiple_operation {
+                format!("{}_META", operation.name.to_shouty_snake_case())
+            } else {
+                "META".to_string()
+            };
+            let const_name = Ident::new(&const_name, Span::call_site());
+            let entries = meta.iter().map(|(key, value)| quote!((#key, #value)));
+            meta_const = quote! {
+                /// The `(key, value)` pairs of the `@meta` directives on the operation.
+                pub const #const_name: &'static [(&'static str, &'static str)] = &[#(#entries),*];
+            };
+        }
+
+        let required_scopes = ::transforms::operation_required_scopes(operation)?;
+        if !required_scopes.is_empty() {
+            let const_name = if multiple_operation {
+                format!("{}_REQUIRED_SCOPES", operation.name.to_shouty_snake_case())
+            } else {
+                "REQUIRED_SCOPES".to_string()
+            };
+            let const_name = Ident::new(&const_name, Span::call_site());
+            required_scopes_const = quote! {
+                /// The auth scopes required by the operation, from its `@requiresScopes` directives.
+                pub const #const_name: &'static [&'static str] = &[#(#required_scopes),*];
+            };
         }
 
-        definitions.extend(
-            definition
-                .field_impls_for_selection(&context, &selection, &prefix)
-                .unwrap(),
+        let http_method = Ident::new(
+            ::transforms::operation_http_method(operation)?,
+            Span::call_site(),
         );
-        definition
-            .response_fields_for_selection(&context, &selection, &prefix)
-            .unwrap()
-    };
+        let const_name = if multiple_operation {
+            format!("{}_HTTP_METHOD", operation.name.to_shouty_snake_case())
+        } else {
+            "HTTP_METHOD".to_string()
+        };
+        let const_name = Ident::new(&const_name, Span::call_site());
+        http_method_const = quote! {
+            /// The HTTP method to send the operation with, from its `@httpMethod` directive.
+            pub const #const_name: ::graphql_client::HttpMethod = ::graphql_client::HttpMethod::#http_method;
+        };
 
-    let enum_definitions = context.schema.enums.values().filter_map(|enm| {
-        if enm.is_required.get() {
-            Some(enm.to_rust(&context))
+        let operation_kind = operation.operation_type.keyword();
+        let const_name = if multiple_operation {
+            format!("{}_OPERATION_KIND", operation.name.to_shouty_snake_case())
         } else {
-            None
-        }
-    });
-    let fragment_definitions: Result<Vec<TokenStream>, _> = context
-        .fragments
-        .values()
-        .filter_map(|fragment| {
-            if fragment.is_required.get() {
-                Some(fragment.to_rust(&context))
+            "OPERATION_KIND".to_string()
+        };
+        let const_name = Ident::new(&const_name, Span::call_site());
+        operation_kind_const = quote! {
+            /// The type of the operation, `"query"`, `"mutation"` or `"subscription"`, for logging.
+            pub const #const_name: &'static str = #operation_kind;
+        };
+
+        let idempotent = ::transforms::operation_is_idempotent(operation)?;
+        let const_name = if multiple_operation {
+            format!("{}_IDEMPOTENT", operation.name.to_shouty_snake_case())
+        } else {
+            "IDEMPOTENT".to_string()
+        };
+        let const_name = Ident::new(&const_name, Span::call_site());
+        idempotent_const = quote! {
+            /// Whether the operation can be retried with an idempotency key, from its `@idempotent` directive.
+            pub const #const_name: bool = #idempotent;
+        };
+
+        if options.variables_schema {
+            let variables_schema = operation.variables_schema();
+            let const_name = if multiple_operation {
+                format!("{}_VARIABLES_SCHEMA", operation.name.to_shouty_snake_case())
             } else {
-                None
-            }
-        })
-        .collect();
-    let fragment_definitions = fragment_definitions?;
-    let variables_struct =
-        operation.expand_variables(&context, &operation.name, multiple_operation);
+                "VARIABLES_SCHEMA".to_string()
+            };
+            let const_name = Ident::new(&const_name, Span::call_site());
+            variables_schema_const = quote! {
+                /// The variable definitions of the operation in GraphQL syntax, empty if it has no variables.
+                pub const #const_name: &'static str = #variables_schema;
+            };
+        }
+
+        let response_data_struct_name = response_data_struct_name(operation, multiple_operation);
+        definitions.push(definition.list_iterator_impls(
+            &context,
+            &response_data_struct_name,
+            selection,
+            prefix,
+        )?);
+        definitions.push(definition.optimistic_struct(
+            &context,
+            &response_data_struct_name,
+            selection,
+            prefix,
+        ));
+        definitions.push(definition.field_accessors(
+            &context,
+            &response_data_struct_name,
+            selection,
+            prefix,
+        ));
+        definitions.push(definition.scalar_collectors(
+            &context,
+            &response_data_struct_name,
+            selection,
+            prefix,
+        ));
+        definitions.push(::estimated_size::struct_impl(
+            &definition.fields,
+            &context,
+            &response_data_struct_name,
+            selection,
+            prefix,
+            context.unknown_fields_names(),
+        ));
+        if options.scalar_collectors {
+            let scalars = ::collectors::response_scalars(&root_name, selection, &context);
+            definitions.push(::collectors::all_values_methods(
+                &context,
+                &response_data_struct_name,
+                &scalars,
+            ));
+        }
+
+        response_from_row_derive = context.sqlx_from_row_derive(&definition.fields, selection);
+
+        if options.root_field_accessor {
+            root_field_accessor = Some(single_root_field(&context, definition, operation)?);
+        }
+
+        definitions.extend(definition.field_impls_for_selection(&context, &selection, &prefix)?);
+        let fields = definition.response_fields_for_selection(&context, &selection, &prefix)?;
+        // The fields of `ResponseData` are resolved with the name of the operation as prefix.
+        let mut described_types = context.described_types.borrow_mut();
+        if let Some(mut response_data) = described_types.remove(prefix) {
+            response_data.name = response_data_struct_name.to_string();
+            described_types.insert(response_data.name.clone(), response_data);
+        }
+        fields
+    };
+
+    let fragment_definitions = fragment_definitions(&context)?;
+    let variables_struct = operation.expand_variables(
+        &context,
+        &operation.name,
+        multiple_operation,
+        shared_variables_with,
+    );
 
     let input_object_definitions: Result<Vec<TokenStream>, _> = context
         .schema
@@ -136,58 +574,275 @@ pub fn response_for_query(
         .collect();
     let input_object_definitions = input_object_definitions?;
 
+    let mut scalar_paths = match &options.scalar_paths {
+        Some(option) => ::scalars::parse_scalar_paths(option, &context.schema)?,
+        None => BTreeMap::new(),
+    };
+    scalar_paths.extend(field_type_paths);
     let scalar_definitions: Vec<TokenStream> = context
         .schema
         .scalars
         .values()
         .filter_map(|s| {
             if s.is_required.get() {
-                Some(s.to_rust())
+                Some(s.to_rust(scalar_paths.get(&s.name)))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // Enums are collected last, since they can be required by any of the above.
+    let enum_definitions: Result<Vec<TokenStream>, _> = context
+        .schema
+        .enums
+        .values()
+        .filter_map(|enm| {
+            if enm.is_required.get() {
+                Some(enm.to_rust(&context))
             } else {
                 None
             }
         })
         .collect();
+    let enum_definitions = enum_definitions?;
 
-    let response_derives = context.response_derives();
+    let response_derives = context.response_data_derives();
+    let rename_all = context.rename_all_annotation();
+    let visibility = context.type_visibility();
 
-    let respons_data_struct_name = if multiple_operation {
-        Ident::new(
-            format!("{}ResponseData", operation.name).as_str(),
-            Span::call_site(),
-        )
+    let respons_data_struct_name = response_data_struct_name(operation, multiple_operation);
+
+    let root_field_accessor = root_field_accessor.map(|(ty, field)| {
+        quote! {
+            impl #respons_data_struct_name {
+                /// The only field selected at the root of the operation.
+                pub fn root_field(&self) -> &#ty {
+                    &self.#field
+                }
+
+                /// Consumes the response data, returning the only field selected at the root of the operation.
+                pub fn into_root_field(self) -> #ty {
+                    self.#field
+                }
+            }
+        }
+    });
+
+    let float_type = options.float_type.to_rust();
+
+    // With `qualified_paths` or `serde_crate`, the derives are paths and nothing needs to be in scope.
+    let serde_derive_import = if options.qualified_paths || options.serde_crate.is_some() {
+        quote!()
     } else {
-        Ident::new("ResponseData", Span::call_site())
+        quote! {
+            use serde_derive::*;
+        }
+    };
+
+    // The `Reflect` derive refers to `bevy_reflect` with a relative path, which the generated module of a 2015 edition crate only resolves if the crate is imported in it.
+    let bevy_reflect_import = if options.bevy_reflect {
+        quote! {
+            #[allow(clippy::single_component_path_imports)]
+            use bevy_reflect;
+        }
+    } else {
+        quote!()
     };
 
-    Ok(quote! {
-        use serde_derive::*;
+    let mut prelude = vec![quote! {
+        #serde_derive_import
+        #bevy_reflect_import
 
         #[allow(dead_code)]
         type Boolean = bool;
         #[allow(dead_code)]
-        type Float = f64;
+        type Float = #float_type;
         #[allow(dead_code)]
         type Int = i64;
         #[allow(dead_code)]
         type ID = String;
+    }];
+    prelude.extend(scalar_definitions);
 
-        #(#scalar_definitions)*
-
-        #(#input_object_definitions)*
+    // With the `testing` feature, for snapshot-style tests. The comparison goes through `Serialize`.
+    let assert_matches_json = if cfg!(feature = "testing") && context.response_is_serializable() {
+        quote! {
+            impl #respons_data_struct_name {
+                /// Panics with a readable diff if the response data, serialized to JSON, does not match the `expected` JSON.
+                pub fn assert_matches_json(&self, expected: &str) {
+                    ::graphql_client::testing::assert_matches_json(self, expected)
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
 
-        #(#enum_definitions)*
+    let field_json = if options.field_json {
+        if !context.response_is_serializable() {
+            Err(CodegenError::Validation(
+                "The field_json option requires Serialize in the response_derives.".to_string(),
+            ))?
+        }
 
-        #(#fragment_definitions)*
+        quote! {
+            impl #respons_data_struct_name {
+                /// The value at the dotted `path` of response keys, like `"hero.friends.0.name"`, serialized to JSON. List items are selected by index. `None` if there is no value at the path.
+                pub fn field_json(&self, path: &str) -> Option<::graphql_client::serde_json::Value> {
+                    ::graphql_client::field_json::extract(self, path)
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
 
-        #(#definitions)*
+    let response_trait = if options.response_trait {
+        if !context.response_is_serializable() {
+            Err(CodegenError::Validation(
+                "The response_trait option requires Serialize in the response_derives.".to_string(),
+            ))?
+        }
+        let operation_name = &operation.name;
 
-        #variables_struct
+        quote! {
+            impl ::graphql_client::GraphQLResponse for #respons_data_struct_name {
+                fn operation_name(&self) -> &'static str {
+                    #operation_name
+                }
 
-        #response_derives
-        pub struct #respons_data_struct_name {
-            #(#response_data_fields,)*
+                fn to_json(&self) -> Result<::graphql_client::serde_json::Value, ::graphql_client::serde_json::Error> {
+                    ::graphql_client::serde_json::to_value(self)
+                }
+            }
         }
+    } else {
+        quote!()
+    };
+
+    let response_data_extra = context.unknown_fields_field();
+    let response_definition = context.transform_type(
+        &respons_data_struct_name.to_string(),
+        quote! {
+            #response_derives
+            #response_from_row_derive
+            #rename_all
+            #visibility struct #respons_data_struct_name {
+                #(#response_data_fields,)*
+                #response_data_extra
+            }
+        },
+    );
+
+    let response = quote! {
+        #response_definition
+
+        #root_field_accessor
+
+        #caught_fields
+
+        #meta_const
+
+        #required_scopes_const
+
+        #operation_kind_const
+
+        #http_method_const
+
+        #idempotent_const
+
+        #variables_schema_const
+
+        #assert_matches_json
+
+        #field_json
+
+        #response_trait
+    };
+
+    context.check_bounded_names()?;
 
+    Ok(OperationCode {
+        prelude,
+        groups: vec![
+            ("inputs", input_object_definitions),
+            ("enums", enum_definitions),
+            ("fragments", fragment_definitions),
+            ("selections", definitions),
+            ("variables", vec![variables_struct]),
+            ("response", vec![response]),
+        ],
+        types: context.described_types.into_inner(),
     })
 }
+
+fn response_data_struct_name(operation: &Operation, multiple_operation: bool) -> Ident {
+    if multiple_operation {
+        Ident::new(
+            format!("{}ResponseData", operation.name).as_str(),
+            Span::call_site(),
+        )
+    } else {
+        Ident::new("ResponseData", Span::call_site())
+    }
+}
+
+/// The type and the name of the struct field for the single root field of the operation, used by the `root_field_accessor` option.
+fn single_root_field(
+    context: &QueryContext,
+    root: &GqlObject,
+    operation: &Operation,
+) -> Result<(TokenStream, Ident), CodegenError> {
+    let field = match operation.selection.0.as_slice() {
+        [SelectionItem::Field(field)] => field,
+        _ => Err(CodegenError::Validation(format!(
+            "The root_field_accessor option requires the {} operation to select exactly one root field.",
+            operation.name
+        )))?,
+    };
+
+    let response_key = field.alias.as_ref().unwrap_or(&field.name);
+    let schema_field = root
+        .fields
+        .iter()
+        .find(|f| f.name == field.name)
+        .ok_or_else(|| ::shared::unknown_field_error(&field.name, &root.name, &root.fields))?;
+
+    if let (DeprecationStatus::Deprecated(_), DeprecationStrategy::Deny) =
+        (&schema_field.deprecation, &context.deprecation_strategy)
+    {
+        Err(CodegenError::Validation(format!(
+            "The root_field_accessor option cannot be used because the {} field is deprecated and denied.",
+            field.name
+        )))?
+    }
+
+    let ty = schema_field.type_.to_rust(
+        context,
+        &format!(
+            "{}{}",
+            operation.name.to_camel_case(),
+            response_key.to_camel_case()
+        ),
+    );
+
+    Ok((ty, ::shared::field_ident(response_key)))
+}
+
+/// The path of the `serde_crate` option, as an absolute path: `my_serde` becomes `::my_serde`, so it resolves from the generated module whatever the edition. `crate::` paths are kept.
+fn absolute_serde_crate(path: &str) -> Result<String, CodegenError> {
+    let path = path.trim();
+    if syn::parse_str::<syn::Path>(path).is_err() {
+        Err(CodegenError::Validation(format!(
+            "The serde_crate option must be a path, like my_serde, but it is {:?}.",
+            path
+        )))?
+    }
+
+    if path.starts_with("::") || path.starts_with("crate::") {
+        Ok(path.to_string())
+    } else {
+        Ok(format!("::{}", path))
+    }
+}
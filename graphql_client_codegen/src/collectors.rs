@@ -0,0 +1,309 @@
+//! The `scalar_collectors` option: `all_*` methods on the `ResponseData` structs, collecting every value of a scalar type in the response.
+//!
+//! Every response struct and enum of the module gets a `collect_*_values` method for each scalar type selected in the query document, pushing the values it holds, directly or in its nested types. The methods are generated for the scalars of the whole document, so that fragments shared by several operations are the same code in each.
+
+use constants::TYPENAME_FIELD;
+use deprecation::{DeprecationStatus, DeprecationStrategy};
+use field_type::FieldType;
+use heck::SnakeCase;
+use objects::GqlObjectField;
+use proc_macro2::{Ident, Span, TokenStream};
+use query::QueryContext;
+use schema::DEFAULT_SCALARS;
+use selection::{Selection, SelectionField, SelectionItem};
+use std::collections::BTreeSet;
+
+/// The scalar types of the fields selected on `type_name`, looking through nested selections and fragments.
+pub(crate) fn response_scalars(
+    type_name: &str,
+    selection: &Selection,
+    context: &QueryContext,
+) -> BTreeSet<String> {
+    let mut scalars = BTreeSet::new();
+    insert_response_scalars(type_name, selection, context, &mut Vec::new(), &mut scalars);
+    scalars
+}
+
+fn insert_response_scalars<'a>(
+    type_name: &str,
+    selection: &'a Selection,
+    context: &'a QueryContext,
+    visited_fragments: &mut Vec<&'a str>,
+    scalars: &mut BTreeSet<String>,
+) {
+    for item in &selection.0 {
+        match item {
+            SelectionItem::Field(selected) => {
+                let schema_field = match collected_field(type_name, selected, context) {
+                    Some(schema_field) => schema_field,
+                    None => continue,
+                };
+                let field_type = schema_field.type_.inner_name_string();
+                if is_scalar(&field_type, context) {
+                    scalars.insert(field_type);
+                } else {
+                    insert_response_scalars(
+                        &field_type,
+                        &selected.fields,
+                        context,
+                        visited_fragments,
+                        scalars,
+                    );
+                }
+            }
+            SelectionItem::InlineFragment(inline) => insert_response_scalars(
+                &inline.on,
+                &inline.fields,
+                context,
+                visited_fragments,
+                scalars,
+            ),
+            SelectionItem::FragmentSpread(spread) => {
+                let name = spread.fragment_name.as_str();
+                if visited_fragments.contains(&name) {
+                    continue;
+                }
+                visited_fragments.push(name);
+
+                if let Some(fragment) = context.fragments.get(name) {
+                    insert_response_scalars(
+                        &fragment.on,
+                        &fragment.selection,
+                        context,
+                        visited_fragments,
+                        scalars,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// The schema field of a field selected on `type_name`, if it is generated in the response struct and its values are collected.
+fn collected_field<'a>(
+    type_name: &str,
+    selected: &SelectionField,
+    context: &'a QueryContext,
+) -> Option<&'a GqlObjectField> {
+    let schema_fields = match (
+        context.schema.objects.get(type_name),
+        context.schema.interfaces.get(type_name),
+    ) {
+        (Some(object), _) => &object.fields,
+        (None, Some(iface)) => &iface.fields,
+        (None, None) => return None,
+    };
+    find_collected_field(schema_fields, selected, context)
+}
+
+/// Like `collected_field`, from the fields of the type. `__typename` is not collected.
+fn find_collected_field<'a>(
+    schema_fields: &'a [GqlObjectField],
+    selected: &SelectionField,
+    context: &QueryContext,
+) -> Option<&'a GqlObjectField> {
+    if selected.name == TYPENAME_FIELD {
+        return None;
+    }
+
+    let schema_field = schema_fields
+        .iter()
+        .find(|field| field.name == selected.name)?;
+    // The field is not generated at all.
+    if let (DeprecationStatus::Deprecated(_), DeprecationStrategy::Deny) =
+        (&schema_field.deprecation, &context.deprecation_strategy)
+    {
+        return None;
+    }
+    if schema_field.external && context.skip_external_fields {
+        return None;
+    }
+
+    Some(schema_field)
+}
+
+fn is_scalar(type_name: &str, context: &QueryContext) -> bool {
+    DEFAULT_SCALARS.contains(&type_name) || context.schema.scalars.contains_key(type_name)
+}
+
+fn collect_method(scalar: &str) -> Ident {
+    Ident::new(
+        &format!("collect_{}_values", scalar.to_snake_case()),
+        Span::call_site(),
+    )
+}
+
+fn scalar_type(scalar: &str, context: &QueryContext) -> TokenStream {
+    FieldType::Named(scalar.to_string()).to_rust(context, "")
+}
+
+/// The statements pushing the values of `scalar` held by `value`, a reference to a value of type `field_type`, if it can hold any.
+fn collect_values(
+    field_type: &FieldType,
+    scalar: &str,
+    context: &QueryContext,
+) -> Option<TokenStream> {
+    match field_type {
+        FieldType::Optional(inner) => collect_values(inner, scalar, context)
+            .map(|collect| quote!(if let Some(value) = value { #collect })),
+        FieldType::Vector(inner) => collect_values(inner, scalar, context)
+            .map(|collect| quote!(for value in value { #collect })),
+        FieldType::Named(name) if name == scalar => Some(quote!(out.push(value);)),
+        FieldType::Named(name) if !context.schema.is_leaf(name) => {
+            let method = collect_method(scalar);
+            Some(quote!(value.#method(out);))
+        }
+        FieldType::Named(_) => None,
+    }
+}
+
+/// The `collect_*_values` methods of a response struct. `on_enum` is whether it has the `on` field of interface structs.
+pub(crate) fn struct_collectors(
+    schema_fields: &[GqlObjectField],
+    context: &QueryContext,
+    struct_name: &Ident,
+    selection: &Selection,
+    prefix: &str,
+    on_enum: bool,
+) -> TokenStream {
+    if context.collected_scalars.is_empty() {
+        return quote!();
+    }
+
+    let methods = context.collected_scalars.iter().map(|scalar| {
+        let method = collect_method(scalar);
+        let ty = scalar_type(scalar, context);
+
+        let fields = selection.0.iter().filter_map(|item| match item {
+            SelectionItem::Field(selected) => {
+                let schema_field = find_collected_field(schema_fields, selected, context)?;
+                let field_type = ::shared::response_field_type(selected, schema_field, context);
+                let collect = collect_values(&field_type, scalar, context)?;
+                let name = ::shared::field_ident(selected.alias.as_ref().unwrap_or(&selected.name));
+
+                if ::transforms::is_caught(selected) {
+                    Some(quote!(if let Ok(value) = &self.#name { #collect }))
+                } else {
+                    Some(quote!({
+                        let value = &self.#name;
+                        #collect
+                    }))
+                }
+            }
+            SelectionItem::FragmentSpread(spread) => {
+                let name = Ident::new(&spread.fragment_name.to_snake_case(), Span::call_site());
+
+                if spread.is_conditional() {
+                    Some(quote!(if let Some(value) = &self.#name { value.#method(out); }))
+                } else {
+                    Some(quote!(self.#name.#method(out);))
+                }
+            }
+            SelectionItem::InlineFragment(inline) if context.interface_flatten => {
+                let (name, _) = ::shared::flattened_interface_names(inline, prefix, context);
+                Some(quote!(self.#name.#method(out);))
+            }
+            SelectionItem::InlineFragment(_) => None,
+        });
+        let on = if on_enum {
+            quote!(self.on.#method(out);)
+        } else {
+            quote!()
+        };
+
+        quote! {
+            #[allow(deprecated, unused_variables, clippy::ptr_arg)]
+            fn #method<'a>(&'a self, out: &mut Vec<&'a #ty>) {
+                #(#fields)*
+                #on
+            }
+        }
+    });
+
+    quote! {
+        impl #struct_name {
+            #(#methods)*
+        }
+    }
+}
+
+/// The `collect_*_values` methods of a union or interface enum, delegating to the struct of the variant.
+pub(crate) fn enum_collectors(
+    context: &QueryContext,
+    enum_name: &Ident,
+    selected_variants: &[String],
+    has_other_variants: bool,
+) -> TokenStream {
+    if context.collected_scalars.is_empty() {
+        return quote!();
+    }
+
+    let methods = context.collected_scalars.iter().map(|scalar| {
+        let method = collect_method(scalar);
+        let ty = scalar_type(scalar, context);
+        let arms = selected_variants.iter().map(|variant| {
+            let variant_name = Ident::new(variant, Span::call_site());
+            quote!(#enum_name::#variant_name(value) => value.#method(out),)
+        });
+        let other_arm = if has_other_variants {
+            quote!(_ => {})
+        } else {
+            quote!()
+        };
+
+        quote! {
+            #[allow(unused_variables, clippy::ptr_arg)]
+            fn #method<'a>(&'a self, out: &mut Vec<&'a #ty>) {
+                match self {
+                    #(#arms)*
+                    #other_arm
+                }
+            }
+        }
+    });
+
+    quote! {
+        impl #enum_name {
+            #(#methods)*
+        }
+    }
+}
+
+/// The public `all_*` methods of the `ResponseData` struct, one for each scalar type in the response of the operation.
+pub(crate) fn all_values_methods(
+    context: &QueryContext,
+    struct_name: &Ident,
+    scalars: &BTreeSet<String>,
+) -> TokenStream {
+    if scalars.is_empty() {
+        return quote!();
+    }
+
+    let methods = scalars.iter().map(|scalar| {
+        let method = collect_method(scalar);
+        let name = Ident::new(
+            &format!("all_{}", scalar.to_snake_case()),
+            Span::call_site(),
+        );
+        let ty = scalar_type(scalar, context);
+        let doc = format!(
+            "Every `{}` value in the response, in the order of the selected fields.",
+            scalar
+        );
+
+        quote! {
+            #[doc = #doc]
+            pub fn #name(&self) -> Vec<&#ty> {
+                let mut values = Vec::new();
+                self.#method(&mut values);
+                values
+            }
+        }
+    });
+
+    quote! {
+        impl #struct_name {
+            #(#methods)*
+        }
+    }
+}
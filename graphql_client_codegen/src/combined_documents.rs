@@ -0,0 +1,283 @@
+//! Documents holding both the type-system definitions of a schema and the operations on it, as they are often written in examples and tests.
+//!
+//! graphql_parser has separate parsers for schemas and for queries, so the top-level definitions are told apart from their first keyword and split in two documents. Each document keeps the lines of the other as empty lines, so the positions in parse errors still point into the combined document.
+
+/// Keywords starting a type-system definition, including `extend`, which takes the keyword of the extended definition after it.
+const TYPE_SYSTEM_KEYWORDS: &[&str] = &[
+    "schema",
+    "scalar",
+    "type",
+    "interface",
+    "union",
+    "enum",
+    "input",
+    "directive",
+    "extend",
+];
+
+/// Keywords starting an executable definition. Anonymous queries start with `{`.
+const EXECUTABLE_KEYWORDS: &[&str] = &["query", "mutation", "subscription", "fragment"];
+
+/// Type-system definitions without a body in braces, so a `{` following them starts an anonymous query.
+const BODILESS_KEYWORDS: &[&str] = &["scalar", "union", "directive"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DefinitionKind {
+    TypeSystem,
+    Executable,
+}
+
+/// A combined document split in its type-system and executable parts.
+#[derive(Debug, PartialEq)]
+pub(crate) struct SplitDocument {
+    /// The schema definitions, extensions and directive definitions.
+    pub(crate) type_system: String,
+    /// The operations and fragments.
+    pub(crate) executable: String,
+    /// Whether the document has any type-system definition.
+    pub(crate) has_type_system: bool,
+}
+
+pub(crate) fn split(document: &str) -> SplitDocument {
+    let definitions = definitions(document);
+    let has_type_system = definitions
+        .iter()
+        .any(|(kind, _)| *kind == DefinitionKind::TypeSystem);
+
+    let mut type_system = String::with_capacity(document.len());
+    let mut executable = String::with_capacity(document.len());
+
+    for (index, (kind, start)) in definitions.iter().enumerate() {
+        // Whatever comes before the first definition, like comments, goes with it.
+        let start = if index == 0 { 0 } else { *start };
+        let end = definitions
+            .get(index + 1)
+            .map(|(_, next)| *next)
+            .unwrap_or_else(|| document.len());
+        let text = &document[start..end];
+        let lines: String = text.chars().filter(|c| *c == '\n').collect();
+
+        match kind {
+            DefinitionKind::TypeSystem => {
+                type_system.push_str(text);
+                executable.push_str(&lines);
+            }
+            DefinitionKind::Executable => {
+                executable.push_str(text);
+                type_system.push_str(&lines);
+            }
+        }
+    }
+
+    if definitions.is_empty() {
+        executable.push_str(document);
+    }
+
+    SplitDocument {
+        type_system,
+        executable,
+        has_type_system,
+    }
+}
+
+/// The kind and the byte offset of the start of each top-level definition.
+fn definitions(document: &str) -> Vec<(DefinitionKind, usize)> {
+    let bytes = document.as_bytes();
+    let mut definitions: Vec<(DefinitionKind, usize)> = Vec::new();
+    let mut depth = 0usize;
+    // The keyword of the definition being read, if its body has not been closed yet.
+    let mut current: Option<&str> = None;
+    // A description or `extend` was read, and the keyword of their definition comes next.
+    let mut pending_keyword = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'"' => {
+                let start = i;
+                i = skip_string(bytes, i);
+                if depth == 0 && !pending_keyword {
+                    definitions.push((DefinitionKind::TypeSystem, start));
+                    current = None;
+                    pending_keyword = true;
+                }
+            }
+            b'{' | b'(' | b'[' => {
+                let starts_anonymous_query = bytes[i] == b'{'
+                    && depth == 0
+                    && !pending_keyword
+                    && starts_definition(current);
+                if starts_anonymous_query {
+                    definitions.push((DefinitionKind::Executable, i));
+                    current = Some("query");
+                }
+                depth += 1;
+                i += 1;
+            }
+            b'}' | b')' | b']' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 && bytes[i] == b'}' {
+                    current = None;
+                }
+                i += 1;
+            }
+            b if b == b'_' || b.is_ascii_alphabetic() => {
+                let start = i;
+                while i < bytes.len() && (bytes[i] == b'_' || bytes[i].is_ascii_alphanumeric()) {
+                    i += 1;
+                }
+                let name = &document[start..i];
+                if depth != 0 {
+                    continue;
+                }
+
+                let kind = if TYPE_SYSTEM_KEYWORDS.contains(&name) {
+                    DefinitionKind::TypeSystem
+                } else if EXECUTABLE_KEYWORDS.contains(&name) {
+                    DefinitionKind::Executable
+                } else {
+                    continue;
+                };
+
+                if pending_keyword {
+                    // The description or `extend` already started the definition.
+                    if let Some(last) = definitions.last_mut() {
+                        last.0 = kind;
+                    }
+                    pending_keyword = name == "extend";
+                    current = Some(name);
+                } else if starts_definition(current) {
+                    definitions.push((kind, start));
+                    pending_keyword = name == "extend";
+                    current = Some(name);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    definitions
+}
+
+/// Whether a definition can start after the one being read: it is over, or it has no body.
+fn starts_definition(current: Option<&str>) -> bool {
+    match current {
+        Some(keyword) => BODILESS_KEYWORDS.contains(&keyword),
+        None => true,
+    }
+}
+
+/// The offset after the string or block string starting at `start`.
+fn skip_string(bytes: &[u8], start: usize) -> usize {
+    if bytes[start..].starts_with(b"\"\"\"") {
+        let mut i = start + 3;
+        while i < bytes.len() {
+            if bytes[i..].starts_with(b"\\\"\"\"") {
+                i += 4;
+            } else if bytes[i..].starts_with(b"\"\"\"") {
+                return i + 3;
+            } else {
+                i += 1;
+            }
+        }
+        i
+    } else {
+        let mut i = start + 1;
+        while i < bytes.len() && bytes[i] != b'"' && bytes[i] != b'\n' {
+            if bytes[i] == b'\\' {
+                i += 1;
+            }
+            i += 1;
+        }
+        (i + 1).min(bytes.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn definitions_are_split_by_kind() {
+        let document = r#"
+type Query {
+  "The hero, for a query"
+  hero(episode: Episode = NEWHOPE): Character
+}
+
+scalar Date
+
+{
+  hero { name }
+}
+
+"An episode"
+enum Episode { NEWHOPE EMPIRE }
+
+extend type Query { date: Date }
+
+query Hero($episode: Episode) {
+  hero(episode: $episode) { ...HeroName }
+}
+
+fragment HeroName on Character { name }
+"#;
+        let split = split(document);
+
+        assert!(split.has_type_system);
+        assert_eq!(split.type_system.lines().count(), document.lines().count());
+        assert_eq!(split.executable.lines().count(), document.lines().count());
+
+        let type_system: Vec<&str> = split
+            .type_system
+            .lines()
+            .filter(|line| !line.is_empty())
+            .collect();
+        assert_eq!(
+            type_system,
+            vec![
+                "type Query {",
+                "  \"The hero, for a query\"",
+                "  hero(episode: Episode = NEWHOPE): Character",
+                "}",
+                "scalar Date",
+                "\"An episode\"",
+                "enum Episode { NEWHOPE EMPIRE }",
+                "extend type Query { date: Date }",
+            ]
+        );
+
+        let executable: Vec<&str> = split
+            .executable
+            .lines()
+            .filter(|line| !line.is_empty())
+            .collect();
+        assert_eq!(
+            executable,
+            vec![
+                "{",
+                "  hero { name }",
+                "}",
+                "query Hero($episode: Episode) {",
+                "  hero(episode: $episode) { ...HeroName }",
+                "}",
+                "fragment HeroName on Character { name }",
+            ]
+        );
+    }
+
+    #[test]
+    fn documents_with_operations_only_are_kept_as_they_are() {
+        let document = "# The hero\nquery Hero { hero { name } }\n";
+        let split = split(document);
+
+        assert!(!split.has_type_system);
+        assert_eq!(split.executable, document);
+        assert_eq!(split.type_system.trim(), "");
+    }
+}
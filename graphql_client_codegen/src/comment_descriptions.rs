@@ -0,0 +1,79 @@
+/// Lines that cannot have a description, so comments before them are left alone.
+const UNDESCRIBABLE_STARTS: &[&str] = &["}", ")", "schema", "extend", "\""];
+
+/// Turns the `#` comments directly above definitions, fields, arguments and enum values into string descriptions, as older SDL documented them that way.
+///
+/// Comments followed by an empty line, trailing comments and comments before something that cannot have a description are left as they are.
+pub(crate) fn comments_to_descriptions(sdl: &str) -> String {
+    let mut out = String::with_capacity(sdl.len());
+    let mut comment: Vec<&str> = Vec::new();
+    let mut in_block_string = false;
+
+    for line in sdl.lines() {
+        let trimmed = line.trim_start();
+
+        if let (false, Some(text)) = (in_block_string, trimmed.strip_prefix('#')) {
+            // Strip the conventional space after the `#` too.
+            comment.push(text.strip_prefix(' ').unwrap_or(text));
+            push_line(&mut out, line);
+            continue;
+        }
+
+        let describable = !in_block_string
+            && !trimmed.is_empty()
+            && !UNDESCRIBABLE_STARTS
+                .iter()
+                .any(|start| trimmed.starts_with(start));
+        if describable && !comment.is_empty() {
+            let indentation = &line[..line.len() - trimmed.len()];
+            let description = comment
+                .join("\n")
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            push_line(&mut out, &format!("{}\"{}\"", indentation, description));
+        }
+        comment.clear();
+
+        if line.matches("\"\"\"").count() % 2 == 1 {
+            in_block_string = !in_block_string;
+        }
+        push_line(&mut out, line);
+    }
+
+    out
+}
+
+fn push_line(out: &mut String, line: &str) {
+    out.push_str(line);
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_above_definitions_become_descriptions() {
+        let sdl = "# A person.\n# With a name.\ntype Person {\n  # The \"full\" name.\n  name: String # Trailing.\n}\n";
+
+        assert_eq!(
+            comments_to_descriptions(sdl),
+            "# A person.\n# With a name.\n\"A person.\\nWith a name.\"\ntype Person {\n  # The \"full\" name.\n  \"The \\\"full\\\" name.\"\n  name: String # Trailing.\n}\n"
+        );
+    }
+
+    #[test]
+    fn detached_comments_are_left_alone() {
+        let sdl = "# Just a comment.\n\ntype Person {\n  name: String\n  # The end.\n}\n";
+
+        assert_eq!(comments_to_descriptions(sdl), sdl);
+    }
+
+    #[test]
+    fn existing_descriptions_win() {
+        let sdl = "# A comment.\n\"\"\"\n# Not a comment.\nA person.\n\"\"\"\ntype Person {\n  name: String\n}\n";
+
+        assert_eq!(comments_to_descriptions(sdl), sdl);
+    }
+}
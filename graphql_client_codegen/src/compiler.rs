@@ -0,0 +1,99 @@
+//! Generating code for many versions of query documents against the same schema, as IDE tooling and language servers do on every edit.
+//!
+//! [`generate_module_token_stream`](::generate_module_token_stream) caches schemas by path, but embedders usually hold the schema and the query in memory. A [`QueryCompiler`] keeps the parsed schema and the options, and only parses and resolves the query on each call.
+//!
+//! ```ignore
+//! let compiler = QueryCompiler::new(Schema::from_sdl(&schema_sdl)?, options);
+//! // On every edit of the buffer:
+//! let result = compiler.compile(&buffer);
+//! ```
+
+use error::CodegenError;
+use graphql_parser;
+use proc_macro2::TokenStream;
+use schema::Schema;
+use GraphQLClientDeriveOptions;
+
+/// Generates the code for query documents given as strings, against a schema parsed once.
+///
+/// # Thread safety
+///
+/// A compiler is neither `Send` nor `Sync`: the [`TokenTransform`](::TokenTransform) of the options is held in an `Rc`, and the schema records the types used by an operation in `Cell`s. Give each thread its own compiler, for example in a `thread_local!`. Each call works on a copy of the schema, so calls do not affect each other.
+pub struct QueryCompiler {
+    schema: Schema,
+    options: GraphQLClientDeriveOptions,
+}
+
+impl QueryCompiler {
+    /// A compiler for queries against `schema`, generated with `options`.
+    pub fn new(schema: Schema, options: GraphQLClientDeriveOptions) -> QueryCompiler {
+        QueryCompiler { schema, options }
+    }
+
+    /// The schema the queries are compiled against.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Replaces the schema, when it changed on disk.
+    pub fn set_schema(&mut self, schema: Schema) {
+        self.schema = schema;
+    }
+
+    /// Parses the query document and generates the code for its module, like [`generate_module_token_stream`](::generate_module_token_stream) does for a query file.
+    pub fn compile(&self, query: &str) -> Result<TokenStream, CodegenError> {
+        let document = graphql_parser::parse_query(query)
+            .map_err(|err| CodegenError::QueryParse(err.to_string()))?;
+        let module = ::generate_module_for_document(
+            query.to_string(),
+            document,
+            self.schema.clone(),
+            self.options.clone(),
+        )?;
+
+        Ok(::module_token_stream(&module))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = include_str!("tests/star_wars_schema.graphql");
+
+    fn compiler() -> QueryCompiler {
+        let mut options = ::tests::test_options();
+        options.struct_name = Some("HeroQuery".to_string());
+        QueryCompiler::new(Schema::from_sdl(SCHEMA).unwrap(), options)
+    }
+
+    #[test]
+    fn the_schema_is_reused_across_edits() {
+        let compiler = compiler();
+        let edits = [
+            "query HeroQuery { hero { __typename name } }",
+            "query HeroQuery { hero { __typename name appearsIn } }",
+            "query HeroQuery { hero { __typename name appearsIn id } }",
+        ];
+
+        let schema: *const Schema = compiler.schema();
+        let outputs: Vec<String> = edits
+            .iter()
+            .map(|query| compiler.compile(query).unwrap().to_string())
+            .collect();
+
+        assert!(outputs[1].contains("appears_in"));
+        assert!(!outputs[0].contains("appears_in"));
+        // Every edit was compiled against the schema parsed once, and the copies of it recorded the types used, not the schema of the compiler.
+        assert!(::std::ptr::eq(compiler.schema(), schema));
+        assert_eq!(compiler.schema(), &Schema::from_sdl(SCHEMA).unwrap());
+    }
+
+    #[test]
+    fn syntax_errors_are_reported() {
+        match compiler().compile("query HeroQuery { hero { name }") {
+            Err(CodegenError::QueryParse(_)) => (),
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -21,40 +21,28 @@ pub(crate) fn typename_field() -> GqlObjectField {
         /// https://github.com/facebook/graphql/blob/master/spec/Section%204%20--%20Introspection.md
         type_: FieldType::Named(string_type()),
         deprecation: DeprecationStatus::Current,
+        arguments: Vec::new(),
+        external: false,
     }
 }
 
-pub(crate) const MULTIPLE_SUBSCRIPTION_FIELDS_ERROR: &str = r##"
-Multiple-field queries on the root subscription field are forbidden by the spec.
+/// The schema directive attaching a numeric code to an enum value, e.g. `ACTIVE @code(n: 3)`.
+pub(crate) const ENUM_CODE_DIRECTIVE: &str = "code";
 
-See: https://github.com/facebook/graphql/blob/master/spec/Section%205%20--%20Validation.md#subscription-operation-definitions
-"##;
+/// The schema directive marking the enum value that unknown values are deserialized to with `enum_unknowns = "default"`, e.g. `UNKNOWN @unknownDefault`.
+pub(crate) const ENUM_UNKNOWN_DEFAULT_DIRECTIVE: &str = "unknownDefault";
 
-/// Error message when a selection set is the root of a query.
-pub(crate) const SELECTION_SET_AT_ROOT: &str = r#"
-Operations in queries must be named.
+/// The federation directive marking the fields a subgraph declares, but another subgraph resolves.
+pub(crate) const EXTERNAL_DIRECTIVE: &str = "external";
 
-Instead of this:
+/// The schema directive tagging types and fields for an audience, e.g. `@tag(name: "public")`, filtered on with the `include_tag` option.
+pub(crate) const TAG_DIRECTIVE: &str = "tag";
 
-{
-  user {
-    name
-    repositories {
-      name
-      commits
-    }
-  }
-}
+/// The argument of the `@deprecated` directive that some schemas use for the date the item will be removed.
+pub(crate) const REMOVAL_DATE_ARGUMENT: &str = "removalDate";
 
-Write this:
+pub(crate) const MULTIPLE_SUBSCRIPTION_FIELDS_ERROR: &str = r##"
+Multiple-field queries on the root subscription field are forbidden by the spec.
 
-query UserRepositories {
-  user {
-    name
-    repositories {
-      name
-      commits
-    }
-  }
-}
-"#;
+See: https://github.com/facebook/graphql/blob/master/spec/Section%205%20--%20Validation.md#subscription-operation-definitions
+"##;
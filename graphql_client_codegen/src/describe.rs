@@ -0,0 +1,759 @@
+//! Describes the shape of the response to an operation at runtime, without generating any Rust code.
+//!
+//! This is meant for dynamic clients that only get to see the query at runtime. It goes through the same validation as the derive (unknown fields, types and fragments are errors), but the result is a runtime description of the response of the operation, not compiled types. `OperationShape::validate` checks a `serde_json::Value` response against it.
+
+use codegen;
+use constants::TYPENAME_FIELD;
+use error::CodegenError;
+use field_type::FieldType;
+use graphql_parser;
+use objects::GqlObjectField;
+use query::QueryContext;
+use schema::{Schema, DEFAULT_SCALARS};
+use selection::{Selection, SelectionItem};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// The shape of the response data of an operation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OperationShape {
+    /// The name of the operation.
+    pub name: String,
+    /// The name of the root type the operation selects on, e.g. `Query`.
+    pub root_type: String,
+    /// The fields of the `data` object in the response.
+    pub fields: Vec<FieldShape>,
+}
+
+/// A field in the response.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldShape {
+    /// The key of the field in the response: the alias if there is one, the field name otherwise.
+    pub response_key: String,
+    /// The name of the field in the schema.
+    pub field_name: String,
+    /// The shape of the value of the field.
+    #[serde(rename = "type")]
+    pub type_: TypeShape,
+    /// The named fragment the field was selected through, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_fragment: Option<String>,
+}
+
+/// The shape of a value in the response.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TypeShape {
+    /// A built-in or custom scalar, by name.
+    Scalar {
+        /// The name of the scalar.
+        name: String,
+    },
+    /// An enum. The server may return values that were added after the schema was fetched.
+    Enum {
+        /// The name of the enum.
+        name: String,
+        /// The values known from the schema.
+        values: Vec<String>,
+    },
+    /// An object, interface or union, with the selected fields.
+    Composite(CompositeShape),
+    /// A value that can be null.
+    Nullable {
+        /// The shape of the value when it is not null.
+        of: Box<TypeShape>,
+    },
+    /// A list of values.
+    List {
+        /// The shape of the items.
+        of: Box<TypeShape>,
+    },
+}
+
+/// The fields selected on an object, interface or union.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompositeShape {
+    /// The name of the type in the schema.
+    pub type_name: String,
+    /// The fields that are present whatever the concrete type of the value.
+    pub fields: Vec<FieldShape>,
+    /// The fields selected through fragments on specific concrete types, by `__typename`.
+    pub variants: BTreeMap<String, Vec<FieldShape>>,
+}
+
+/// A place where a response does not match an [`OperationShape`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShapeMismatch {
+    /// Where the mismatch is, e.g. `hero/friends/0/name`.
+    pub path: String,
+    /// What was expected there.
+    pub message: String,
+}
+
+/// The name of the anonymous operation of a query, if it has one.
+const ANONYMOUS_OPERATION_NAME: &str = "Anonymous";
+
+/// Describes the response of an operation from `query`. If `operation_name` is `None`, the first operation in the document is used.
+pub fn describe_operation(
+    schema: &Schema,
+    query: &str,
+    operation_name: Option<&str>,
+) -> Result<OperationShape, CodegenError> {
+    let mut document = graphql_parser::parse_query(query)
+        .map_err(|err| CodegenError::QueryParse(err.to_string()))?;
+    codegen::name_anonymous_operation(&mut document, ANONYMOUS_OPERATION_NAME)?;
+
+    let operations = codegen::all_operations(&document);
+    let operation = match operation_name {
+        Some(name) => operations
+            .iter()
+            .find(|op| op.name == name)
+            .ok_or_else(|| {
+                CodegenError::Validation(format!(
+                    "There is no operation named {} in the query.",
+                    name
+                ))
+            })?,
+        None => operations.first().ok_or_else(|| {
+            CodegenError::Validation("The query does not define any operation.".to_string())
+        })?,
+    };
+
+    let mut context = QueryContext::new(schema.clone(), Default::default());
+    codegen::ingest_fragments(&mut context, &document)?;
+
+    let root_type = operation.root_name(schema).ok_or_else(|| {
+        CodegenError::Validation(format!(
+            "The schema does not define a root type for the {} operation.",
+            operation.name
+        ))
+    })?;
+
+    operation.validate_selection_not_empty()?;
+    operation.validate_condition_variables(&context)?;
+    operation.validate_argument_variables(&context)?;
+    operation.validate_list_item_variables(&context)?;
+
+    if operation.is_subscription() {
+        operation.validate_subscription_root(&context)?;
+    }
+
+    let composite = describe_composite(
+        &context,
+        &root_type,
+        &operation.selection,
+        None,
+        &mut Vec::new(),
+    )?;
+
+    Ok(OperationShape {
+        name: operation.name.clone(),
+        root_type,
+        fields: composite.fields,
+    })
+}
+
+impl OperationShape {
+    /// Checks that the `data` of a response has the described shape. Custom scalars accept any value.
+    pub fn validate(&self, data: &Value) -> Result<(), Vec<ShapeMismatch>> {
+        let mut mismatches = Vec::new();
+        validate_fields(&self.fields, data, "", &mut mismatches);
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+
+    /// Renders the resolved selection as an indented tree, one field per line with its type in GraphQL syntax. Fields selected through a named fragment are marked with the fragment, and the fields only selected on some concrete types are listed under `on <Type>`.
+    pub fn explain(&self) -> String {
+        let mut out = format!("{}: {}\n", self.name, self.root_type);
+        explain_fields(&self.fields, 1, &mut out);
+        out
+    }
+}
+
+/// Describes and explains every operation in `query`, or only the one named `operation_name`. See [`OperationShape::explain`].
+pub fn explain_operations(
+    schema: &Schema,
+    query: &str,
+    operation_name: Option<&str>,
+) -> Result<String, CodegenError> {
+    let names: Vec<String> = match operation_name {
+        Some(name) => vec![name.to_string()],
+        None => {
+            let mut document = graphql_parser::parse_query(query)
+                .map_err(|err| CodegenError::QueryParse(err.to_string()))?;
+            codegen::name_anonymous_operation(&mut document, ANONYMOUS_OPERATION_NAME)?;
+            codegen::all_operations(&document)
+                .into_iter()
+                .map(|operation| operation.name)
+                .collect()
+        }
+    };
+
+    let mut explanations = Vec::with_capacity(names.len());
+    for name in &names {
+        explanations.push(describe_operation(schema, query, Some(name))?.explain());
+    }
+
+    Ok(explanations.join("\n"))
+}
+
+fn explain_fields(fields: &[FieldShape], depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+
+    for field in fields {
+        out.push_str(&indent);
+        out.push_str(&field.response_key);
+        if field.response_key != field.field_name {
+            out.push_str(&format!(" ({})", field.field_name));
+        }
+        out.push_str(&format!(": {}", field.type_.graphql_type()));
+        if let Some(fragment) = &field.from_fragment {
+            out.push_str(&format!(" [from fragment {}]", fragment));
+        }
+        out.push('\n');
+
+        if let Some(composite) = field.type_.composite() {
+            explain_fields(&composite.fields, depth + 1, out);
+            for (on, variant_fields) in &composite.variants {
+                out.push_str(&format!("{}  on {}:\n", indent, on));
+                explain_fields(variant_fields, depth + 2, out);
+            }
+        }
+    }
+}
+
+impl TypeShape {
+    /// The type in GraphQL syntax, e.g. `[Character]!`.
+    fn graphql_type(&self) -> String {
+        match self {
+            TypeShape::Nullable { of } => {
+                let inner = of.graphql_type();
+                inner.trim_end_matches('!').to_string()
+            }
+            TypeShape::List { of } => format!("[{}]!", of.graphql_type()),
+            TypeShape::Scalar { name } | TypeShape::Enum { name, .. } => format!("{}!", name),
+            TypeShape::Composite(composite) => format!("{}!", composite.type_name),
+        }
+    }
+
+    /// The composite at the bottom of the lists and nullable wrappers, if any.
+    fn composite(&self) -> Option<&CompositeShape> {
+        match self {
+            TypeShape::Nullable { of } | TypeShape::List { of } => of.composite(),
+            TypeShape::Composite(composite) => Some(composite),
+            TypeShape::Scalar { .. } | TypeShape::Enum { .. } => None,
+        }
+    }
+}
+
+fn schema_fields<'a>(
+    context: &'a QueryContext,
+    type_name: &str,
+) -> Result<&'a [GqlObjectField], CodegenError> {
+    if let Some(object) = context.schema.objects.get(type_name) {
+        return Ok(&object.fields);
+    }
+
+    if let Some(interface) = context.schema.interfaces.get(type_name) {
+        return Ok(&interface.fields);
+    }
+
+    if context.schema.unions.contains_key(type_name) {
+        return Ok(&[]);
+    }
+
+    Err(CodegenError::UnknownType {
+        ty: type_name.to_string(),
+    })
+}
+
+/// Whether a fragment on `on` applies to every value of `type_name`.
+fn applies_to_all(context: &QueryContext, on: &str, type_name: &str) -> bool {
+    on == type_name
+        || context
+            .schema
+            .interfaces
+            .get(on)
+            .map(|iface| iface.implemented_by.contains(type_name))
+            .unwrap_or(false)
+}
+
+fn describe_composite<'a>(
+    context: &'a QueryContext,
+    type_name: &str,
+    selection: &'a Selection,
+    fragment: Option<&str>,
+    expanding: &mut Vec<&'a str>,
+) -> Result<CompositeShape, CodegenError> {
+    let mut shape = CompositeShape {
+        type_name: type_name.to_string(),
+        fields: Vec::new(),
+        variants: BTreeMap::new(),
+    };
+    collect_fields(
+        context, type_name, selection, fragment, expanding, &mut shape,
+    )?;
+    Ok(shape)
+}
+
+/// Collects the fields of the selection on `type_name` in `shape`. `fragment` is the named fragment the selection belongs to, if any, and `expanding` the named fragments being expanded around the selection, to reject the spreads that would expand forever.
+fn collect_fields<'a>(
+    context: &'a QueryContext,
+    type_name: &str,
+    selection: &'a Selection,
+    fragment: Option<&str>,
+    expanding: &mut Vec<&'a str>,
+    shape: &mut CompositeShape,
+) -> Result<(), CodegenError> {
+    for item in &selection.0 {
+        match item {
+            SelectionItem::Field(field) => {
+                let field_type = if field.name == TYPENAME_FIELD {
+                    FieldType::Named(::constants::string_type())
+                } else {
+                    let schema_fields = schema_fields(context, type_name)?;
+                    let schema_field = schema_fields
+                        .iter()
+                        .find(|f| f.name == field.name)
+                        .ok_or_else(|| {
+                            ::shared::unknown_field_error(&field.name, type_name, schema_fields)
+                        })?;
+                    ::shared::selected_field_type(field, schema_field)
+                };
+
+                shape.fields.push(FieldShape {
+                    response_key: field.alias.clone().unwrap_or_else(|| field.name.clone()),
+                    field_name: field.name.clone(),
+                    type_: describe_type(context, &field_type, &field.fields, expanding)?,
+                    from_fragment: fragment.map(String::from),
+                });
+            }
+            SelectionItem::FragmentSpread(spread) => {
+                let fragment = context
+                    .fragments
+                    .get(&spread.fragment_name)
+                    .ok_or_else(|| CodegenError::UnknownFragment {
+                        name: spread.fragment_name.clone(),
+                    })?;
+                ::shared::check_fragment_spread(
+                    &fragment.on,
+                    type_name,
+                    Some(&fragment.name),
+                    context,
+                )?;
+                if expanding.contains(&fragment.name.as_str()) {
+                    Err(::fragments::spread_cycle_error(expanding, &fragment.name))?
+                }
+
+                expanding.push(&fragment.name);
+                collect_refined_fields(
+                    context,
+                    type_name,
+                    &fragment.on,
+                    &fragment.selection,
+                    Some(&fragment.name),
+                    expanding,
+                    shape,
+                )?;
+                expanding.pop();
+            }
+            SelectionItem::InlineFragment(inline) => {
+                ::shared::check_fragment_spread(&inline.on, type_name, None, context)?;
+                collect_refined_fields(
+                    context,
+                    type_name,
+                    &inline.on,
+                    &inline.fields,
+                    fragment,
+                    expanding,
+                    shape,
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the fields of a fragment on `on` in `shape`. When the fragment only applies to some of the values of `type_name`, its fields go to the variants of the concrete types both can be, since the variants are looked up by `__typename`.
+fn collect_refined_fields<'a>(
+    context: &'a QueryContext,
+    type_name: &str,
+    on: &str,
+    selection: &'a Selection,
+    fragment: Option<&str>,
+    expanding: &mut Vec<&'a str>,
+    shape: &mut CompositeShape,
+) -> Result<(), CodegenError> {
+    if applies_to_all(context, on, type_name) {
+        return collect_fields(context, type_name, selection, fragment, expanding, shape);
+    }
+
+    let refined = describe_composite(context, on, selection, fragment, expanding)?;
+    let possible = context.schema.possible_types(type_name);
+    for concrete in context.schema.possible_types(on) {
+        if possible.contains(&concrete) {
+            shape
+                .variants
+                .entry(concrete.to_string())
+                .or_default()
+                .extend(refined.fields.iter().cloned());
+        }
+    }
+    Ok(())
+}
+
+fn describe_type<'a>(
+    context: &'a QueryContext,
+    field_type: &FieldType,
+    selection: &'a Selection,
+    expanding: &mut Vec<&'a str>,
+) -> Result<TypeShape, CodegenError> {
+    match field_type {
+        FieldType::Optional(inner) => Ok(TypeShape::Nullable {
+            of: Box::new(describe_type(context, inner, selection, expanding)?),
+        }),
+        FieldType::Vector(inner) => Ok(TypeShape::List {
+            of: Box::new(describe_type(context, inner, selection, expanding)?),
+        }),
+        FieldType::Named(name) => {
+            if DEFAULT_SCALARS.contains(&name.as_str()) || context.schema.scalars.contains_key(name)
+            {
+                return Ok(TypeShape::Scalar { name: name.clone() });
+            }
+
+            if let Some(enm) = context.schema.enums.get(name) {
+                return Ok(TypeShape::Enum {
+                    name: name.clone(),
+                    values: enm.variants.iter().map(|v| v.name.clone()).collect(),
+                });
+            }
+
+            describe_composite(context, name, selection, None, expanding).map(TypeShape::Composite)
+        }
+    }
+}
+
+fn join_path(path: &str, segment: &str) -> String {
+    if path.is_empty() {
+        segment.to_string()
+    } else {
+        format!("{}/{}", path, segment)
+    }
+}
+
+fn validate_fields(
+    fields: &[FieldShape],
+    value: &Value,
+    path: &str,
+    mismatches: &mut Vec<ShapeMismatch>,
+) {
+    let object = match value.as_object() {
+        Some(object) => object,
+        None => {
+            mismatches.push(ShapeMismatch {
+                path: path.to_string(),
+                message: "expected an object".to_string(),
+            });
+            return;
+        }
+    };
+
+    for field in fields {
+        let field_path = join_path(path, &field.response_key);
+        match object.get(&field.response_key) {
+            Some(field_value) => validate_value(&field.type_, field_value, &field_path, mismatches),
+            None => mismatches.push(ShapeMismatch {
+                path: field_path,
+                message: "missing field".to_string(),
+            }),
+        }
+    }
+}
+
+fn validate_value(
+    shape: &TypeShape,
+    value: &Value,
+    path: &str,
+    mismatches: &mut Vec<ShapeMismatch>,
+) {
+    let mut mismatch = |message: &str| {
+        mismatches.push(ShapeMismatch {
+            path: path.to_string(),
+            message: message.to_string(),
+        })
+    };
+
+    match shape {
+        TypeShape::Nullable { of } => {
+            if !value.is_null() {
+                validate_value(of, value, path, mismatches)
+            }
+        }
+        _ if value.is_null() => mismatch("unexpected null"),
+        TypeShape::List { of } => match value.as_array() {
+            Some(items) => {
+                for (idx, item) in items.iter().enumerate() {
+                    validate_value(of, item, &join_path(path, &idx.to_string()), mismatches)
+                }
+            }
+            None => mismatch("expected a list"),
+        },
+        TypeShape::Enum { .. } => {
+            if !value.is_string() {
+                mismatch("expected an enum value")
+            }
+        }
+        TypeShape::Scalar { name } => {
+            let matches = match name.as_str() {
+                "String" => value.is_string(),
+                "ID" => value.is_string() || value.is_i64(),
+                "Int" => value.is_i64(),
+                "Float" => value.is_number(),
+                "Boolean" => value.is_boolean(),
+                _ => true,
+            };
+            if !matches {
+                mismatch(&format!("expected a value of type {}", name))
+            }
+        }
+        TypeShape::Composite(composite) => {
+            validate_fields(&composite.fields, value, path, mismatches);
+            let variant = value
+                .get(TYPENAME_FIELD)
+                .and_then(|typename| typename.as_str())
+                .and_then(|typename| composite.variants.get(typename));
+            if let Some(variant_fields) = variant {
+                validate_fields(variant_fields, value, path, mismatches);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = include_str!("tests/star_wars_schema.graphql");
+
+    const QUERY: &str = r#"
+        query HeroQuery {
+          hero {
+            __typename
+            name
+            ... on Droid {
+              primaryFunction
+            }
+            friends {
+              name
+            }
+          }
+        }
+    "#;
+
+    fn hero_shape() -> OperationShape {
+        let schema = Schema::from_sdl(SCHEMA).unwrap();
+        describe_operation(&schema, QUERY, None).unwrap()
+    }
+
+    #[test]
+    fn describe_operation_works() {
+        let shape = hero_shape();
+
+        assert_eq!(shape.name, "HeroQuery");
+        assert_eq!(shape.root_type, "Query");
+        assert_eq!(shape.fields.len(), 1);
+
+        let hero = match &shape.fields[0].type_ {
+            TypeShape::Nullable { of } => match of.as_ref() {
+                TypeShape::Composite(composite) => composite.clone(),
+                other => panic!("unexpected shape: {:?}", other),
+            },
+            other => panic!("unexpected shape: {:?}", other),
+        };
+
+        assert_eq!(hero.type_name, "Character");
+        let keys: Vec<&str> = hero
+            .fields
+            .iter()
+            .map(|f| f.response_key.as_str())
+            .collect();
+        assert_eq!(keys, vec!["__typename", "name", "friends"]);
+        assert_eq!(hero.variants["Droid"][0].field_name, "primaryFunction");
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let schema = Schema::from_sdl(SCHEMA).unwrap();
+        let err = describe_operation(&schema, "query Q { hero { nickname } }", None).unwrap_err();
+
+        match err {
+            CodegenError::UnknownField { field, .. } => assert_eq!(field, "nickname"),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validate_accepts_matching_response() {
+        let data = json!({
+            "hero": {
+                "__typename": "Droid",
+                "name": "R2-D2",
+                "primaryFunction": "Astromech",
+                "friends": [{ "name": "Luke Skywalker" }, null],
+            }
+        });
+
+        assert_eq!(hero_shape().validate(&data), Ok(()));
+    }
+
+    #[test]
+    fn validate_reports_mismatches_with_their_path() {
+        let data = json!({
+            "hero": {
+                "__typename": "Droid",
+                "name": 2,
+                "friends": [{}],
+            }
+        });
+
+        let paths: Vec<String> = hero_shape()
+            .validate(&data)
+            .unwrap_err()
+            .into_iter()
+            .map(|mismatch| mismatch.path)
+            .collect();
+
+        assert_eq!(
+            paths,
+            vec!["hero/name", "hero/friends/0/name", "hero/primaryFunction"]
+        );
+    }
+
+    #[test]
+    fn the_root_typename_can_be_the_only_field() {
+        let schema = Schema::from_sdl(SCHEMA).unwrap();
+        let shape = describe_operation(&schema, "{ __typename }", None).unwrap();
+
+        assert_eq!(shape.fields.len(), 1);
+        assert_eq!(shape.fields[0].response_key, "__typename");
+        assert_eq!(
+            shape.fields[0].type_,
+            TypeShape::Scalar {
+                name: "String".to_string()
+            }
+        );
+        assert_eq!(shape.validate(&json!({ "__typename": "Query" })), Ok(()));
+    }
+
+    #[test]
+    fn explain_renders_the_resolved_selection_tree() {
+        let schema = Schema::from_sdl(SCHEMA).unwrap();
+        let query = r#"
+            query HeroQuery {
+              hero {
+                __typename
+                ...CharacterFields
+                ...DroidFields
+                best: friends {
+                  name
+                }
+              }
+            }
+
+            fragment CharacterFields on Character {
+              name
+              appearsIn
+            }
+
+            fragment DroidFields on Droid {
+              primaryFunction
+            }
+        "#;
+
+        assert_eq!(
+            explain_operations(&schema, query, None).unwrap(),
+            [
+                "HeroQuery: Query",
+                "  hero: Character",
+                "    __typename: String!",
+                "    name: String! [from fragment CharacterFields]",
+                "    appearsIn: [Episode]! [from fragment CharacterFields]",
+                "    best (friends): [Character]",
+                "      name: String!",
+                "    on Droid:",
+                "      primaryFunction: String [from fragment DroidFields]",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn fragments_spreading_themselves_are_rejected() {
+        let schema = Schema::from_sdl(SCHEMA).unwrap();
+        let query = r#"
+            query HeroQuery {
+              hero {
+                ...HeroFields
+              }
+            }
+
+            fragment HeroFields on Character {
+              name
+              friends {
+                ...FriendFields
+              }
+            }
+
+            fragment FriendFields on Character {
+              friends {
+                ...HeroFields
+              }
+            }
+        "#;
+
+        match describe_operation(&schema, query, None) {
+            Err(err @ CodegenError::Validation(_)) => assert_eq!(
+                format!("{}", err),
+                "The FriendFields fragment spreads itself through FriendFields -> HeroFields -> FriendFields. Fragment spreads must not form cycles."
+            ),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn fragments_on_interfaces_are_variants_of_each_implementor() {
+        let schema = Schema::from_sdl(SCHEMA).unwrap();
+        let query = r#"
+            query SearchQuery {
+              search(text: "R2") {
+                __typename
+                ... on Character {
+                  name
+                }
+              }
+            }
+        "#;
+        let shape = describe_operation(&schema, query, None).unwrap();
+
+        let result = shape.fields[0].type_.composite().unwrap();
+        let variants: Vec<&str> = result.variants.keys().map(String::as_str).collect();
+        assert_eq!(variants, vec!["Droid", "Human"]);
+
+        let data = json!({
+            "search": [
+                { "__typename": "Droid", "name": "R2-D2" },
+                { "__typename": "Human" },
+                { "__typename": "Starship" },
+            ]
+        });
+        let mismatches = shape.validate(&data).unwrap_err();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].path, "search/1/name");
+    }
+}
@@ -1,12 +1,119 @@
+use constants::{ENUM_CODE_DIRECTIVE, ENUM_UNKNOWN_DEFAULT_DIRECTIVE};
+use error::CodegenError;
+use graphql_parser::schema;
 use proc_macro2::{Ident, Span, TokenStream};
 use std::cell::Cell;
+use std::convert::TryFrom;
+
+/// A method generated on the enums from the argument of a directive on their values, configured with the `enum_value_methods` option.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumValueMethod {
+    /// The name of the directive on the values, without the `@`.
+    pub directive: String,
+    /// The argument of the directive returned by the method.
+    pub argument: String,
+}
+
+/// Parses the `enum_value_methods` option, `directive(argument)` pairs separated by commas, like `"weight(n), label(text)"`.
+pub(crate) fn parse_enum_value_methods(option: &str) -> Result<Vec<EnumValueMethod>, CodegenError> {
+    let mut methods: Vec<EnumValueMethod> = Vec::new();
+
+    for entry in option.split(',') {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            continue;
+        }
+
+        let method = match (entry.find('('), entry.ends_with(')')) {
+            (Some(idx), true) => EnumValueMethod {
+                directive: entry[..idx].trim().trim_start_matches('@').to_string(),
+                argument: entry[idx + 1..entry.len() - 1].trim().to_string(),
+            },
+            _ => Err(CodegenError::Validation(format!(
+                "Expected `directive(argument)` in enum_value_methods, got `{}`.",
+                entry
+            )))?,
+        };
+
+        if method.directive.is_empty() || method.argument.is_empty() {
+            Err(CodegenError::Validation(format!(
+                "Expected `directive(argument)` in enum_value_methods, got `{}`.",
+                entry
+            )))?
+        }
+        if method.directive == ENUM_CODE_DIRECTIVE {
+            Err(CodegenError::Validation(format!(
+                "The @{} directive cannot be in enum_value_methods, it already generates the `code` method.",
+                ENUM_CODE_DIRECTIVE
+            )))?
+        }
+        if methods.iter().any(|m| m.directive == method.directive) {
+            Err(CodegenError::Validation(format!(
+                "The @{} directive is in enum_value_methods more than once.",
+                method.directive
+            )))?
+        }
+
+        methods.push(method);
+    }
+
+    Ok(methods)
+}
 
 pub const ENUMS_PREFIX: &str = "";
 
+/// What the generated enums do with the values that are not in the schema, e.g. values added to the schema after the code was generated.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub enum EnumUnknowns {
+    /// Capture them in an `Other(String)` variant (default).
+    #[default]
+    Fallback,
+    /// Deserialize them to a default variant: the value marked with the `@unknownDefault` directive in the schema, or the first value. There is no `Other` variant.
+    Default,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct EnumVariant {
     pub description: Option<String>,
     pub name: String,
+    /// The directives applied to the value in the schema. Introspection does not expose them, so this is always empty for JSON schemas.
+    pub directives: Vec<schema::Directive>,
+}
+
+impl EnumVariant {
+    /// The `n` argument of the `@code` directive on this value, if there is one.
+    fn code(&self) -> Result<Option<i32>, CodegenError> {
+        let directive = match self
+            .directives
+            .iter()
+            .find(|directive| directive.name == ENUM_CODE_DIRECTIVE)
+        {
+            Some(directive) => directive,
+            None => return Ok(None),
+        };
+
+        match directive.arguments.iter().find(|(name, _)| name == "n") {
+            Some((_, schema::Value::Int(number))) => {
+                let n = number.as_i64().ok_or_else(|| {
+                    CodegenError::Validation(format!(
+                        "The `n` argument of the @{} directive on the {} enum value does not fit in an i32.",
+                        ENUM_CODE_DIRECTIVE, self.name
+                    ))
+                })?;
+                match i32::try_from(n) {
+                    Ok(n) => Ok(Some(n)),
+                    Err(_) => Err(CodegenError::Validation(format!(
+                        "The `n` argument of the @{} directive on the {} enum value does not fit in an i32: {}.",
+                        ENUM_CODE_DIRECTIVE, self.name, n
+                    ))),
+                }
+            }
+            _ => Err(CodegenError::Validation(format!(
+                "The @{} directive on the {} enum value must have an integer `n` argument",
+                ENUM_CODE_DIRECTIVE, self.name
+            ))),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -18,8 +125,33 @@ pub struct GqlEnum {
 }
 
 impl GqlEnum {
-    pub(crate) fn to_rust(&self, query_context: &::query::QueryContext) -> TokenStream {
+    /// The value that unknown values are deserialized to with `enum_unknowns = "default"`: the one marked with the `@unknownDefault` directive, or the first one.
+    fn unknown_default(&self) -> Result<&EnumVariant, CodegenError> {
+        let mut marked = self.variants.iter().filter(|v| {
+            v.directives
+                .iter()
+                .any(|directive| directive.name == ENUM_UNKNOWN_DEFAULT_DIRECTIVE)
+        });
+
+        match (marked.next(), marked.next()) {
+            (Some(first), Some(second)) => Err(CodegenError::Validation(format!(
+                "Both the {} and {} values of {} have the @{} directive. Only one value can be the default for unknown values.",
+                first.name, second.name, self.name, ENUM_UNKNOWN_DEFAULT_DIRECTIVE
+            ))),
+            (Some(variant), None) => Ok(variant),
+            (None, _) => self.variants.first().ok_or_else(|| {
+                CodegenError::Validation(format!("The {} enum has no values.", self.name))
+            }),
+        }
+    }
+
+    pub(crate) fn to_rust(
+        &self,
+        query_context: &::query::QueryContext,
+    ) -> Result<TokenStream, CodegenError> {
         let derives = query_context.response_enum_derives();
+        let visibility = query_context.type_visibility();
+        let serde = query_context.serde_path();
         let variant_names: Vec<TokenStream> = self
             .variants
             .iter()
@@ -46,32 +178,508 @@ impl GqlEnum {
 
         let name = name_ident.clone();
 
-        quote! {
-            #derives
-            pub enum #name {
-                #(#variant_names,)*
-                Other(String),
+        // With `enum_unknowns = "default"`, unknown values go to an existing variant instead of `Other`.
+        let (other_variant, other_display, unknown_value) = match query_context.enum_unknowns {
+            EnumUnknowns::Fallback => (
+                quote!(Other(String),),
+                quote!(#name::Other(ref s) => &s,),
+                quote!(#name::Other(s.to_string())),
+            ),
+            EnumUnknowns::Default => {
+                let default = Ident::new(&self.unknown_default()?.name, Span::call_site());
+                (quote!(), quote!(), quote!(#name::#default))
+            }
+        };
+
+        // With the `case_insensitive_enums` option, the value is replaced by the spelling of the schema before parsing.
+        let case_folding = if query_context.case_insensitive_enums {
+            quote! {
+                const VALUES: &[&str] = &[#(#variant_str,)*];
+                let s = if VALUES.contains(&s.as_str()) {
+                    s
+                } else {
+                    match VALUES.iter().find(|value| value.eq_ignore_ascii_case(&s)) {
+                        Some(value) => value.to_string(),
+                        None => s,
+                    }
+                };
+            }
+        } else {
+            quote!()
+        };
+
+        let code_method = self.code_method(&name, query_context)?;
+        let value_methods: Vec<TokenStream> = query_context
+            .enum_value_methods
+            .iter()
+            .map(|method| self.value_method(&name, method, query_context))
+            .collect::<Result<_, _>>()?;
+
+        let estimated_size = match query_context.enum_unknowns {
+            EnumUnknowns::Fallback if query_context.estimated_size => quote! {
+                impl ::graphql_client::estimated_size::EstimatedSize for #name {
+                    fn heap_size(&self) -> usize {
+                        match self {
+                            #name::Other(s) => s.capacity(),
+                            _ => 0,
+                        }
+                    }
+                }
+            },
+            _ if query_context.estimated_size => quote! {
+                impl ::graphql_client::estimated_size::EstimatedSize for #name {
+                    fn heap_size(&self) -> usize {
+                        0
+                    }
+                }
+            },
+            _ => quote!(),
+        };
+
+        // With the `test_defaults` option, the first value is the default.
+        let default_impl = match constructors.first() {
+            Some(first) if query_context.test_defaults => quote! {
+                impl Default for #name {
+                    fn default() -> Self {
+                        #first
+                    }
+                }
+            },
+            _ => quote!(),
+        };
+
+        let all_variants = if query_context.enum_all_variants {
+            quote! {
+                impl #name {
+                    /// Every value of the enum known from the schema, in the order of the schema. `Other` is not included.
+                    pub fn all_variants() -> &'static [#name] {
+                        const ALL_VARIANTS: &'static [#name] = &[#(#constructors,)*];
+                        ALL_VARIANTS
+                    }
+                }
             }
+        } else {
+            quote!()
+        };
+
+        let description = self.description.as_ref().map(|d| quote!(#[doc = #d]));
+        let definition = query_context.transform_type(
+            &self.name,
+            quote! {
+                #description
+                #derives
+                #visibility enum #name {
+                    #(#variant_names,)*
+                    #other_variant
+                }
+            },
+        );
 
-            impl ::serde::Serialize for #name {
-                fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
-                    ser.serialize_str(match *self {
+        Ok(quote! {
+            #definition
+
+            impl ::std::fmt::Display for #name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    f.write_str(match *self {
                         #(#constructors => #variant_str,)*
-                        #name::Other(ref s) => &s,
+                        #other_display
                     })
                 }
             }
 
-            impl<'de> ::serde::Deserialize<'de> for #name {
-                fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-                    let s = <String>::deserialize(deserializer)?;
+            impl ::std::str::FromStr for #name {
+                type Err = ::std::string::ParseError;
 
-                    match s.as_str() {
+                fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                    match s {
                         #(#variant_str => Ok(#constructors),)*
-                        _ => Ok(#name::Other(s)),
+                        _ => Ok(#unknown_value),
+                    }
+                }
+            }
+
+            // Serde goes through `Display` and `FromStr`, so both always agree on the GraphQL spelling of the values.
+            impl #serde::Serialize for #name {
+                fn serialize<S: #serde::Serializer>(&self, ser: S) -> ::std::result::Result<S::Ok, S::Error> {
+                    ser.collect_str(self)
+                }
+            }
+
+            impl<'de> #serde::Deserialize<'de> for #name {
+                fn deserialize<D: #serde::Deserializer<'de>>(deserializer: D) -> ::std::result::Result<Self, D::Error> {
+                    let s = <::std::string::String as #serde::Deserialize>::deserialize(deserializer)?;
+                    #case_folding
+
+                    match s.parse() {
+                        Ok(value) => Ok(value),
+                        Err(never) => match never {},
                     }
                 }
             }
+
+            #code_method
+
+            #(#value_methods)*
+
+            #default_impl
+
+            #all_variants
+
+            #estimated_size
+        })
+    }
+
+    /// Generates the `code` method when the values of the enum are annotated with the `@code` directive in the schema.
+    ///
+    /// It is an error to annotate only some of the values.
+    fn code_method(
+        &self,
+        name: &Ident,
+        query_context: &::query::QueryContext,
+    ) -> Result<TokenStream, CodegenError> {
+        let codes: Vec<Option<i32>> = self
+            .variants
+            .iter()
+            .map(|v| v.code())
+            .collect::<Result<_, _>>()?;
+
+        if codes.iter().all(|code| code.is_none()) {
+            return Ok(quote!());
         }
+
+        let arms: Vec<TokenStream> = self
+            .variants
+            .iter()
+            .zip(codes.iter())
+            .map(|(variant, code)| {
+                let code = code.ok_or_else(|| {
+                    CodegenError::Validation(format!(
+                        "The {} enum value of {} is missing the @{} directive. Either all or none of the values must have it.",
+                        variant.name,
+                        self.name,
+                        ENUM_CODE_DIRECTIVE
+                    ))
+                })?;
+                let variant = Ident::new(&variant.name, Span::call_site());
+                Ok(quote!(#name::#variant => Some(#code)))
+            })
+            .collect::<Result<_, CodegenError>>()?;
+        let other_arm = match query_context.enum_unknowns {
+            EnumUnknowns::Fallback => quote!(#name::Other(_) => None,),
+            EnumUnknowns::Default => quote!(),
+        };
+
+        Ok(quote! {
+            impl #name {
+                /// The numeric code attached to the value with the `@code` directive in the schema. `None` for unknown values.
+                pub fn code(&self) -> Option<i32> {
+                    match *self {
+                        #(#arms,)*
+                        #other_arm
+                    }
+                }
+            }
+        })
+    }
+
+    /// Generates a method returning the argument of a directive configured with the `enum_value_methods` option, when the values of the enum have it. The return type follows the literals: `i32` for integers, `f64` for floats, `&'static str` for strings and `bool` for booleans, wrapped in an `Option` when there is an `Other` variant.
+    ///
+    /// Like for `@code`, it is an error to annotate only some of the values.
+    fn value_method(
+        &self,
+        name: &Ident,
+        method: &EnumValueMethod,
+        query_context: &::query::QueryContext,
+    ) -> Result<TokenStream, CodegenError> {
+        let values: Vec<Option<&schema::Value>> = self
+            .variants
+            .iter()
+            .map(|variant| {
+                let directive = match variant
+                    .directives
+                    .iter()
+                    .find(|directive| directive.name == method.directive)
+                {
+                    Some(directive) => directive,
+                    None => return Ok(None),
+                };
+
+                match directive
+                    .arguments
+                    .iter()
+                    .find(|(name, _)| name == &method.argument)
+                {
+                    Some((_, value)) => Ok(Some(value)),
+                    None => Err(CodegenError::Validation(format!(
+                        "The @{} directive on the {} enum value is missing the `{}` argument.",
+                        method.directive, variant.name, method.argument
+                    ))),
+                }
+            })
+            .collect::<Result<_, CodegenError>>()?;
+
+        if values.iter().all(|value| value.is_none()) {
+            return Ok(quote!());
+        }
+
+        let mut values_with_variants = Vec::with_capacity(values.len());
+        for (variant, value) in self.variants.iter().zip(values) {
+            match value {
+                Some(value) => values_with_variants.push((variant, value)),
+                None => Err(CodegenError::Validation(format!(
+                    "The {} enum value of {} is missing the @{} directive. Either all or none of the values must have it.",
+                    variant.name, self.name, method.directive
+                )))?,
+            }
+        }
+
+        let is_float = values_with_variants
+            .iter()
+            .any(|(_, value)| matches!(value, schema::Value::Float(_)));
+        let mut ty: Option<TokenStream> = None;
+        let mut arms = Vec::with_capacity(values_with_variants.len());
+        for (variant, value) in values_with_variants {
+            let (value_ty, literal) = match value {
+                schema::Value::Int(n) if is_float => {
+                    let n = n.as_i64().unwrap_or_default() as f64;
+                    (quote!(f64), quote!(#n))
+                }
+                schema::Value::Float(n) => (quote!(f64), quote!(#n)),
+                schema::Value::Int(n) => match n.as_i64().filter(|n| *n as i32 as i64 == *n) {
+                    Some(n) => {
+                        let n = n as i32;
+                        (quote!(i32), quote!(#n))
+                    }
+                    None => Err(CodegenError::Validation(format!(
+                        "The `{}` argument of the @{} directive on the {} enum value does not fit in an i32.",
+                        method.argument, method.directive, variant.name
+                    )))?,
+                },
+                schema::Value::String(s) => (quote!(&'static str), quote!(#s)),
+                schema::Value::Boolean(b) => (quote!(bool), quote!(#b)),
+                _ => Err(CodegenError::Validation(format!(
+                    "The `{}` argument of the @{} directive on the {} enum value must be an Int, a Float, a String or a Boolean.",
+                    method.argument, method.directive, variant.name
+                )))?,
+            };
+
+            match &ty {
+                Some(ty) if ty.to_string() != value_ty.to_string() => Err(CodegenError::Validation(format!(
+                    "The `{}` arguments of the @{} directive on the values of {} have different types.",
+                    method.argument, method.directive, self.name
+                )))?,
+                _ => ty = Some(value_ty),
+            }
+
+            let variant = Ident::new(&variant.name, Span::call_site());
+            arms.push((variant, literal));
+        }
+
+        let method_name = ::shared::field_ident(&method.directive);
+        let doc = format!(
+            "The `{}` argument of the `@{}` directive on the value in the schema.",
+            method.argument, method.directive
+        );
+        let (ty, arms, other_arm, doc) = match query_context.enum_unknowns {
+            EnumUnknowns::Fallback => {
+                let arms: Vec<TokenStream> = arms
+                    .into_iter()
+                    .map(|(variant, literal)| quote!(#name::#variant => Some(#literal)))
+                    .collect();
+                (
+                    quote!(Option<#ty>),
+                    arms,
+                    quote!(#name::Other(_) => None,),
+                    format!("{} `None` for unknown values.", doc),
+                )
+            }
+            EnumUnknowns::Default => {
+                let arms: Vec<TokenStream> = arms
+                    .into_iter()
+                    .map(|(variant, literal)| quote!(#name::#variant => #literal))
+                    .collect();
+                (quote!(#ty), arms, quote!(), doc)
+            }
+        };
+
+        Ok(quote! {
+            impl #name {
+                #[doc = #doc]
+                pub fn #method_name(&self) -> #ty {
+                    match *self {
+                        #(#arms,)*
+                        #other_arm
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphql_parser;
+    use query::QueryContext;
+    use schema::Schema;
+
+    fn status_enum(schema: &str) -> GqlEnum {
+        let schema = Schema::from(graphql_parser::parse_schema(schema).unwrap());
+        schema.enums.get("Status").unwrap().clone()
+    }
+
+    #[test]
+    fn code_directive_is_captured_on_enum_values() {
+        let enm = status_enum("enum Status { ACTIVE @code(n: 1) DELETED @code(n: 3) }");
+
+        let codes: Vec<Option<i32>> = enm.variants.iter().map(|v| v.code().unwrap()).collect();
+
+        assert_eq!(codes, vec![Some(1), Some(3)]);
+    }
+
+    #[test]
+    fn enums_without_code_directive_have_no_code_method() {
+        let enm = status_enum("enum Status { ACTIVE DELETED }");
+        let context = QueryContext::new_empty();
+
+        let generated = enm.to_rust(&context).unwrap().to_string();
+
+        assert!(!generated.contains("fn code"));
+    }
+
+    #[test]
+    fn enum_type_description_is_a_doc_comment() {
+        let enm = status_enum(
+            r#"
+            "The status of an account."
+            enum Status {
+              "Can log in."
+              ACTIVE
+              DELETED
+            }
+            "#,
+        );
+        let context = QueryContext::new_empty();
+
+        let generated = enm.to_rust(&context).unwrap().to_string();
+
+        assert!(generated.starts_with("# [ doc = \"The status of an account.\" ]"));
+        assert!(generated.contains("# [ doc = \"Can log in.\" ] ACTIVE"));
+    }
+
+    #[test]
+    fn code_directive_must_be_on_every_value() {
+        let enm = status_enum("enum Status { ACTIVE @code(n: 1) DELETED }");
+        let context = QueryContext::new_empty();
+
+        let err = enm.to_rust(&context).unwrap_err();
+
+        assert_eq!(
+            format!("{}", err),
+            "The DELETED enum value of Status is missing the @code directive. Either all or none of the values must have it."
+        );
+    }
+
+    #[test]
+    fn code_directive_argument_must_be_an_integer() {
+        let enm = status_enum(r#"enum Status { ACTIVE @code(n: "one") }"#);
+        let context = QueryContext::new_empty();
+
+        let err = enm.to_rust(&context).unwrap_err();
+
+        assert_eq!(
+            format!("{}", err),
+            "The @code directive on the ACTIVE enum value must have an integer `n` argument"
+        );
+    }
+
+    #[test]
+    fn code_directive_argument_must_fit_in_an_i32() {
+        let enm = status_enum("enum Status { ACTIVE @code(n: 3000000000) }");
+        let context = QueryContext::new_empty();
+
+        let err = enm.to_rust(&context).unwrap_err();
+
+        assert_eq!(
+            format!("{}", err),
+            "The `n` argument of the @code directive on the ACTIVE enum value does not fit in an i32: 3000000000."
+        );
+    }
+
+    fn weight_context() -> QueryContext {
+        let mut context = QueryContext::new_empty();
+        context.enum_value_methods = parse_enum_value_methods("weight(n)").unwrap();
+        context
+    }
+
+    #[test]
+    fn value_methods_return_the_directive_argument() {
+        let enm = status_enum("enum Status { ACTIVE @weight(n: 1.5) DELETED @weight(n: 3) }");
+
+        let generated = enm.to_rust(&weight_context()).unwrap().to_string();
+
+        assert!(generated.contains("pub fn weight ( & self ) -> Option < f64 >"));
+        assert!(generated.contains("Status :: DELETED => Some ( 3f64 )"));
+    }
+
+    #[test]
+    fn value_method_directive_must_be_on_every_value() {
+        let enm = status_enum("enum Status { ACTIVE @weight(n: 1) DELETED }");
+
+        let err = enm.to_rust(&weight_context()).unwrap_err();
+
+        assert_eq!(
+            format!("{}", err),
+            "The DELETED enum value of Status is missing the @weight directive. Either all or none of the values must have it."
+        );
+    }
+
+    #[test]
+    fn value_method_arguments_must_have_the_same_type() {
+        let enm = status_enum(r#"enum Status { ACTIVE @weight(n: 1) DELETED @weight(n: "2") }"#);
+
+        let err = enm.to_rust(&weight_context()).unwrap_err();
+
+        assert_eq!(
+            format!("{}", err),
+            "The `n` arguments of the @weight directive on the values of Status have different types."
+        );
+    }
+
+    #[test]
+    fn value_methods_option_is_parsed() {
+        assert_eq!(
+            parse_enum_value_methods("weight(n), @label( text )").unwrap(),
+            vec![
+                EnumValueMethod {
+                    directive: "weight".to_string(),
+                    argument: "n".to_string(),
+                },
+                EnumValueMethod {
+                    directive: "label".to_string(),
+                    argument: "text".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            format!("{}", parse_enum_value_methods("weight").unwrap_err()),
+            "Expected `directive(argument)` in enum_value_methods, got `weight`."
+        );
+        assert_eq!(
+            format!("{}", parse_enum_value_methods("code(n)").unwrap_err()),
+            "The @code directive cannot be in enum_value_methods, it already generates the `code` method."
+        );
+    }
+
+    #[test]
+    fn unknown_default_directive_can_only_be_on_one_value() {
+        let enm = status_enum("enum Status { ACTIVE @unknownDefault DELETED @unknownDefault }");
+
+        let err = enm.unknown_default().unwrap_err();
+
+        assert_eq!(
+            format!("{}", err),
+            "Both the ACTIVE and DELETED values of Status have the @unknownDefault directive. Only one value can be the default for unknown values."
+        );
     }
 }
@@ -0,0 +1,75 @@
+use std::io;
+use std::path::PathBuf;
+
+/// The errors that can happen during code generation.
+#[derive(Debug, Error)]
+pub enum CodegenError {
+    /// A query or schema file could not be read.
+    #[error(
+        r#"
+            Could not find file with path: {}
+            Hint: file paths in the GraphQLQuery attribute are relative to the project root (location of the Cargo.toml). Example: query_path = "src/my_query.graphql".
+            "#,
+        path.display()
+    )]
+    Io {
+        /// The path of the file we tried to read.
+        path: PathBuf,
+        /// The underlying IO error.
+        #[source]
+        source: io::Error,
+    },
+    /// A query or schema file is neither valid UTF-8 nor valid UTF-16.
+    #[error("{} is not valid UTF-8 or UTF-16 text.", path.display())]
+    Encoding {
+        /// The path of the file.
+        path: PathBuf,
+    },
+    /// The query document is not valid GraphQL. The message contains the location of the error.
+    #[error("{0}")]
+    QueryParse(String),
+    /// The schema is not valid GraphQL SDL. The message contains the location of the error.
+    #[error("{0}")]
+    SchemaParse(String),
+    /// The JSON schema is not a valid introspection response.
+    #[error("Could not parse the JSON schema: {0}")]
+    JsonSchema(#[from] ::serde_json::Error),
+    /// A fragment spread refers to a fragment that is not defined in the query document.
+    #[error("Unknown fragment: {name}")]
+    UnknownFragment {
+        /// The name of the spread fragment.
+        name: String,
+    },
+    /// The selection refers to a type that is not defined in the schema.
+    #[error("Unknown type: {ty}")]
+    UnknownType {
+        /// The name of the type.
+        ty: String,
+    },
+    /// A field is selected that does not exist on the type.
+    #[error("Could not find field `{field}` on `{type_name}`. Available fields: `{available}`.")]
+    UnknownField {
+        /// The name of the selected field.
+        field: String,
+        /// The type the field was selected on.
+        type_name: String,
+        /// The fields that exist on the type, separated with backticks.
+        available: String,
+    },
+    /// A selection on a union or interface does not include `__typename`, which we need to tell the variants apart.
+    #[error("Missing __typename in selection for {selection_name}. Add `__typename` to the selection on the {type_name} {kind}: it is needed to tell the possible types apart.")]
+    MissingTypename {
+        /// A description of the selection.
+        selection_name: String,
+        /// The name of the union or interface.
+        type_name: String,
+        /// Either `union` or `interface`.
+        kind: &'static str,
+    },
+    /// The graphql-config file is invalid, or does not define the paths we need.
+    #[error("{0}")]
+    Config(String),
+    /// The query or schema is valid GraphQL, but is not supported or invalid for code generation.
+    #[error("{0}")]
+    Validation(String),
+}
@@ -0,0 +1,101 @@
+//! The `estimated_size` option: implementations of `graphql_client::estimated_size::EstimatedSize` for the response structs and enums, adding up the heap sizes of their fields and variants, so `estimated_size()` can be called on the response data.
+
+use deprecation::{DeprecationStatus, DeprecationStrategy};
+use heck::SnakeCase;
+use objects::GqlObjectField;
+use proc_macro2::{Ident, Span, TokenStream};
+use query::QueryContext;
+use selection::{Selection, SelectionItem};
+
+/// The `EstimatedSize` implementation of a response struct. `other_fields` are the fields generated outside of the selection, like the `on` enum of interface structs and the `extra` map of the `unknown_fields` option.
+pub(crate) fn struct_impl(
+    schema_fields: &[GqlObjectField],
+    context: &QueryContext,
+    struct_name: &Ident,
+    selection: &Selection,
+    prefix: &str,
+    other_fields: &[&str],
+) -> TokenStream {
+    if !context.estimated_size {
+        return quote!();
+    }
+
+    let fields = selection
+        .0
+        .iter()
+        .filter_map(|item| match item {
+            SelectionItem::Field(selected) => {
+                // Unknown fields are reported when generating the response struct itself.
+                let schema_field = schema_fields
+                    .iter()
+                    .find(|field| field.name == selected.name)?;
+                if let (DeprecationStatus::Deprecated(_), DeprecationStrategy::Deny) =
+                    (&schema_field.deprecation, &context.deprecation_strategy)
+                {
+                    return None;
+                }
+                if ::shared::is_skipped_external(schema_field, context) {
+                    return None;
+                }
+
+                Some(::shared::field_ident(
+                    selected.alias.as_ref().unwrap_or(&selected.name),
+                ))
+            }
+            SelectionItem::FragmentSpread(spread) => Some(Ident::new(
+                &spread.fragment_name.to_snake_case(),
+                Span::call_site(),
+            )),
+            SelectionItem::InlineFragment(inline) if context.interface_flatten => {
+                Some(::shared::flattened_interface_names(inline, prefix, context).0)
+            }
+            SelectionItem::InlineFragment(_) => None,
+        })
+        .chain(
+            other_fields
+                .iter()
+                .map(|field| Ident::new(field, Span::call_site())),
+        );
+
+    quote! {
+        impl ::graphql_client::estimated_size::EstimatedSize for #struct_name {
+            #[allow(deprecated)]
+            fn heap_size(&self) -> usize {
+                0 #(+ ::graphql_client::estimated_size::EstimatedSize::heap_size(&self.#fields))*
+            }
+        }
+    }
+}
+
+/// The `EstimatedSize` implementation of a union or interface enum, delegating to the struct of the variant. The variants without a selection hold nothing.
+pub(crate) fn enum_impl(
+    context: &QueryContext,
+    enum_name: &Ident,
+    selected_variants: &[String],
+    has_other_variants: bool,
+) -> TokenStream {
+    if !context.estimated_size {
+        return quote!();
+    }
+
+    let arms = selected_variants.iter().map(|variant| {
+        let variant_name = Ident::new(variant, Span::call_site());
+        quote!(#enum_name::#variant_name(value) => ::graphql_client::estimated_size::EstimatedSize::heap_size(value),)
+    });
+    let other_arm = if has_other_variants {
+        quote!(_ => 0,)
+    } else {
+        quote!()
+    };
+
+    quote! {
+        impl ::graphql_client::estimated_size::EstimatedSize for #enum_name {
+            fn heap_size(&self) -> usize {
+                match self {
+                    #(#arms)*
+                    #other_arm
+                }
+            }
+        }
+    }
+}
@@ -21,6 +21,9 @@ impl FieldType {
             prefix.to_string()
         };
         match &self {
+            FieldType::Named(ref name) if name == "String" && context.compact_str => {
+                quote!(::compact_str::CompactString)
+            }
             FieldType::Named(ref name) => {
                 let full_name = if context
                     .schema
@@ -195,4 +198,24 @@ mod tests {
         };
         assert_eq!(FieldType::from(ty), FieldType::Named("Cat".to_string()));
     }
+
+    #[test]
+    fn compact_str_replaces_the_string_scalar_inside_wrappers() {
+        let mut context = QueryContext::new_empty();
+        context.compact_str = true;
+        let ty = FieldType::Optional(Box::new(FieldType::Vector(Box::new(FieldType::Named(
+            "String".to_string(),
+        )))));
+
+        assert_eq!(
+            ty.to_rust(&context, "").to_string(),
+            "Option < Vec < :: compact_str :: CompactString > >"
+        );
+        assert_eq!(
+            FieldType::Named("ID".to_string())
+                .to_rust(&context, "")
+                .to_string(),
+            "ID"
+        );
+    }
 }
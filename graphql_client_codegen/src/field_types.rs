@@ -0,0 +1,113 @@
+//! The `field_types` option, mapping the fields of a loosely typed custom scalar, like `JSON`, to a Rust type of their own when they always hold the same shape.
+//!
+//! Each mapped field gets a scalar of its own in the schema the module is generated from, named after the type and the field, and its alias points to the given type. The field then has that type wherever it is selected, in the response structs as in the accessors or the optimistic structs.
+
+use error::CodegenError;
+use field_type::FieldType;
+use heck::CamelCase;
+use scalars::{parse_type_path, split_top_level, Scalar};
+use schema::Schema;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+
+/// Parses the `field_types` option, `Type.field = path` pairs separated by commas, like `"Repository.metadata = crate::RepoMetadata"`, and gives each field its own scalar in the schema. Returns the path of each new scalar, by name, to use as its alias like the `scalar_paths`.
+pub(crate) fn apply_field_types(
+    option: &str,
+    schema: &mut Schema,
+) -> Result<BTreeMap<String, syn::Path>, CodegenError> {
+    let mut paths = BTreeMap::new();
+
+    for entry in split_top_level(option) {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            continue;
+        }
+
+        let (coordinate, path) = match entry.find('=') {
+            Some(idx) => (entry[..idx].trim(), entry[idx + 1..].trim()),
+            None => Err(CodegenError::Validation(format!(
+                "Expected `Type.field = path` in field_types, got `{}`.",
+                entry
+            )))?,
+        };
+        let (type_name, field_name) = match coordinate.find('.') {
+            Some(idx) => (&coordinate[..idx], &coordinate[idx + 1..]),
+            None => Err(CodegenError::Validation(format!(
+                "Expected `Type.field = path` in field_types, got `{}`.",
+                entry
+            )))?,
+        };
+
+        let path = parse_type_path(path, &format!("the {} field", coordinate))?;
+        let scalar_name = format!("{}{}", type_name, field_name.to_camel_case());
+        if schema_has_type(schema, &scalar_name) {
+            Err(CodegenError::Validation(format!(
+                "field_types cannot map {}: its type would be named {}, like a type of the schema.",
+                coordinate, scalar_name
+            )))?
+        }
+
+        let fields = match (
+            schema.objects.get_mut(type_name),
+            schema.interfaces.get_mut(type_name),
+        ) {
+            (Some(object), _) => &mut object.fields,
+            (None, Some(interface)) => &mut interface.fields,
+            (None, None) => Err(CodegenError::Validation(format!(
+                "field_types maps {}, but {} is not an object or interface type of the schema.",
+                coordinate, type_name
+            )))?,
+        };
+        let field = fields
+            .iter_mut()
+            .find(|field| field.name == field_name)
+            .ok_or_else(|| {
+                CodegenError::Validation(format!(
+                    "field_types maps {}, but {} has no {} field.",
+                    coordinate, type_name, field_name
+                ))
+            })?;
+
+        let scalar = field.type_.inner_name_string();
+        if !schema.scalars.contains_key(&scalar) {
+            Err(CodegenError::Validation(format!(
+                "field_types maps {}, which is of type {}. Only the fields of a custom scalar type, like JSON, can be mapped.",
+                coordinate, scalar
+            )))?
+        }
+
+        field.type_ = rename_inner(&field.type_, &scalar_name);
+        schema.scalars.insert(
+            scalar_name.clone(),
+            Scalar {
+                name: scalar_name.clone(),
+                description: Some(format!(
+                    "The type of the `{}` field, a {} mapped by the field_types option.",
+                    coordinate, scalar
+                )),
+                is_required: Cell::new(false),
+            },
+        );
+        paths.insert(scalar_name, path);
+    }
+
+    Ok(paths)
+}
+
+fn schema_has_type(schema: &Schema, name: &str) -> bool {
+    schema.scalars.contains_key(name)
+        || schema.objects.contains_key(name)
+        || schema.interfaces.contains_key(name)
+        || schema.unions.contains_key(name)
+        || schema.enums.contains_key(name)
+        || schema.inputs.contains_key(name)
+}
+
+/// The field type with another named type inside its lists and options.
+fn rename_inner(field_type: &FieldType, name: &str) -> FieldType {
+    match field_type {
+        FieldType::Named(_) => FieldType::Named(name.to_string()),
+        FieldType::Optional(inner) => FieldType::Optional(Box::new(rename_inner(inner, name))),
+        FieldType::Vector(inner) => FieldType::Vector(Box::new(rename_inner(inner, name))),
+    }
+}
@@ -1,6 +1,8 @@
+use constants::TYPENAME_FIELD;
+use error::CodegenError;
 use proc_macro2::{Ident, Span, TokenStream};
 use query::QueryContext;
-use selection::Selection;
+use selection::{Selection, SelectionItem};
 use std::cell::Cell;
 
 /// Represents a fragment extracted from a query document.
@@ -18,36 +20,236 @@ pub struct GqlFragment {
 
 impl GqlFragment {
     /// Generate all the Rust code required by the fragment's selection.
-    pub(crate) fn to_rust(&self, context: &QueryContext) -> Result<TokenStream, ::failure::Error> {
-        let derives = context.response_derives();
+    pub(crate) fn to_rust(&self, context: &QueryContext) -> Result<TokenStream, CodegenError> {
+        let derives = context.response_struct_derives();
         let name_ident = Ident::new(&self.name, Span::call_site());
         let opt_object = context.schema.objects.get(&self.on);
-        let (field_impls, fields) = if let Some(object) = opt_object {
-            let field_impls =
-                object.field_impls_for_selection(context, &self.selection, &self.name)?;
-            let fields =
-                object.response_fields_for_selection(context, &self.selection, &self.name)?;
-            (field_impls, fields)
-        } else if let Some(iface) = context.schema.interfaces.get(&self.on) {
-            let field_impls =
-                iface.field_impls_for_selection(context, &self.selection, &self.name)?;
-            let fields =
-                iface.response_fields_for_selection(context, &self.selection, &self.name)?;
-            (field_impls, fields)
-        } else {
-            panic!(
-                "fragment '{}' cannot operate on unknown type '{}'",
-                self.name, self.on
-            );
+        let (field_impls, fields, optimistic, accessors, collectors, estimated_size) =
+            if let Some(object) = opt_object {
+                let field_impls =
+                    object.field_impls_for_selection(context, &self.selection, &self.name)?;
+                let fields =
+                    object.response_fields_for_selection(context, &self.selection, &self.name)?;
+                let optimistic =
+                    object.optimistic_struct(context, &name_ident, &self.selection, &self.name);
+                let accessors =
+                    object.field_accessors(context, &name_ident, &self.selection, &self.name);
+                let collectors =
+                    object.scalar_collectors(context, &name_ident, &self.selection, &self.name);
+                let estimated_size = ::estimated_size::struct_impl(
+                    &object.fields,
+                    context,
+                    &name_ident,
+                    &self.selection,
+                    &self.name,
+                    &[],
+                );
+                (
+                    field_impls,
+                    fields,
+                    optimistic,
+                    accessors,
+                    collectors,
+                    estimated_size,
+                )
+            } else if let Some(iface) = context.schema.interfaces.get(&self.on) {
+                let field_impls =
+                    iface.field_impls_for_selection(context, &self.selection, &self.name)?;
+                let fields =
+                    iface.response_fields_for_selection(context, &self.selection, &self.name)?;
+                let optimistic = iface.optimistic_struct(
+                    context,
+                    &name_ident,
+                    &self.selection,
+                    &self.name,
+                    quote!(),
+                );
+                let accessors =
+                    iface.field_accessors(context, &name_ident, &self.selection, &self.name);
+                let collectors = iface.scalar_collectors(
+                    context,
+                    &name_ident,
+                    &self.selection,
+                    &self.name,
+                    false,
+                );
+                let estimated_size = iface.estimated_size_impl(
+                    context,
+                    &name_ident,
+                    &self.selection,
+                    &self.name,
+                    &[],
+                );
+                (
+                    field_impls,
+                    fields,
+                    optimistic,
+                    accessors,
+                    collectors,
+                    estimated_size,
+                )
+            } else {
+                panic!(
+                    "fragment '{}' cannot operate on unknown type '{}'",
+                    self.name, self.on
+                );
+            };
+
+        let from_row_derive = match opt_object {
+            Some(object) => context.sqlx_from_row_derive(&object.fields, &self.selection),
+            None => quote!(),
         };
+        let rename_all = context.rename_all_annotation();
+        let visibility = context.type_visibility();
+        let definition = context.transform_type(
+            &self.name,
+            quote! {
+                #derives
+                #from_row_derive
+                #rename_all
+                #visibility struct #name_ident {
+                    #(#fields,)*
+                }
+            },
+        );
+
+        // Any selection may ask for `__typename`, so it only tells that the fragment was sent when the fragment selects nothing else.
+        let mut response_keys = self.response_keys(context);
+        if response_keys.iter().any(|key| key != TYPENAME_FIELD) {
+            response_keys.retain(|key| key != TYPENAME_FIELD);
+        }
 
         Ok(quote! {
-            #derives
-            pub struct #name_ident {
-                #(#fields,)*
+            #definition
+
+            impl ::graphql_client::conditional_fragments::ConditionalFragment for #name_ident {
+                const RESPONSE_KEYS: &'static [&'static str] = &[#(#response_keys),*];
             }
 
             #(#field_impls)*
+
+            #optimistic
+
+            #accessors
+
+            #collectors
+
+            #estimated_size
         })
     }
+
+    /// The response keys of the fields the fragment selects, including through the fragments it spreads.
+    pub(crate) fn response_keys(&self, context: &QueryContext) -> Vec<String> {
+        let mut keys = Vec::new();
+        collect_response_keys(
+            &self.selection,
+            context,
+            &mut vec![self.name.as_str()],
+            &mut keys,
+        );
+        keys
+    }
+}
+
+fn collect_response_keys<'a>(
+    selection: &'a Selection,
+    context: &'a QueryContext,
+    visited_fragments: &mut Vec<&'a str>,
+    keys: &mut Vec<String>,
+) {
+    for item in &selection.0 {
+        match item {
+            SelectionItem::Field(field) => {
+                let key = field.alias.as_ref().unwrap_or(&field.name);
+                if !keys.contains(key) {
+                    keys.push(key.clone());
+                }
+            }
+            SelectionItem::InlineFragment(inline) => {
+                collect_response_keys(&inline.fields, context, visited_fragments, keys)
+            }
+            SelectionItem::FragmentSpread(spread) => {
+                let name = spread.fragment_name.as_str();
+                if visited_fragments.contains(&name) {
+                    continue;
+                }
+                visited_fragments.push(name);
+
+                if let Some(fragment) = context.fragments.get(name) {
+                    collect_response_keys(&fragment.selection, context, visited_fragments, keys);
+                }
+            }
+        }
+    }
+}
+
+/// The error for a spread of `name` while the `expanding` fragments are being expanded, with the spreads forming the cycle.
+pub(crate) fn spread_cycle_error(expanding: &[&str], name: &str) -> CodegenError {
+    let start = expanding
+        .iter()
+        .position(|fragment| *fragment == name)
+        .unwrap_or(0);
+    let cycle: Vec<&str> = expanding[start..]
+        .iter()
+        .cloned()
+        .chain(::std::iter::once(name))
+        .collect();
+
+    CodegenError::Validation(format!(
+        "The {} fragment spreads itself through {}. Fragment spreads must not form cycles.",
+        name,
+        cycle.join(" -> ")
+    ))
+}
+
+/// Rejects the fragments spreading themselves, directly or through other fragments. The spec forbids these cycles, and their structs would contain themselves.
+pub(crate) fn validate_spread_cycles(context: &QueryContext) -> Result<(), CodegenError> {
+    let mut checked = Vec::new();
+    for fragment in context.fragments.values() {
+        check_spreads(
+            &fragment.selection,
+            context,
+            &mut vec![fragment.name.as_str()],
+            &mut checked,
+        )?;
+        checked.push(fragment.name.as_str());
+    }
+
+    Ok(())
+}
+
+/// Follows the spreads of the selection, with the `expanding` fragments around it. The `checked` fragments are known not to start a cycle.
+fn check_spreads<'a>(
+    selection: &'a Selection,
+    context: &'a QueryContext,
+    expanding: &mut Vec<&'a str>,
+    checked: &mut Vec<&'a str>,
+) -> Result<(), CodegenError> {
+    for item in &selection.0 {
+        match item {
+            SelectionItem::Field(field) => {
+                check_spreads(&field.fields, context, expanding, checked)?
+            }
+            SelectionItem::InlineFragment(inline) => {
+                check_spreads(&inline.fields, context, expanding, checked)?
+            }
+            SelectionItem::FragmentSpread(spread) => {
+                let name = spread.fragment_name.as_str();
+                if expanding.contains(&name) {
+                    Err(spread_cycle_error(expanding, name))?
+                }
+                if checked.contains(&name) {
+                    continue;
+                }
+
+                if let Some(fragment) = context.fragments.get(name) {
+                    expanding.push(name);
+                    check_spreads(&fragment.selection, context, expanding, checked)?;
+                    expanding.pop();
+                }
+            }
+        }
+    }
+
+    Ok(())
 }
@@ -0,0 +1,236 @@
+//! Support for [graphql-config](https://github.com/prisma/graphql-config) files (`.graphqlrc`, `graphql.config.yml`...), which other GraphQL tools use to find the schema and the query documents of a project.
+
+use error::CodegenError;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The file names we look for, in order, when no config file is given explicitly.
+pub const CONFIG_FILE_NAMES: &[&str] = &[
+    ".graphqlrc",
+    ".graphqlrc.yml",
+    ".graphqlrc.yaml",
+    ".graphqlrc.json",
+    "graphql.config.yml",
+    "graphql.config.yaml",
+    "graphql.config.json",
+];
+
+/// The locations of the schema and query documents of one project, resolved relative to the config file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectConfig {
+    /// The path of the schema file.
+    pub schema: PathBuf,
+    /// The paths or glob patterns of the query documents.
+    pub documents: Vec<PathBuf>,
+}
+
+impl ProjectConfig {
+    /// The query documents matching the `documents` patterns, sorted and deduplicated. The schema is left out, in case a pattern also matches it.
+    pub fn document_paths(&self) -> Result<Vec<PathBuf>, CodegenError> {
+        let mut paths = Vec::new();
+
+        for pattern in &self.documents {
+            let pattern = pattern.to_string_lossy();
+            let matches = ::glob::glob(&pattern).map_err(|err| {
+                CodegenError::Config(format!("Invalid documents pattern {}: {}", pattern, err))
+            })?;
+
+            for path in matches {
+                let path = path.map_err(|err| CodegenError::Config(err.to_string()))?;
+                if path.is_file() && path != self.schema {
+                    paths.push(path);
+                }
+            }
+        }
+
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+}
+
+/// Either a single value or a list, as graphql-config allows for `schema` and `documents`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawProject {
+    schema: Option<OneOrMany>,
+    documents: Option<OneOrMany>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawConfig {
+    #[serde(flatten)]
+    root: RawProject,
+    projects: Option<BTreeMap<String, RawProject>>,
+}
+
+/// Looks for a graphql-config file in `dir`.
+pub fn find_config(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Reads the config file at `path` and returns the selected project. Paths in the project are relative to the directory of the config file.
+///
+/// If the config defines several `projects`, `project` must name one of them.
+pub fn load_project(path: &Path, project: Option<&str>) -> Result<ProjectConfig, CodegenError> {
+    let source = ::read_file(path)?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    parse_project(&source, base_dir, project)
+}
+
+/// Parses a graphql-config file, in YAML or JSON, and returns the selected project. See [load_project].
+pub fn parse_project(
+    source: &str,
+    base_dir: &Path,
+    project: Option<&str>,
+) -> Result<ProjectConfig, CodegenError> {
+    let config: RawConfig = ::serde_yaml::from_str(source).map_err(|err| {
+        CodegenError::Config(format!("Could not parse the graphql-config file: {}", err))
+    })?;
+
+    let (name, raw) = match (config.projects, project) {
+        (Some(mut projects), Some(name)) => {
+            let available = projects.keys().cloned().collect::<Vec<_>>().join(", ");
+            let raw = projects.remove(name).ok_or_else(|| {
+                CodegenError::Config(format!(
+                    "The graphql-config file has no {} project. Available projects: {}.",
+                    name, available
+                ))
+            })?;
+            (Some(name), raw)
+        }
+        (Some(projects), None) => Err(CodegenError::Config(format!(
+            "The graphql-config file defines several projects, please select one of them: {}.",
+            projects.keys().cloned().collect::<Vec<_>>().join(", ")
+        )))?,
+        (None, Some(name)) => Err(CodegenError::Config(format!(
+            "The {} project was selected, but the graphql-config file does not define projects.",
+            name
+        )))?,
+        (None, None) => (None, config.root),
+    };
+    let context = name
+        .map(|name| format!("the {} project", name))
+        .unwrap_or_else(|| "the graphql-config file".to_string());
+
+    let mut schemas = raw
+        .schema
+        .ok_or_else(|| CodegenError::Config(format!("No schema is defined for {}.", context)))?
+        .into_vec();
+    if schemas.len() != 1 {
+        Err(CodegenError::Config(format!(
+            "Only a single schema file is supported, but {} has {}.",
+            context,
+            schemas.len()
+        )))?
+    }
+    let schema = schemas.remove(0);
+    if schema.starts_with("http://") || schema.starts_with("https://") {
+        Err(CodegenError::Config(format!(
+            "The schema of {} is a URL ({}). Download it with `graphql-client introspect-schema` and point the config to the file.",
+            context, schema
+        )))?
+    }
+
+    let documents = raw.documents.map(OneOrMany::into_vec).unwrap_or_default();
+
+    Ok(ProjectConfig {
+        schema: base_dir.join(schema),
+        documents: documents
+            .into_iter()
+            .map(|document| base_dir.join(document))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTI_PROJECT_CONFIG: &str = r#"
+projects:
+  app:
+    schema: app/schema.graphql
+    documents:
+      - app/src/**/*.graphql
+      - app/shared.graphql
+  admin:
+    schema: admin/schema.json
+"#;
+
+    #[test]
+    fn single_project_yaml_config_is_parsed() {
+        let config = "schema: schema.graphql\ndocuments: 'queries/*.graphql'\n";
+
+        assert_eq!(
+            parse_project(config, Path::new("/project"), None).unwrap(),
+            ProjectConfig {
+                schema: PathBuf::from("/project/schema.graphql"),
+                documents: vec![PathBuf::from("/project/queries/*.graphql")],
+            }
+        );
+    }
+
+    #[test]
+    fn json_config_is_parsed() {
+        let config = r#"{ "schema": ["schema.json"], "documents": ["a.graphql", "b.graphql"] }"#;
+
+        assert_eq!(
+            parse_project(config, Path::new(""), None).unwrap(),
+            ProjectConfig {
+                schema: PathBuf::from("schema.json"),
+                documents: vec![PathBuf::from("a.graphql"), PathBuf::from("b.graphql")],
+            }
+        );
+    }
+
+    #[test]
+    fn multi_project_config_requires_a_project_name() {
+        let err = parse_project(MULTI_PROJECT_CONFIG, Path::new(""), None).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "The graphql-config file defines several projects, please select one of them: admin, app."
+        );
+    }
+
+    #[test]
+    fn project_is_selected_by_name() {
+        let app = parse_project(MULTI_PROJECT_CONFIG, Path::new(""), Some("app")).unwrap();
+        assert_eq!(app.schema, PathBuf::from("app/schema.graphql"));
+        assert_eq!(app.documents.len(), 2);
+
+        let admin = parse_project(MULTI_PROJECT_CONFIG, Path::new(""), Some("admin")).unwrap();
+        assert_eq!(admin.schema, PathBuf::from("admin/schema.json"));
+        assert!(admin.documents.is_empty());
+
+        let err = parse_project(MULTI_PROJECT_CONFIG, Path::new(""), Some("web")).unwrap_err();
+        assert!(err.to_string().contains("has no web project"));
+    }
+
+    #[test]
+    fn remote_schema_is_rejected() {
+        let err = parse_project("schema: https://example.com/graphql", Path::new(""), None)
+            .unwrap_err();
+
+        assert!(err.to_string().contains("is a URL"));
+    }
+}
@@ -1,7 +1,7 @@
 use deprecation::DeprecationStatus;
-use failure;
+use error::CodegenError;
+use field_type::FieldType;
 use graphql_parser;
-use heck::SnakeCase;
 use introspection_response;
 use objects::GqlObjectField;
 use proc_macro2::{Ident, Span, TokenStream};
@@ -30,29 +30,96 @@ impl GqlInput {
         })
     }
 
-    pub(crate) fn to_rust(&self, context: &QueryContext) -> Result<TokenStream, failure::Error> {
+    pub(crate) fn to_rust(&self, context: &QueryContext) -> Result<TokenStream, CodegenError> {
         let name = Ident::new(&self.name, Span::call_site());
         let mut fields: Vec<&GqlObjectField> = self.fields.values().collect();
         fields.sort_unstable_by(|a, b| a.name.cmp(&b.name));
         let fields = fields.iter().map(|field| {
             let ty = field.type_.to_rust(&context, "");
             context.schema.require(&field.type_.inner_name_string());
-            let original_name = &field.name;
-            let snake_case_name = field.name.to_snake_case();
-            let rename = ::shared::field_rename_annotation(&original_name, &snake_case_name);
-            let name = Ident::new(&snake_case_name, Span::call_site());
+            let name = ::shared::field_ident(&field.name);
+            let rename = ::shared::field_rename_annotation(
+                &field.name,
+                &name.to_string(),
+                context.rename_all_camel_case,
+            );
 
             quote!(#rename pub #name: #ty)
         });
         let variables_derives = context.variables_derives();
+        let rename_all = context.rename_all_annotation();
+        let visibility = context.type_visibility();
+        let patch = if context.input_patches {
+            self.patch_to_rust(context)
+        } else {
+            quote!()
+        };
+
+        let definition = context.transform_type(
+            &self.name,
+            quote! {
+                #variables_derives
+                #rename_all
+                #visibility struct #name {
+                    #(#fields,)*
+                }
+            },
+        );
 
         Ok(quote! {
-            #variables_derives
-            pub struct #name {
-                #(#fields,)*
-            }
+            #definition
+
+            #patch
         })
     }
+
+    /// The `Patch` version of the input object: every field is a `Tristate`, and absent fields are not serialized.
+    fn patch_to_rust(&self, context: &QueryContext) -> TokenStream {
+        let name = Ident::new(&format!("{}Patch", self.name), Span::call_site());
+        let mut fields: Vec<&GqlObjectField> = self.fields.values().collect();
+        fields.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        let fields = fields.iter().map(|field| {
+            // The nullability is expressed by the `Tristate`.
+            let ty = match &field.type_ {
+                FieldType::Optional(inner) => inner.to_rust(context, ""),
+                ty => ty.to_rust(context, ""),
+            };
+            let name = ::shared::field_ident(&field.name);
+            let rename = ::shared::field_rename_annotation(
+                &field.name,
+                &name.to_string(),
+                context.rename_all_camel_case,
+            );
+
+            // Absent fields are left out when the patch is deserialized too.
+            let default = if context.variables_try_from {
+                quote!(#[serde(default)])
+            } else {
+                quote!()
+            };
+
+            quote! {
+                #rename
+                #default
+                #[serde(skip_serializing_if = "::graphql_client::Tristate::is_absent")]
+                pub #name: ::graphql_client::Tristate<#ty>
+            }
+        });
+        let patch_derives = context.patch_derives();
+        let rename_all = context.rename_all_annotation();
+        let visibility = context.type_visibility();
+
+        context.transform_type(
+            &name.to_string(),
+            quote! {
+                #patch_derives
+                #rename_all
+                #visibility struct #name {
+                    #(#fields,)*
+                }
+            },
+        )
+    }
 }
 
 impl ::std::convert::From<graphql_parser::schema::InputObjectType> for GqlInput {
@@ -70,6 +137,8 @@ impl ::std::convert::From<graphql_parser::schema::InputObjectType> for GqlInput
                         name: field.name,
                         type_: field.value_type.into(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     };
                     (name, field)
                 })
@@ -100,6 +169,8 @@ impl ::std::convert::From<introspection_response::FullType> for GqlInput {
                             .expect("type on input object field")
                             .into(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     };
                     (name, field)
                 })
@@ -128,6 +199,8 @@ mod tests {
                         name: "pawsCount".to_string(),
                         type_: FieldType::Named(float_type()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                 ),
                 (
@@ -137,6 +210,8 @@ mod tests {
                         name: "offsprings".to_string(),
                         type_: FieldType::Vector(Box::new(FieldType::Named("Cat".to_string()))),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                 ),
                 (
@@ -148,6 +223,8 @@ mod tests {
                             "CatRequirements".to_string(),
                         ))),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                 ),
             ]
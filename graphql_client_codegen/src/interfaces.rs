@@ -1,4 +1,4 @@
-use failure;
+use error::CodegenError;
 use objects::GqlObjectField;
 use proc_macro2::{Ident, Span, TokenStream};
 use query::QueryContext;
@@ -7,7 +7,8 @@ use shared::*;
 use std::borrow::Cow;
 use std::cell::Cell;
 use std::collections::HashSet;
-use unions::union_variants;
+use type_map::FieldDescription;
+use unions::{enum_default_impl, typename_accessor, union_variants, variant_accessors};
 
 /// Represents an Interface type extracted from the schema.
 #[derive(Debug, Clone, PartialEq)]
@@ -35,12 +36,17 @@ impl GqlInterface {
                 // Only keep what we can handle
                 .filter(|f| match f {
                     SelectionItem::Field(f) => f.name != "__typename",
-                    SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name }) => {
+                    SelectionItem::FragmentSpread(SelectionFragmentSpread {
+                        fragment_name,
+                        ..
+                    }) => {
                         // only if the fragment refers to the interface’s own fields (to take into account type-refining fragments)
                         let fragment = query_context
                             .fragments
                             .get(fragment_name)
-                            .ok_or_else(|| format_err!("Unknown fragment: {}", &fragment_name))
+                            .ok_or_else(|| CodegenError::UnknownFragment {
+                                name: fragment_name.to_string(),
+                            })
                             // TODO: fix this
                             .unwrap();
 
@@ -61,11 +67,16 @@ impl GqlInterface {
                 // Only keep what we can handle
                 .filter(|f| match f {
                     SelectionItem::InlineFragment(_) => true,
-                    SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name }) => {
+                    SelectionItem::FragmentSpread(SelectionFragmentSpread {
+                        fragment_name,
+                        ..
+                    }) => {
                         let fragment = query_context
                             .fragments
                             .get(fragment_name)
-                            .ok_or_else(|| format_err!("Unknown fragment: {}", &fragment_name))
+                            .ok_or_else(|| CodegenError::UnknownFragment {
+                                name: fragment_name.to_string(),
+                            })
                             // TODO: fix this
                             .unwrap();
 
@@ -96,8 +107,9 @@ impl GqlInterface {
         context: &QueryContext,
         selection: &Selection,
         prefix: &str,
-    ) -> Result<Vec<TokenStream>, failure::Error> {
+    ) -> Result<Vec<TokenStream>, CodegenError> {
         ::shared::field_impls_for_selection(
+            &self.name,
             &self.fields,
             context,
             &self.object_selection(selection, context),
@@ -111,7 +123,7 @@ impl GqlInterface {
         context: &QueryContext,
         selection: &Selection,
         prefix: &str,
-    ) -> Result<Vec<TokenStream>, failure::Error> {
+    ) -> Result<Vec<TokenStream>, CodegenError> {
         response_fields_for_selection(
             &self.name,
             &self.fields,
@@ -121,23 +133,105 @@ impl GqlInterface {
         )
     }
 
+    /// The getter methods for the fields of the interface's struct. See [::shared::field_accessors].
+    pub(crate) fn field_accessors(
+        &self,
+        context: &QueryContext,
+        struct_name: &Ident,
+        selection: &Selection,
+        prefix: &str,
+    ) -> TokenStream {
+        ::shared::field_accessors(
+            &self.fields,
+            context,
+            struct_name,
+            &self.object_selection(selection, context),
+            prefix,
+        )
+    }
+
+    /// The `collect_*_values` methods of the interface's struct. See [::collectors::struct_collectors].
+    pub(crate) fn scalar_collectors(
+        &self,
+        context: &QueryContext,
+        struct_name: &Ident,
+        selection: &Selection,
+        prefix: &str,
+        on_enum: bool,
+    ) -> TokenStream {
+        ::collectors::struct_collectors(
+            &self.fields,
+            context,
+            struct_name,
+            &self.object_selection(selection, context),
+            prefix,
+            on_enum,
+        )
+    }
+
+    /// The `EstimatedSize` implementation of the interface's struct. See [::estimated_size::struct_impl].
+    pub(crate) fn estimated_size_impl(
+        &self,
+        context: &QueryContext,
+        struct_name: &Ident,
+        selection: &Selection,
+        prefix: &str,
+        other_fields: &[&str],
+    ) -> TokenStream {
+        ::estimated_size::struct_impl(
+            &self.fields,
+            context,
+            struct_name,
+            &self.object_selection(selection, context),
+            prefix,
+            other_fields,
+        )
+    }
+
+    /// The `Optimistic` mirror of the interface's struct. See [::shared::optimistic_struct].
+    pub(crate) fn optimistic_struct(
+        &self,
+        context: &QueryContext,
+        struct_name: &Ident,
+        selection: &Selection,
+        prefix: &str,
+        extra_fields: TokenStream,
+    ) -> TokenStream {
+        ::shared::optimistic_struct(
+            &self.fields,
+            context,
+            struct_name,
+            &self.object_selection(selection, context),
+            prefix,
+            extra_fields,
+        )
+    }
+
     /// Generate all the code for the interface.
     pub(crate) fn response_for_selection(
         &self,
         query_context: &QueryContext,
         selection: &Selection,
         prefix: &str,
-    ) -> Result<TokenStream, failure::Error> {
+    ) -> Result<TokenStream, CodegenError> {
         let name = Ident::new(&prefix, Span::call_site());
         let derives = query_context.response_derives();
+        let struct_derives = query_context.response_struct_derives();
+        let visibility = query_context.type_visibility();
 
-        selection.extract_typename().ok_or_else(|| {
-            format_err!(
-                "Missing __typename in selection for the {} interface (type: {})",
-                prefix,
-                self.name
-            )
-        })?;
+        let typename_field =
+            selection
+                .extract_typename()
+                .ok_or_else(|| CodegenError::MissingTypename {
+                    selection_name: prefix.into(),
+                    type_name: self.name.clone(),
+                    kind: "interface",
+                })?;
+        // `__typename` may be aliased, the enum is tagged by the key it has in the response.
+        let typename_key = typename_field
+            .alias
+            .as_ref()
+            .unwrap_or(&typename_field.name);
 
         let object_fields =
             self.response_fields_for_selection(query_context, &selection, prefix)?;
@@ -147,32 +241,176 @@ impl GqlInterface {
         let union_selection = self.union_selection(&selection, &query_context);
 
         let (mut union_variants, union_children, used_variants) =
-            union_variants(&union_selection, query_context, prefix)?;
+            union_variants(&self.name, &union_selection, query_context, prefix)?;
 
-        union_variants.extend(
+        let unused_variants = || {
             self.implemented_by
                 .iter()
                 .filter(|obj| used_variants.iter().find(|v| v == obj).is_none())
-                .map(|v| {
-                    let v = Ident::new(v, Span::call_site());
-                    quote!(#v)
-                }),
-        );
+        };
+
+        union_variants.extend(unused_variants().map(|v| {
+            let v = Ident::new(v, Span::call_site());
+            quote!(#v)
+        }));
+
+        let rename_all = query_context.rename_all_annotation();
 
         let attached_enum_name = Ident::new(&format!("{}On", name), Span::call_site());
-        let (attached_enum, last_object_field) = if !union_variants.is_empty() {
+        let (attached_enum, last_object_field, optimistic_on_field) = if !union_variants.is_empty()
+        {
+            let accessors = if query_context.variant_accessors {
+                variant_accessors(
+                    query_context,
+                    &attached_enum_name,
+                    prefix,
+                    &used_variants,
+                    unused_variants(),
+                )
+            } else {
+                quote!()
+            };
+            let typename_accessor =
+                typename_accessor(&attached_enum_name, &used_variants, unused_variants());
+            let default_impl = if query_context.test_defaults {
+                enum_default_impl(
+                    query_context,
+                    &attached_enum_name,
+                    prefix,
+                    &used_variants,
+                    unused_variants().next(),
+                )
+            } else {
+                quote!()
+            };
+            let enum_definition = query_context.transform_type(
+                &attached_enum_name.to_string(),
+                quote! {
+                    #derives
+                    #[serde(tag = #typename_key)]
+                    #visibility enum #attached_enum_name {
+                        #(#union_variants,)*
+                    }
+                },
+            );
+            let collectors = ::collectors::enum_collectors(
+                query_context,
+                &attached_enum_name,
+                &used_variants,
+                unused_variants().next().is_some(),
+            );
+            let estimated_size = ::estimated_size::enum_impl(
+                query_context,
+                &attached_enum_name,
+                &used_variants,
+                unused_variants().next().is_some(),
+            );
             let attached_enum = quote! {
-                #derives
-                #[serde(tag = "__typename")]
-                pub enum #attached_enum_name {
-                    #(#union_variants,)*
-                }
+                #enum_definition
+
+                #typename_accessor
+
+                #accessors
+
+                #default_impl
+
+                #collectors
+
+                #estimated_size
             };
+            query_context.describe_field(
+                prefix,
+                FieldDescription::new(
+                    "on",
+                    None,
+                    &quote!(#attached_enum_name),
+                    self.name.clone(),
+                    false,
+                ),
+            );
             let last_object_field = quote!(#[serde(flatten)] pub on: #attached_enum_name,);
-            (attached_enum, last_object_field)
+            let optimistic_on_field = quote!(pub on: Option<#attached_enum_name>,);
+            (attached_enum, last_object_field, optimistic_on_field)
+        } else {
+            // Without implementing types there is no `on` enum to tag, so `__typename` is a field of its own, which also keeps it out of the unknown fields.
+            query_context.describe_field(
+                prefix,
+                FieldDescription::new(
+                    "typename",
+                    Some(typename_key),
+                    &quote!(String),
+                    "String!".to_string(),
+                    false,
+                ),
+            );
+            let unknown_fields = query_context.unknown_fields_field();
+            let last_object_field = quote! {
+                #[serde(rename = #typename_key)]
+                pub typename: String,
+                #unknown_fields
+            };
+            let optimistic_typename_field = quote!(pub typename: Option<String>,);
+            (quote!(), last_object_field, optimistic_typename_field)
+        };
+        // The concrete type is only known through the `on` enum, the struct delegates to it.
+        let struct_typename = if attached_enum.is_empty() {
+            quote! {
+                impl #name {
+                    pub fn typename(&self) -> &str {
+                        &self.typename
+                    }
+                }
+            }
         } else {
-            (quote!(), quote!())
+            quote! {
+                impl #name {
+                    pub fn typename(&self) -> &'static str {
+                        self.on.typename()
+                    }
+                }
+            }
+        };
+        // With a variant for every implementing type, the fields they all select can be read without matching.
+        let common_accessors = if attached_enum.is_empty() || unused_variants().next().is_some() {
+            quote!()
+        } else {
+            common_variant_accessors(
+                query_context,
+                &name,
+                &attached_enum_name,
+                prefix,
+                &used_variants,
+            )
         };
+        let optimistic =
+            self.optimistic_struct(query_context, &name, selection, prefix, optimistic_on_field);
+        let accessors = self.field_accessors(query_context, &name, selection, prefix);
+        let collectors = self.scalar_collectors(
+            query_context,
+            &name,
+            selection,
+            prefix,
+            !attached_enum.is_empty(),
+        );
+        let estimated_size = if attached_enum.is_empty() {
+            let other_fields: Vec<&str> = ::std::iter::once("typename")
+                .chain(query_context.unknown_fields_names().iter().cloned())
+                .collect();
+            self.estimated_size_impl(query_context, &name, selection, prefix, &other_fields)
+        } else {
+            self.estimated_size_impl(query_context, &name, selection, prefix, &["on"])
+        };
+        let definition = query_context.transform_type(
+            prefix,
+            quote! {
+                #struct_derives
+                #rename_all
+                #visibility struct #name {
+                    #(#object_fields,)*
+                    #last_object_field
+                }
+            },
+        );
 
         Ok(quote! {
 
@@ -182,12 +420,91 @@ impl GqlInterface {
 
             #attached_enum
 
-            #derives
-            pub struct #name {
-                #(#object_fields,)*
-                #last_object_field
+            #definition
+
+            #struct_typename
+
+            #common_accessors
+
+            #optimistic
+
+            #accessors
+
+            #collectors
+
+            #estimated_size
+        })
+    }
+}
+
+/// Getters on an interface struct for the fields selected with the same response key and type in every variant of its `on` enum, like an `id` selected in each inline fragment, returning a reference to the field of the variant. The fields are compared as recorded for the type map, so fields with a nested selection, which get a struct for each variant, are left out, and so are the fields of the interface struct itself.
+fn common_variant_accessors(
+    context: &QueryContext,
+    struct_name: &Ident,
+    enum_name: &Ident,
+    prefix: &str,
+    variants: &[String],
+) -> TokenStream {
+    let described_types = context.described_types.borrow();
+    let fields_of = |type_name: &str| {
+        described_types
+            .get(type_name)
+            .map(|description| description.fields.as_slice())
+            .unwrap_or(&[])
+    };
+    let struct_fields = fields_of(prefix);
+    let variant_fields: Vec<&[FieldDescription]> = variants
+        .iter()
+        .map(|variant| fields_of(&context.variant_type_name(prefix, variant)))
+        .collect();
+    let (first, rest) = match variant_fields.split_first() {
+        Some(split) => split,
+        None => return quote!(),
+    };
+
+    let accessors = first
+        .iter()
+        .filter(|field| {
+            field.response_key.is_some()
+                && field.name != "typename"
+                && struct_fields.iter().all(|f| f.name != field.name)
+                && rest.iter().all(|fields| fields.contains(field))
+        })
+        .map(|field| {
+            let name = Ident::new(&field.name, Span::call_site());
+            let ty: TokenStream = field
+                .rust_type
+                .parse()
+                .expect("the recorded Rust type parses");
+            let arms = variants.iter().map(|variant| {
+                let variant = Ident::new(variant, Span::call_site());
+                quote!(#enum_name::#variant(value) => &value.#name,)
+            });
+            let doc = format!(
+                "The `{}` field, selected on every variant.",
+                field.response_key.as_ref().unwrap()
+            );
+
+            quote! {
+                #[doc = #doc]
+                #[allow(deprecated)]
+                pub fn #name(&self) -> &#ty {
+                    match &self.on {
+                        #(#arms)*
+                    }
+                }
             }
         })
+        .collect::<Vec<_>>();
+
+    if accessors.is_empty() {
+        return quote!();
+    }
+
+    quote! {
+        impl #struct_name {
+            #(#accessors)*
+        }
     }
 }
 
@@ -211,6 +528,8 @@ mod tests {
         let typename_field = ::selection::SelectionItem::Field(::selection::SelectionField {
             alias: None,
             name: "__typename".to_string(),
+            directives: Vec::new(),
+            arguments: Vec::new(),
             fields: Selection(vec![]),
         });
         let selection = Selection(vec![typename_field.clone()]);
@@ -237,6 +556,8 @@ mod tests {
         let typename_field = ::selection::SelectionItem::Field(::selection::SelectionField {
             alias: None,
             name: "__typename".to_string(),
+            directives: Vec::new(),
+            arguments: Vec::new(),
             fields: Selection(vec![]),
         });
         let selection = Selection(vec![typename_field]);
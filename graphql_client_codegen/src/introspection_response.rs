@@ -55,8 +55,8 @@ impl ::serde::Serialize for __DirectiveLocation {
 
 impl<'de> ::serde::Deserialize<'de> for __DirectiveLocation {
     fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        let s = <&'de str>::deserialize(deserializer)?;
-        match s {
+        let s = <String>::deserialize(deserializer)?;
+        match s.as_str() {
             "QUERY" => Ok(__DirectiveLocation::QUERY),
             "MUTATION" => Ok(__DirectiveLocation::MUTATION),
             "SUBSCRIPTION" => Ok(__DirectiveLocation::SUBSCRIPTION),
@@ -75,7 +75,7 @@ impl<'de> ::serde::Deserialize<'de> for __DirectiveLocation {
             "ENUM_VALUE" => Ok(__DirectiveLocation::ENUM_VALUE),
             "INPUT_OBJECT" => Ok(__DirectiveLocation::INPUT_OBJECT),
             "INPUT_FIELD_DEFINITION" => Ok(__DirectiveLocation::INPUT_FIELD_DEFINITION),
-            _ => Ok(__DirectiveLocation::Other(s.to_string())),
+            _ => Ok(__DirectiveLocation::Other(s)),
         }
     }
 }
@@ -111,8 +111,8 @@ impl ::serde::Serialize for __TypeKind {
 
 impl<'de> ::serde::Deserialize<'de> for __TypeKind {
     fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        let s = <&'de str>::deserialize(deserializer)?;
-        match s {
+        let s = <String>::deserialize(deserializer)?;
+        match s.as_str() {
             "SCALAR" => Ok(__TypeKind::SCALAR),
             "OBJECT" => Ok(__TypeKind::OBJECT),
             "INTERFACE" => Ok(__TypeKind::INTERFACE),
@@ -121,16 +121,27 @@ impl<'de> ::serde::Deserialize<'de> for __TypeKind {
             "INPUT_OBJECT" => Ok(__TypeKind::INPUT_OBJECT),
             "LIST" => Ok(__TypeKind::LIST),
             "NON_NULL" => Ok(__TypeKind::NON_NULL),
-            _ => Ok(__TypeKind::Other(s.to_string())),
+            _ => Ok(__TypeKind::Other(s)),
         }
     }
 }
 
+/// Descriptions that are not strings, like the `false` or numbers some buggy generators write, are dropped instead of failing the whole schema.
+fn lenient_description<'de, D: ::serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    match <::serde_json::Value as ::serde::Deserialize>::deserialize(deserializer)? {
+        ::serde_json::Value::String(description) => Ok(Some(description)),
+        _ => Ok(None),
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FullType {
     pub kind: Option<__TypeKind>,
     pub name: Option<String>,
+    #[serde(default, deserialize_with = "lenient_description")]
     pub description: Option<String>,
     pub fields: Option<Vec<Option<FullTypeFields>>>,
     pub input_fields: Option<Vec<Option<FullTypeInputFields>>>,
@@ -143,7 +154,7 @@ pub struct FullType {
 #[serde(rename_all = "camelCase")]
 pub struct FullTypeFieldsArgs {
     #[serde(flatten)]
-    input_value: InputValue,
+    pub input_value: InputValue,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -157,6 +168,7 @@ pub struct FullTypeFieldsType {
 #[serde(rename_all = "camelCase")]
 pub struct FullTypeFields {
     pub name: Option<String>,
+    #[serde(default, deserialize_with = "lenient_description")]
     pub description: Option<String>,
     pub args: Option<Vec<Option<FullTypeFieldsArgs>>>,
     #[serde(rename = "type")]
@@ -183,6 +195,7 @@ pub struct FullTypeInterfaces {
 #[serde(rename_all = "camelCase")]
 pub struct FullTypeEnumValues {
     pub name: Option<String>,
+    #[serde(default, deserialize_with = "lenient_description")]
     pub description: Option<String>,
     pub is_deprecated: Option<Boolean>,
     pub deprecation_reason: Option<String>,
@@ -199,6 +212,7 @@ pub struct FullTypePossibleTypes {
 #[serde(rename_all = "camelCase")]
 pub struct InputValue {
     pub name: Option<String>,
+    #[serde(default, deserialize_with = "lenient_description")]
     pub description: Option<String>,
     #[serde(rename = "type")]
     pub type_: Option<InputValueType>,
@@ -311,6 +325,7 @@ pub struct RustIntrospectionQuerySchemaDirectivesArgs {
 #[serde(rename_all = "camelCase")]
 pub struct RustIntrospectionQuerySchemaDirectives {
     pub name: Option<String>,
+    #[serde(default, deserialize_with = "lenient_description")]
     pub description: Option<String>,
     pub locations: Option<Vec<Option<__DirectiveLocation>>>,
     pub args: Option<Vec<Option<RustIntrospectionQuerySchemaDirectivesArgs>>>,
@@ -323,32 +338,42 @@ pub struct RustIntrospectionQuerySchema {
     pub mutation_type: Option<RustIntrospectionQuerySchemaMutationType>,
     pub subscription_type: Option<RustIntrospectionQuerySchemaSubscriptionType>,
     pub types: Option<Vec<Option<RustIntrospectionQuerySchemaTypes>>>,
-    directives: Option<Vec<Option<RustIntrospectionQuerySchemaDirectives>>>,
+    pub directives: Option<Vec<Option<RustIntrospectionQuerySchemaDirectives>>>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
-pub(crate) struct Schema {
-    #[serde(rename = "__schema")]
-    pub schema: Option<RustIntrospectionQuerySchema>,
+/// An introspection response, in any of the shapes tools save it in: the full response (`{"data": {"__schema": {...}}}`), only the `__schema` field (`{"__schema": {...}}`), or the schema object itself, with or without `data` around it. The nesting is detected from the keys.
+#[derive(Debug)]
+pub(crate) struct IntrospectionResponse {
+    schema: RustIntrospectionQuerySchema,
 }
 
-#[derive(Deserialize, Debug)]
-pub(crate) struct FullResponse<T> {
-    data: T,
+impl IntrospectionResponse {
+    pub(crate) fn into_schema(self) -> RustIntrospectionQuerySchema {
+        self.schema
+    }
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(untagged)]
-pub(crate) enum IntrospectionResponse {
-    FullResponse(FullResponse<Schema>),
-    Schema(Schema),
-}
+impl<'de> ::serde::Deserialize<'de> for IntrospectionResponse {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
 
-impl IntrospectionResponse {
-    pub(crate) fn into_schema(self) -> Schema {
-        match self {
-            IntrospectionResponse::FullResponse(full_response) => full_response.data,
-            IntrospectionResponse::Schema(schema) => schema,
+        let mut value = ::serde_json::Value::deserialize(deserializer)?;
+
+        for key in &["data", "__schema"] {
+            let nested = value.as_object_mut().and_then(|object| object.remove(*key));
+            if let Some(nested) = nested {
+                value = nested;
+            }
         }
+
+        if value.get("types").is_none() && value.get("queryType").is_none() {
+            return Err(D::Error::custom(
+                "expected an introspection response, with a `__schema` or a `types` field",
+            ));
+        }
+
+        let schema = ::serde_json::from_value(value).map_err(D::Error::custom)?;
+
+        Ok(IntrospectionResponse { schema })
     }
 }
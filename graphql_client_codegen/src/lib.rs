@@ -5,8 +5,7 @@
 //!
 //! It is not meant to be used directly by users of the library.
 
-#[macro_use]
-extern crate failure;
+extern crate glob;
 extern crate graphql_parser;
 extern crate heck;
 extern crate itertools;
@@ -17,17 +16,37 @@ extern crate proc_macro2;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
+#[cfg_attr(test, macro_use)]
 extern crate serde_json;
+extern crate serde_yaml;
+extern crate sha2;
 extern crate syn;
 #[macro_use]
 extern crate quote;
+#[macro_use]
+extern crate thiserror;
 
 use proc_macro2::TokenStream;
+use std::rc::Rc;
 use syn::Visibility;
 
+pub use enums::EnumUnknowns;
+pub use error::CodegenError;
+pub use scalars::FloatType;
+pub use token_transform::TokenTransform;
+pub use transforms::SensitiveFields;
+
+mod arguments;
 mod codegen;
+mod collectors;
+mod combined_documents;
+mod comment_descriptions;
+pub mod compiler;
 /// Deprecation-related code
 pub mod deprecation;
+pub mod describe;
+mod error;
+pub mod graphql_config;
 mod introspection_response;
 mod query;
 /// Contains the [Schema] type and its implementation.
@@ -35,30 +54,42 @@ pub mod schema;
 
 mod constants;
 mod enums;
+mod estimated_size;
 mod field_type;
+mod field_types;
 mod fragments;
 mod inputs;
 mod interfaces;
+mod meta_fields;
 mod objects;
 mod operations;
+mod persisted_queries;
+mod query_hash;
 mod scalars;
 mod selection;
 mod shared;
+mod tags;
+mod token_transform;
+mod transforms;
+/// A machine-readable description of the generated response structs.
+pub mod type_map;
+mod typename;
 mod unions;
 mod variables;
 
-use heck::SnakeCase;
+use heck::{CamelCase, ShoutySnakeCase, SnakeCase};
 
 #[cfg(test)]
 mod tests;
 use proc_macro2::{Ident, Span};
 
-type CacheMap<T> =
-    ::std::sync::Mutex<::std::collections::hash_map::HashMap<::std::path::PathBuf, T>>;
+type CacheMap<K, T> = ::std::sync::Mutex<::std::collections::hash_map::HashMap<K, T>>;
 
 lazy_static! {
-    static ref SCHEMA_CACHE: CacheMap<schema::Schema> = CacheMap::default();
-    static ref QUERY_CACHE: CacheMap<(String, graphql_parser::query::Document)> =
+    /// Schemas are keyed by path and by whether comments are parsed as descriptions.
+    static ref SCHEMA_CACHE: CacheMap<(::std::path::PathBuf, bool), schema::Schema> =
+        CacheMap::default();
+    static ref QUERY_CACHE: CacheMap<::std::path::PathBuf, (String, graphql_parser::query::Document)> =
         CacheMap::default();
 }
 
@@ -77,6 +108,112 @@ pub struct GraphQLClientDeriveOptions {
     pub deprecation_strategy: Option<deprecation::DeprecationStrategy>,
     /// target struct visibility.
     pub module_visibility: Visibility,
+    /// Generate `is_*` and `as_*` methods on the enums for union and interface variants.
+    pub variant_accessors: bool,
+    /// When generating code for multiple operations, operations whose variables are identical share the same `Variables` type (the later ones become type aliases).
+    pub shared_variables: bool,
+    /// Generate `root_field` and `into_root_field` methods on the response data, for operations selecting a single root field.
+    pub root_field_accessor: bool,
+    /// Implement `IntoIterator` and `FromIterator` for the response structs whose only field is a list.
+    pub list_iterators: bool,
+    /// Rename the fields of the generated structs with `#[serde(rename_all = "camelCase")]`, keeping per-field renames only for the fields that do not follow the camelCase rule.
+    pub rename_all_camel_case: bool,
+    /// Generate a `Patch` version of each input object, with every field wrapped in `graphql_client::Tristate`, for partial updates.
+    pub input_patches: bool,
+    /// Treat the `#` comments above definitions and fields of SDL schemas as descriptions, like older SDL did.
+    pub comment_descriptions: bool,
+    /// Generate `new` and `with_variables` methods on the struct, for building the request body in a chain: `MyQuery::new().with_variables(variables).build()`. `new` returns the struct itself, so it must be a unit struct.
+    pub with_variables: bool,
+    /// Implement `Default` for the response types, for test scaffolding. Enums default to their first variant.
+    pub test_defaults: bool,
+    /// Generate an `all_variants` function on enums, returning every known value (the `Other` fallback excluded).
+    pub enum_all_variants: bool,
+    /// Generate an `Optimistic` mirror of each response struct, with every field optional, for optimistic updates.
+    pub optimistic_responses: bool,
+    /// Generate getter methods for the fields of the response structs, returning `Copy` scalars by value and the other fields by reference.
+    pub field_accessors: bool,
+    /// Implement `TryFrom<serde_json::Value>` for the `Variables` structs, for variables collected dynamically. The variables and input types then also derive `Deserialize`.
+    pub variables_try_from: bool,
+    /// Generate the inline fragments on interfaces, in selections on objects implementing them, as structs flattened into the object's struct, like fragment spreads.
+    pub interface_flatten: bool,
+    /// What the generated enums do with the values that are not in the schema.
+    pub enum_unknowns: EnumUnknowns,
+    /// Derive the `rkyv` traits on the response types, for zero-copy archives. Requires the `rkyv` feature.
+    pub rkyv: bool,
+    /// Generate a `field_json` method on the response data, returning the value at a dotted path of response keys as JSON. Requires `Serialize` in the response derives.
+    pub field_json: bool,
+    /// Skip the `__typename` fields of the response structs when serializing them, e.g. in proxies forwarding normalized responses. The `__typename` tags of union and interface enums are kept.
+    pub strip_typename: bool,
+    /// The visibility of the generated types, `pub` by default. `pub(crate)` keeps them out of the public API of the crate.
+    pub visibility: Option<Visibility>,
+    /// Hooks post-processing the generated types and fields, see [`TokenTransform`].
+    pub transform: Option<Rc<dyn TokenTransform>>,
+    /// The Rust type of the `Float` scalar, `f64` by default.
+    pub float_type: FloatType,
+    /// Derive `::serde::Serialize` and `::serde::Deserialize` by path, instead of importing the serde_derive macros in the generated module, so the code does not depend on the names in scope. Requires the `derive` feature of serde.
+    pub qualified_paths: bool,
+    /// The Rust types of custom scalars, as `Scalar = path` pairs separated by commas, e.g. `"DateTime = crate::types::DateTime"`. The other scalars are the types with the same names in the module of the derive.
+    pub scalar_paths: Option<String>,
+    /// Generate a `VARIABLES_SCHEMA` constant with the variable definitions of the operation in GraphQL syntax, e.g. `($id: ID!, $limit: Int = 10)`.
+    pub variables_schema: bool,
+    /// Leave the fields with the federation `@external` directive out of the response types, instead of generating them with a note in their documentation. Subgraphs resolve these fields elsewhere, but the response still contains them when they are selected.
+    pub skip_external_fields: bool,
+    /// Generate a typestate builder for the `Variables` struct, `Variables::builder()`, whose `build` method only exists once every required variable is set.
+    pub variables_builder: bool,
+    /// Generate the fields of the `String` scalar as `compact_str::CompactString`, which keeps short strings inline instead of allocating them. Requires the `compact_str` feature, and the crate needs to depend on `compact_str` with its `serde` feature.
+    pub compact_str: bool,
+    /// Derive `bevy_reflect::Reflect` on the response types, so they can be registered for reflection in Bevy. Requires the `bevy` feature, and the crate needs to depend on `bevy_reflect`.
+    pub bevy_reflect: bool,
+    /// Generate a `variables_as_pairs` method on the `Variables` structs, returning each variable serialized on its own as a `(name, serde_json::Value)` pair, for transports sending variables as form fields or query parameters.
+    pub variables_as_pairs: bool,
+    /// Only generate the object and interface fields tagged with `@tag(name: "...")` for this audience, directly or through their type, and the types that still have fields. Selecting another field is an error. Needs an SDL schema.
+    pub include_tag: Option<String>,
+    /// Generate an `all_*` method on `ResponseData` for each scalar type in the response, like `all_id`, returning references to every value of that type.
+    pub scalar_collectors: bool,
+    /// Add `__typename` to the selections on interfaces and unions that do not select it, instead of failing with a missing `__typename` error. The generated code needs it to tell the possible types apart.
+    pub add_typename: bool,
+    /// Derive `sqlx::FromRow` on the flat response structs, whose fields all hold scalars, so cached responses can be read back from SQL rows. Requires the `sqlx` feature, and the crate needs to depend on `sqlx`.
+    pub sqlx_from_row: bool,
+    /// Generate the nullable list fields of the response as `Vec`s instead of `Option<Vec>`s, with `null` and missing lists deserialized as empty.
+    pub empty_lists: bool,
+    /// Add a `#[serde(flatten)] pub extra: HashMap<String, serde_json::Value>` field to the response structs, collecting the keys of the response no field of the struct reads, to detect the server sending data the query does not expect. Structs with the `on` enum of an interface do not get it, the structs of the variants do. Cannot be combined with the `sqlx_from_row`, `rkyv` and `bevy_reflect` options.
+    pub unknown_fields: bool,
+    /// The path of a renamed or vendored serde crate, like `my_serde`: the serde derives are written with this path, as with `qualified_paths`, and the types deriving them get a `#[serde(crate = "...")]` annotation. A bare crate name is made absolute, `::my_serde`. Paths starting with `crate::`, for a re-export, are kept as they are. Requires the `derive` feature of the crate.
+    pub serde_crate: Option<String>,
+    /// Implement `graphql_client::estimated_size::EstimatedSize` on the response types, for a rough estimate of the memory held by a response with `estimated_size()`. Custom scalar types must implement the trait too.
+    pub estimated_size: bool,
+    /// Methods on the enums returning data attached to their values with directives in the schema, as `directive(argument)` pairs separated by commas. With `"weight(n)"`, an enum whose values have `@weight(n: 5)` gets a `weight` method returning the `n` of the value. The arguments must be `Int`s, `Float`s, `String`s or `Boolean`s, of the same type on all values, and either all or none of the values of an enum must have the directive.
+    pub enum_value_methods: Option<String>,
+    /// The path of a generic type wrapping the responses, like `crate::Envelope`. `from_response_str` deserializes the body to the wrapper of the response data, e.g. `crate::Envelope<ResponseData>`, instead of `graphql_client::Response<ResponseData>`. The path is resolved from the module of the struct under derive.
+    pub response_wrapper: Option<String>,
+    /// Only put the response derives outside of the standard library and serde on `ResponseData`, not on the nested types, to save code size. The derives of the standard library and serde, like `Debug` or `PartialEq`, are still on every type, since `ResponseData` can only derive them if its fields implement them. The derive sets this with `derives_on_nested = "false"`.
+    pub top_level_derives_only: bool,
+    /// The path of a persisted query manifest, a JSON object mapping the operation names to the hashes they are registered with. Each operation must be registered with the `QUERY_HASH` or the `NORMALIZED_QUERY_HASH` of the module, and gets a `PERSISTED_QUERY_HASH` constant with the registered hash.
+    pub persisted_query_manifest: Option<std::path::PathBuf>,
+    /// Derives only applied to the generated types when testing, like `"Debug, PartialEq"`, with `#[cfg_attr(test, derive(...))]`. The serde derives cannot be test-only.
+    pub test_only_derives: Option<String>,
+    /// Generate an `omitted_conditional_fields` method on the response structs, listing the fields that are non-null in the schema but were left out of the response because of their `@include` or `@skip` directive.
+    pub conditional_fields_report: bool,
+    /// Rust types for fields of a custom scalar type, as `Type.field = path` pairs separated by commas, like `"Repository.metadata = crate::RepoMetadata"`. This gives the fields of a loosely typed scalar like `JSON` a struct when they always hold the same shape. The types must implement `Deserialize`, and the other traits the response types derive.
+    pub field_types: Option<String>,
+    /// Implement `graphql_client::GraphQLResponse` for the `ResponseData` types, an object-safe trait to store the responses of different operations as `Box<dyn GraphQLResponse>`. Requires `Serialize` in the response derives.
+    pub response_trait: bool,
+    /// The longest name of the types generated for nested selections, whose names add up the response keys of the fields leading to them. Longer names are cut, and end with 8 digits of a hash of the full name. Names that would still be the same are an error. It must be at least 16.
+    pub max_identifier_length: Option<usize>,
+    /// Deserialize the enum values case-insensitively, for servers sending `active` for `ACTIVE`. A value matching a variant exactly is preferred. The values matching none go to `Other` or the default variant, following `enum_unknowns`.
+    pub case_insensitive_enums: bool,
+    /// Inner attributes added at the top of the generated module, like `"#![allow(clippy::all)] #![cfg_attr(coverage, coverage(off))]"`.
+    pub module_attributes: Option<String>,
+    /// Generate a `{Operation}Result` type alias in the module for each operation, like `pub type HeroQueryResult = graphql_client::Response<ResponseData>;`, naming the full result `from_response_str` returns. With the `response_wrapper` option, the alias is the wrapper of the response data.
+    pub result_aliases: bool,
+    /// How the fields with the `@sensitive` client directive are serialized: as `"[redacted]"`, or left out of the output. Deserialization is unchanged.
+    pub sensitive_fields: SensitiveFields,
+    /// Generate a positional `new` constructor on the `Variables` structs, taking the required variables in the order the operation declares them, like `Variables::new(id, 10)`. The nullable variables are `None`, and the variables with a default value get it.
+    pub variables_constructor: bool,
+    /// Keep the raw JSON of the response data along with the typed structs: the `ResponseData` of the `GraphQLQuery` implementation is a `graphql_client::raw::WithRaw`, dereferencing to the generated `ResponseData`, with the JSON value in its `raw` field. The response is held twice, so it takes more memory.
+    pub raw_response: bool,
+    /// Generate a `cache_key` function on each operation, taking its variables and returning a stable key for client-side caches, or the error serializing the variables: the operation name, the `QUERY_HASH` and the variables serialized as canonical JSON, like `HeroQuery:4a5b...:{"episode":"EMPIRE"}`.
+    pub cache_keys: bool,
 }
 
 /// Generates the code for a Rust module given a query, a schema and options.
@@ -84,26 +221,361 @@ pub fn generate_module_token_stream(
     query_path: std::path::PathBuf,
     schema_path: std::path::PathBuf,
     options: Option<GraphQLClientDeriveOptions>,
-) -> Result<TokenStream, failure::Error> {
+) -> Result<TokenStream, CodegenError> {
+    let comment_descriptions = options
+        .as_ref()
+        .map(|options| options.comment_descriptions)
+        .unwrap_or(false);
+    let schema = load_schema(schema_path, comment_descriptions)?;
+
+    generate_module_token_stream_with_schema(query_path, schema, options)
+}
+
+/// Generates the code for a document holding the type-system definitions of the schema along with the operations, so no separate schema file is needed. Only the operations and fragments end up in the query string sent to the server.
+pub fn generate_module_token_stream_from_combined_document(
+    document_path: std::path::PathBuf,
+    options: Option<GraphQLClientDeriveOptions>,
+) -> Result<TokenStream, CodegenError> {
     let options = options.unwrap();
+    let document = read_file(&document_path)?;
+    let split = combined_documents::split(&document);
+    if !split.has_type_system {
+        Err(CodegenError::Validation(format!(
+            "The query document {} has no type-system definitions to use as the schema. Add them, or give a schema_path.",
+            document_path.display()
+        )))?
+    }
 
-    let module_visibility = options.module_visibility.clone();
-    let response_derives = options.additional_derives.clone();
+    let schema = if options.comment_descriptions {
+        schema::Schema::from_sdl_with_comment_descriptions(&split.type_system)?
+    } else {
+        schema::Schema::from_sdl(&split.type_system)?
+    };
+    let query = graphql_parser::parse_query(&split.executable)
+        .map_err(|err| CodegenError::QueryParse(err.to_string()))?;
+    let query_string = split.executable.trim().to_string();
+
+    let module = generate_module_for_document(query_string, query, schema, options)?;
+
+    Ok(module_token_stream(&module))
+}
+
+/// Reads and parses the schema, or gets it from the cache.
+fn load_schema(
+    schema_path: std::path::PathBuf,
+    comment_descriptions: bool,
+) -> Result<schema::Schema, CodegenError> {
+    let mut lock = SCHEMA_CACHE.lock().expect("schema cache is poisoned");
+    match lock.entry((schema_path, comment_descriptions)) {
+        ::std::collections::hash_map::Entry::Occupied(o) => Ok(o.get().clone()),
+        ::std::collections::hash_map::Entry::Vacant(v) => {
+            let schema_string = read_file(&v.key().0)?;
+            let schema = {
+                let extension = v
+                    .key()
+                    .0
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .unwrap_or("INVALID");
+
+                match extension {
+                    // `.graphqls` and `.sdl` are common in JVM tooling.
+                    "graphql" | "gql" | "graphqls" | "sdl" if comment_descriptions => {
+                        schema::Schema::from_sdl_with_comment_descriptions(&schema_string)?
+                    }
+                    "graphql" | "gql" | "graphqls" | "sdl" => {
+                        schema::Schema::from_sdl(&schema_string)?
+                    }
+                    "json" => schema::Schema::from_json(&schema_string)?,
+                    extension => panic!("Unsupported extension for the GraphQL schema: {} (only .json, .graphql, .gql, .graphqls and .sdl are supported)", extension)
+                }
+            };
+
+            Ok(v.insert(schema).clone())
+        }
+    }
+}
+
+/// Generates the code for a Rust module given a query, an already parsed schema and options. This is useful when the schema does not come from a file, for example when it is read from stdin.
+pub fn generate_module_token_stream_with_schema(
+    query_path: std::path::PathBuf,
+    schema: schema::Schema,
+    options: Option<GraphQLClientDeriveOptions>,
+) -> Result<TokenStream, CodegenError> {
+    let module = generate_module(query_path, schema, options.unwrap())?;
+
+    Ok(module_token_stream(&module))
+}
+
+/// Describes the response structs that [generate_module_token_stream] generates for a query, schema and options. See [type_map::TypeMap].
+pub fn generate_type_map(
+    query_path: std::path::PathBuf,
+    schema_path: std::path::PathBuf,
+    options: GraphQLClientDeriveOptions,
+) -> Result<type_map::TypeMap, CodegenError> {
+    let schema = load_schema(schema_path, options.comment_descriptions)?;
+
+    generate_type_map_with_schema(query_path, schema, options)
+}
+
+/// Like [generate_type_map], with an already parsed schema.
+pub fn generate_type_map_with_schema(
+    query_path: std::path::PathBuf,
+    schema: schema::Schema,
+    options: GraphQLClientDeriveOptions,
+) -> Result<type_map::TypeMap, CodegenError> {
+    let module = generate_module(query_path, schema, options)?;
+
+    Ok(type_map::merge(
+        module
+            .operations
+            .into_iter()
+            .map(|(code, _, _)| code.types)
+            .collect(),
+    ))
+}
+
+/// Generates the code for all the operations in the query files matching a glob pattern, like `queries/*.graphql`, in one module. The documents are merged, so fragments defined in one file can be spread in the others, but operation and fragment names must be unique across files.
+///
+/// Each operation gets a unit struct implementing `GraphQLQuery` in the module, named after the operation.
+pub fn generate_module_token_stream_from_glob(
+    query_path_glob: &str,
+    schema_path: std::path::PathBuf,
+    options: GraphQLClientDeriveOptions,
+) -> Result<TokenStream, CodegenError> {
+    let schema = load_schema(schema_path, options.comment_descriptions)?;
+
+    let mut query_strings = Vec::new();
+    let mut definitions = Vec::new();
+    let mut operation_files: Vec<(String, std::path::PathBuf)> = Vec::new();
+    // The definition of each fragment in GraphQL syntax, with its file.
+    let mut fragment_files: Vec<(String, String, std::path::PathBuf)> = Vec::new();
+
+    for query_path in glob_query_paths(query_path_glob)? {
+        let (mut query_string, mut query) = load_query(query_path.clone())?;
+
+        // Each operation gets a struct, an anonymous one is named after its file.
+        let file_name = query_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default()
+            .to_camel_case();
+        if codegen::name_anonymous_operation(&mut query, &file_name)? {
+            query_string = query.to_string();
+        }
+
+        for operation in codegen::all_operations(&query) {
+            if let Some((_, other_path)) = operation_files
+                .iter()
+                .find(|(name, _)| name == &operation.name)
+            {
+                Err(CodegenError::Validation(format!(
+                    "The {} operation is defined in both {} and {}. Operation names must be unique across the files matched by {}.",
+                    operation.name,
+                    other_path.display(),
+                    query_path.display(),
+                    query_path_glob
+                )))?
+            }
+            operation_files.push((operation.name, query_path.clone()));
+        }
+
+        for definition in &query.definitions {
+            let fragment = match definition {
+                graphql_parser::query::Definition::Fragment(fragment) => fragment,
+                _ => continue,
+            };
+            let source = graphql_parser::query::Document {
+                definitions: vec![definition.clone()],
+            }
+            .to_string();
+            if let Some((_, other_source, other_path)) = fragment_files
+                .iter()
+                .find(|(name, _, _)| name == &fragment.name)
+            {
+                let difference = if other_source == &source {
+                    ".".to_string()
+                } else {
+                    format!(
+                        ", differently:\n{}: {}\n{}: {}",
+                        other_path.display(),
+                        other_source.trim(),
+                        query_path.display(),
+                        source.trim()
+                    )
+                };
+                Err(CodegenError::Validation(format!(
+                    "The {} fragment is defined in both {} and {}{}\nFragment names must be unique across the files matched by {}.",
+                    fragment.name,
+                    other_path.display(),
+                    query_path.display(),
+                    difference,
+                    query_path_glob
+                )))?
+            }
+            fragment_files.push((fragment.name.clone(), source, query_path.clone()));
+        }
+
+        query_strings.push(query_string);
+        definitions.extend(query.definitions);
+    }
+
+    let query = graphql_parser::query::Document { definitions };
+    let mut module =
+        generate_module_for_document(query_strings.join("\n"), query, schema, options)?;
+    module.operation_structs = true;
 
-    // The user can determine what to do about deprecations.
-    let deprecation_strategy = options.deprecation_strategy.clone().unwrap_or_default();
+    Ok(module_token_stream(&module))
+}
+
+/// The files matching the pattern, sorted so the generated code does not depend on the order of the directory entries.
+fn glob_query_paths(query_path_glob: &str) -> Result<Vec<std::path::PathBuf>, CodegenError> {
+    let matches = ::glob::glob(query_path_glob).map_err(|err| {
+        CodegenError::Validation(format!(
+            "Invalid query_path_glob pattern {}: {}",
+            query_path_glob, err
+        ))
+    })?;
+
+    let mut paths = Vec::new();
+    for path in matches {
+        let path = path.map_err(|err| CodegenError::Validation(err.to_string()))?;
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+
+    if paths.is_empty() {
+        Err(CodegenError::Validation(format!(
+            "No query file matches {}.",
+            query_path_glob
+        )))?
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+fn module_token_stream(module: &GeneratedModule) -> TokenStream {
+    let schema_and_operations = module
+        .operations
+        .iter()
+        .map(|(code, operation_name, operation_name_literal)| {
+            let prelude = &code.prelude;
+            let groups = code.groups.iter().flat_map(|(_, group)| group);
+            let schema_output = quote! {
+                #(#prelude)*
+
+                #(#groups)*
+            };
+            (
+                schema_output,
+                operation_name.clone(),
+                operation_name_literal.as_str(),
+            )
+        })
+        .collect();
+
+    build_module_token_stream(module, schema_and_operations)
+}
+
+/// Everything we generate for a query document, before it is assembled into a module.
+struct GeneratedModule {
+    module_visibility: Visibility,
+    module_name: Ident,
+    struct_name: Option<Ident>,
+    query_string: String,
+    schema_hash: Option<String>,
+    with_variables: bool,
+    /// The type `from_response_str` deserializes to, generic over the response data, with the `response_wrapper` option.
+    response_wrapper: Option<syn::Path>,
+    /// The hashes the operations are registered with, by operation name, with the `persisted_query_manifest` option.
+    persisted_query_hashes: ::std::collections::BTreeMap<String, String>,
+    /// The inner attributes of the `module_attributes` option.
+    module_attributes: Vec<syn::Attribute>,
+    /// Whether each operation gets a `{Operation}Result` type alias, with the `result_aliases` option.
+    result_aliases: bool,
+    /// Whether the response data is wrapped in `graphql_client::raw::WithRaw`, with the `raw_response` option.
+    raw_response: bool,
+    /// Whether each operation gets a `cache_key` function, with the `cache_keys` option.
+    cache_keys: bool,
+    /// Whether each operation gets its own struct in the module, instead of implementing `GraphQLQuery` for `struct_name`.
+    operation_structs: bool,
+    operations: Vec<(codegen::OperationCode, Ident, String)>,
+}
 
+fn generate_module(
+    query_path: std::path::PathBuf,
+    schema: schema::Schema,
+    options: GraphQLClientDeriveOptions,
+) -> Result<GeneratedModule, CodegenError> {
     // We need to qualify the query with the path to the crate it is part of
-    let (query_string, query) = {
-        let mut lock = QUERY_CACHE.lock().expect("query cache is poisoned");
-        match lock.entry(query_path) {
-            ::std::collections::hash_map::Entry::Occupied(o) => o.get().clone(),
-            ::std::collections::hash_map::Entry::Vacant(v) => {
-                let query_string = read_file(v.key())?;
-                let query = graphql_parser::parse_query(&query_string)?;
-                v.insert((query_string, query)).clone()
+    let (query_string, query) = load_query(query_path)?;
+
+    generate_module_for_document(query_string, query, schema, options)
+}
+
+/// Reads and parses a query document, or gets it from the cache.
+fn load_query(
+    query_path: std::path::PathBuf,
+) -> Result<(String, graphql_parser::query::Document), CodegenError> {
+    let mut lock = QUERY_CACHE.lock().expect("query cache is poisoned");
+    match lock.entry(query_path) {
+        ::std::collections::hash_map::Entry::Occupied(o) => Ok(o.get().clone()),
+        ::std::collections::hash_map::Entry::Vacant(v) => {
+            let query_string = read_file(v.key())?;
+            if combined_documents::split(&query_string).has_type_system {
+                Err(CodegenError::Validation(format!(
+                    "The query document {} contains type-system definitions, but a schema is given too. Remove the schema_path to use the definitions as the schema, or move them to the schema.",
+                    v.key().display()
+                )))?
             }
+            let query = graphql_parser::parse_query(&query_string)
+                .map_err(|err| CodegenError::QueryParse(err.to_string()))?;
+            Ok(v.insert((query_string, query)).clone())
         }
+    }
+}
+
+fn generate_module_for_document(
+    query_string: String,
+    mut query: graphql_parser::query::Document,
+    schema: schema::Schema,
+    options: GraphQLClientDeriveOptions,
+) -> Result<GeneratedModule, CodegenError> {
+    let module_visibility = options.module_visibility.clone();
+
+    let mut schema = match &options.include_tag {
+        Some(tag) => tags::retain_tagged(schema, tag)?,
+        None => schema,
+    };
+    meta_fields::add_meta_fields(&mut schema);
+
+    // An anonymous operation is named after the struct, or the module.
+    let module_struct_name = options
+        .module_name
+        .as_ref()
+        .map(|name| name.to_camel_case());
+    let anonymous_name = options
+        .struct_name
+        .clone()
+        .or(module_struct_name)
+        .or_else(|| options.operation_name.clone())
+        .unwrap_or_default();
+    let mut rewritten = codegen::name_anonymous_operation(&mut query, &anonymous_name)?;
+    if options.add_typename {
+        rewritten |= typename::add_typename(&mut query, &schema);
+    }
+    let query_string = if rewritten {
+        query.to_string()
+    } else {
+        query_string
+    };
+
+    // Client directives only drive codegen, the server must not see them.
+    let query_string = match transforms::strip_transform_directives(&query) {
+        Some(stripped) => stripped.to_string(),
+        None => query_string,
     };
 
     // Determine which operation we are generating code for. This will be used in operationName.
@@ -118,38 +590,6 @@ pub fn generate_module_token_stream(
         codegen::all_operations(&query)
     };
 
-    // Check the schema cache.
-    let schema = {
-        let mut lock = SCHEMA_CACHE.lock().expect("schema cache is poisoned");
-        match lock.entry(schema_path) {
-            ::std::collections::hash_map::Entry::Occupied(o) => o.get().clone(),
-            ::std::collections::hash_map::Entry::Vacant(v) => {
-                let schema_string = read_file(v.key())?;
-                let schema = {
-                    let extension = v
-                        .key()
-                        .extension()
-                        .and_then(|e| e.to_str())
-                        .unwrap_or("INVALID");
-
-                    match extension {
-                        "graphql" | "gql" => {
-                            let s = graphql_parser::schema::parse_schema(&schema_string)?;
-                            schema::Schema::from(s)
-                        }
-                        "json" => {
-                            let parsed: introspection_response::IntrospectionResponse = ::serde_json::from_str(&schema_string)?;
-                            schema::Schema::from(parsed)
-                        }
-                        extension => panic!("Unsupported extension for the GraphQL schema: {} (only .json and .graphql are supported)", extension)
-                    }
-                };
-
-                v.insert(schema).clone()
-            }
-        }
-    };
-
     let struct_name = if options.struct_name.is_some() {
         Some(Ident::new(
             options.struct_name.clone().unwrap().as_str(),
@@ -169,63 +609,217 @@ pub fn generate_module_token_stream(
         Span::call_site(),
     );
 
+    // The response data is the type argument of the wrapper, so the path cannot have its own.
+    let response_wrapper = match &options.response_wrapper {
+        Some(path) => match syn::parse_str::<syn::Path>(path.trim()) {
+            Ok(parsed) if parsed.segments.iter().all(|s| s.arguments.is_empty()) => Some(parsed),
+            _ => Err(CodegenError::Validation(format!(
+                "The response_wrapper option must be a path, like crate::Envelope, but it is {:?}.",
+                path
+            )))?,
+        },
+        None => None,
+    };
+
+    let module_attributes = match &options.module_attributes {
+        Some(attributes) => {
+            syn::parse::Parser::parse_str(syn::Attribute::parse_inner, attributes).map_err(
+                |_| {
+                    CodegenError::Validation(format!(
+                        "The module_attributes option must be inner attributes, like #![allow(clippy::all)], but it is {:?}.",
+                        attributes
+                    ))
+                },
+            )?
+        }
+        None => Vec::new(),
+    };
+
+    let persisted_query_hashes = match &options.persisted_query_manifest {
+        Some(manifest_path) => {
+            persisted_queries::registered_hashes(manifest_path, &query_string, &operations)?
+        }
+        None => ::std::collections::BTreeMap::new(),
+    };
+
     let operation_count = operations.len();
 
     let multiple_operations = operation_count > 1;
 
-    let mut schema_and_operations = Vec::with_capacity(operation_count);
+    let mut operation_codes = Vec::with_capacity(operation_count);
+    let mut generated_definitions = ::std::collections::HashSet::new();
 
-    for operation in &operations {
-        let schema_output = codegen::response_for_query(
+    for (idx, operation) in operations.iter().enumerate() {
+        let shared_variables_with = if options.shared_variables && multiple_operations {
+            operations[..idx]
+                .iter()
+                .find(|previous| previous.has_same_variables(operation))
+                .map(|previous| previous.name.as_str())
+        } else {
+            None
+        };
+        let mut code = codegen::response_for_query(
             schema.clone(),
             query.clone(),
             &operation,
-            response_derives.clone(),
-            deprecation_strategy.clone(),
+            &options,
             multiple_operations,
+            shared_variables_with,
         )?;
+        code.remove_duplicates(&mut generated_definitions);
         let operation_name = Ident::new(operation.name.as_str(), Span::call_site());
-        schema_and_operations.push((schema_output, operation_name, operation.name.as_str()));
+        operation_codes.push((code, operation_name, operation.name.clone()));
     }
 
-    let result = build_module_token_stream(
-        &module_visibility,
-        &module_name,
-        &struct_name,
-        &query_string,
-        schema_and_operations,
-    );
+    Ok(GeneratedModule {
+        module_visibility,
+        module_name,
+        struct_name,
+        query_string,
+        schema_hash: schema.source_hash.clone(),
+        with_variables: options.with_variables,
+        response_wrapper,
+        persisted_query_hashes,
+        module_attributes,
+        result_aliases: options.result_aliases,
+        raw_response: options.raw_response,
+        cache_keys: options.cache_keys,
+        operation_structs: false,
+        operations: operation_codes,
+    })
+}
 
-    Ok(result)
+/// Generates the same code as the derive, and writes it to `out_path`. This is meant for build scripts: write the file to `OUT_DIR` and `include!` it next to the struct named in `options.struct_name`. It avoids expanding the derive on every compilation, and the generated code can be read.
+pub fn generate_to_file(
+    schema_path: std::path::PathBuf,
+    query_path: std::path::PathBuf,
+    options: GraphQLClientDeriveOptions,
+    out_path: &std::path::Path,
+) -> Result<(), CodegenError> {
+    let generated = generate_module_token_stream(query_path, schema_path, Some(options))?;
+
+    write_file(out_path, &generated)
+}
+
+/// Like [generate_to_file], but splits the generated module across several files in `out_dir`, for very large queries: one file per group of definitions (input objects, enums, fragments, selections, variables and response data), each in its own submodule, and a `mod.rs` that declares and re-exports them. `include!` the `mod.rs` file next to the struct named in `options.struct_name`.
+pub fn generate_to_directory(
+    schema_path: std::path::PathBuf,
+    query_path: std::path::PathBuf,
+    options: GraphQLClientDeriveOptions,
+    out_dir: &std::path::Path,
+) -> Result<(), CodegenError> {
+    let schema = load_schema(schema_path, options.comment_descriptions)?;
+    let module = generate_module(query_path, schema, options)?;
+
+    ::std::fs::create_dir_all(out_dir).map_err(|source| CodegenError::Io {
+        path: out_dir.to_path_buf(),
+        source,
+    })?;
+
+    // The groups of all the operations end up in the same files, like they would end up in the same module.
+    let mut group_files: Vec<(&'static str, TokenStream)> = Vec::new();
+    for (code, _, _) in &module.operations {
+        for (group_name, group) in &code.groups {
+            let group = quote!(#(#group)*);
+            match group_files.iter_mut().find(|(name, _)| name == group_name) {
+                Some((_, file)) => file.extend(group),
+                None => group_files.push((group_name, group)),
+            }
+        }
+    }
+    group_files.retain(|(_, file)| !file.is_empty());
+
+    let mut submodules = Vec::with_capacity(group_files.len());
+    for (group_name, file) in &group_files {
+        let file_name = format!("{}.rs", group_name);
+        write_file(&out_dir.join(&file_name), file)?;
+
+        let submodule = Ident::new(group_name, Span::call_site());
+        submodules.push(quote! {
+            mod #submodule {
+                #[allow(unused_imports)]
+                use super::*;
+
+                include!(#file_name);
+            }
+            pub use self::#submodule::*;
+        });
+    }
+
+    let schema_and_operations = module
+        .operations
+        .iter()
+        .enumerate()
+        .map(|(idx, (code, operation_name, operation_name_literal))| {
+            let prelude = &code.prelude;
+            // The submodules are declared once, with the first operation.
+            let submodules = if idx == 0 { &submodules[..] } else { &[] };
+            let schema_output = quote! {
+                #(#prelude)*
+
+                #(#submodules)*
+            };
+            (
+                schema_output,
+                operation_name.clone(),
+                operation_name_literal.as_str(),
+            )
+        })
+        .collect();
+
+    let generated = build_module_token_stream(&module, schema_and_operations);
+
+    write_file(&out_dir.join("mod.rs"), &generated)
+}
+
+fn write_file(path: &std::path::Path, tokens: &TokenStream) -> Result<(), CodegenError> {
+    ::std::fs::write(path, tokens.to_string()).map_err(|source| CodegenError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
 }
 
 fn build_module_token_stream(
-    module_visibility: &syn::Visibility,
-    module_name: &Ident,
-    struct_name: &Option<Ident>,
-    query_string: &str,
+    module: &GeneratedModule,
     schema_and_operations: Vec<(TokenStream, Ident, &str)>,
 ) -> TokenStream {
     let mut schema_token_streams = vec![];
     let mut trait_token_streams = vec![];
     let multiple_operations = schema_and_operations.len() > 1;
+    let struct_name = if module.operation_structs {
+        None
+    } else {
+        module.struct_name.clone()
+    };
     for (schema_output, operation_name, operation_name_literal) in schema_and_operations {
         let (schema_token_stream, trait_token_stream) = build_query_struct_token_stream(
-            &module_name,
+            &module.module_name,
             struct_name.clone(),
             &schema_output,
             &operation_name,
             operation_name_literal,
             multiple_operations,
+            module.with_variables,
+            module.response_wrapper.as_ref(),
+            module
+                .persisted_query_hashes
+                .get(operation_name_literal)
+                .map(String::as_str),
+            module.result_aliases,
+            module.raw_response,
+            module.cache_keys,
+            module.operation_structs,
         );
         schema_token_streams.push(schema_token_stream);
         trait_token_streams.push(trait_token_stream);
     }
 
     merge_with_common_token_stream(
-        &module_visibility,
-        &module_name,
-        query_string,
+        &module.module_visibility,
+        &module.module_name,
+        &module.query_string,
+        module.schema_hash.as_deref(),
+        &module.module_attributes,
         schema_token_streams,
         trait_token_streams,
     )
@@ -235,24 +829,43 @@ fn merge_with_common_token_stream(
     module_visibility: &syn::Visibility,
     module_name: &Ident,
     query_string: &str,
+    schema_hash: Option<&str>,
+    module_attributes: &[syn::Attribute],
     schema_token_streams: Vec<TokenStream>,
     trait_token_streams: Vec<TokenStream>,
 ) -> TokenStream {
+    let query_hash = query_hash::sha256_hex(query_string);
+    let document_sha256 = query_hash::sha256_bytes(query_string);
+    let normalized_query_hash = query_hash::sha256_hex(&query_hash::normalize_query(query_string));
+    let schema_hash = schema_hash.map(|schema_hash| {
+        quote! {
+            /// The hex-encoded SHA-256 hash of the schema the module was generated from. Compare it with the hash of the schema you deploy against to catch stale generated code.
+            pub const SCHEMA_HASH: &'static str = #schema_hash;
+        }
+    });
+
     quote!(
         #module_visibility mod #module_name {
             #![allow(non_camel_case_types)]
             #![allow(non_snake_case)]
             #![allow(dead_code)]
-
-            use serde;
+            #(#module_attributes)*
 
             pub const QUERY: &'static str = #query_string;
+            /// The hex-encoded SHA-256 hash of `QUERY`, for automatic persisted queries.
+            pub const QUERY_HASH: &'static str = #query_hash;
+            /// The raw bytes of `QUERY_HASH`, for systems comparing binary digests.
+            pub const DOCUMENT_SHA256: [u8; 32] = [#(#document_sha256),*];
+            /// The hex-encoded SHA-256 hash of `QUERY`, with comments removed and whitespace and commas collapsed, for servers that normalize queries before hashing them.
+            pub const NORMALIZED_QUERY_HASH: &'static str = #normalized_query_hash;
+            #schema_hash
             #(#schema_token_streams)*
         }
         #(#trait_token_streams)*
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn build_query_struct_token_stream(
     module_name: &Ident,
     struct_name: Option<Ident>,
@@ -260,12 +873,53 @@ fn build_query_struct_token_stream(
     operation_name: &Ident,
     operation_name_literal: &str,
     multiple_operations: bool,
+    with_variables: bool,
+    response_wrapper: Option<&syn::Path>,
+    persisted_query_hash: Option<&str>,
+    result_alias: bool,
+    raw_response: bool,
+    cache_key: bool,
+    operation_struct: bool,
 ) -> (TokenStream, TokenStream) {
     let struct_name = if struct_name.is_some() {
         struct_name.unwrap()
     } else {
         operation_name.clone()
     };
+    let (struct_definition, struct_name) = if operation_struct {
+        (
+            quote!(pub struct #struct_name;),
+            quote!(#module_name::#struct_name),
+        )
+    } else {
+        (quote!(), quote!(#struct_name))
+    };
+    let (operation_name_const, http_method_const, idempotent_const, persisted_hash_const) =
+        if multiple_operations {
+            let prefix = operation_name_literal.to_shouty_snake_case();
+            (
+                Ident::new(&format!("{}_OPERATION_NAME", prefix), Span::call_site()),
+                Ident::new(&format!("{}_HTTP_METHOD", prefix), Span::call_site()),
+                Ident::new(&format!("{}_IDEMPOTENT", prefix), Span::call_site()),
+                Ident::new(
+                    &format!("{}_PERSISTED_QUERY_HASH", prefix),
+                    Span::call_site(),
+                ),
+            )
+        } else {
+            (
+                Ident::new("OPERATION_NAME", Span::call_site()),
+                Ident::new("HTTP_METHOD", Span::call_site()),
+                Ident::new("IDEMPOTENT", Span::call_site()),
+                Ident::new("PERSISTED_QUERY_HASH", Span::call_site()),
+            )
+        };
+    let persisted_query_hash = persisted_query_hash.map(|hash| {
+        quote! {
+            /// The hash the operation is registered with in the persisted query manifest.
+            pub const #persisted_hash_const: &'static str = #hash;
+        }
+    });
 
     let (respons_data_struct_name, variables_struct_name) = if multiple_operations {
         (
@@ -285,20 +939,109 @@ fn build_query_struct_token_stream(
         )
     };
 
+    // The response data type, as seen from the generated module.
+    let response_data = if raw_response {
+        quote!(::graphql_client::raw::WithRaw<#respons_data_struct_name>)
+    } else {
+        quote!(#respons_data_struct_name)
+    };
+    let result_alias = if result_alias {
+        let alias_name = Ident::new(
+            &format!("{}Result", operation_name_literal),
+            Span::call_site(),
+        );
+        let doc = format!(
+            "The full result of the {} operation, as `from_response_str` returns it: the response data, or the errors.",
+            operation_name_literal
+        );
+        // The alias is in the generated module, so a relative wrapper path is resolved from its parent.
+        let result_type = match response_wrapper {
+            Some(wrapper) if is_absolute_path(wrapper) => quote!(#wrapper<#response_data>),
+            Some(wrapper) => quote!(super::#wrapper<#response_data>),
+            None => quote!(::graphql_client::Response<#response_data>),
+        };
+        quote! {
+            #[doc = #doc]
+            pub type #alias_name = #result_type;
+        }
+    } else {
+        quote!()
+    };
+
     let schema_token = quote!(
-        pub const OPERATION_NAME: &'static str = #operation_name_literal;
+        pub const #operation_name_const: &'static str = #operation_name_literal;
+        #persisted_query_hash
+        #result_alias
+        #struct_definition
         #schema_output
     );
+    let with_variables = if with_variables {
+        quote! {
+            impl #struct_name {
+                /// Starts building the request body for the operation. Chain it with `with_variables` and `build`.
+                pub fn new() -> Self {
+                    #struct_name
+                }
+
+                /// Sets the variables of the operation. Call `build` on the result to get the request body.
+                pub fn with_variables(self, variables: #module_name::#variables_struct_name) -> ::graphql_client::WithVariables<Self> {
+                    ::graphql_client::WithVariables::new(variables)
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+    let response_data = if raw_response {
+        quote!(::graphql_client::raw::WithRaw<#module_name::#respons_data_struct_name>)
+    } else {
+        quote!(#module_name::#respons_data_struct_name)
+    };
+    let response_type = match response_wrapper {
+        Some(wrapper) => quote!(#wrapper<#response_data>),
+        None => quote!(::graphql_client::Response<#response_data>),
+    };
+    let cache_key = if cache_key {
+        quote! {
+            impl #struct_name {
+                /// A stable key for caching the response to the operation with these variables: the operation name, the hash of the query and the variables as canonical JSON. Equal variables give equal keys. Variables that cannot be serialized, like a custom scalar failing to, are an error.
+                pub fn cache_key(variables: &#module_name::#variables_struct_name) -> Result<String, ::graphql_client::serde_json::Error> {
+                    ::graphql_client::cache_key::cache_key(#module_name::#operation_name_const, #module_name::QUERY_HASH, variables)
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
     let trait_token = quote!(
+        #with_variables
+        #cache_key
+
+        impl #struct_name {
+            /// Builds the request body of the operation, with the query, variables and operation name, and serializes it to a JSON string ready to send.
+            pub fn to_request_body(variables: #module_name::#variables_struct_name) -> Result<String, ::graphql_client::serde_json::Error> {
+                ::graphql_client::serde_json::to_string(&<Self as ::graphql_client::GraphQLQuery>::build_query(variables))
+            }
+
+            /// Deserializes the body of a response to the operation: a `graphql_client::Response`, or the type of the `response_wrapper` option.
+            pub fn from_response_str(body: &str) -> Result<#response_type, ::graphql_client::serde_json::Error> {
+                ::graphql_client::serde_json::from_str(body)
+            }
+        }
+
         impl ::graphql_client::GraphQLQuery for #struct_name {
             type Variables = #module_name::#variables_struct_name;
-            type ResponseData = #module_name::#respons_data_struct_name;
+            type ResponseData = #response_data;
+
+            const HTTP_METHOD: ::graphql_client::HttpMethod = #module_name::#http_method_const;
+
+            const IDEMPOTENT: bool = #module_name::#idempotent_const;
 
             fn build_query(variables: Self::Variables) -> ::graphql_client::QueryBody<Self::Variables> {
                 ::graphql_client::QueryBody {
                     variables,
                     query: #module_name::QUERY,
-                    operation_name: #module_name::OPERATION_NAME,
+                    operation_name: #module_name::#operation_name_const,
                 }
 
             }
@@ -307,20 +1050,58 @@ fn build_query_struct_token_stream(
     (schema_token, trait_token)
 }
 
-fn read_file(path: &::std::path::Path) -> Result<String, failure::Error> {
-    use std::io::prelude::*;
-
-    let mut out = String::new();
-    let mut file = ::std::fs::File::open(path).map_err(|io_err| {
-        let err: failure::Error = io_err.into();
-        err.context(format!(
-            r#"
-            Could not find file with path: {}
-            Hint: file paths in the GraphQLQuery attribute are relative to the project root (location of the Cargo.toml). Example: query_path = "src/my_query.graphql".
-            "#,
-            path.display()
-        ))
+/// Whether the path starts with `::` or `crate`, so it resolves the same from any module.
+fn is_absolute_path(path: &syn::Path) -> bool {
+    path.leading_colon.is_some()
+        || path
+            .segments
+            .first()
+            .map(|segment| segment.value().ident == "crate")
+            .unwrap_or(false)
+}
+
+/// Reads a schema or query file the way the derive does, accepting UTF-8 with or without a byte order mark, and UTF-16.
+pub fn read_file(path: &::std::path::Path) -> Result<String, CodegenError> {
+    let bytes = ::std::fs::read(path).map_err(|source| CodegenError::Io {
+        path: path.to_path_buf(),
+        source,
     })?;
-    file.read_to_string(&mut out)?;
-    Ok(out)
+
+    decode_text(&bytes).ok_or_else(|| CodegenError::Encoding {
+        path: path.to_path_buf(),
+    })
+}
+
+/// Decodes the contents of a query or schema file. Files saved on Windows often start with a byte order mark, or are encoded in UTF-16, so we accept UTF-8 with or without a BOM, and UTF-16. Without a BOM, UTF-16 is only tried when the zero bytes look like it, so a file in an 8-bit encoding like Latin-1 is an encoding error rather than garbled text.
+fn decode_text(bytes: &[u8]) -> Option<String> {
+    match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8(rest.to_vec()).ok(),
+        [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, u16::from_be_bytes),
+        // ASCII in UTF-16 is also valid UTF-8, with a zero byte in every other place, so it is told apart first.
+        _ if looks_like_utf16(bytes, 1) => decode_utf16(bytes, u16::from_le_bytes),
+        _ if looks_like_utf16(bytes, 0) => decode_utf16(bytes, u16::from_be_bytes),
+        _ => String::from_utf8(bytes.to_vec()).ok(),
+    }
+}
+
+/// Whether the bytes look like UTF-16 text with the high byte of each code unit at `high_byte`. GraphQL documents are mostly ASCII, so at least half of their code units have a zero high byte, which no 8-bit encoding has.
+fn looks_like_utf16(bytes: &[u8], high_byte: usize) -> bool {
+    let units = bytes.chunks_exact(2);
+    if bytes.is_empty() || !units.remainder().is_empty() {
+        return false;
+    }
+
+    let zero_high_bytes = units.filter(|unit| unit[high_byte] == 0).count();
+    zero_high_bytes * 2 >= bytes.len() / 2
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Option<String> {
+    let pairs = bytes.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+
+    let units: Vec<u16> = pairs.map(|pair| from_bytes([pair[0], pair[1]])).collect();
+    String::from_utf16(&units).ok()
 }
@@ -0,0 +1,164 @@
+//! The `__schema` and `__type(name:)` meta-fields of the query root, and the introspection types they return, so queries inspecting the schema get typed responses like any other query.
+
+use deprecation::DeprecationStatus;
+use field_type::FieldType;
+use graphql_parser;
+use objects::GqlObjectField;
+use schema::Schema;
+
+/// The introspection types, from the June 2018 edition of the spec.
+const INTROSPECTION_SDL: &str = r#"
+type __Schema {
+  types: [__Type!]!
+  queryType: __Type!
+  mutationType: __Type
+  subscriptionType: __Type
+  directives: [__Directive!]!
+}
+
+type __Type {
+  kind: __TypeKind!
+  name: String
+  description: String
+  fields(includeDeprecated: Boolean = false): [__Field!]
+  interfaces: [__Type!]
+  possibleTypes: [__Type!]
+  enumValues(includeDeprecated: Boolean = false): [__EnumValue!]
+  inputFields: [__InputValue!]
+  ofType: __Type
+}
+
+type __Field {
+  name: String!
+  description: String
+  args: [__InputValue!]!
+  type: __Type!
+  isDeprecated: Boolean!
+  deprecationReason: String
+}
+
+type __InputValue {
+  name: String!
+  description: String
+  type: __Type!
+  defaultValue: String
+}
+
+type __EnumValue {
+  name: String!
+  description: String
+  isDeprecated: Boolean!
+  deprecationReason: String
+}
+
+type __Directive {
+  name: String!
+  description: String
+  locations: [__DirectiveLocation!]!
+  args: [__InputValue!]!
+}
+
+enum __TypeKind {
+  SCALAR
+  OBJECT
+  INTERFACE
+  UNION
+  ENUM
+  INPUT_OBJECT
+  LIST
+  NON_NULL
+}
+
+enum __DirectiveLocation {
+  QUERY
+  MUTATION
+  SUBSCRIPTION
+  FIELD
+  FRAGMENT_DEFINITION
+  FRAGMENT_SPREAD
+  INLINE_FRAGMENT
+  SCHEMA
+  SCALAR
+  OBJECT
+  FIELD_DEFINITION
+  ARGUMENT_DEFINITION
+  INTERFACE
+  UNION
+  ENUM
+  ENUM_VALUE
+  INPUT_OBJECT
+  INPUT_FIELD_DEFINITION
+}
+"#;
+
+/// Adds the introspection types the schema does not define, and the `__schema` and `__type(name: String!)` fields to its query type. Introspected schemas usually list the types already, but neither SDL nor introspection has the meta-fields.
+pub(crate) fn add_meta_fields(schema: &mut Schema) {
+    let introspection = Schema::from(
+        graphql_parser::parse_schema(INTROSPECTION_SDL).expect("the introspection types parse"),
+    );
+    for (name, object) in introspection.objects {
+        schema.objects.entry(name).or_insert(object);
+    }
+    for (name, enm) in introspection.enums {
+        schema.enums.entry(name).or_insert(enm);
+    }
+
+    let objects = &mut schema.objects;
+    let query_type = match schema
+        .query_type
+        .as_ref()
+        .and_then(|name| objects.get_mut(name))
+    {
+        Some(query_type) => query_type,
+        None => return,
+    };
+    let meta_fields = vec![
+        meta_field(
+            "__schema",
+            FieldType::Named("__Schema".to_string()),
+            Vec::new(),
+        ),
+        meta_field(
+            "__type",
+            FieldType::Optional(Box::new(FieldType::Named("__Type".to_string()))),
+            vec![("name".to_string(), FieldType::Named("String".to_string()))],
+        ),
+    ];
+    for field in meta_fields {
+        if query_type.fields.iter().all(|f| f.name != field.name) {
+            query_type.fields.push(field);
+        }
+    }
+}
+
+fn meta_field(name: &str, type_: FieldType, arguments: Vec<(String, FieldType)>) -> GqlObjectField {
+    GqlObjectField {
+        description: None,
+        name: name.to_string(),
+        type_,
+        deprecation: DeprecationStatus::Current,
+        arguments,
+        external: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meta_fields_are_added_to_the_query_type() {
+        let mut schema =
+            Schema::from_sdl("schema { query: Query } type Query { ping: String }").unwrap();
+        add_meta_fields(&mut schema);
+
+        let fields: Vec<&str> = schema.objects["Query"]
+            .fields
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect();
+        assert_eq!(fields, vec!["__typename", "ping", "__schema", "__type"]);
+        assert!(schema.objects.contains_key("__Field"));
+        assert!(schema.enums.contains_key("__TypeKind"));
+    }
+}
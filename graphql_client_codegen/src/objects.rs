@@ -1,13 +1,13 @@
 use constants::*;
 use deprecation::DeprecationStatus;
-use failure;
+use error::CodegenError;
 use field_type::FieldType;
 use graphql_parser::schema;
 use proc_macro2::{Ident, Span, TokenStream};
 use query::QueryContext;
 use schema::Schema;
 use selection::*;
-use shared::{field_impls_for_selection, response_fields_for_selection};
+use shared::{field_impls_for_selection, list_iterator_impls, response_fields_for_selection};
 use std::borrow::Cow;
 use std::cell::Cell;
 
@@ -25,6 +25,10 @@ pub struct GqlObjectField {
     pub name: String,
     pub type_: FieldType,
     pub deprecation: DeprecationStatus,
+    /// The names and types of the arguments of the field.
+    pub arguments: Vec<(String, FieldType)>,
+    /// Whether the field has the federation `@external` directive: the subgraph declares it, but another subgraph resolves it. Introspection does not expose directives, so it is only known from SDL schemas.
+    pub external: bool,
 }
 
 fn parse_deprecation_info(field: &schema::Field) -> DeprecationStatus {
@@ -52,11 +56,74 @@ fn parse_deprecation_info(field: &schema::Field) -> DeprecationStatus {
         None
     };
     match deprecated {
-        Some(_) => DeprecationStatus::Deprecated(reason),
+        Some(d) => DeprecationStatus::Deprecated(with_removal_date(reason, d)),
         None => DeprecationStatus::Current,
     }
 }
 
+/// Appends the `removalDate` argument of the `@deprecated` directive, that some schemas add, to the deprecation reason, like `Use name instead. Removal on 2025-06-01.`. Introspection does not expose it, so it is only known from SDL schemas.
+fn with_removal_date(reason: Option<String>, deprecated: &schema::Directive) -> Option<String> {
+    let removal_date = deprecated
+        .arguments
+        .iter()
+        .find(|(name, _)| name == REMOVAL_DATE_ARGUMENT)
+        .and_then(|(_, value)| match value {
+            schema::Value::String(date) => Some(date),
+            schema::Value::Null => None,
+            _ => panic!("deprecation removal date is not a string"),
+        });
+
+    match (reason, removal_date) {
+        (Some(reason), Some(date)) => {
+            let separator = if reason.ends_with('.') { " " } else { ". " };
+            Some(format!("{}{}Removal on {}.", reason, separator, date))
+        }
+        (None, Some(date)) => Some(format!("Removal on {}.", date)),
+        (reason, None) => reason,
+    }
+}
+
+/// Whether the directives of a field defined in SDL include the federation `@external` directive.
+pub(crate) fn is_external(directives: &[schema::Directive]) -> bool {
+    directives
+        .iter()
+        .any(|directive| directive.name == EXTERNAL_DIRECTIVE)
+}
+
+/// The names and types of the arguments of a field defined in SDL, sorted by name like [introspected_arguments].
+pub(crate) fn sdl_arguments(arguments: &[schema::InputValue]) -> Vec<(String, FieldType)> {
+    let mut arguments: Vec<(String, FieldType)> = arguments
+        .iter()
+        .map(|argument| {
+            (
+                argument.name.clone(),
+                FieldType::from(argument.value_type.clone()),
+            )
+        })
+        .collect();
+    arguments.sort_by(|a, b| a.0.cmp(&b.0));
+    arguments
+}
+
+/// The names and types of the arguments of an introspected field, sorted by name.
+pub(crate) fn introspected_arguments(
+    arguments: Option<Vec<Option<::introspection_response::FullTypeFieldsArgs>>>,
+) -> Vec<(String, FieldType)> {
+    let mut arguments: Vec<(String, FieldType)> = arguments
+        .unwrap_or_default()
+        .into_iter()
+        .flatten()
+        .map(|argument| {
+            (
+                argument.input_value.name.expect("argument name"),
+                FieldType::from(argument.input_value.type_.expect("argument type")),
+            )
+        })
+        .collect();
+    arguments.sort_by(|a, b| a.0.cmp(&b.0));
+    arguments
+}
+
 impl GqlObject {
     pub fn new(name: Cow<str>, description: Option<&str>) -> GqlObject {
         GqlObject {
@@ -77,6 +144,8 @@ impl GqlObject {
                 name: f.name.clone(),
                 type_: FieldType::from(f.field_type.clone()),
                 deprecation,
+                arguments: sdl_arguments(&f.arguments),
+                external: is_external(&f.directives),
             }
         }));
         item
@@ -100,6 +169,8 @@ impl GqlObject {
                     name: t.name.expect("field name"),
                     type_: FieldType::from(t.type_.expect("field type")),
                     deprecation,
+                    arguments: introspected_arguments(t.args),
+                    external: false,
                 }
             })
         });
@@ -124,30 +195,84 @@ impl GqlObject {
         query_context: &QueryContext,
         selection: &Selection,
         prefix: &str,
-    ) -> Result<TokenStream, failure::Error> {
-        let derives = query_context.response_derives();
+    ) -> Result<TokenStream, CodegenError> {
+        let derives = query_context.response_struct_derives();
+        let from_row_derive = query_context.sqlx_from_row_derive(&self.fields, selection);
         let name = Ident::new(prefix, Span::call_site());
         let fields = self.response_fields_for_selection(query_context, selection, prefix)?;
         let field_impls = self.field_impls_for_selection(query_context, selection, &prefix)?;
         let description = self.description.as_ref().map(|desc| quote!(#[doc = #desc]));
+        let list_iterator_impls =
+            self.list_iterator_impls(query_context, &name, selection, prefix)?;
+        let rename_all = query_context.rename_all_annotation();
+        let visibility = query_context.type_visibility();
+        let optimistic = self.optimistic_struct(query_context, &name, selection, prefix);
+        let accessors = self.field_accessors(query_context, &name, selection, prefix);
+        let collectors = self.scalar_collectors(query_context, &name, selection, prefix);
+        let estimated_size = ::estimated_size::struct_impl(
+            &self.fields,
+            query_context,
+            &name,
+            selection,
+            prefix,
+            query_context.unknown_fields_names(),
+        );
+        let extra = query_context.unknown_fields_field();
+        let definition = query_context.transform_type(
+            prefix,
+            quote! {
+                #derives
+                #from_row_derive
+                #description
+                #rename_all
+                #visibility struct #name {
+                    #(#fields,)*
+                    #extra
+                }
+            },
+        );
         Ok(quote! {
             #(#field_impls)*
 
-            #derives
-            #description
-            pub struct #name {
-                #(#fields,)*
-            }
+            #definition
+
+            #list_iterator_impls
+
+            #optimistic
+
+            #accessors
+
+            #collectors
+
+            #estimated_size
         })
     }
 
+    /// See [shared::list_iterator_impls].
+    pub(crate) fn list_iterator_impls(
+        &self,
+        query_context: &QueryContext,
+        struct_name: &Ident,
+        selection: &Selection,
+        prefix: &str,
+    ) -> Result<TokenStream, CodegenError> {
+        list_iterator_impls(
+            &self.name,
+            &self.fields,
+            query_context,
+            struct_name,
+            selection,
+            prefix,
+        )
+    }
+
     pub(crate) fn field_impls_for_selection(
         &self,
         query_context: &QueryContext,
         selection: &Selection,
         prefix: &str,
-    ) -> Result<Vec<TokenStream>, failure::Error> {
-        field_impls_for_selection(&self.fields, query_context, selection, prefix)
+    ) -> Result<Vec<TokenStream>, CodegenError> {
+        field_impls_for_selection(&self.name, &self.fields, query_context, selection, prefix)
     }
 
     pub(crate) fn response_fields_for_selection(
@@ -155,9 +280,56 @@ impl GqlObject {
         query_context: &QueryContext,
         selection: &Selection,
         prefix: &str,
-    ) -> Result<Vec<TokenStream>, failure::Error> {
+    ) -> Result<Vec<TokenStream>, CodegenError> {
         response_fields_for_selection(&self.name, &self.fields, query_context, selection, prefix)
     }
+
+    /// The getter methods for the fields of the struct for the selection. See [::shared::field_accessors].
+    pub(crate) fn field_accessors(
+        &self,
+        query_context: &QueryContext,
+        struct_name: &Ident,
+        selection: &Selection,
+        prefix: &str,
+    ) -> TokenStream {
+        ::shared::field_accessors(&self.fields, query_context, struct_name, selection, prefix)
+    }
+
+    /// The `collect_*_values` methods of the struct for the selection. See [::collectors::struct_collectors].
+    pub(crate) fn scalar_collectors(
+        &self,
+        query_context: &QueryContext,
+        struct_name: &Ident,
+        selection: &Selection,
+        prefix: &str,
+    ) -> TokenStream {
+        ::collectors::struct_collectors(
+            &self.fields,
+            query_context,
+            struct_name,
+            selection,
+            prefix,
+            false,
+        )
+    }
+
+    /// The `Optimistic` mirror of the struct for the selection. See [::shared::optimistic_struct].
+    pub(crate) fn optimistic_struct(
+        &self,
+        query_context: &QueryContext,
+        struct_name: &Ident,
+        selection: &Selection,
+        prefix: &str,
+    ) -> TokenStream {
+        ::shared::optimistic_struct(
+            &self.fields,
+            query_context,
+            struct_name,
+            selection,
+            prefix,
+            quote!(),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -227,6 +399,48 @@ mod test {
         let _ = parse_deprecation_info(&mock_field(vec![directive]));
     }
 
+    #[test]
+    fn deprecation_with_removal_date() {
+        let directive = schema::Directive {
+            position: Pos::default(),
+            name: "deprecated".to_string(),
+            arguments: vec![
+                (
+                    "reason".to_string(),
+                    query::Value::String("Use `name` instead".to_string()),
+                ),
+                (
+                    "removalDate".to_string(),
+                    query::Value::String("2025-06-01".to_string()),
+                ),
+            ],
+        };
+        let result = parse_deprecation_info(&mock_field(vec![directive]));
+        assert_eq!(
+            DeprecationStatus::Deprecated(Some(
+                "Use `name` instead. Removal on 2025-06-01.".to_string()
+            )),
+            result
+        );
+    }
+
+    #[test]
+    fn deprecation_with_removal_date_and_no_reason() {
+        let directive = schema::Directive {
+            position: Pos::default(),
+            name: "deprecated".to_string(),
+            arguments: vec![(
+                "removalDate".to_string(),
+                query::Value::String("2025-06-01".to_string()),
+            )],
+        };
+        let result = parse_deprecation_info(&mock_field(vec![directive]));
+        assert_eq!(
+            DeprecationStatus::Deprecated(Some("Removal on 2025-06-01.".to_string())),
+            result
+        );
+    }
+
     #[test]
     fn no_deprecation() {
         let result = parse_deprecation_info(&mock_field(vec![]));
@@ -1,9 +1,11 @@
 use constants::*;
-use graphql_parser::query::OperationDefinition;
-use heck::SnakeCase;
+use error::CodegenError;
+use field_type::FieldType;
+use graphql_parser::query::{Directive, OperationDefinition, Value};
+use heck::CamelCase;
 use proc_macro2::{Span, TokenStream};
 use query::QueryContext;
-use selection::Selection;
+use selection::{Selection, SelectionItem};
 use syn::Ident;
 use variables::Variable;
 
@@ -14,12 +16,25 @@ pub enum OperationType {
     Subscription,
 }
 
+impl OperationType {
+    /// The keyword of the operation type in GraphQL syntax, like `query`.
+    pub(crate) fn keyword(&self) -> &'static str {
+        match self {
+            OperationType::Query => "query",
+            OperationType::Mutation => "mutation",
+            OperationType::Subscription => "subscription",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Operation {
     pub name: String,
     pub operation_type: OperationType,
     pub variables: Vec<Variable>,
     pub selection: Selection,
+    /// The directives on the operation itself, e.g. `@meta`.
+    pub directives: Vec<Directive>,
 }
 
 impl Operation {
@@ -38,12 +53,197 @@ impl Operation {
         }
     }
 
+    /// Operations must select at least one field.
+    pub(crate) fn validate_selection_not_empty(&self) -> Result<(), CodegenError> {
+        if self.selection.0.is_empty() {
+            Err(CodegenError::Validation(format!(
+                "The {} operation must select at least one field.",
+                self.name
+            )))?
+        }
+
+        Ok(())
+    }
+
+    /// Per spec, subscriptions must select exactly one root field. Fields selected through fragments count too.
+    pub(crate) fn validate_subscription_root(
+        &self,
+        context: &QueryContext,
+    ) -> Result<(), CodegenError> {
+        let mut root_fields = Vec::new();
+        collect_root_fields(&self.selection, context, &mut Vec::new(), &mut root_fields)?;
+
+        if root_fields.len() != 1 {
+            Err(CodegenError::Validation(format!(
+                "The {} subscription selects {} root fields ({}).\n{}",
+                self.name,
+                root_fields.len(),
+                root_fields.join(", "),
+                MULTIPLE_SUBSCRIPTION_FIELDS_ERROR
+            )))?
+        }
+
+        Ok(())
+    }
+
+    /// The `if` argument of `@include` and `@skip` on the selected fields, including those of the fragments, must be a `Boolean` literal or a variable declared by the operation as a `Boolean`.
+    pub(crate) fn validate_condition_variables(
+        &self,
+        context: &QueryContext,
+    ) -> Result<(), CodegenError> {
+        let mut conditions = Vec::new();
+        collect_conditions(&self.selection, context, &mut Vec::new(), &mut conditions)?;
+
+        for (condition, directive, field) in conditions {
+            match condition {
+                Value::Boolean(_) => (),
+                Value::Variable(variable) => {
+                    let declared = self
+                        .variables
+                        .iter()
+                        .find(|declared| &declared.name == variable)
+                        .ok_or_else(|| {
+                            CodegenError::Validation(format!(
+                                "The ${} variable of @{} on the {} field is not declared by the {} operation.",
+                                variable, directive, field, self.name
+                            ))
+                        })?;
+                    let non_null = match &declared.ty {
+                        FieldType::Optional(inner) => &**inner,
+                        ty => ty,
+                    };
+                    if *non_null != FieldType::Named("Boolean".to_string()) {
+                        Err(CodegenError::Validation(format!(
+                            "The ${} variable of @{} on the {} field is declared as {} by the {} operation, but the if argument is a Boolean.",
+                            variable,
+                            directive,
+                            field,
+                            ::arguments::graphql_type(&declared.ty),
+                            self.name
+                        )))?
+                    }
+                }
+                literal => Err(CodegenError::Validation(format!(
+                    "The if argument of @{} on the {} field is {}, but it is a Boolean.",
+                    directive, field, literal
+                )))?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The variables passed to the arguments of the selected fields and of their directives must be declared by the operation. The fragments are shared by the operations of the document, so the variables of the fragments it spreads, directly or through other fragments, are checked for each operation.
+    pub(crate) fn validate_argument_variables(
+        &self,
+        context: &QueryContext,
+    ) -> Result<(), CodegenError> {
+        let mut usages = Vec::new();
+        collect_argument_variables(&self.selection, context, None, &mut Vec::new(), &mut usages)?;
+
+        for (variable, usage) in usages {
+            if self
+                .variables
+                .iter()
+                .all(|declared| declared.name != variable)
+            {
+                Err(CodegenError::Validation(format!(
+                    "The ${} variable of {} is not declared by the {} operation.",
+                    variable, usage, self.name
+                )))?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The variables used as items of list literals, like `$a` in `ids: [$a, $b, 3]`, must be declared with a type the items of the list accept. The literal items are checked with the other literal arguments.
+    pub(crate) fn validate_list_item_variables(
+        &self,
+        context: &QueryContext,
+    ) -> Result<(), CodegenError> {
+        let root_name = match self.root_name(&context.schema) {
+            Some(root_name) => root_name,
+            None => return Ok(()),
+        };
+        let mut usages = Vec::new();
+        collect_list_item_variables(
+            &self.selection,
+            &root_name,
+            context,
+            None,
+            &mut Vec::new(),
+            &mut usages,
+        )?;
+
+        for (variable, item_type, usage) in usages {
+            // Undeclared variables are reported by `validate_argument_variables`.
+            let declared = match self.variables.iter().find(|v| v.name == variable) {
+                Some(declared) => declared,
+                None => continue,
+            };
+            // A variable with a default value is never null, even if its type is nullable.
+            let declared_type = match (&declared.ty, &declared.default) {
+                (FieldType::Optional(inner), Some(default)) if *default != Value::Null => &**inner,
+                (ty, _) => ty,
+            };
+
+            if !variable_type_allowed(declared_type, &item_type) {
+                Err(CodegenError::Validation(format!(
+                    "The ${} variable in the list of {} is declared as {} by the {} operation, but the items of the list are {}.",
+                    variable,
+                    usage,
+                    ::arguments::graphql_type(&declared.ty),
+                    self.name,
+                    ::arguments::graphql_type(&item_type)
+                )))?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The variable definitions in GraphQL syntax, e.g. `($id: ID!, $limit: Int = 10)`, or an empty string if the operation has no variables.
+    pub(crate) fn variables_schema(&self) -> String {
+        if self.variables.is_empty() {
+            return String::new();
+        }
+
+        let definitions: Vec<String> = self
+            .variables
+            .iter()
+            .map(|variable| {
+                let ty = ::arguments::graphql_type(&variable.ty);
+                match &variable.default {
+                    Some(default) => format!("${}: {} = {}", variable.name, ty, default),
+                    None => format!("${}: {}", variable.name, ty),
+                }
+            })
+            .collect();
+
+        format!("({})", definitions.join(", "))
+    }
+
+    /// Whether both operations declare the same variables, regardless of their order.
+    pub(crate) fn has_same_variables(&self, other: &Operation) -> bool {
+        let sorted = |op: &Operation| {
+            let mut variables: Vec<Variable> = op.variables.clone();
+            variables.sort_by(|a, b| a.name.cmp(&b.name));
+            variables
+        };
+
+        sorted(self) == sorted(other)
+    }
+
     /// Generate the Variables struct and all the necessary supporting code.
+    ///
+    /// If `shared_with` is the name of another operation, we only generate an alias to its Variables struct.
     pub(crate) fn expand_variables(
         &self,
         context: &QueryContext,
         operation_name: &str,
         multiple_operations: bool,
+        shared_with: Option<&str>,
     ) -> TokenStream {
         let variables = &self.variables;
         let variables_struct_name = if multiple_operations {
@@ -54,20 +254,56 @@ impl Operation {
         } else {
             Ident::new("Variables", Span::call_site())
         };
+        let visibility = context.type_visibility();
+
+        if let Some(shared_with) = shared_with {
+            let shared_struct_name =
+                Ident::new(&format!("{}Variables", shared_with), Span::call_site());
+            return quote!(#visibility type #variables_struct_name = #shared_struct_name;);
+        }
 
         let variables_derives = context.variables_derives();
 
         if variables.is_empty() {
-            return quote!(#variables_derives
-            pub struct #variables_struct_name;);
+            // Any object, or no variables at all, converts to a struct without variables.
+            let try_from = try_from_value_impl(
+                context,
+                &variables_struct_name,
+                quote! {
+                    match value {
+                        ::graphql_client::serde_json::Value::Object(_) | ::graphql_client::serde_json::Value::Null => Ok(#variables_struct_name),
+                        value => ::graphql_client::serde_json::from_value(value),
+                    }
+                },
+            );
+            let definition = context.transform_type(
+                &variables_struct_name.to_string(),
+                quote! {
+                    #variables_derives
+                    #visibility struct #variables_struct_name;
+                },
+            );
+            let as_pairs = variables_as_pairs_impl(context, variables, &variables_struct_name);
+            return quote!(#definition
+
+            #try_from
+
+            #as_pairs);
+        }
+
+        // The types of the variables are required before the definitions are collected, including the custom scalars and enums used only by the variables and never in the response.
+        for variable in variables {
+            context.schema.require(&variable.ty.inner_name_string());
         }
 
         let fields = variables.iter().map(|variable| {
-            let name = &variable.name;
-            let ty = variable.ty.to_rust(context, "");
-            let snake_case_name = name.to_snake_case();
-            let rename = ::shared::field_rename_annotation(&name, &snake_case_name);
-            let name = Ident::new(&snake_case_name, Span::call_site());
+            let ty = variable.rust_type(context);
+            let name = ::shared::field_ident(&variable.name);
+            let rename = ::shared::field_rename_annotation(
+                &variable.name,
+                &name.to_string(),
+                context.rename_all_camel_case,
+            );
 
             quote!(#rename pub #name: #ty)
         });
@@ -76,19 +312,627 @@ impl Operation {
             .iter()
             .map(|variable| variable.generate_default_value_constructor(context));
 
+        let rename_all = context.rename_all_annotation();
+        let builder = variables_builder_impl(context, variables, &variables_struct_name);
+        let constructor = variables_constructor_impl(context, variables, &variables_struct_name);
+        let try_from = try_from_value_impl(
+            context,
+            &variables_struct_name,
+            quote!(::graphql_client::serde_json::from_value(value)),
+        );
+        let as_pairs = variables_as_pairs_impl(context, variables, &variables_struct_name);
+
+        let definition = context.transform_type(
+            &variables_struct_name.to_string(),
+            quote! {
+                #variables_derives
+                #rename_all
+                #visibility struct #variables_struct_name {
+                    #(#fields,)*
+                }
+            },
+        );
+
         quote! {
-            #variables_derives
-            pub struct #variables_struct_name {
-                #(#fields,)*
-            }
+            #definition
 
             impl #variables_struct_name {
                 #(#default_constructors)*
             }
+
+            #constructor
+
+            #builder
+
+            #try_from
+
+            #as_pairs
+        }
+    }
+}
+
+/// The positional constructor of a `Variables` struct, with the `variables_constructor` option: `new` takes the required variables in the order the operation declares them, and the others are `None` or their default value.
+fn variables_constructor_impl(
+    context: &QueryContext,
+    variables: &[Variable],
+    variables_struct_name: &Ident,
+) -> TokenStream {
+    if !context.variables_constructor {
+        return quote!();
+    }
+
+    let required: Vec<&Variable> = variables
+        .iter()
+        .filter(|variable| variable.is_required(context))
+        .collect();
+    let arguments = required.iter().map(|variable| {
+        let name = ::shared::field_ident(&variable.name);
+        let ty = variable.rust_type(context);
+        quote!(#name: #ty)
+    });
+    let fields = variables.iter().map(|variable| {
+        let name = ::shared::field_ident(&variable.name);
+        if variable.is_required(context) {
+            quote!(#name)
+        } else if variable.ty.is_optional() {
+            quote!(#name: None)
+        } else {
+            let constructor = variable.default_value_constructor_name();
+            quote!(#name: #variables_struct_name::#constructor())
+        }
+    });
+    let doc = if required.is_empty() {
+        "The variables of the operation, all `None` or their default value.".to_string()
+    } else {
+        format!(
+            "The variables of the operation, from the required ones in order: {}. The others are `None` or their default value.",
+            required
+                .iter()
+                .map(|variable| format!("`${}`", variable.name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+
+    quote! {
+        impl #variables_struct_name {
+            #[doc = #doc]
+            pub fn new(#(#arguments),*) -> Self {
+                #variables_struct_name {
+                    #(#fields,)*
+                }
+            }
+        }
+    }
+}
+
+/// The typestate builder of a `Variables` struct, with the `variables_builder` option. Each required variable is a type parameter of the builder, `graphql_client::builder::Unset` until its setter is called, and `build` is only implemented once they are all set. The other variables start as `None` or their default value.
+fn variables_builder_impl(
+    context: &QueryContext,
+    variables: &[Variable],
+    variables_struct_name: &Ident,
+) -> TokenStream {
+    if !context.variables_builder {
+        return quote!();
+    }
+
+    let builder_name = Ident::new(
+        &format!("{}Builder", variables_struct_name),
+        Span::call_site(),
+    );
+    let visibility = context.type_visibility();
+    let names: Vec<Ident> = variables
+        .iter()
+        .map(|variable| ::shared::field_ident(&variable.name))
+        .collect();
+    let types: Vec<TokenStream> = variables
+        .iter()
+        .map(|variable| variable.rust_type(context))
+        .collect();
+    // The type parameter of each required variable, `None` for the others.
+    let params: Vec<Option<Ident>> = variables
+        .iter()
+        .map(|variable| {
+            if variable.is_required(context) {
+                Some(Ident::new(
+                    &format!("T{}", variable.name.to_camel_case()),
+                    Span::call_site(),
+                ))
+            } else {
+                None
+            }
+        })
+        .collect();
+    let generics = |args: Vec<TokenStream>| {
+        if args.is_empty() {
+            quote!()
+        } else {
+            quote!(<#(#args),*>)
+        }
+    };
+
+    let declared_params = generics(params.iter().flatten().map(|p| quote!(#p)).collect());
+    let unset_params = generics(
+        params
+            .iter()
+            .flatten()
+            .map(|_| quote!(::graphql_client::builder::Unset))
+            .collect(),
+    );
+    let set_params = generics(
+        params
+            .iter()
+            .zip(&types)
+            .filter(|(param, _)| param.is_some())
+            .map(|(_, ty)| ty.clone())
+            .collect(),
+    );
+
+    let field_types = params.iter().zip(&types).map(|(param, ty)| match param {
+        Some(param) => quote!(#param),
+        None => ty.clone(),
+    });
+    let initial_values = variables.iter().zip(&params).map(|(variable, param)| {
+        if param.is_some() {
+            quote!(::graphql_client::builder::Unset)
+        } else if variable.ty.is_optional() {
+            quote!(None)
+        } else {
+            let constructor = variable.default_value_constructor_name();
+            quote!(#variables_struct_name::#constructor())
+        }
+    });
+
+    let setters =
+        names
+            .iter()
+            .zip(&types)
+            .zip(&params)
+            .enumerate()
+            .map(|(index, ((name, ty), param))| {
+                if param.is_none() {
+                    return quote! {
+                        pub fn #name(self, #name: #ty) -> Self {
+                            #builder_name { #name, ..self }
+                        }
+                    };
+                }
+
+                let result_params = generics(
+                    params
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(other, param)| {
+                            if other == index {
+                                Some(ty.clone())
+                            } else {
+                                param.as_ref().map(|param| quote!(#param))
+                            }
+                        })
+                        .collect(),
+                );
+                let others: Vec<&Ident> = names
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != index)
+                    .map(|(_, other)| other)
+                    .collect();
+                let other_fields = &others;
+                let other_values = &others;
+                quote! {
+                    pub fn #name(self, #name: #ty) -> #builder_name #result_params {
+                        #builder_name { #name, #(#other_fields: self.#other_values,)* }
+                    }
+                }
+            });
+
+    let fields = &names;
+    let values = &names;
+    quote! {
+        /// Builds the variables of the operation. `build` is only available once every required variable is set.
+        #visibility struct #builder_name #declared_params {
+            #(#fields: #field_types,)*
+        }
+
+        impl #variables_struct_name {
+            /// Starts building the variables, with the optional ones unset or set to their default value.
+            pub fn builder() -> #builder_name #unset_params {
+                #builder_name {
+                    #(#fields: #initial_values,)*
+                }
+            }
+        }
+
+        impl #declared_params #builder_name #declared_params {
+            #(#setters)*
+        }
+
+        impl #builder_name #set_params {
+            pub fn build(self) -> #variables_struct_name {
+                #variables_struct_name {
+                    #(#fields: self.#values,)*
+                }
+            }
+        }
+    }
+}
+
+/// The `TryFrom<serde_json::Value>` implementation for a `Variables` struct, with the `variables_try_from` option. Missing required variables and values of the wrong type are reported as `serde_json` errors.
+fn try_from_value_impl(
+    context: &QueryContext,
+    variables_struct_name: &Ident,
+    conversion: TokenStream,
+) -> TokenStream {
+    if !context.variables_try_from {
+        return quote!();
+    }
+
+    quote! {
+        impl ::std::convert::TryFrom<::graphql_client::serde_json::Value> for #variables_struct_name {
+            type Error = ::graphql_client::serde_json::Error;
+
+            fn try_from(value: ::graphql_client::serde_json::Value) -> ::std::result::Result<Self, Self::Error> {
+                #conversion
+            }
+        }
+    }
+}
+
+/// The `variables_as_pairs` method of a `Variables` struct, with the `variables_as_pairs` option: each variable serialized on its own, with its GraphQL name, for transports sending them as separate parameters. Nullable variables set to `None` are left out.
+fn variables_as_pairs_impl(
+    context: &QueryContext,
+    variables: &[Variable],
+    variables_struct_name: &Ident,
+) -> TokenStream {
+    if !context.variables_as_pairs {
+        return quote!();
+    }
+
+    let pairs = variables.iter().map(|variable| {
+        let name = &variable.name;
+        let field = ::shared::field_ident(name);
+        let to_value = quote! {
+            ::graphql_client::serde_json::to_value(value).expect("variables can be serialized")
+        };
+
+        if variable.ty.is_optional() {
+            quote! {
+                if let Some(value) = &self.#field {
+                    pairs.push((#name.to_string(), #to_value));
+                }
+            }
+        } else {
+            quote! {
+                let value = &self.#field;
+                pairs.push((#name.to_string(), #to_value));
+            }
+        }
+    });
+
+    quote! {
+        impl #variables_struct_name {
+            /// The variables as `(name, value)` pairs, each serialized on its own, e.g. to send them as query parameters. Variables set to `None` are left out.
+            #[allow(unused_mut)]
+            pub fn variables_as_pairs(&self) -> Vec<(String, ::graphql_client::serde_json::Value)> {
+                let mut pairs = Vec::new();
+                #(#pairs)*
+                pairs
+            }
         }
     }
 }
 
+/// The response keys (aliases or names) of the fields in the selection, looking through fragments.
+fn collect_root_fields<'a>(
+    selection: &'a Selection,
+    context: &'a QueryContext,
+    visited_fragments: &mut Vec<&'a str>,
+    root_fields: &mut Vec<&'a str>,
+) -> Result<(), CodegenError> {
+    for item in &selection.0 {
+        match item {
+            SelectionItem::Field(field) => {
+                let key = field.alias.as_ref().unwrap_or(&field.name);
+                if !root_fields.contains(&key.as_str()) {
+                    root_fields.push(key);
+                }
+            }
+            SelectionItem::InlineFragment(inline) => {
+                collect_root_fields(&inline.fields, context, visited_fragments, root_fields)?
+            }
+            SelectionItem::FragmentSpread(spread) => {
+                let name = spread.fragment_name.as_str();
+                if visited_fragments.contains(&name) {
+                    continue;
+                }
+                visited_fragments.push(name);
+                let fragment =
+                    context
+                        .fragments
+                        .get(name)
+                        .ok_or_else(|| CodegenError::UnknownFragment {
+                            name: name.to_string(),
+                        })?;
+                collect_root_fields(&fragment.selection, context, visited_fragments, root_fields)?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The `(condition, directive, field)` of every `@include` or `@skip` on a field with an `if` argument, looking through fragments.
+fn collect_conditions<'a>(
+    selection: &'a Selection,
+    context: &'a QueryContext,
+    visited_fragments: &mut Vec<&'a str>,
+    conditions: &mut Vec<(&'a Value, &'a str, &'a str)>,
+) -> Result<(), CodegenError> {
+    for item in &selection.0 {
+        match item {
+            SelectionItem::Field(field) => {
+                for (directive, condition) in field.conditions() {
+                    if let Some(condition) = condition {
+                        conditions.push((condition, directive, &field.name));
+                    }
+                }
+                collect_conditions(&field.fields, context, visited_fragments, conditions)?
+            }
+            SelectionItem::InlineFragment(inline) => {
+                collect_conditions(&inline.fields, context, visited_fragments, conditions)?
+            }
+            SelectionItem::FragmentSpread(spread) => {
+                let name = spread.fragment_name.as_str();
+                if visited_fragments.contains(&name) {
+                    continue;
+                }
+                visited_fragments.push(name);
+                let fragment =
+                    context
+                        .fragments
+                        .get(name)
+                        .ok_or_else(|| CodegenError::UnknownFragment {
+                            name: name.to_string(),
+                        })?;
+                collect_conditions(&fragment.selection, context, visited_fragments, conditions)?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The variables in the arguments of the selected fields and of their directives, with a description of where they are used, like `the first argument on the friends field of the FriendsFragment fragment`.
+fn collect_argument_variables<'a>(
+    selection: &'a Selection,
+    context: &'a QueryContext,
+    fragment_name: Option<&'a str>,
+    visited_fragments: &mut Vec<&'a str>,
+    usages: &mut Vec<(&'a str, String)>,
+) -> Result<(), CodegenError> {
+    let in_fragment = fragment_name
+        .map(|name| format!(" of the {} fragment", name))
+        .unwrap_or_default();
+
+    for item in &selection.0 {
+        match item {
+            SelectionItem::Field(field) => {
+                for (argument, value) in &field.arguments {
+                    let mut variables = Vec::new();
+                    value_variables(value, &mut variables);
+                    usages.extend(variables.into_iter().map(|variable| {
+                        let usage = format!(
+                            "the {} argument on the {} field{}",
+                            argument, field.name, in_fragment
+                        );
+                        (variable, usage)
+                    }));
+                }
+                for (directive, arguments) in &field.directives {
+                    for (argument, value) in arguments {
+                        let mut variables = Vec::new();
+                        value_variables(value, &mut variables);
+                        usages.extend(variables.into_iter().map(|variable| {
+                            let usage = format!(
+                                "the {} argument of @{} on the {} field{}",
+                                argument, directive, field.name, in_fragment
+                            );
+                            (variable, usage)
+                        }));
+                    }
+                }
+                collect_argument_variables(
+                    &field.fields,
+                    context,
+                    fragment_name,
+                    visited_fragments,
+                    usages,
+                )?
+            }
+            SelectionItem::InlineFragment(inline) => collect_argument_variables(
+                &inline.fields,
+                context,
+                fragment_name,
+                visited_fragments,
+                usages,
+            )?,
+            SelectionItem::FragmentSpread(spread) => {
+                let name = spread.fragment_name.as_str();
+                if visited_fragments.contains(&name) {
+                    continue;
+                }
+                visited_fragments.push(name);
+                let fragment =
+                    context
+                        .fragments
+                        .get(name)
+                        .ok_or_else(|| CodegenError::UnknownFragment {
+                            name: name.to_string(),
+                        })?;
+                collect_argument_variables(
+                    &fragment.selection,
+                    context,
+                    Some(name),
+                    visited_fragments,
+                    usages,
+                )?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The variables used as list items in the arguments of the selected fields of `type_name`, with the type of the items and a description of the argument, looking through fragments. Unknown fields and arguments are reported when generating the response types.
+fn collect_list_item_variables<'a>(
+    selection: &'a Selection,
+    type_name: &str,
+    context: &'a QueryContext,
+    fragment_name: Option<&'a str>,
+    visited_fragments: &mut Vec<&'a str>,
+    usages: &mut Vec<(&'a str, FieldType, String)>,
+) -> Result<(), CodegenError> {
+    let in_fragment = fragment_name
+        .map(|name| format!(" of the {} fragment", name))
+        .unwrap_or_default();
+    let schema_fields = match (
+        context.schema.objects.get(type_name),
+        context.schema.interfaces.get(type_name),
+    ) {
+        (Some(object), _) => &object.fields[..],
+        (None, Some(interface)) => &interface.fields[..],
+        (None, None) => &[],
+    };
+
+    for item in &selection.0 {
+        match item {
+            SelectionItem::Field(field) => {
+                let schema_field = match schema_fields.iter().find(|f| f.name == field.name) {
+                    Some(schema_field) => schema_field,
+                    None => continue,
+                };
+                for (argument, value) in &field.arguments {
+                    let ty = match schema_field.arguments.iter().find(|(a, _)| a == argument) {
+                        Some((_, ty)) => ty,
+                        None => continue,
+                    };
+                    let mut variables = Vec::new();
+                    list_item_variables(value, ty, context, &mut variables);
+                    usages.extend(variables.into_iter().map(|(variable, item_type)| {
+                        let usage = format!(
+                            "the {} argument on the {} field{}",
+                            argument, field.name, in_fragment
+                        );
+                        (variable, item_type, usage)
+                    }));
+                }
+                collect_list_item_variables(
+                    &field.fields,
+                    &schema_field.type_.inner_name_string(),
+                    context,
+                    fragment_name,
+                    visited_fragments,
+                    usages,
+                )?
+            }
+            SelectionItem::InlineFragment(inline) => collect_list_item_variables(
+                &inline.fields,
+                &inline.on,
+                context,
+                fragment_name,
+                visited_fragments,
+                usages,
+            )?,
+            SelectionItem::FragmentSpread(spread) => {
+                let name = spread.fragment_name.as_str();
+                if visited_fragments.contains(&name) {
+                    continue;
+                }
+                visited_fragments.push(name);
+                let fragment =
+                    context
+                        .fragments
+                        .get(name)
+                        .ok_or_else(|| CodegenError::UnknownFragment {
+                            name: name.to_string(),
+                        })?;
+                collect_list_item_variables(
+                    &fragment.selection,
+                    &fragment.on,
+                    context,
+                    Some(name),
+                    visited_fragments,
+                    usages,
+                )?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The variables among the items of the list literals in a value of type `ty`, including the lists nested in lists and input objects, with the type of the items.
+fn list_item_variables<'a>(
+    value: &'a Value,
+    ty: &FieldType,
+    context: &QueryContext,
+    variables: &mut Vec<(&'a str, FieldType)>,
+) {
+    match (value, ty) {
+        (value, FieldType::Optional(inner)) => {
+            list_item_variables(value, inner, context, variables)
+        }
+        (Value::List(items), FieldType::Vector(item_type)) => {
+            for item in items {
+                match item {
+                    Value::Variable(variable) => variables.push((variable, (**item_type).clone())),
+                    item => list_item_variables(item, item_type, context, variables),
+                }
+            }
+        }
+        (Value::Object(fields), FieldType::Named(name)) => {
+            if let Some(input) = context.schema.inputs.get(name) {
+                for (field_name, value) in fields {
+                    if let Some(field) = input.fields.get(field_name) {
+                        list_item_variables(value, &field.type_, context, variables);
+                    }
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Whether a variable of type `variable` can be used where a value of type `location` is expected: a non-null variable fits a nullable location, but not the other way around, and the named types must be the same.
+fn variable_type_allowed(variable: &FieldType, location: &FieldType) -> bool {
+    match (variable, location) {
+        (FieldType::Optional(variable), FieldType::Optional(location)) => {
+            variable_type_allowed(variable, location)
+        }
+        (FieldType::Optional(_), _) => false,
+        (variable, FieldType::Optional(location)) => variable_type_allowed(variable, location),
+        (FieldType::Vector(variable), FieldType::Vector(location)) => {
+            variable_type_allowed(variable, location)
+        }
+        (FieldType::Named(variable), FieldType::Named(location)) => variable == location,
+        _ => false,
+    }
+}
+
+/// The variables in an argument value, including those nested in lists and input objects.
+fn value_variables<'a>(value: &'a Value, variables: &mut Vec<&'a str>) {
+    match value {
+        Value::Variable(variable) => variables.push(variable),
+        Value::List(items) => items
+            .iter()
+            .for_each(|item| value_variables(item, variables)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|field| value_variables(field, variables)),
+        _ => (),
+    }
+}
+
 impl ::std::convert::From<OperationDefinition> for Operation {
     fn from(definition: OperationDefinition) -> Operation {
         match definition {
@@ -101,6 +945,7 @@ impl ::std::convert::From<OperationDefinition> for Operation {
                     .map(|v| v.clone().into())
                     .collect(),
                 selection: (&q.selection_set).into(),
+                directives: q.directives.clone(),
             },
             OperationDefinition::Mutation(m) => Operation {
                 name: m.name.expect("unnamed operation"),
@@ -111,6 +956,7 @@ impl ::std::convert::From<OperationDefinition> for Operation {
                     .map(|v| v.clone().into())
                     .collect(),
                 selection: (&m.selection_set).into(),
+                directives: m.directives.clone(),
             },
             OperationDefinition::Subscription(s) => Operation {
                 name: s.name.expect("unnamed operation"),
@@ -121,8 +967,9 @@ impl ::std::convert::From<OperationDefinition> for Operation {
                     .map(|v| v.clone().into())
                     .collect(),
                 selection: (&s.selection_set).into(),
+                directives: s.directives.clone(),
             },
-            OperationDefinition::SelectionSet(_) => panic!(SELECTION_SET_AT_ROOT),
+            OperationDefinition::SelectionSet(_) => panic!("unnamed operation"),
         }
     }
 }
@@ -139,6 +986,7 @@ impl<'a> ::std::convert::From<&'a OperationDefinition> for Operation {
                     .map(|v| v.clone().into())
                     .collect(),
                 selection: (&q.selection_set).into(),
+                directives: q.directives.clone(),
             },
             OperationDefinition::Mutation(ref m) => Operation {
                 name: m.name.clone().expect("unnamed operation"),
@@ -149,6 +997,7 @@ impl<'a> ::std::convert::From<&'a OperationDefinition> for Operation {
                     .map(|v| v.clone().into())
                     .collect(),
                 selection: (&m.selection_set).into(),
+                directives: m.directives.clone(),
             },
             OperationDefinition::Subscription(ref s) => Operation {
                 name: s.name.clone().expect("unnamed operation"),
@@ -159,8 +1008,9 @@ impl<'a> ::std::convert::From<&'a OperationDefinition> for Operation {
                     .map(|v| v.clone().into())
                     .collect(),
                 selection: (&s.selection_set).into(),
+                directives: s.directives.clone(),
             },
-            OperationDefinition::SelectionSet(_) => panic!(SELECTION_SET_AT_ROOT),
+            OperationDefinition::SelectionSet(_) => panic!("unnamed operation"),
         }
     }
 }
@@ -0,0 +1,57 @@
+//! Persisted query manifests, the JSON objects mapping the operation names to the hashes they are registered with on the server, e.g. `{"HeroQuery": "4a5b..."}`.
+//!
+//! The registered hash of each operation must be the `QUERY_HASH` or the `NORMALIZED_QUERY_HASH` of the generated module, so a client sending only the hash runs the query it was generated from.
+
+use error::CodegenError;
+use operations::Operation;
+use query_hash;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The registered hash of each operation, by operation name, checked against the hashes of `query_string`.
+pub(crate) fn registered_hashes(
+    manifest_path: &Path,
+    query_string: &str,
+    operations: &[Operation],
+) -> Result<BTreeMap<String, String>, CodegenError> {
+    let manifest = ::read_file(manifest_path)?;
+    let manifest: BTreeMap<String, String> = ::serde_json::from_str(&manifest).map_err(|err| {
+        CodegenError::Validation(format!(
+            "The persisted query manifest {} must be a JSON object mapping operation names to hashes: {}",
+            manifest_path.display(),
+            err
+        ))
+    })?;
+
+    let computed = [
+        query_hash::sha256_hex(query_string),
+        query_hash::sha256_hex(&query_hash::normalize_query(query_string)),
+    ];
+
+    operations
+        .iter()
+        .map(|operation| {
+            let registered = manifest.get(&operation.name).ok_or_else(|| {
+                CodegenError::Validation(format!(
+                    "The {} operation is not registered in the persisted query manifest {}.",
+                    operation.name,
+                    manifest_path.display()
+                ))
+            })?;
+            let registered = registered.trim().to_lowercase();
+
+            if !computed.contains(&registered) {
+                Err(CodegenError::Validation(format!(
+                    "The {} operation is registered with the hash {} in the persisted query manifest {}, but the query hashes to {}, or {} normalized. Register the query again.",
+                    operation.name,
+                    registered,
+                    manifest_path.display(),
+                    computed[0],
+                    computed[1]
+                )))?
+            }
+
+            Ok((operation.name.clone(), registered))
+        })
+        .collect()
+}
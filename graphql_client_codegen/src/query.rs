@@ -1,19 +1,126 @@
 use deprecation::DeprecationStrategy;
-use failure;
+use enums::{EnumUnknowns, EnumValueMethod};
+use error::CodegenError;
+use field_type::FieldType;
 use fragments::GqlFragment;
+use heck::CamelCase;
 use itertools::Itertools;
+use objects::GqlObjectField;
 use proc_macro2::Span;
 use proc_macro2::TokenStream;
-use schema::Schema;
-use selection::Selection;
-use std::collections::BTreeMap;
+use schema::{Schema, DEFAULT_SCALARS};
+use selection::{Selection, SelectionItem};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
 use syn::Ident;
+use token_transform::TokenTransform;
+use transforms::SensitiveFields;
+use type_map::{FieldDescription, TypeDescription};
+
+/// The length of the hash ending the names shortened by the `max_identifier_length` option.
+const NAME_HASH_LENGTH: usize = 8;
+
+/// The smallest value of the `max_identifier_length` option, so the shortened names keep a part of the full name before their hash.
+pub(crate) const MIN_IDENTIFIER_LENGTH: usize = 16;
+
+/// The derives that `f32` and `f64` do not implement.
+const FLOAT_INCOMPATIBLE_DERIVES: &[&str] = &["Eq", "Ord", "Hash"];
+
+/// The derives of the standard library and serde, which need the fields to implement the trait. They stay on the nested types with the `top_level_derives_only` option, since `ResponseData` could not derive them otherwise.
+const STRUCTURAL_DERIVES: &[&str] = &[
+    "Debug",
+    "Clone",
+    "Copy",
+    "PartialEq",
+    "Eq",
+    "PartialOrd",
+    "Ord",
+    "Hash",
+    "Default",
+    "Serialize",
+    "Deserialize",
+];
 
 /// This holds all the information we need during the code generation phase.
 pub(crate) struct QueryContext {
     pub fragments: BTreeMap<String, GqlFragment>,
     pub schema: Schema,
     pub deprecation_strategy: DeprecationStrategy,
+    /// Whether to generate `is_*` and `as_*` methods on union and interface enums.
+    pub variant_accessors: bool,
+    /// Whether to implement `IntoIterator` and `FromIterator` for structs wrapping a single list.
+    pub list_iterators: bool,
+    /// Whether to rename struct fields with a container-level `#[serde(rename_all = "camelCase")]`, instead of one `#[serde(rename)]` per field.
+    pub rename_all_camel_case: bool,
+    /// Whether to generate a `Patch` version of each input object.
+    pub input_patches: bool,
+    /// Whether to implement `Default` for the response types, for test scaffolding.
+    pub test_defaults: bool,
+    /// Whether to generate an `all_variants` function on enums.
+    pub enum_all_variants: bool,
+    /// Whether to generate the `Optimistic` mirrors of the response structs.
+    pub optimistic_responses: bool,
+    /// Whether to generate getter methods for the fields of the response structs.
+    pub field_accessors: bool,
+    /// Whether to generate the `omitted_conditional_fields` methods of the response structs.
+    pub conditional_fields_report: bool,
+    /// Whether the enums are deserialized case-insensitively.
+    pub case_insensitive_enums: bool,
+    /// How the fields with the `@sensitive` directive are serialized.
+    pub sensitive_fields: SensitiveFields,
+    /// Whether to implement `TryFrom<serde_json::Value>` for the `Variables` structs.
+    pub variables_try_from: bool,
+    /// Whether inline fragments on interfaces are flattened into the structs of the objects implementing them.
+    pub interface_flatten: bool,
+    /// What the generated enums do with unknown values.
+    pub enum_unknowns: EnumUnknowns,
+    /// Whether the response types derive the `rkyv` traits.
+    pub rkyv: bool,
+    /// Whether the `__typename` fields of the response structs are skipped when serializing.
+    pub strip_typename: bool,
+    /// The visibility of the generated types, if not `pub`.
+    pub visibility: Option<syn::Visibility>,
+    /// The hooks post-processing the generated code, if any.
+    pub transform: Option<Rc<dyn TokenTransform>>,
+    /// Whether the serde derives are emitted as paths.
+    pub qualified_paths: bool,
+    /// Whether the `@external` fields are left out of the response types.
+    pub skip_external_fields: bool,
+    /// Whether the `Variables` structs get a typestate builder.
+    pub variables_builder: bool,
+    /// Whether the `Variables` structs get a positional `new` constructor.
+    pub variables_constructor: bool,
+    /// Whether the `String` scalar is generated as `compact_str::CompactString`.
+    pub compact_str: bool,
+    /// Whether the response types derive `bevy_reflect::Reflect`.
+    pub bevy_reflect: bool,
+    /// Whether the `Variables` structs get a `variables_as_pairs` method.
+    pub variables_as_pairs: bool,
+    /// Whether the flat response structs derive `sqlx::FromRow`.
+    pub sqlx_from_row: bool,
+    /// Whether the nullable lists of the response are generated as `Vec`s defaulting to empty.
+    pub empty_lists: bool,
+    /// Whether the response structs collect the keys no field reads into an `extra` map.
+    pub unknown_fields: bool,
+    /// The absolute path of the serde crate, with the `serde_crate` option.
+    pub serde_crate: Option<String>,
+    /// Whether the response types implement `graphql_client::estimated_size::EstimatedSize`.
+    pub estimated_size: bool,
+    /// The directives and arguments generating methods on the enums, with the `enum_value_methods` option.
+    pub enum_value_methods: Vec<EnumValueMethod>,
+    /// Whether the derives outside of the standard library and serde are only on `ResponseData`, with the `top_level_derives_only` option.
+    pub top_level_derives_only: bool,
+    /// The derives only applied when testing, with `#[cfg_attr(test, derive(...))]`, from the `test_only_derives` option.
+    test_only_derives: Vec<Ident>,
+    /// The scalar types with a `collect_*_values` method on every response type, with the `scalar_collectors` option. Empty otherwise.
+    pub collected_scalars: BTreeSet<String>,
+    /// The response structs resolved so far, by name, for the type map.
+    pub described_types: RefCell<BTreeMap<String, TypeDescription>>,
+    /// The longest name of the types generated for the nested selections, from the `max_identifier_length` option.
+    pub max_identifier_length: Option<usize>,
+    /// The names given so far with the `max_identifier_length` option, shortened or as they are, by full name.
+    bounded_names: RefCell<BTreeMap<String, String>>,
     variables_derives: Vec<Ident>,
     response_derives: Vec<Ident>,
 }
@@ -25,6 +132,43 @@ impl QueryContext {
             fragments: BTreeMap::new(),
             schema,
             deprecation_strategy,
+            variant_accessors: false,
+            list_iterators: false,
+            rename_all_camel_case: false,
+            input_patches: false,
+            test_defaults: false,
+            enum_all_variants: false,
+            optimistic_responses: false,
+            field_accessors: false,
+            conditional_fields_report: false,
+            case_insensitive_enums: false,
+            sensitive_fields: SensitiveFields::default(),
+            variables_try_from: false,
+            interface_flatten: false,
+            enum_unknowns: EnumUnknowns::default(),
+            rkyv: false,
+            strip_typename: false,
+            visibility: None,
+            transform: None,
+            qualified_paths: false,
+            skip_external_fields: false,
+            variables_builder: false,
+            variables_constructor: false,
+            compact_str: false,
+            bevy_reflect: false,
+            variables_as_pairs: false,
+            sqlx_from_row: false,
+            empty_lists: false,
+            unknown_fields: false,
+            serde_crate: None,
+            estimated_size: false,
+            enum_value_methods: Vec::new(),
+            top_level_derives_only: false,
+            test_only_derives: Vec::new(),
+            collected_scalars: BTreeSet::new(),
+            described_types: RefCell::new(BTreeMap::new()),
+            max_identifier_length: None,
+            bounded_names: RefCell::new(BTreeMap::new()),
             variables_derives: vec![Ident::new("Serialize", Span::call_site())],
             response_derives: vec![Ident::new("Deserialize", Span::call_site())],
         }
@@ -43,6 +187,43 @@ impl QueryContext {
             fragments: BTreeMap::new(),
             schema: Schema::new(),
             deprecation_strategy: DeprecationStrategy::Allow,
+            variant_accessors: false,
+            list_iterators: false,
+            rename_all_camel_case: false,
+            input_patches: false,
+            test_defaults: false,
+            enum_all_variants: false,
+            optimistic_responses: false,
+            field_accessors: false,
+            conditional_fields_report: false,
+            case_insensitive_enums: false,
+            sensitive_fields: SensitiveFields::default(),
+            variables_try_from: false,
+            interface_flatten: false,
+            enum_unknowns: EnumUnknowns::default(),
+            rkyv: false,
+            strip_typename: false,
+            visibility: None,
+            transform: None,
+            qualified_paths: false,
+            skip_external_fields: false,
+            variables_builder: false,
+            variables_constructor: false,
+            compact_str: false,
+            bevy_reflect: false,
+            variables_as_pairs: false,
+            sqlx_from_row: false,
+            empty_lists: false,
+            unknown_fields: false,
+            serde_crate: None,
+            estimated_size: false,
+            enum_value_methods: Vec::new(),
+            top_level_derives_only: false,
+            test_only_derives: Vec::new(),
+            collected_scalars: BTreeSet::new(),
+            described_types: RefCell::new(BTreeMap::new()),
+            max_identifier_length: None,
+            bounded_names: RefCell::new(BTreeMap::new()),
             variables_derives: vec![Ident::new("Serialize", Span::call_site())],
             response_derives: vec![Ident::new("Deserialize", Span::call_site())],
         }
@@ -53,7 +234,7 @@ impl QueryContext {
         ty: &str,
         selection: &Selection,
         prefix: &str,
-    ) -> Result<TokenStream, failure::Error> {
+    ) -> Result<TokenStream, CodegenError> {
         if let Some(enm) = self.schema.enums.get(ty) {
             enm.is_required.set(true);
             Ok(quote!()) // we already expand enums separately
@@ -74,10 +255,10 @@ impl QueryContext {
     pub(crate) fn ingest_additional_derives(
         &mut self,
         attribute_value: &str,
-    ) -> Result<(), failure::Error> {
+    ) -> Result<(), CodegenError> {
         if self.response_derives.len() > 1 {
-            return Err(format_err!(
-                "ingest_additional_derives should only be called once"
+            return Err(CodegenError::Validation(
+                "ingest_additional_derives should only be called once".to_string(),
             ));
         }
 
@@ -96,38 +277,448 @@ impl QueryContext {
         Ok(())
     }
 
+    /// Reads the `test_only_derives` option. The serde derives are rejected, since the `#[serde]` annotations of the types need them in every build, and the derives that are already response or variables derives are skipped.
+    pub(crate) fn ingest_test_only_derives(
+        &mut self,
+        attribute_value: &str,
+    ) -> Result<(), CodegenError> {
+        for derive in attribute_value.split(',').map(|s| s.trim()) {
+            if derive.is_empty() {
+                continue;
+            }
+            if derive == "Serialize" || derive == "Deserialize" {
+                Err(CodegenError::Validation(format!(
+                    "{} cannot be in test_only_derives, the serde annotations of the generated types need it in every build.",
+                    derive
+                )))?
+            }
+            if self.response_derives.iter().any(|d| d == derive)
+                || self.variables_derives.iter().any(|d| d == derive)
+            {
+                continue;
+            }
+            self.test_only_derives
+                .push(Ident::new(derive, Span::call_site()));
+        }
+        Ok(())
+    }
+
+    /// The `#[cfg_attr(test, derive(...))]` attribute of the `test_only_derives` option. With `top_level_derives_only`, the types nested in `ResponseData` only get the derives of the standard library.
+    fn test_only_derives_attribute(&self, nested: bool) -> TokenStream {
+        let derives: Vec<&Ident> = self
+            .test_only_derives
+            .iter()
+            .filter(|derive| {
+                !nested
+                    || !self.top_level_derives_only
+                    || STRUCTURAL_DERIVES.contains(&derive.to_string().as_str())
+            })
+            .unique()
+            .collect();
+
+        if derives.is_empty() {
+            quote!()
+        } else {
+            quote!(#[cfg_attr(test, derive( #(#derives),* ))])
+        }
+    }
+
+    /// A derive as written in `#[derive(...)]`: the serde derives are paths with the `qualified_paths` and `serde_crate` options.
+    fn derive_path(&self, derive: &Ident) -> TokenStream {
+        if (self.qualified_paths || self.serde_crate.is_some())
+            && (derive == "Serialize" || derive == "Deserialize")
+        {
+            let serde = self.serde_path();
+            quote!(#serde::#derive)
+        } else {
+            quote!(#derive)
+        }
+    }
+
+    /// The path of the serde crate in the generated code, `::serde` unless the `serde_crate` option is set.
+    pub(crate) fn serde_path(&self) -> TokenStream {
+        match &self.serde_crate {
+            Some(path) => path
+                .parse()
+                .expect("the serde_crate option is a valid path"),
+            None => quote!(::serde),
+        }
+    }
+
+    /// The `#[serde(crate = "...")]` annotation of the types deriving serde traits, with the `serde_crate` option.
+    fn serde_crate_annotation(&self) -> TokenStream {
+        match &self.serde_crate {
+            Some(path) => quote!(#[serde(crate = #path)]),
+            None => quote!(),
+        }
+    }
+
     pub(crate) fn variables_derives(&self) -> TokenStream {
-        let derives = self.variables_derives.iter().unique();
+        let derives = self
+            .variables_derive_idents()
+            .unique()
+            .map(|derive| self.derive_path(&derive));
+        let test_only_derives = self.test_only_derives_attribute(false);
+        let serde_crate = self.serde_crate_annotation();
 
         quote! {
             #[derive( #(#derives),* )]
+            #test_only_derives
+            #serde_crate
         }
     }
 
+    /// The derives for the patch input types: the variables derives, and `Default` so omitted fields can be filled in with `..Default::default()`.
+    pub(crate) fn patch_derives(&self) -> TokenStream {
+        let default = Ident::new("Default", Span::call_site());
+        let derives = self
+            .variables_derive_idents()
+            .chain(::std::iter::once(default))
+            .unique()
+            .map(|derive| self.derive_path(&derive));
+        let test_only_derives = self.test_only_derives_attribute(false);
+        let serde_crate = self.serde_crate_annotation();
+
+        quote! {
+            #[derive( #(#derives),* )]
+            #test_only_derives
+            #serde_crate
+        }
+    }
+
+    /// The variables derives, with `Deserialize` when the variables are converted from JSON values by the `variables_try_from` option.
+    fn variables_derive_idents<'a>(&'a self) -> impl Iterator<Item = Ident> + 'a {
+        let deserialize = if self.variables_try_from {
+            Some(Ident::new("Deserialize", Span::call_site()))
+        } else {
+            None
+        };
+        self.variables_derives.iter().cloned().chain(deserialize)
+    }
+
+    /// The visibility keyword of the generated structs, enums and type aliases.
+    pub(crate) fn type_visibility(&self) -> TokenStream {
+        match &self.visibility {
+            Some(visibility) => quote!(#visibility),
+            None => quote!(pub),
+        }
+    }
+
+    /// Whether the response types derive `Serialize`.
+    pub(crate) fn response_is_serializable(&self) -> bool {
+        self.response_derives
+            .iter()
+            .any(|derive| derive == "Serialize")
+    }
+
+    /// The response derives that floats do not implement, so the types deriving them cannot hold `Float` values.
+    pub(crate) fn float_incompatible_derives(&self) -> Vec<String> {
+        self.response_derives
+            .iter()
+            .chain(&self.test_only_derives)
+            .map(|derive| derive.to_string())
+            .filter(|derive| FLOAT_INCOMPATIBLE_DERIVES.contains(&derive.as_str()))
+            .unique()
+            .collect()
+    }
+
+    /// The response derives of the types nested in `ResponseData`: without the derives outside of the standard library and serde with the `top_level_derives_only` option.
+    fn nested_response_derives<'a>(&'a self) -> impl Iterator<Item = &'a Ident> + 'a {
+        self.response_derives.iter().filter(move |derive| {
+            !self.top_level_derives_only
+                || STRUCTURAL_DERIVES.contains(&derive.to_string().as_str())
+        })
+    }
+
     pub(crate) fn response_derives(&self) -> TokenStream {
-        let derives = self.response_derives.iter().unique();
+        let derives = self
+            .nested_response_derives()
+            .unique()
+            .map(|derive| self.derive_path(derive));
+        let test_only_derives = self.test_only_derives_attribute(true);
+        let rkyv_derives = self.rkyv_derives();
+        let reflect_derive = self.reflect_derive();
+        let serde_crate = self.serde_crate_annotation();
 
         quote! {
             #[derive( #(#derives),* )]
+            #test_only_derives
+            #rkyv_derives
+            #reflect_derive
+            #serde_crate
         }
     }
 
-    pub(crate) fn response_enum_derives(&self) -> TokenStream {
-        let enum_derives: Vec<_> = self
+    /// Records the fields of a response struct for the type map.
+    pub(crate) fn describe_type(
+        &self,
+        name: &str,
+        graphql_type: &str,
+        fields: Vec<FieldDescription>,
+    ) {
+        self.described_types.borrow_mut().insert(
+            name.to_string(),
+            TypeDescription {
+                name: name.to_string(),
+                graphql_type: graphql_type.to_string(),
+                fields,
+            },
+        );
+    }
+
+    /// Adds a field generated outside of the selection, like the `on` enum of interface structs, to a response struct recorded for the type map.
+    pub(crate) fn describe_field(&self, type_name: &str, field: FieldDescription) {
+        if let Some(description) = self.described_types.borrow_mut().get_mut(type_name) {
+            description.fields.push(field);
+        }
+    }
+
+    /// Runs the type hook of the `transform` option on the definition of a generated struct or enum.
+    pub(crate) fn transform_type(&self, type_name: &str, tokens: TokenStream) -> TokenStream {
+        match &self.transform {
+            Some(transform) => transform.transform_type(type_name, tokens),
+            None => tokens,
+        }
+    }
+
+    /// Runs the field hook of the `transform` option on a field of a response struct.
+    pub(crate) fn transform_field(
+        &self,
+        type_name: &str,
+        response_key: &str,
+        tokens: TokenStream,
+    ) -> TokenStream {
+        match &self.transform {
+            Some(transform) => transform.transform_field(type_name, response_key, tokens),
+            None => tokens,
+        }
+    }
+
+    /// The `rkyv` derives of the response types, with the `rkyv` option. They are paths, since the names collide with the serde derives.
+    fn rkyv_derives(&self) -> TokenStream {
+        if self.rkyv {
+            quote!(#[derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize)])
+        } else {
+            quote!()
+        }
+    }
+
+    /// The `bevy_reflect::Reflect` derive of the response types, with the `bevy_reflect` option.
+    fn reflect_derive(&self) -> TokenStream {
+        if self.bevy_reflect {
+            quote!(#[derive(::bevy_reflect::Reflect)])
+        } else {
+            quote!()
+        }
+    }
+
+    /// The `extra` field of the response structs with the `unknown_fields` option. It is the last field, so the fragments and the `on` enum read their keys first.
+    pub(crate) fn unknown_fields_field(&self) -> TokenStream {
+        if !self.unknown_fields {
+            return quote!();
+        }
+
+        quote! {
+            #[serde(flatten)]
+            pub extra: ::std::collections::HashMap<String, ::graphql_client::serde_json::Value>,
+        }
+    }
+
+    /// The name of the field generated by `unknown_fields_field`, if any.
+    pub(crate) fn unknown_fields_names(&self) -> &'static [&'static str] {
+        if self.unknown_fields {
+            &["extra"]
+        } else {
+            &[]
+        }
+    }
+
+    /// The `sqlx::FromRow` derive of a response struct with the `sqlx_from_row` option, if the struct is flat: every field holds a scalar, possibly nullable. Nested objects, lists, enums, fragments and `@catch` fields cannot be read from a column, the structs with them do not get the derive.
+    pub(crate) fn sqlx_from_row_derive(
+        &self,
+        schema_fields: &[GqlObjectField],
+        selection: &Selection,
+    ) -> TokenStream {
+        if !self.sqlx_from_row || !self.is_flat_selection(schema_fields, selection) {
+            return quote!();
+        }
+
+        quote!(#[derive(::sqlx::FromRow)])
+    }
+
+    fn is_flat_selection(&self, schema_fields: &[GqlObjectField], selection: &Selection) -> bool {
+        selection.0.iter().all(|item| {
+            let selected = match item {
+                SelectionItem::Field(selected) => selected,
+                _ => return false,
+            };
+            if ::transforms::is_caught(selected) {
+                return false;
+            }
+            let schema_field = match schema_fields
+                .iter()
+                .find(|field| field.name == selected.name)
+            {
+                Some(schema_field) => schema_field,
+                None => return false,
+            };
+
+            let scalar = match ::shared::response_field_type(selected, schema_field, self) {
+                FieldType::Optional(inner) => match *inner {
+                    FieldType::Named(name) => name,
+                    _ => return false,
+                },
+                FieldType::Named(name) => name,
+                FieldType::Vector(_) => return false,
+            };
+            DEFAULT_SCALARS.contains(&scalar.as_str()) || self.schema.scalars.contains_key(&scalar)
+        })
+    }
+
+    /// The name of the type generated for the selection on a field of the type named `prefix`, e.g. `HeroQueryHeroFriends`. See [QueryContext::bounded_name].
+    pub(crate) fn field_type_name(&self, prefix: &str, response_key: &str) -> String {
+        self.bounded_name(format!(
+            "{}{}",
+            prefix.to_camel_case(),
+            response_key.to_camel_case()
+        ))
+    }
+
+    /// The name of the struct for the selection on the `variant` of the union or interface type named `prefix`. See [QueryContext::bounded_name].
+    pub(crate) fn variant_type_name(&self, prefix: &str, variant: &str) -> String {
+        self.bounded_name(format!("{}On{}", prefix, variant))
+    }
+
+    /// The name as it is, or shortened to the `max_identifier_length` option: the start of the name, followed by the first 8 digits of the hash of the full name in uppercase hex. The shortened name only depends on the full name. Two names ending up the same are reported by [QueryContext::check_bounded_names].
+    pub(crate) fn bounded_name(&self, name: String) -> String {
+        let max_length = match self.max_identifier_length {
+            Some(max_length) => max_length,
+            None => return name,
+        };
+
+        let bounded = if name.len() > max_length {
+            let hash = ::query_hash::sha256_hex(&name).to_uppercase();
+            // `max_length` is at least `MIN_IDENTIFIER_LENGTH`, so part of the name is kept.
+            format!(
+                "{}{}",
+                &name[..max_length - NAME_HASH_LENGTH],
+                &hash[..NAME_HASH_LENGTH]
+            )
+        } else {
+            name.clone()
+        };
+        self.bounded_names
+            .borrow_mut()
+            .insert(name, bounded.clone());
+
+        bounded
+    }
+
+    /// Checks that the names given by [QueryContext::bounded_name] are unique: two long names starting the same can share the start of their hash, and a shortened name can be the full name of another type.
+    pub(crate) fn check_bounded_names(&self) -> Result<(), CodegenError> {
+        let bounded_names = self.bounded_names.borrow();
+        let mut full_names: BTreeMap<&str, &str> = BTreeMap::new();
+
+        for (name, bounded) in bounded_names.iter() {
+            if let Some(other) = full_names.insert(bounded, name) {
+                Err(CodegenError::Validation(format!(
+                    "The {} and {} types would both be named {} with the max_identifier_length option. Choose another length.",
+                    other, name, bounded
+                )))?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The derives for the response structs: the response derives, and `Default` with the `test_defaults` option. Enums implement `Default` by hand instead, see [::unions::enum_default_impl].
+    pub(crate) fn response_struct_derives(&self) -> TokenStream {
+        self.struct_derives(self.nested_response_derives(), true)
+    }
+
+    /// The derives of the `ResponseData` struct, which has all the response derives.
+    pub(crate) fn response_data_derives(&self) -> TokenStream {
+        self.struct_derives(self.response_derives.iter(), false)
+    }
+
+    fn struct_derives<'a>(
+        &self,
+        derives: impl Iterator<Item = &'a Ident>,
+        nested: bool,
+    ) -> TokenStream {
+        let default = if self.test_defaults {
+            Some(Ident::new("Default", Span::call_site()))
+        } else {
+            None
+        };
+        let derives = derives
+            .cloned()
+            .chain(default)
+            .unique()
+            .map(|derive| self.derive_path(&derive));
+        let test_only_derives = self.test_only_derives_attribute(nested);
+        let rkyv_derives = self.rkyv_derives();
+        let reflect_derive = self.reflect_derive();
+        let serde_crate = self.serde_crate_annotation();
+
+        quote! {
+            #[derive( #(#derives),* )]
+            #test_only_derives
+            #rkyv_derives
+            #reflect_derive
+            #serde_crate
+        }
+    }
+
+    /// The derives for the `Optimistic` mirrors of the response structs: the response derives without the serde ones, since these types are built by hand, and `Default`.
+    pub(crate) fn optimistic_derives(&self) -> TokenStream {
+        let default = Ident::new("Default", Span::call_site());
+        let derives = self
             .response_derives
             .iter()
+            .filter(|derive| *derive != "Serialize" && *derive != "Deserialize")
+            .chain(::std::iter::once(&default))
+            .unique();
+
+        quote! {
+            #[derive( #(#derives),* )]
+        }
+    }
+
+    /// The container-level rename annotation for structs, if the `rename_all_camel_case` option is enabled.
+    pub(crate) fn rename_all_annotation(&self) -> TokenStream {
+        if self.rename_all_camel_case {
+            quote!(#[serde(rename_all = "camelCase")])
+        } else {
+            quote!()
+        }
+    }
+
+    pub(crate) fn response_enum_derives(&self) -> TokenStream {
+        let enum_derives: Vec<_> = self
+            .nested_response_derives()
             .filter(|derive| {
                 !derive.to_string().contains("erialize")
                     && !derive.to_string().contains("Deserialize")
             })
             .collect();
+        let test_only_derives = self.test_only_derives_attribute(true);
+        let rkyv_derives = self.rkyv_derives();
+        let reflect_derive = self.reflect_derive();
 
         if !enum_derives.is_empty() {
             quote! {
                 #[derive( #(#enum_derives),* )]
+                #test_only_derives
+                #rkyv_derives
+                #reflect_derive
             }
         } else {
-            quote!()
+            quote! {
+                #test_only_derives
+                #rkyv_derives
+                #reflect_derive
+            }
         }
     }
 }
@@ -170,6 +761,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rkyv_derives_are_added_to_the_response_types() {
+        let mut context = QueryContext::new_empty();
+        context.rkyv = true;
+
+        let rkyv_derives =
+            "# [ derive ( :: rkyv :: Archive , :: rkyv :: Serialize , :: rkyv :: Deserialize ) ]";
+        assert_eq!(
+            context.response_struct_derives().to_string(),
+            format!("# [ derive ( Deserialize ) ] {}", rkyv_derives)
+        );
+        assert_eq!(context.response_enum_derives().to_string(), rkyv_derives);
+    }
+
+    #[test]
+    fn reflect_derive_is_added_to_the_response_types() {
+        let mut context = QueryContext::new_empty();
+        context.bevy_reflect = true;
+
+        let reflect_derive = "# [ derive ( :: bevy_reflect :: Reflect ) ]";
+        assert_eq!(
+            context.response_struct_derives().to_string(),
+            format!("# [ derive ( Deserialize ) ] {}", reflect_derive)
+        );
+        assert_eq!(context.response_enum_derives().to_string(), reflect_derive);
+    }
+
+    fn selected(name: &str) -> SelectionItem {
+        SelectionItem::Field(::selection::SelectionField {
+            alias: None,
+            name: name.to_string(),
+            directives: Vec::new(),
+            arguments: Vec::new(),
+            fields: Selection(Vec::new()),
+        })
+    }
+
+    fn schema_field(name: &str, type_: FieldType) -> GqlObjectField {
+        GqlObjectField {
+            description: None,
+            name: name.to_string(),
+            type_,
+            deprecation: ::deprecation::DeprecationStatus::Current,
+            arguments: Vec::new(),
+            external: false,
+        }
+    }
+
+    #[test]
+    fn from_row_is_only_derived_on_flat_structs() {
+        let mut context = QueryContext::new_empty();
+        context.sqlx_from_row = true;
+        let fields = vec![
+            schema_field("id", FieldType::Named("ID".to_string())),
+            schema_field(
+                "name",
+                FieldType::Optional(Box::new(FieldType::Named("String".to_string()))),
+            ),
+            schema_field(
+                "tags",
+                FieldType::Vector(Box::new(FieldType::Named("String".to_string()))),
+            ),
+        ];
+
+        let flat = Selection(vec![selected("id"), selected("name")]);
+        assert_eq!(
+            context.sqlx_from_row_derive(&fields, &flat).to_string(),
+            "# [ derive ( :: sqlx :: FromRow ) ]"
+        );
+
+        let with_list = Selection(vec![selected("id"), selected("tags")]);
+        assert_eq!(
+            context
+                .sqlx_from_row_derive(&fields, &with_list)
+                .to_string(),
+            ""
+        );
+    }
+
     #[test]
     fn response_derives_fails_when_called_twice() {
         let mut context = QueryContext::new_empty();
@@ -179,4 +849,51 @@ mod tests {
             .is_ok());
         assert!(context.ingest_additional_derives("Serialize").is_err());
     }
+
+    #[test]
+    fn bounded_names_only_depend_on_the_full_name() {
+        let names = [
+            "HeroQueryHeroFriendsOfTheFirstKind",
+            "HeroQueryHeroFriendsOfTheSecondKind",
+            "HeroQueryHero",
+        ];
+        let bounded = |names: &[&str]| {
+            let mut context = QueryContext::new_empty();
+            context.max_identifier_length = Some(20);
+            names
+                .iter()
+                .map(|name| (name.to_string(), context.bounded_name(name.to_string())))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        let mut reversed = names;
+        reversed.reverse();
+        let bounded_names = bounded(&names);
+        assert_eq!(bounded_names, bounded(&reversed));
+        assert_eq!(bounded_names["HeroQueryHero"], "HeroQueryHero");
+        for name in &names[..2] {
+            let shortened = &bounded_names[*name];
+            assert_eq!(shortened.len(), 20);
+            assert!(shortened.starts_with("HeroQueryHer"));
+        }
+        assert_ne!(bounded_names[names[0]], bounded_names[names[1]]);
+    }
+
+    #[test]
+    fn bounded_names_must_not_collide_with_full_names() {
+        let mut context = QueryContext::new_empty();
+        context.max_identifier_length = Some(16);
+        let shortened = context.bounded_name("HeroQueryHeroFriends".to_string());
+        context.check_bounded_names().unwrap();
+
+        context.bounded_name(shortened.clone());
+        let err = context.check_bounded_names().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "The {} and HeroQueryHeroFriends types would both be named {} with the max_identifier_length option. Choose another length.",
+                shortened, shortened
+            )
+        );
+    }
 }
@@ -0,0 +1,170 @@
+use sha2::{Digest, Sha256};
+
+/// Characters around which whitespace is never significant.
+const PUNCTUATORS: &[char] = &[
+    '!', '$', '&', '(', ')', '.', ':', '=', '@', '[', ']', '{', '}', '|',
+];
+
+/// The raw SHA-256 hash of the query.
+pub(crate) fn sha256_bytes(query: &str) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&Sha256::digest(query.as_bytes()));
+    bytes
+}
+
+/// The hex-encoded SHA-256 hash of the query, as used by automatic persisted queries.
+pub(crate) fn sha256_hex(query: &str) -> String {
+    sha256_bytes(query)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Normalizes the ignored tokens of a query, so cosmetically different queries have the same hash:
+///
+/// - comments are removed,
+/// - commas, whitespace and line terminators are collapsed into a single space,
+/// - that space is removed at the start and end of the query, and next to punctuators (`{`, `(`, `:`, `...`...).
+///
+/// String literals are kept as they are. Nothing is reordered.
+pub(crate) fn normalize_query(query: &str) -> String {
+    let mut normalized = String::with_capacity(query.len());
+    let mut pending_space = false;
+    let mut chars = query.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '#' => {
+                while chars.peek().map(|c| *c != '\n' && *c != '\r').unwrap_or(false) {
+                    chars.next();
+                }
+                pending_space = true;
+            }
+            ' ' | '\t' | '\n' | '\r' | ',' | '\u{feff}' => pending_space = true,
+            _ => {
+                let previous_is_punctuator = normalized
+                    .chars()
+                    .last()
+                    .map(|last| PUNCTUATORS.contains(&last))
+                    .unwrap_or(true);
+                if pending_space && !previous_is_punctuator && !PUNCTUATORS.contains(&c) {
+                    normalized.push(' ');
+                }
+                pending_space = false;
+
+                normalized.push(c);
+
+                if c == '"' {
+                    copy_string(&mut chars, &mut normalized);
+                }
+            }
+        }
+    }
+
+    normalized
+}
+
+/// Copies a string literal verbatim, after its opening quote.
+fn copy_string<I: Iterator<Item = char>>(
+    chars: &mut ::std::iter::Peekable<I>,
+    normalized: &mut String,
+) {
+    // Block strings start with three quotes and can contain single quotes.
+    if chars.peek() == Some(&'"') {
+        chars.next();
+        normalized.push('"');
+        if chars.peek() != Some(&'"') {
+            // That was the empty string.
+            return;
+        }
+        chars.next();
+        normalized.push('"');
+
+        let mut closing_quotes = 0;
+        while let Some(c) = chars.next() {
+            normalized.push(c);
+            match c {
+                '"' => {
+                    closing_quotes += 1;
+                    if closing_quotes == 3 {
+                        return;
+                    }
+                }
+                '\\' => {
+                    closing_quotes = 0;
+                    if let Some(escaped) = chars.next() {
+                        normalized.push(escaped);
+                    }
+                }
+                _ => closing_quotes = 0,
+            }
+        }
+        return;
+    }
+
+    while let Some(c) = chars.next() {
+        normalized.push(c);
+        match c {
+            '"' => return,
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    normalized.push(escaped);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_hex_works() {
+        assert_eq!(
+            sha256_hex(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn normalized_query_is_stable_across_cosmetic_changes() {
+        let compact = "query Q($id: ID!) { user(id: $id) { name, ...on User { email } } }";
+        let pretty = r#"
+            # Fetches one user.
+            query Q(
+              $id: ID!
+            ) {
+              user(id: $id) {
+                name
+                ... on User {
+                  email
+                }
+              }
+            }
+        "#;
+
+        assert_eq!(
+            normalize_query(compact),
+            "query Q($id:ID!){user(id:$id){name...on User{email}}}"
+        );
+        assert_eq!(normalize_query(compact), normalize_query(pretty));
+    }
+
+    #[test]
+    fn normalization_keeps_string_literals() {
+        assert_eq!(
+            normalize_query(r#"{ search(text: "a,  b # \" c") }"#),
+            r#"{search(text:"a,  b # \" c")}"#
+        );
+        assert_eq!(
+            normalize_query(r#"{ search(text: """ a "quoted"   block """) }"#),
+            r#"{search(text:""" a "quoted"   block """)}"#
+        );
+        assert_eq!(
+            normalize_query(r#"{ search(text: "", limit: 2) }"#),
+            r#"{search(text:"" limit:2)}"#
+        );
+    }
+}
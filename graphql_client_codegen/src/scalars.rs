@@ -1,5 +1,28 @@
+use error::CodegenError;
 use proc_macro2;
+use schema::Schema;
 use std::cell::Cell;
+use std::collections::BTreeMap;
+
+/// The Rust type of the GraphQL `Float` scalar.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum FloatType {
+    /// `f64` (default), which holds any value the server can send.
+    #[default]
+    F64,
+    /// `f32`, half the size, but with about 7 significant digits instead of 15.
+    F32,
+}
+
+impl FloatType {
+    /// The Rust type as tokens.
+    pub(crate) fn to_rust(self) -> proc_macro2::TokenStream {
+        match self {
+            FloatType::F64 => quote!(f64),
+            FloatType::F32 => quote!(f32),
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Ord, Eq)]
 pub struct Scalar {
@@ -9,14 +32,130 @@ pub struct Scalar {
 }
 
 impl Scalar {
-    // TODO: do something smarter here
-    pub fn to_rust(&self) -> proc_macro2::TokenStream {
+    /// The type alias for the scalar: the path given in the `scalar_paths` option, or the type with the same name in the module of the derive.
+    pub fn to_rust(&self, path: Option<&syn::Path>) -> proc_macro2::TokenStream {
         use proc_macro2::{Ident, Span};
         let ident = Ident::new(&self.name, Span::call_site());
         let description = match &self.description {
             Some(d) => quote!(#[doc = #d]),
             None => quote!(),
         };
-        quote!(#description type #ident = super::#ident;)
+        let rust_type = if let Some(path) = path {
+            Some(quote!(#path))
+        } else if cfg!(feature = "integer_scalars") {
+            integer_scalar(&self.name)
+        } else {
+            None
+        };
+        let rust_type = rust_type.unwrap_or_else(|| quote!(super::#ident));
+        quote!(#description type #ident = #rust_type;)
+    }
+}
+
+/// Parses the `scalar_paths` option, `Scalar = path` pairs separated by commas, like `"DateTime = crate::types::DateTime, Email = String"`.
+///
+/// The paths are emitted as given, in the generated module: `super::` refers to the module of the derive, and `crate::` and `::` paths are absolute.
+pub(crate) fn parse_scalar_paths(
+    option: &str,
+    schema: &Schema,
+) -> Result<BTreeMap<String, syn::Path>, CodegenError> {
+    let mut paths = BTreeMap::new();
+
+    for entry in split_top_level(option) {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            continue;
+        }
+
+        let (scalar, path) = match entry.find('=') {
+            Some(idx) => (entry[..idx].trim(), entry[idx + 1..].trim()),
+            None => Err(CodegenError::Validation(format!(
+                "Expected `Scalar = path` in scalar_paths, got `{}`.",
+                entry
+            )))?,
+        };
+
+        if !schema.scalars.contains_key(scalar) {
+            Err(CodegenError::Validation(format!(
+                "scalar_paths maps {}, which is not a custom scalar of the schema.",
+                scalar
+            )))?
+        }
+
+        let parsed = parse_type_path(path, &format!("the {} scalar", scalar))?;
+        paths.insert(scalar.to_string(), parsed);
+    }
+
+    Ok(paths)
+}
+
+/// Parses the path of a Rust type given in an option, for the `subject` of the error messages, like "the DateTime scalar".
+pub(crate) fn parse_type_path(path: &str, subject: &str) -> Result<syn::Path, CodegenError> {
+    let parsed: syn::Path = syn::parse_str(path).map_err(|_| {
+        CodegenError::Validation(format!(
+            "The path `{}` for {} is not a valid Rust path.",
+            path, subject
+        ))
+    })?;
+    check_path_prefix(&parsed, path, subject)?;
+
+    Ok(parsed)
+}
+
+/// `crate` and `self` can only start a relative path, and `super` can only follow them or another `super`. Rust rejects the other placements with confusing errors in the generated code.
+fn check_path_prefix(parsed: &syn::Path, path: &str, subject: &str) -> Result<(), CodegenError> {
+    let mut in_prefix = parsed.leading_colon.is_none();
+
+    for (idx, segment) in parsed.segments.iter().enumerate() {
+        let ident = segment.ident.to_string();
+        let misplaced = match ident.as_str() {
+            "crate" | "self" => !in_prefix || idx > 0,
+            "super" => !in_prefix,
+            _ => {
+                in_prefix = false;
+                false
+            }
+        };
+
+        if misplaced {
+            Err(CodegenError::Validation(format!(
+                "The path `{}` for {} is malformed: `{}` can only appear at the start of a path.",
+                path, subject, ident
+            )))?
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits on the commas that are not between angle brackets, so paths can have generic arguments.
+pub(crate) fn split_top_level(option: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (idx, c) in option.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                entries.push(&option[start..idx]);
+                start = idx + 1;
+            }
+            _ => (),
+        }
+    }
+    entries.push(&option[start..]);
+
+    entries
+}
+
+/// The Rust type of the integer scalars mapped by the `integer_scalars` feature. `Long` is a wrapper, because some servers send it as a string.
+fn integer_scalar(name: &str) -> Option<proc_macro2::TokenStream> {
+    match name {
+        "Long" => Some(quote!(::graphql_client::scalars::Long)),
+        "Short" => Some(quote!(i16)),
+        "Byte" => Some(quote!(i8)),
+        _ => None,
     }
 }
@@ -1,6 +1,6 @@
 use deprecation::DeprecationStatus;
 use enums::{EnumVariant, GqlEnum};
-use failure;
+use error::CodegenError;
 use field_type::FieldType;
 use graphql_parser::{self, schema};
 use inputs::GqlInput;
@@ -24,6 +24,12 @@ pub struct Schema {
     pub(crate) query_type: Option<String>,
     pub(crate) mutation_type: Option<String>,
     pub(crate) subscription_type: Option<String>,
+    /// The hex-encoded SHA-256 hash of the source the schema was parsed from, if we have it.
+    pub(crate) source_hash: Option<String>,
+    /// The names of the `@tag` directives on the object and interface types and their fields, under `Type` and `Type.field`. Introspection does not expose directives, so they are only known from SDL schemas.
+    pub(crate) tags: BTreeMap<String, Vec<String>>,
+    /// The names of the directives the schema declares, like `defer` or `cacheControl`. They are sent to the server with the query, while the other directives on fields must be client directives.
+    pub(crate) directives: BTreeSet<String>,
 }
 
 impl Schema {
@@ -38,26 +44,115 @@ impl Schema {
             query_type: None,
             mutation_type: None,
             subscription_type: None,
+            source_hash: None,
+            tags: BTreeMap::new(),
+            directives: BTreeSet::new(),
         }
     }
 
     pub(crate) fn ingest_interface_implementations(
         &mut self,
         impls: BTreeMap<String, Vec<String>>,
-    ) -> Result<(), failure::Error> {
+    ) -> Result<(), CodegenError> {
         impls
             .into_iter()
             .map(|(iface_name, implementors)| {
                 let iface = self
                     .interfaces
                     .get_mut(&iface_name)
-                    .ok_or_else(|| format_err!("interface not found: {}", iface_name))?;
+                    .ok_or_else(|| CodegenError::UnknownType {
+                        ty: iface_name.clone(),
+                    })?;
                 iface.implemented_by = implementors.into_iter().collect();
                 Ok(())
             })
             .collect()
     }
 
+    /// Unions can only have object members per the spec, but some federated or extended schemas list interfaces too. Since the `__typename` in responses is always an object type, interface members are replaced with the objects implementing them.
+    fn expand_interface_union_members(&mut self) {
+        let interfaces = &self.interfaces;
+
+        for union in self.unions.values_mut() {
+            let interface_members: Vec<String> = union
+                .variants
+                .iter()
+                .filter(|member| interfaces.contains_key(*member))
+                .cloned()
+                .collect();
+
+            for member in interface_members {
+                union.variants.remove(&member);
+                union
+                    .variants
+                    .extend(interfaces[&member].implemented_by.iter().cloned());
+            }
+        }
+    }
+
+    /// Parses a schema written in the GraphQL schema definition language.
+    pub fn from_sdl(sdl: &str) -> Result<Schema, CodegenError> {
+        let document = graphql_parser::schema::parse_schema(sdl)
+            .map_err(|err| CodegenError::SchemaParse(err.to_string()))?;
+        let mut schema = Schema::from(document);
+        schema.source_hash = Some(::query_hash::sha256_hex(sdl));
+        Ok(schema)
+    }
+
+    /// Parses a schema written in older SDL, where `#` comments directly above definitions, fields and enum values serve as descriptions. String descriptions are also supported.
+    pub fn from_sdl_with_comment_descriptions(sdl: &str) -> Result<Schema, CodegenError> {
+        let described = ::comment_descriptions::comments_to_descriptions(sdl);
+        let mut schema = Schema::from_sdl(&described)?;
+        schema.source_hash = Some(::query_hash::sha256_hex(sdl));
+        Ok(schema)
+    }
+
+    /// Parses a schema from the JSON of an introspection query response.
+    pub fn from_json(json: &str) -> Result<Schema, CodegenError> {
+        let response: ::introspection_response::IntrospectionResponse =
+            ::serde_json::from_str(json)?;
+        let mut schema = Schema::from(response);
+        schema.source_hash = Some(::query_hash::sha256_hex(json));
+        Ok(schema)
+    }
+
+    /// Parses a schema that can be either JSON or SDL, when there is no file extension to tell them apart. JSON introspection responses are objects, and SDL documents cannot start with `{`.
+    pub fn from_json_or_sdl(source: &str) -> Result<Schema, CodegenError> {
+        if source.trim_start().starts_with('{') {
+            Schema::from_json(source)
+        } else {
+            Schema::from_sdl(source)
+        }
+    }
+
+    /// Whether the type is a scalar or an enum, which cannot have a selection set.
+    pub(crate) fn is_leaf(&self, typename_: &str) -> bool {
+        DEFAULT_SCALARS.contains(&typename_)
+            || self.scalars.contains_key(typename_)
+            || self.enums.contains_key(typename_)
+    }
+
+    /// The types a value of the type can be at runtime: the type itself for an object, the implementors of an interface, or the members of a union.
+    pub(crate) fn possible_types<'a>(&'a self, typename_: &'a str) -> Vec<&'a str> {
+        if let Some(iface) = self.interfaces.get(typename_) {
+            iface.implemented_by.iter().map(|s| s.as_str()).collect()
+        } else if let Some(union) = self.unions.get(typename_) {
+            union.variants.iter().map(|s| s.as_str()).collect()
+        } else {
+            vec![typename_]
+        }
+    }
+
+    /// Whether a fragment on `on` can be spread in a selection on `typename_`, that is whether a value can be of both types.
+    pub(crate) fn fragment_is_possible(&self, on: &str, typename_: &str) -> bool {
+        let possible = self.possible_types(typename_);
+        on == typename_
+            || self
+                .possible_types(on)
+                .iter()
+                .any(|ty| possible.contains(ty))
+    }
+
     pub(crate) fn require(&self, typename_: &str) {
         DEFAULT_SCALARS
             .iter()
@@ -101,6 +196,12 @@ impl ::std::convert::From<graphql_parser::schema::Document> for Schema {
                                 .and_modify(|objects| objects.push(name.clone()))
                                 .or_insert_with(|| vec![name.clone()]);
                         }
+                        ::tags::record_tags(
+                            &mut schema.tags,
+                            &obj.name,
+                            &obj.directives,
+                            &obj.fields,
+                        );
 
                         schema
                             .objects
@@ -118,6 +219,7 @@ impl ::std::convert::From<graphql_parser::schema::Document> for Schema {
                                     .map(|v| EnumVariant {
                                         description: v.description.clone(),
                                         name: v.name.clone(),
+                                        directives: v.directives.clone(),
                                     })
                                     .collect(),
                                 is_required: false.into(),
@@ -137,8 +239,9 @@ impl ::std::convert::From<graphql_parser::schema::Document> for Schema {
                     schema::TypeDefinition::Union(union) => {
                         let variants: BTreeSet<String> = union.types.into_iter().collect();
                         schema.unions.insert(
-                            union.name,
+                            union.name.clone(),
                             GqlUnion {
+                                name: union.name,
                                 variants,
                                 description: union.description,
                                 is_required: false.into(),
@@ -146,6 +249,12 @@ impl ::std::convert::From<graphql_parser::schema::Document> for Schema {
                         );
                     }
                     schema::TypeDefinition::Interface(interface) => {
+                        ::tags::record_tags(
+                            &mut schema.tags,
+                            &interface.name,
+                            &interface.directives,
+                            &interface.fields,
+                        );
                         let mut iface = GqlInterface::new(
                             interface.name.clone().into(),
                             interface.description.as_ref().map(|d| d.as_str()),
@@ -157,6 +266,8 @@ impl ::std::convert::From<graphql_parser::schema::Document> for Schema {
                                 name: f.name.clone(),
                                 type_: FieldType::from(f.field_type.clone()),
                                 deprecation: DeprecationStatus::Current,
+                                arguments: ::objects::sdl_arguments(&f.arguments),
+                                external: ::objects::is_external(&f.directives),
                             }));
                         schema.interfaces.insert(interface.name, iface);
                     }
@@ -166,7 +277,9 @@ impl ::std::convert::From<graphql_parser::schema::Document> for Schema {
                             .insert(input.name.clone(), GqlInput::from(input));
                     }
                 },
-                schema::Definition::DirectiveDefinition(_) => (),
+                schema::Definition::DirectiveDefinition(directive) => {
+                    schema.directives.insert(directive.name);
+                }
                 schema::Definition::TypeExtension(_extension) => (),
                 schema::Definition::SchemaDefinition(definition) => {
                     schema.query_type = definition.query;
@@ -179,6 +292,7 @@ impl ::std::convert::From<graphql_parser::schema::Document> for Schema {
         schema
             .ingest_interface_implementations(interface_implementations)
             .expect("schema ingestion");
+        schema.expand_interface_union_members();
 
         schema
     }
@@ -189,11 +303,17 @@ impl ::std::convert::From<::introspection_response::IntrospectionResponse> for S
         use introspection_response::__TypeKind;
 
         let mut schema = Schema::new();
-        let root = src.into_schema().schema.expect("__schema is not null");
+        let root = src.into_schema();
 
         schema.query_type = root.query_type.and_then(|ty| ty.name);
         schema.mutation_type = root.mutation_type.and_then(|ty| ty.name);
         schema.subscription_type = root.subscription_type.and_then(|ty| ty.name);
+        schema.directives = root
+            .directives
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|directive| directive.and_then(|directive| directive.name))
+            .collect();
 
         // Holds which objects implement which interfaces so we can populate GqlInterface#implemented_by later.
         // It maps interface names to a vec of implementation names.
@@ -218,6 +338,7 @@ impl ::std::convert::From<::introspection_response::IntrospectionResponse> for S
                             t.clone().map(|t| EnumVariant {
                                 description: t.description,
                                 name: t.name.expect("enum variant name"),
+                                directives: Vec::new(),
                             })
                         })
                         .filter_map(|t| t)
@@ -257,6 +378,7 @@ impl ::std::convert::From<::introspection_response::IntrospectionResponse> for S
                     schema.unions.insert(
                         name.clone(),
                         GqlUnion {
+                            name: name.clone(),
                             description: ty.description.as_ref().map(|d| d.to_owned()),
                             variants,
                             is_required: false.into(),
@@ -298,6 +420,8 @@ impl ::std::convert::From<::introspection_response::IntrospectionResponse> for S
                                 name: f.name.expect("field name"),
                                 type_: FieldType::from(f.type_.expect("field type")),
                                 deprecation: DeprecationStatus::Current,
+                                arguments: ::objects::introspected_arguments(f.args),
+                                external: false,
                             }),
                     );
                     schema.interfaces.insert(name, iface);
@@ -312,6 +436,7 @@ impl ::std::convert::From<::introspection_response::IntrospectionResponse> for S
         schema
             .ingest_interface_implementations(interface_implementations)
             .expect("schema ingestion");
+        schema.expand_interface_union_members();
 
         schema
     }
@@ -338,18 +463,24 @@ mod tests {
                         name: TYPENAME_FIELD.to_string(),
                         type_: FieldType::Named(string_type()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
                         name: "id".to_string(),
                         type_: FieldType::Named("ID".to_string()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
                         name: "name".to_string(),
                         type_: FieldType::Named("String".to_string()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
@@ -360,12 +491,25 @@ mod tests {
                             ))),
                         )))),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
                         name: "friendsConnection".to_string(),
                         type_: FieldType::Named("FriendsConnection".to_string()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: vec![
+                            (
+                                "after".to_string(),
+                                FieldType::Optional(Box::new(FieldType::Named("ID".to_string()))),
+                            ),
+                            (
+                                "first".to_string(),
+                                FieldType::Optional(Box::new(FieldType::Named("Int".to_string()))),
+                            ),
+                        ],
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
@@ -374,6 +518,8 @@ mod tests {
                             FieldType::Named("Episode".to_string()),
                         )))),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
@@ -382,6 +528,8 @@ mod tests {
                             "String".to_string()
                         ))),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                 ],
                 is_required: false.into(),
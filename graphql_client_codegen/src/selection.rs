@@ -1,16 +1,54 @@
 use constants::*;
-use graphql_parser::query::SelectionSet;
+use graphql_parser::query::{SelectionSet, Value};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct SelectionField {
     pub alias: Option<String>,
     pub name: String,
+    /// The names and arguments of the directives on the field, e.g. `trim` for `name @trim`.
+    pub directives: Vec<(String, Vec<(String, Value)>)>,
+    /// The arguments passed to the field, as variables or literals.
+    pub arguments: Vec<(String, Value)>,
     pub fields: Selection,
 }
 
+impl SelectionField {
+    /// Whether the server may leave the field out, because of an `@include` or `@skip` directive. With both, the field is included unless it is skipped, so either can leave it out. `@include(if: true)` and `@skip(if: false)` always keep it.
+    pub fn is_conditional(&self) -> bool {
+        self.conditions().any(|(directive, condition)| {
+            condition != Some(&Value::Boolean(directive == "include"))
+        })
+    }
+
+    /// The `@include` and `@skip` directives on the field, with the value of their `if` argument.
+    pub fn conditions<'a>(&'a self) -> impl Iterator<Item = (&'a str, Option<&'a Value>)> + 'a {
+        self.directives
+            .iter()
+            .filter(|(name, _)| name == "include" || name == "skip")
+            .map(|(name, arguments)| {
+                let condition = arguments
+                    .iter()
+                    .find(|(argument, _)| argument == "if")
+                    .map(|(_, value)| value);
+                (name.as_str(), condition)
+            })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct SelectionFragmentSpread {
     pub fragment_name: String,
+    /// The names of the directives on the spread, e.g. `include` for `...Details @include(if: $details)`.
+    pub directives: Vec<String>,
+}
+
+impl SelectionFragmentSpread {
+    /// Whether the server may leave the fragment out, because of an `@include` or `@skip` directive.
+    pub fn is_conditional(&self) -> bool {
+        self.directives
+            .iter()
+            .any(|name| name == "include" || name == "skip")
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -57,11 +95,18 @@ impl<'a> ::std::convert::From<&'a SelectionSet> for Selection {
                 Selection::Field(f) => SelectionItem::Field(SelectionField {
                     alias: f.alias.as_ref().map(|alias| alias.to_string()),
                     name: f.name.to_string(),
+                    directives: f
+                        .directives
+                        .iter()
+                        .map(|d| (d.name.to_string(), d.arguments.clone()))
+                        .collect(),
+                    arguments: f.arguments.clone(),
                     fields: (&f.selection_set).into(),
                 }),
                 Selection::FragmentSpread(spread) => {
                     SelectionItem::FragmentSpread(SelectionFragmentSpread {
                         fragment_name: spread.fragment_name.to_string(),
+                        directives: spread.directives.iter().map(|d| d.name.to_string()).collect(),
                     })
                 }
                 Selection::InlineFragment(inline) => {
@@ -76,6 +121,12 @@ impl<'a> ::std::convert::From<&'a SelectionSet> for Selection {
                     })
                 }
             };
+            // Identical field selections collapse into one, as in the field merging rules of the spec.
+            if let SelectionItem::Field(_) = converted {
+                if items.contains(&converted) {
+                    continue;
+                }
+            }
             items.push(converted);
         }
 
@@ -129,23 +180,32 @@ mod tests {
             Selection(vec![SelectionItem::Field(SelectionField {
                 alias: None,
                 name: "animal".to_string(),
+                directives: Vec::new(),
+                arguments: Vec::new(),
                 fields: Selection(vec![
                     SelectionItem::Field(SelectionField {
                         alias: None,
                         name: "isCat".to_string(),
+                        directives: Vec::new(),
+                        arguments: Vec::new(),
                         fields: Selection(Vec::new()),
                     }),
                     SelectionItem::Field(SelectionField {
                         alias: None,
                         name: "isHorse".to_string(),
+                        directives: Vec::new(),
+                        arguments: Vec::new(),
                         fields: Selection(Vec::new()),
                     }),
                     SelectionItem::FragmentSpread(SelectionFragmentSpread {
                         fragment_name: "Timestamps".to_string(),
+                        directives: Vec::new(),
                     }),
                     SelectionItem::Field(SelectionField {
                         alias: None,
                         name: "barks".to_string(),
+                        directives: Vec::new(),
+                        arguments: Vec::new(),
                         fields: Selection(Vec::new()),
                     }),
                     SelectionItem::InlineFragment(SelectionInlineFragment {
@@ -153,17 +213,23 @@ mod tests {
                         fields: Selection(vec![SelectionItem::Field(SelectionField {
                             alias: None,
                             name: "rating".to_string(),
+                            directives: Vec::new(),
+                            arguments: Vec::new(),
                             fields: Selection(Vec::new()),
                         })]),
                     }),
                     SelectionItem::Field(SelectionField {
                         alias: None,
                         name: "pawsCount".to_string(),
+                        directives: Vec::new(),
+                        arguments: Vec::new(),
                         fields: Selection(Vec::new()),
                     }),
                     SelectionItem::Field(SelectionField {
                         alias: Some("aliased".to_string()),
                         name: "sillyName".to_string(),
+                        directives: Vec::new(),
+                        arguments: Vec::new(),
                         fields: Selection(Vec::new()),
                     }),
                 ]),
@@ -1,11 +1,15 @@
+use constants::TYPENAME_FIELD;
 use deprecation::{DeprecationStatus, DeprecationStrategy};
-use failure;
+use error::CodegenError;
+use field_type::FieldType;
+use fragments::GqlFragment;
 use heck::{CamelCase, SnakeCase};
 use itertools::Itertools;
 use objects::GqlObjectField;
 use proc_macro2::{Ident, Span, TokenStream};
 use query::QueryContext;
 use selection::*;
+use type_map::FieldDescription;
 
 pub(crate) fn render_object_field(
     field_name: &str,
@@ -13,61 +17,77 @@ pub(crate) fn render_object_field(
     description: Option<&str>,
     status: &DeprecationStatus,
     strategy: &DeprecationStrategy,
+    rename_all_camel_case: bool,
 ) -> TokenStream {
-    #[allow(unused_assignments)]
-    let mut deprecation = quote!();
+    // If the field is deprecated and we are denying usage, don't generate the
+    // field in rust at all and short-circuit.
+    let deprecation = match deprecation_annotation(status, strategy) {
+        Some(deprecation) => deprecation,
+        None => return quote!(),
+    };
+
+    let description = description.map(|s| quote!(#[doc = #s]));
+
+    let name_ident = field_ident(field_name);
+    let rename = ::shared::field_rename_annotation(
+        &field_name,
+        &name_ident.to_string(),
+        rename_all_camel_case,
+    );
+
+    quote!(#description #deprecation #rename pub #name_ident: #field_type)
+}
+
+/// The `#[deprecated]` annotation for a field, if any. `None` if the field is not generated at all because of the deprecation strategy.
+fn deprecation_annotation(
+    status: &DeprecationStatus,
+    strategy: &DeprecationStrategy,
+) -> Option<TokenStream> {
     match (status, strategy) {
-        // If the field is deprecated and we are denying usage, don't generate the
-        // field in rust at all and short-circuit.
-        (DeprecationStatus::Deprecated(_), DeprecationStrategy::Deny) => return quote!(),
+        (DeprecationStatus::Deprecated(_), DeprecationStrategy::Deny) => None,
         // Everything is allowed so there is nothing to do.
-        (_, DeprecationStrategy::Allow) => deprecation = quote!(),
+        (_, DeprecationStrategy::Allow) => Some(quote!()),
         // Current so there is nothing to do.
-        (DeprecationStatus::Current, _) => deprecation = quote!(),
+        (DeprecationStatus::Current, _) => Some(quote!()),
         // A reason was provided, translate it to a note.
         (DeprecationStatus::Deprecated(Some(reason)), DeprecationStrategy::Warn) => {
-            deprecation = quote!(#[deprecated(note = #reason)])
+            Some(quote!(#[deprecated(note = #reason)]))
         }
         // No reason provided, just mark as deprecated.
         (DeprecationStatus::Deprecated(None), DeprecationStrategy::Warn) => {
-            deprecation = quote!(#[deprecated])
+            Some(quote!(#[deprecated]))
         }
-    };
-
-    let description = description.map(|s| quote!(#[doc = #s]));
+    }
+}
 
+/// The name of the struct field for a GraphQL field: snake case, with a trailing underscore for Rust keywords. The serde rename annotation maps it back to the GraphQL name.
+pub(crate) fn field_ident(field_name: &str) -> Ident {
     // List of keywords based on https://doc.rust-lang.org/grammar.html#keywords
     let reserved = &[
-        "abstract", "alignof", "as", "become", "box", "break", "const", "continue", "crate", "do",
-        "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
-        "macro", "match", "mod", "move", "mut", "offsetof", "override", "priv", "proc", "pub",
-        "pure", "ref", "return", "Self", "self", "sizeof", "static", "struct", "super", "trait",
-        "true", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+        "abstract", "alignof", "as", "async", "await", "become", "box", "break", "const",
+        "continue", "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for",
+        "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "offsetof",
+        "override", "priv", "proc", "pub", "pure", "ref", "return", "Self", "self", "sizeof",
+        "static", "struct", "super", "trait", "true", "try", "type", "typeof", "unsafe",
+        "unsized", "use", "virtual", "where", "while", "yield",
     ];
 
-    if reserved.contains(&field_name) {
-        let name_ident = Ident::new(&format!("{}_", field_name), Span::call_site());
-        return quote! {
-            #description
-            #deprecation
-            #[serde(rename = #field_name)]
-            pub #name_ident: #field_type
-        };
-    }
-
+    // Check after converting, since e.g. `Type` becomes `type`.
     let snake_case_name = field_name.to_snake_case();
-    let rename = ::shared::field_rename_annotation(&field_name, &snake_case_name);
-    let name_ident = Ident::new(&snake_case_name, Span::call_site());
-
-    quote!(#description #deprecation #rename pub #name_ident: #field_type)
+    if reserved.contains(&snake_case_name.as_str()) {
+        Ident::new(&format!("{}_", snake_case_name), Span::call_site())
+    } else {
+        Ident::new(&snake_case_name, Span::call_site())
+    }
 }
 
 pub(crate) fn field_impls_for_selection(
+    type_name: &str,
     fields: &[GqlObjectField],
     context: &QueryContext,
     selection: &Selection,
     prefix: &str,
-) -> Result<Vec<TokenStream>, failure::Error> {
+) -> Result<Vec<TokenStream>, CodegenError> {
     selection
         .0
         .iter()
@@ -79,11 +99,41 @@ pub(crate) fn field_impls_for_selection(
                 let ty = fields
                     .iter()
                     .find(|f| &f.name == name)
-                    .ok_or_else(|| format_err!("could not find field `{}`", name))?
+                    .ok_or_else(|| unknown_field_error(name, type_name, fields))?
                     .type_
                     .inner_name_string();
-                let prefix = format!("{}{}", prefix.to_camel_case(), alias.to_camel_case());
+
+                check_subselection(selected, type_name, &ty, context)?;
+
+                let prefix = context.field_type_name(prefix, alias);
+
+                if let Some((tag, content)) = ::transforms::adjacent_tagging(selected)? {
+                    let union = context.schema.unions.get(&ty).ok_or_else(|| {
+                        CodegenError::Validation(format!(
+                            "The @adjacentlyTagged directive can only be used on union fields, but {} is a {}.",
+                            name, ty
+                        ))
+                    })?;
+                    return union.adjacently_tagged_response_for_selection(
+                        context,
+                        &selected.fields,
+                        &prefix,
+                        &tag,
+                        &content,
+                    );
+                }
+
                 context.maybe_expand_field(&ty, &selected.fields, &prefix)
+            } else if let SelectionItem::InlineFragment(inline) = selected {
+                let (_, struct_name) = flattened_interface(type_name, inline, context, prefix)?;
+                // The struct is generated like a fragment on the interface would be.
+                let fragment = GqlFragment {
+                    name: struct_name.to_string(),
+                    on: inline.on.clone(),
+                    selection: inline.fields.clone(),
+                    is_required: true.into(),
+                };
+                fragment.to_rust(context)
             } else {
                 Ok(quote!())
             }
@@ -91,14 +141,225 @@ pub(crate) fn field_impls_for_selection(
         .collect()
 }
 
+/// Checks that a field of a leaf type (a scalar or an enum) has no selection set, and that a field of a composite type (an object, an interface or a union) has one.
+fn check_subselection(
+    selected: &SelectionField,
+    type_name: &str,
+    field_type: &str,
+    context: &QueryContext,
+) -> Result<(), CodegenError> {
+    let has_selection = !selected.fields.0.is_empty();
+
+    if context.schema.is_leaf(field_type) {
+        if has_selection {
+            Err(CodegenError::Validation(format!(
+                "The {} field on {} is of type {}, which is a scalar or an enum. It cannot have a selection set.",
+                selected.name, type_name, field_type
+            )))?
+        }
+        return Ok(());
+    }
+
+    let kind = if context.schema.objects.contains_key(field_type) {
+        "an object"
+    } else if context.schema.interfaces.contains_key(field_type) {
+        "an interface"
+    } else if context.schema.unions.contains_key(field_type) {
+        "a union"
+    } else {
+        // Unknown types are reported when generating their code.
+        return Ok(());
+    };
+    if !has_selection {
+        Err(CodegenError::Validation(format!(
+            "The {} field on {} is of type {}, which is {}. It needs a selection set.",
+            selected.name, type_name, field_type, kind
+        )))?
+    }
+
+    Ok(())
+}
+
+/// The field and struct names of an inline fragment on an interface in a selection on an object type, with the `interface_flatten` option, e.g. `named_fields: MyQueryUserNamedFields` for `... on Named`. The struct holds the fields selected on the interface, and it is flattened into the object's struct.
+fn flattened_interface(
+    type_name: &str,
+    inline: &SelectionInlineFragment,
+    context: &QueryContext,
+    prefix: &str,
+) -> Result<(Ident, Ident), CodegenError> {
+    if !context.interface_flatten {
+        Err(CodegenError::Validation(
+            "unimplemented: inline fragment on object field".to_string(),
+        ))?
+    }
+
+    let implemented = context
+        .schema
+        .interfaces
+        .get(&inline.on)
+        .map(|iface| iface.implemented_by.contains(type_name))
+        .unwrap_or(false);
+    if !implemented {
+        Err(CodegenError::Validation(format!(
+            "The inline fragment on {} in a selection on {} cannot be flattened, {} is not an interface implemented by {}.",
+            inline.on, type_name, inline.on, type_name
+        )))?
+    }
+
+    Ok(flattened_interface_names(inline, prefix, context))
+}
+
+/// Identical field selections are merged when the selection is built, so the fields left with the same response key differ in their name, arguments, directives or subselection.
+fn check_conflicting_response_keys(
+    type_name: &str,
+    selection: &Selection,
+) -> Result<(), CodegenError> {
+    let mut keys: Vec<&str> = Vec::new();
+    for item in &selection.0 {
+        if let SelectionItem::Field(field) = item {
+            let key = field.alias.as_ref().unwrap_or(&field.name).as_str();
+            if keys.contains(&key) {
+                Err(CodegenError::Validation(format!(
+                    "The {} field is selected more than once on {}, with different fields, arguments, directives or subselections. Selections of the same response key are only merged when they are identical.",
+                    key, type_name
+                )))?
+            }
+            keys.push(key);
+        }
+    }
+
+    Ok(())
+}
+
+/// Fragment spreads are flattened structs, and serde hands each key of the JSON object to only one of them or to the fields next to them, so a response key cannot be selected both next to a fragment spread and in the fragment, or in two fragments.
+fn check_fragment_response_keys(
+    type_name: &str,
+    selection: &Selection,
+    context: &QueryContext,
+) -> Result<(), CodegenError> {
+    let mut keys: Vec<(String, Option<&str>)> = Vec::new();
+    for item in &selection.0 {
+        let (selected_keys, fragment) = match item {
+            SelectionItem::Field(field) => (
+                vec![field.alias.as_ref().unwrap_or(&field.name).clone()],
+                None,
+            ),
+            SelectionItem::FragmentSpread(spread) => {
+                match context.fragments.get(&spread.fragment_name) {
+                    Some(fragment) => (
+                        fragment.response_keys(context),
+                        Some(fragment.name.as_str()),
+                    ),
+                    None => continue,
+                }
+            }
+            SelectionItem::InlineFragment(_) => continue,
+        };
+
+        for key in selected_keys {
+            match keys.iter().find(|(other, _)| *other == key) {
+                Some((_, other_fragment)) if *other_fragment != fragment => {
+                    let place = |fragment: Option<&str>| match fragment {
+                        Some(fragment) => format!("in the {} fragment", fragment),
+                        None => format!("on {}", type_name),
+                    };
+                    Err(CodegenError::Validation(format!(
+                        "The {} field is selected both {} and {}. Fragment spreads are flattened into the same struct, which reads each field of the response only once, so select it in only one of them.",
+                        key,
+                        place(*other_fragment),
+                        place(fragment)
+                    )))?
+                }
+                _ => keys.push((key, fragment)),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Flattened structs read their fields from the same JSON object, and serde hands each key to only one of them, so a response key cannot be selected both on the object and in an inline fragment, or in two inline fragments.
+fn check_flattened_response_keys(
+    type_name: &str,
+    selection: &Selection,
+) -> Result<(), CodegenError> {
+    let selected_fields = selection.0.iter().flat_map(|item| match item {
+        SelectionItem::Field(field) => vec![(field, None)],
+        SelectionItem::InlineFragment(inline) => inline
+            .fields
+            .0
+            .iter()
+            .filter_map(|item| match item {
+                SelectionItem::Field(field) => Some((field, Some(inline.on.as_str()))),
+                _ => None,
+            })
+            .collect(),
+        SelectionItem::FragmentSpread(_) => Vec::new(),
+    });
+    let place = |on: Option<&str>| match on {
+        Some(on) => format!("in the inline fragment on {}", on),
+        None => format!("on {}", type_name),
+    };
+
+    let mut keys: Vec<(&str, Option<&str>)> = Vec::new();
+    for (field, on) in selected_fields {
+        let key = field.alias.as_ref().unwrap_or(&field.name).as_str();
+        match keys.iter().find(|(other, _)| *other == key) {
+            Some((_, other_on)) if *other_on != on => Err(CodegenError::Validation(format!(
+                "The {} field is selected both {} and {}. With interface_flatten, select it only once, since the inline fragments are flattened into the same struct.",
+                key,
+                place(*other_on),
+                place(on)
+            )))?,
+            _ => keys.push((key, on)),
+        }
+    }
+
+    Ok(())
+}
+
+/// The type of the field holding a fragment spread. A fragment behind `@include` or `@skip` is either there as a whole or not at all.
+fn fragment_spread_type(spread: &SelectionFragmentSpread) -> TokenStream {
+    let type_name = Ident::new(&spread.fragment_name, Span::call_site());
+
+    if spread.is_conditional() {
+        quote!(Option<#type_name>)
+    } else {
+        quote!(#type_name)
+    }
+}
+
+pub(crate) fn flattened_interface_names(
+    inline: &SelectionInlineFragment,
+    prefix: &str,
+    context: &QueryContext,
+) -> (Ident, Ident) {
+    let field_name = Ident::new(
+        &format!("{}_fields", inline.on.to_snake_case()),
+        Span::call_site(),
+    );
+    let struct_name = Ident::new(
+        &context.bounded_name(format!("{}{}Fields", prefix.to_camel_case(), inline.on)),
+        Span::call_site(),
+    );
+    (field_name, struct_name)
+}
+
 pub(crate) fn response_fields_for_selection(
     type_name: &str,
     schema_fields: &[GqlObjectField],
     context: &QueryContext,
     selection: &Selection,
     prefix: &str,
-) -> Result<Vec<TokenStream>, failure::Error> {
-    selection
+) -> Result<Vec<TokenStream>, CodegenError> {
+    check_conflicting_response_keys(type_name, selection)?;
+    check_fragment_response_keys(type_name, selection, context)?;
+    if context.interface_flatten {
+        check_flattened_response_keys(type_name, selection)?;
+    }
+
+    let mut described = Vec::new();
+    let fields: Result<Vec<TokenStream>, CodegenError> = selection
         .0
         .iter()
         .map(|item| match item {
@@ -109,43 +370,115 @@ pub(crate) fn response_fields_for_selection(
                 let schema_field = &schema_fields
                     .iter()
                     .find(|field| &field.name == name)
-                    .ok_or_else(|| {
-                        format_err!(
-                            "Could not find field `{}` on `{}`. Available fields: `{}`.",
-                            name.as_str(),
-                            type_name,
-                            schema_fields
-                                .iter()
-                                .map(|ref field| &field.name)
-                                .format("`, `"),
-                        )
-                    })?;
-                let ty = schema_field.type_.to_rust(
-                    context,
-                    &format!("{}{}", prefix.to_camel_case(), alias.to_camel_case()),
-                );
+                    .ok_or_else(|| unknown_field_error(name, type_name, schema_fields))?;
+                ::arguments::validate_arguments(f, schema_field, type_name, context)?;
+                if is_skipped_external(schema_field, context) {
+                    return Ok(quote!());
+                }
+                let ty = response_field_type(f, schema_field, context).to_rust(context, &context.field_type_name(prefix, alias));
+                let ty = if ::transforms::is_caught(f) {
+                    // `FieldError` does not implement `Reflect`.
+                    if context.bevy_reflect {
+                        Err(CodegenError::Validation(format!(
+                            "The {} field has the @catch directive, which cannot be combined with the bevy_reflect option.",
+                            f.name
+                        )))?
+                    }
+                    quote!(Result<#ty, ::graphql_client::catch::FieldError>)
+                } else {
+                    ty
+                };
 
-                Ok(render_object_field(
+                let description = field_description(schema_field);
+                let field = render_object_field(
                     alias,
                     &ty,
-                    schema_field.description.as_ref().map(|s| s.as_str()),
+                    description.as_ref().map(|s| s.as_str()),
                     &schema_field.deprecation,
                     &context.deprecation_strategy,
-                ))
+                    context.rename_all_camel_case,
+                );
+
+                // The field can be skipped entirely because of the deprecation strategy.
+                if field.is_empty() {
+                    return Ok(field);
+                }
+
+                let transform = if defaults_to_empty_list(f, schema_field, context) {
+                    quote!(#[serde(default, deserialize_with = "::graphql_client::empty_lists::deserialize")])
+                } else {
+                    ::transforms::deserialize_with_annotation(f, schema_field, &context.schema)?
+                };
+                // The transform helpers work on `String`s.
+                if context.compact_str && !transform.is_empty() && !::transforms::is_caught(f) {
+                    Err(CodegenError::Validation(format!(
+                        "The {} field has a transform directive, which cannot be combined with the compact_str option.",
+                        f.name
+                    )))?
+                }
+                let skip = if context.strip_typename && f.name == TYPENAME_FIELD {
+                    quote!(#[serde(skip_serializing)])
+                } else {
+                    ::transforms::sensitive_annotation(f, context)
+                };
+                described.push(FieldDescription::new(
+                    &field_ident(alias).to_string(),
+                    Some(alias),
+                    &ty,
+                    ::arguments::graphql_type(&schema_field.type_),
+                    selected_field_type(f, schema_field).is_optional(),
+                ));
+                Ok(context.transform_field(prefix, alias, quote!(#transform #skip #field)))
             }
             SelectionItem::FragmentSpread(fragment) => {
+                if let Some(definition) = context.fragments.get(&fragment.fragment_name) {
+                    check_fragment_spread(
+                        &definition.on,
+                        type_name,
+                        Some(&fragment.fragment_name),
+                        context,
+                    )?;
+                }
                 let field_name =
                     Ident::new(&fragment.fragment_name.to_snake_case(), Span::call_site());
                 context.require(&fragment.fragment_name);
-                let type_name = Ident::new(&fragment.fragment_name, Span::call_site());
+                let field_type = fragment_spread_type(fragment);
+                if let Some(definition) = context.fragments.get(&fragment.fragment_name) {
+                    described.push(FieldDescription::new(
+                        &field_name.to_string(),
+                        None,
+                        &field_type,
+                        definition.on.clone(),
+                        fragment.is_conditional(),
+                    ));
+                }
+                // serde reads a flattened `Option` as `Some` whenever the fragment's fields can be missing, and as `None` on any error, so whether the fragment was sent is told from its response keys.
+                let deserialize_with = if fragment.is_conditional() {
+                    quote!(#[serde(deserialize_with = "::graphql_client::conditional_fragments::deserialize")])
+                } else {
+                    quote!()
+                };
                 Ok(quote! {
                     #[serde(flatten)]
-                    pub #field_name: #type_name
+                    #deserialize_with
+                    pub #field_name: #field_type
+                })
+            }
+            SelectionItem::InlineFragment(inline) => {
+                let (field_name, struct_name) =
+                    flattened_interface(type_name, inline, context, prefix)?;
+                described.push(FieldDescription::new(
+                    &field_name.to_string(),
+                    None,
+                    &quote!(#struct_name),
+                    inline.on.clone(),
+                    false,
+                ));
+                Ok(quote! {
+                    #[serde(flatten)]
+                    pub #field_name: #struct_name
                 })
             }
-            SelectionItem::InlineFragment(_) => Err(format_err!(
-                "unimplemented: inline fragment on object field"
-            ))?,
         })
         .filter(|x| match x {
             // Remove empty fields so callers always know a field has some
@@ -153,16 +486,461 @@ pub(crate) fn response_fields_for_selection(
             Ok(f) => !f.is_empty(),
             Err(_) => true,
         })
-        .collect()
+        .collect();
+
+    let fields = fields?;
+    context.describe_type(prefix, type_name, described);
+    Ok(fields)
+}
+
+/// `IntoIterator` (by value and by reference) and `FromIterator` implementations for a response struct whose only field is a non-nullable list, if the `list_iterators` option is enabled.
+///
+/// This is deliberately conservative: structs with any other field, including `__typename` or a fragment, are left alone.
+pub(crate) fn list_iterator_impls(
+    type_name: &str,
+    schema_fields: &[GqlObjectField],
+    context: &QueryContext,
+    struct_name: &Ident,
+    selection: &Selection,
+    prefix: &str,
+) -> Result<TokenStream, CodegenError> {
+    if !context.list_iterators {
+        return Ok(quote!());
+    }
+
+    let selected = match selection.0.as_slice() {
+        [SelectionItem::Field(selected)] => selected,
+        _ => return Ok(quote!()),
+    };
+
+    let schema_field = schema_fields
+        .iter()
+        .find(|field| field.name == selected.name)
+        .ok_or_else(|| unknown_field_error(&selected.name, type_name, schema_fields))?;
+
+    if is_skipped_external(schema_field, context) {
+        return Ok(quote!());
+    }
+
+    let field_type = response_field_type(selected, schema_field, context);
+    let item_type = match (
+        &field_type,
+        &schema_field.deprecation,
+        &context.deprecation_strategy,
+    ) {
+        // The field is not generated at all.
+        (_, DeprecationStatus::Deprecated(_), DeprecationStrategy::Deny) => return Ok(quote!()),
+        (FieldType::Vector(item_type), _, _) => item_type,
+        _ => return Ok(quote!()),
+    };
+
+    let alias = selected.alias.as_ref().unwrap_or(&selected.name);
+    let item_type = item_type.to_rust(context, &context.field_type_name(prefix, alias));
+    let field = field_ident(alias);
+
+    Ok(quote! {
+        impl IntoIterator for #struct_name {
+            type Item = #item_type;
+            type IntoIter = ::std::vec::IntoIter<#item_type>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.#field.into_iter()
+            }
+        }
+
+        impl<'a> IntoIterator for &'a #struct_name {
+            type Item = &'a #item_type;
+            type IntoIter = ::std::slice::Iter<'a, #item_type>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.#field.iter()
+            }
+        }
+
+        impl ::std::iter::FromIterator<#item_type> for #struct_name {
+            fn from_iter<I: IntoIterator<Item = #item_type>>(iter: I) -> Self {
+                #struct_name {
+                    #field: iter.into_iter().collect(),
+                }
+            }
+        }
+    })
+}
+
+/// The `Optimistic` mirror of a response struct, if the `optimistic_responses` option is enabled: every field is an `Option`, so a predicted response can be built from the fields known in advance.
+///
+/// Selections on objects and interfaces use their own mirror. Unions, the `on` enums of interfaces and `@catch` fields keep their regular type. `extra_fields` is appended to the fields of the selection.
+pub(crate) fn optimistic_struct(
+    schema_fields: &[GqlObjectField],
+    context: &QueryContext,
+    struct_name: &Ident,
+    selection: &Selection,
+    prefix: &str,
+    extra_fields: TokenStream,
+) -> TokenStream {
+    if !context.optimistic_responses {
+        return quote!();
+    }
+
+    let fields = selection.0.iter().filter_map(|item| match item {
+        SelectionItem::Field(f) => {
+            let alias = f.alias.as_ref().unwrap_or(&f.name);
+            // Unknown fields are reported when generating the response struct itself.
+            let schema_field = schema_fields.iter().find(|field| field.name == f.name)?;
+            if let (DeprecationStatus::Deprecated(_), DeprecationStrategy::Deny) =
+                (&schema_field.deprecation, &context.deprecation_strategy)
+            {
+                return None;
+            }
+            if is_skipped_external(schema_field, context) {
+                return None;
+            }
+
+            let type_name = schema_field.type_.inner_name_string();
+            let field_type = response_field_type(f, schema_field, context);
+            let field_prefix = context.field_type_name(prefix, alias);
+            let ty = if ::transforms::is_caught(f) {
+                let ty = field_type.to_rust(context, &field_prefix);
+                quote!(Result<#ty, ::graphql_client::catch::FieldError>)
+            } else if context.schema.objects.contains_key(&type_name)
+                || context.schema.interfaces.contains_key(&type_name)
+            {
+                field_type.to_rust(context, &format!("{}Optimistic", field_prefix))
+            } else {
+                field_type.to_rust(context, &field_prefix)
+            };
+            let name = field_ident(alias);
+
+            Some(quote!(pub #name: Option<#ty>))
+        }
+        SelectionItem::FragmentSpread(fragment) => {
+            let field_name = Ident::new(&fragment.fragment_name.to_snake_case(), Span::call_site());
+            let type_name = Ident::new(
+                &format!("{}Optimistic", fragment.fragment_name),
+                Span::call_site(),
+            );
+
+            Some(quote!(pub #field_name: Option<#type_name>))
+        }
+        // Inline fragments that cannot be flattened are reported when generating the response struct itself.
+        SelectionItem::InlineFragment(inline) if context.interface_flatten => {
+            let (field_name, struct_name) = flattened_interface_names(inline, prefix, context);
+            let type_name = Ident::new(&format!("{}Optimistic", struct_name), Span::call_site());
+
+            Some(quote!(pub #field_name: Option<#type_name>))
+        }
+        SelectionItem::InlineFragment(_) => None,
+    });
+
+    let name = Ident::new(&format!("{}Optimistic", struct_name), Span::call_site());
+    let doc = format!(
+        "`{}` with every field optional, to build a predicted response for optimistic updates.",
+        struct_name
+    );
+    let derives = context.optimistic_derives();
+    let visibility = context.type_visibility();
+
+    context.transform_type(
+        &name.to_string(),
+        quote! {
+            #[doc = #doc]
+            #derives
+            #visibility struct #name {
+                #(#fields,)*
+                #extra_fields
+            }
+        },
+    )
+}
+
+/// Getter methods for the fields of a response struct, if the `field_accessors` option is enabled, and the `omitted_conditional_fields` method of the `conditional_fields_report` option. Fields of a `Copy` type (`Int`, `Float` and `Boolean`, optional or not) are returned by value, the others by reference.
+pub(crate) fn field_accessors(
+    schema_fields: &[GqlObjectField],
+    context: &QueryContext,
+    struct_name: &Ident,
+    selection: &Selection,
+    prefix: &str,
+) -> TokenStream {
+    if !context.field_accessors && !context.conditional_fields_report {
+        return quote!();
+    }
+
+    let report = conditional_fields_report(schema_fields, context, selection);
+    let selected: &[SelectionItem] = if context.field_accessors {
+        &selection.0
+    } else {
+        &[]
+    };
+    let accessors = selected.iter().filter_map(|item| match item {
+        SelectionItem::Field(f) => {
+            let alias = f.alias.as_ref().unwrap_or(&f.name);
+            // Unknown fields are reported when generating the response struct itself.
+            let schema_field = schema_fields.iter().find(|field| field.name == f.name)?;
+            if is_skipped_external(schema_field, context) {
+                return None;
+            }
+            let deprecation =
+                deprecation_annotation(&schema_field.deprecation, &context.deprecation_strategy)?;
+            let field_type = response_field_type(f, schema_field, context);
+            let ty = field_type.to_rust(context, &context.field_type_name(prefix, alias));
+            let name = field_ident(alias);
+
+            if !::transforms::is_caught(f) && is_copy(&field_type) {
+                Some(quote! {
+                    #deprecation
+                    #[allow(deprecated)]
+                    pub fn #name(&self) -> #ty {
+                        self.#name
+                    }
+                })
+            } else {
+                let ty = if ::transforms::is_caught(f) {
+                    quote!(Result<#ty, ::graphql_client::catch::FieldError>)
+                } else {
+                    ty
+                };
+                Some(quote! {
+                    #deprecation
+                    #[allow(deprecated)]
+                    pub fn #name(&self) -> &#ty {
+                        &self.#name
+                    }
+                })
+            }
+        }
+        SelectionItem::FragmentSpread(fragment) => {
+            let name = Ident::new(&fragment.fragment_name.to_snake_case(), Span::call_site());
+            let ty = fragment_spread_type(fragment);
+
+            Some(quote! {
+                pub fn #name(&self) -> &#ty {
+                    &self.#name
+                }
+            })
+        }
+        SelectionItem::InlineFragment(inline) if context.interface_flatten => {
+            let (name, type_name) = flattened_interface_names(inline, prefix, context);
+
+            Some(quote! {
+                pub fn #name(&self) -> &#type_name {
+                    &self.#name
+                }
+            })
+        }
+        SelectionItem::InlineFragment(_) => None,
+    });
+
+    quote! {
+        impl #struct_name {
+            #(#accessors)*
+            #report
+        }
+    }
+}
+
+/// The `omitted_conditional_fields` method of the `conditional_fields_report` option, listing the response keys of the fields that are only optional because of an `@include` or `@skip` directive, and are `None`. Fields that are nullable in the schema are not listed, since `None` does not tell whether the server left them out.
+fn conditional_fields_report(
+    schema_fields: &[GqlObjectField],
+    context: &QueryContext,
+    selection: &Selection,
+) -> TokenStream {
+    if !context.conditional_fields_report {
+        return quote!();
+    }
+
+    let checks = selection.0.iter().filter_map(|item| {
+        let f = match item {
+            SelectionItem::Field(f) if f.is_conditional() => f,
+            _ => return None,
+        };
+        // Unknown fields are reported when generating the response struct itself.
+        let schema_field = schema_fields.iter().find(|field| field.name == f.name)?;
+        if schema_field.type_.is_optional() || is_skipped_external(schema_field, context) {
+            return None;
+        }
+        deprecation_annotation(&schema_field.deprecation, &context.deprecation_strategy)?;
+        let alias = f.alias.as_ref().unwrap_or(&f.name);
+        let name = field_ident(alias);
+
+        if ::transforms::is_caught(f) {
+            Some(quote! {
+                if let Ok(None) = self.#name {
+                    omitted.push(#alias);
+                }
+            })
+        } else {
+            Some(quote! {
+                if self.#name.is_none() {
+                    omitted.push(#alias);
+                }
+            })
+        }
+    });
+
+    quote! {
+        /// The fields that are non-null in the schema, but were left out of the response because of their `@include` or `@skip` directive, by response key.
+        #[allow(deprecated)]
+        pub fn omitted_conditional_fields(&self) -> Vec<&'static str> {
+            #[allow(unused_mut)]
+            let mut omitted = Vec::new();
+            #(#checks)*
+            omitted
+        }
+    }
+}
+
+/// Whether the Rust type of the field is `Copy`. Enums are not, because of their `Other(String)` variant.
+fn is_copy(field_type: &FieldType) -> bool {
+    match field_type {
+        FieldType::Optional(inner) => is_copy(inner),
+        FieldType::Vector(_) => false,
+        FieldType::Named(name) => name == "Int" || name == "Float" || name == "Boolean",
+    }
+}
+
+/// Whether the field is left out of the response types, because it is `@external` and the `skip_external_fields` option is set.
+pub(crate) fn is_skipped_external(schema_field: &GqlObjectField, context: &QueryContext) -> bool {
+    schema_field.external && context.skip_external_fields
+}
+
+/// The documentation of a response field: its description in the schema, with a note if it is `@external`.
+fn field_description(schema_field: &GqlObjectField) -> Option<String> {
+    const EXTERNAL_NOTE: &str = "Resolved by another subgraph (`@external`).";
+
+    match (&schema_field.description, schema_field.external) {
+        (Some(description), true) => Some(format!("{}\n\n{}", description, EXTERNAL_NOTE)),
+        (None, true) => Some(EXTERNAL_NOTE.to_string()),
+        (description, false) => description.clone(),
+    }
+}
+
+/// The type of a selected field: the type in the schema, made optional when an `@include` or `@skip` directive lets the server leave the field out.
+pub(crate) fn selected_field_type(
+    selected: &SelectionField,
+    schema_field: &GqlObjectField,
+) -> FieldType {
+    match &schema_field.type_ {
+        FieldType::Optional(_) => schema_field.type_.clone(),
+        ty if selected.is_conditional() => FieldType::Optional(Box::new(ty.clone())),
+        ty => ty.clone(),
+    }
+}
+
+/// The type of a selected field in the response structs: [selected_field_type], without the `Option` of the nullable lists generated as `Vec`s by the `empty_lists` option.
+pub(crate) fn response_field_type(
+    selected: &SelectionField,
+    schema_field: &GqlObjectField,
+    context: &QueryContext,
+) -> FieldType {
+    match selected_field_type(selected, schema_field) {
+        FieldType::Optional(inner) if defaults_to_empty_list(selected, schema_field, context) => {
+            *inner
+        }
+        field_type => field_type,
+    }
+}
+
+/// Whether the selected field is a nullable list generated as a `Vec` defaulting to empty, with the `empty_lists` option. The fields with a transform directive or `@catch` keep their `Option`, since they have their own `deserialize_with` helper.
+pub(crate) fn defaults_to_empty_list(
+    selected: &SelectionField,
+    schema_field: &GqlObjectField,
+    context: &QueryContext,
+) -> bool {
+    if !context.empty_lists
+        || selected
+            .directives
+            .iter()
+            .any(|(name, _)| !::transforms::is_server_directive(name, &context.schema))
+    {
+        return false;
+    }
+
+    match selected_field_type(selected, schema_field) {
+        FieldType::Optional(inner) => matches!(*inner, FieldType::Vector(_)),
+        _ => false,
+    }
+}
+
+/// Checks that a fragment, named (`fragment`) or inline, on `on` can be spread in a selection on `type_name`: it must be the same type, an interface or union sharing some object types with it, or an object it can be.
+pub(crate) fn check_fragment_spread(
+    on: &str,
+    type_name: &str,
+    fragment: Option<&str>,
+    context: &QueryContext,
+) -> Result<(), CodegenError> {
+    if context.schema.fragment_is_possible(on, type_name) {
+        return Ok(());
+    }
+
+    let spread = match fragment {
+        Some(name) => format!("The {} fragment", name),
+        None => "The inline fragment".to_string(),
+    };
+    Err(CodegenError::Validation(format!(
+        "{} on {} cannot be spread in a selection on {}, a value cannot be of both types.",
+        spread, on, type_name
+    )))
+}
+
+pub(crate) fn unknown_field_error(
+    field_name: &str,
+    type_name: &str,
+    schema_fields: &[GqlObjectField],
+) -> CodegenError {
+    CodegenError::UnknownField {
+        field: field_name.to_string(),
+        type_name: type_name.to_string(),
+        available: schema_fields
+            .iter()
+            .map(|field| &field.name)
+            .format("`, `")
+            .to_string(),
+    }
 }
 
 /// Given the GraphQL schema name for an object/interface/input object field and
 /// the equivalent rust name, produces a serde annotation to map them during
 /// (de)serialization if it is necessary, otherwise an empty TokenStream.
-pub(crate) fn field_rename_annotation(graphql_name: &str, rust_name: &str) -> TokenStream {
-    if graphql_name != rust_name {
+///
+/// With `rename_all_camel_case`, the container already renames fields to camelCase, so we only need an annotation for the fields that do not follow that rule.
+pub(crate) fn field_rename_annotation(
+    graphql_name: &str,
+    rust_name: &str,
+    rename_all_camel_case: bool,
+) -> TokenStream {
+    let serde_name = if rename_all_camel_case {
+        serde_camel_case(rust_name)
+    } else {
+        rust_name.to_string()
+    };
+
+    if graphql_name != serde_name {
         quote!(#[serde(rename = #graphql_name)])
     } else {
         quote!()
     }
 }
+
+/// The name serde gives to a field under `#[serde(rename_all = "camelCase")]`.
+fn serde_camel_case(rust_name: &str) -> String {
+    let mut camel_case = String::with_capacity(rust_name.len());
+    let mut capitalize = false;
+
+    for c in rust_name.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize && !camel_case.is_empty() {
+            camel_case.push(c.to_ascii_uppercase());
+            capitalize = false;
+        } else {
+            camel_case.push(if camel_case.is_empty() {
+                c.to_ascii_lowercase()
+            } else {
+                c
+            });
+            capitalize = false;
+        }
+    }
+
+    camel_case
+}
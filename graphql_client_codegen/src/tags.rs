@@ -0,0 +1,143 @@
+//! The `include_tag` option: generating only the parts of the schema tagged for an audience with the `@tag` directive, like `name: String @tag(name: "public")`.
+
+use constants::{TAG_DIRECTIVE, TYPENAME_FIELD};
+use error::CodegenError;
+use graphql_parser::schema;
+use objects::GqlObjectField;
+use schema::Schema;
+use std::collections::BTreeMap;
+
+/// Records the names of the `@tag` directives on an object or interface type and its fields, under `Type` and `Type.field`.
+pub(crate) fn record_tags(
+    tags: &mut BTreeMap<String, Vec<String>>,
+    type_name: &str,
+    directives: &[schema::Directive],
+    fields: &[schema::Field],
+) {
+    insert_tags(tags, type_name.to_string(), directives);
+    for field in fields {
+        insert_tags(
+            tags,
+            format!("{}.{}", type_name, field.name),
+            &field.directives,
+        );
+    }
+}
+
+fn insert_tags(
+    tags: &mut BTreeMap<String, Vec<String>>,
+    key: String,
+    directives: &[schema::Directive],
+) {
+    let names: Vec<String> = directives
+        .iter()
+        .filter(|directive| directive.name == TAG_DIRECTIVE)
+        .filter_map(|directive| {
+            directive
+                .arguments
+                .iter()
+                .find(|(name, _)| name == "name")
+                .and_then(|(_, value)| match value {
+                    schema::Value::String(tag) => Some(tag.clone()),
+                    _ => None,
+                })
+        })
+        .collect();
+
+    if !names.is_empty() {
+        tags.insert(key, names);
+    }
+}
+
+fn has_tag(tags: &BTreeMap<String, Vec<String>>, key: &str, tag: &str) -> bool {
+    tags.get(key)
+        .map(|names| names.iter().any(|name| name == tag))
+        .unwrap_or(false)
+}
+
+/// Removes the object and interface fields that are not tagged with `tag`, directly or through their type, and the types left without fields. Selecting a removed field is then an unknown field error. The root operation types are always kept, and enums, scalars, input objects and unions are not filtered.
+pub(crate) fn retain_tagged(mut schema: Schema, tag: &str) -> Result<Schema, CodegenError> {
+    if schema.tags.is_empty() {
+        Err(CodegenError::Validation(format!(
+            "The include_tag option generates the parts of the schema tagged with @tag(name: \"{}\"), but the schema has no @tag directive. Introspection does not expose directives, so this needs an SDL schema.",
+            tag
+        )))?
+    }
+
+    let tags = &schema.tags;
+    let is_kept = |type_name: &str, field: &GqlObjectField| {
+        field.name == TYPENAME_FIELD
+            || has_tag(tags, type_name, tag)
+            || has_tag(tags, &format!("{}.{}", type_name, field.name), tag)
+            || has_tag(tags, &field.type_.inner_name_string(), tag)
+    };
+    for object in schema.objects.values_mut() {
+        let name = object.name.clone();
+        object.fields.retain(|field| is_kept(&name, field));
+    }
+    for iface in schema.interfaces.values_mut() {
+        let name = iface.name.clone();
+        iface.fields.retain(|field| is_kept(&name, field));
+    }
+
+    let roots: Vec<&String> = vec![
+        schema.query_type.as_ref(),
+        schema.mutation_type.as_ref(),
+        schema.subscription_type.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let is_empty =
+        |fields: &[GqlObjectField]| fields.iter().all(|field| field.name == TYPENAME_FIELD);
+
+    // Removing a type removes the fields of that type, which can leave other types empty.
+    loop {
+        let excluded: Vec<String> = schema
+            .objects
+            .values()
+            .filter(|object| !roots.contains(&&object.name) && is_empty(&object.fields))
+            .map(|object| object.name.clone())
+            .chain(
+                schema
+                    .interfaces
+                    .values()
+                    .filter(|iface| !roots.contains(&&iface.name) && is_empty(&iface.fields))
+                    .map(|iface| iface.name.clone()),
+            )
+            .collect();
+
+        if excluded.is_empty() {
+            break;
+        }
+
+        for name in &excluded {
+            schema.objects.remove(name);
+            schema.interfaces.remove(name);
+        }
+        for object in schema.objects.values_mut() {
+            object
+                .fields
+                .retain(|field| !excluded.contains(&field.type_.inner_name_string()));
+        }
+        for iface in schema.interfaces.values_mut() {
+            iface
+                .fields
+                .retain(|field| !excluded.contains(&field.type_.inner_name_string()));
+        }
+    }
+
+    let objects = &schema.objects;
+    for union in schema.unions.values_mut() {
+        union
+            .variants
+            .retain(|variant| objects.contains_key(variant));
+    }
+    for iface in schema.interfaces.values_mut() {
+        iface
+            .implemented_by
+            .retain(|object| objects.contains_key(object));
+    }
+
+    Ok(schema)
+}
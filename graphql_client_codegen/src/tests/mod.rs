@@ -1 +1,1697 @@
 mod github;
+
+use proc_macro2::TokenStream;
+use std::path::PathBuf;
+use {
+    generate_module_token_stream, generate_module_token_stream_from_combined_document,
+    generate_module_token_stream_from_glob, CodegenError, EnumUnknowns, FloatType,
+    GraphQLClientDeriveOptions, SensitiveFields, TokenTransform,
+};
+
+pub(crate) fn test_options() -> GraphQLClientDeriveOptions {
+    GraphQLClientDeriveOptions {
+        operation_name: None,
+        struct_name: None,
+        module_name: Some("test_query".to_string()),
+        additional_derives: None,
+        deprecation_strategy: None,
+        module_visibility: ::syn::Visibility::Inherited,
+        variant_accessors: false,
+        shared_variables: false,
+        root_field_accessor: false,
+        list_iterators: false,
+        rename_all_camel_case: false,
+        input_patches: false,
+        comment_descriptions: false,
+        with_variables: false,
+        test_defaults: false,
+        enum_all_variants: false,
+        optimistic_responses: false,
+        field_accessors: false,
+        variables_try_from: false,
+        interface_flatten: false,
+        enum_unknowns: EnumUnknowns::default(),
+        rkyv: false,
+        field_json: false,
+        strip_typename: false,
+        visibility: None,
+        transform: None,
+        float_type: FloatType::default(),
+        qualified_paths: false,
+        scalar_paths: None,
+        variables_schema: false,
+        skip_external_fields: false,
+        variables_builder: false,
+        compact_str: false,
+        bevy_reflect: false,
+        variables_as_pairs: false,
+        include_tag: None,
+        scalar_collectors: false,
+        add_typename: false,
+        sqlx_from_row: false,
+        empty_lists: false,
+        unknown_fields: false,
+        serde_crate: None,
+        estimated_size: false,
+        enum_value_methods: None,
+        response_wrapper: None,
+        top_level_derives_only: false,
+        persisted_query_manifest: None,
+        test_only_derives: None,
+        conditional_fields_report: false,
+        field_types: None,
+        response_trait: false,
+        max_identifier_length: None,
+        case_insensitive_enums: false,
+        module_attributes: None,
+        result_aliases: false,
+        sensitive_fields: SensitiveFields::default(),
+        variables_constructor: false,
+        raw_response: false,
+        cache_keys: false,
+    }
+}
+
+fn tests_path(file_name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests")
+        .join(file_name)
+}
+
+#[test]
+fn missing_query_file_is_an_io_error() {
+    let result = generate_module_token_stream(
+        tests_path("this_query_does_not_exist.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(CodegenError::Io { path, .. }) => {
+            assert_eq!(path, tests_path("this_query_does_not_exist.graphql"))
+        }
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn unknown_field_error_lists_available_fields() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_unknown_field_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::UnknownField { .. }) => assert_eq!(
+            format!("{}", err),
+            "Could not find field `nickname` on `Droid`. Available fields: `__typename`, `id`, `name`, `friends`, `friendsConnection`, `appearsIn`, `primaryFunction`."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn operations_with_identical_variables_can_share_them() {
+    let options = GraphQLClientDeriveOptions {
+        shared_variables: true,
+        ..test_options()
+    };
+
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_shared_variables_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains("pub struct DroidNameVariables"));
+    assert!(generated.contains("pub type HumanNameVariables = DroidNameVariables ;"));
+    assert!(!generated.contains("pub struct HumanNameVariables"));
+    assert!(generated.contains("pub struct HeroNameVariables"));
+}
+
+#[test]
+fn variables_are_not_shared_by_default() {
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_shared_variables_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains("pub struct DroidNameVariables"));
+    assert!(generated.contains("pub struct HumanNameVariables"));
+}
+
+#[test]
+fn literal_arguments_of_the_right_type_are_accepted() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_literal_arguments_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    assert!(result.is_ok(), "{:?}", result.map(|_| ()));
+}
+
+#[test]
+fn literal_arguments_of_the_wrong_type_are_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_mistyped_literal_argument_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The first argument on the friendsConnection field of Droid expects a value of type Int, but got \"ten\"."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn misspelled_enum_arguments_are_rejected_with_a_suggestion() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_misspelled_enum_argument_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The episode argument on the hero field of Query expects a value of the Episode enum, but EMPIRR is not one of its values. Did you mean EMPIRE?"
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn fragment_spread_cycles_are_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_cyclic_fragments_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The FriendFields fragment spreads itself through FriendFields -> HeroFields -> FriendFields. Fragment spreads must not form cycles."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn fragments_on_unrelated_types_cannot_be_spread() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_incompatible_fragment_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The StarshipFields fragment on Starship cannot be spread in a selection on Droid, a value cannot be of both types."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn inline_fragments_on_types_not_implementing_the_interface_are_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_incompatible_inline_fragment_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The inline fragment on Starship cannot be spread in a selection on Character, a value cannot be of both types."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn undeclared_condition_variables_are_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_undeclared_condition_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The $withAppearances variable of @include on the appearsIn field is not declared by the UndeclaredConditionQuery operation."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn non_boolean_condition_variables_are_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_non_boolean_condition_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The $withAppearances variable of @include on the appearsIn field is declared as String! by the NonBooleanConditionQuery operation, but the if argument is a Boolean."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn anonymous_operation_next_to_named_operations_is_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_anonymous_and_named_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The query document defines an anonymous operation next to other operations. An anonymous operation must be the only one in its document, give it a name."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn subscription_with_multiple_root_fields_is_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("subscription_multiple_root_fields_query.graphql"),
+        tests_path("subscription_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert!(format!("{}", err).starts_with(
+            "The MultipleRootFields subscription selects 2 root fields (newDogs, dogBirthdays)."
+        )),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn subscription_with_a_single_root_field_is_accepted() {
+    let result = generate_module_token_stream(
+        tests_path("subscription_aliased_root_field_query.graphql"),
+        tests_path("subscription_schema.graphql"),
+        Some(test_options()),
+    );
+
+    assert!(result.is_ok(), "{:?}", result.map(|_| ()));
+}
+
+#[test]
+fn root_field_accessor_requires_a_single_root_field() {
+    let options = GraphQLClientDeriveOptions {
+        root_field_accessor: true,
+        operation_name: Some("MultipleRootFields".to_string()),
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("star_wars_multiple_root_fields_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The root_field_accessor option requires the MultipleRootFields operation to select exactly one root field."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn selection_set_on_a_scalar_is_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("scalar_subselection_query.graphql"),
+        tests_path("scalar_subselection_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The location field on Place is of type Coordinates, which is a scalar or an enum. It cannot have a selection set."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn selection_set_on_an_enum_is_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("enum_subselection_query.graphql"),
+        tests_path("subselection_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The status field on Shop is of type Status, which is a scalar or an enum. It cannot have a selection set."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn object_field_without_selection_set_is_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("missing_subselection_query.graphql"),
+        tests_path("subselection_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The owner field on Shop is of type Owner, which is an object. It needs a selection set."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn scalar_used_as_a_leaf_uses_the_mapped_type() {
+    let generated = generate_module_token_stream(
+        tests_path("scalar_leaf_query.graphql"),
+        tests_path("scalar_subselection_schema.graphql"),
+        Some(test_options()),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains("type Coordinates = super :: Coordinates ;"));
+    assert!(generated.contains("pub location : Option < Coordinates >"));
+}
+
+#[test]
+fn scalar_paths_are_emitted_as_given() {
+    let generated = generate_module_token_stream(
+        tests_path("scalar_leaf_query.graphql"),
+        tests_path("scalar_subselection_schema.graphql"),
+        Some(GraphQLClientDeriveOptions {
+            scalar_paths: Some("Coordinates = crate::geo::Point<f64, f64>".to_string()),
+            ..test_options()
+        }),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains("type Coordinates = crate :: geo :: Point < f64 , f64 > ;"));
+}
+
+#[test]
+fn malformed_scalar_paths_are_rejected() {
+    let generate = |scalar_paths: &str| {
+        let result = generate_module_token_stream(
+            tests_path("scalar_leaf_query.graphql"),
+            tests_path("scalar_subselection_schema.graphql"),
+            Some(GraphQLClientDeriveOptions {
+                scalar_paths: Some(scalar_paths.to_string()),
+                ..test_options()
+            }),
+        );
+        match result {
+            Err(CodegenError::Validation(message)) => message,
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    };
+
+    assert_eq!(
+        generate("Coordinates"),
+        "Expected `Scalar = path` in scalar_paths, got `Coordinates`."
+    );
+    assert_eq!(
+        generate("Coords = crate::geo::Point"),
+        "scalar_paths maps Coords, which is not a custom scalar of the schema."
+    );
+    assert_eq!(
+        generate("Coordinates = crate::geo::"),
+        "The path `crate::geo::` for the Coordinates scalar is not a valid Rust path."
+    );
+    assert_eq!(
+        generate("Coordinates = geo::super::Point"),
+        "The path `geo::super::Point` for the Coordinates scalar is malformed: `super` can only appear at the start of a path."
+    );
+}
+
+#[test]
+fn field_types_get_a_scalar_of_their_own() {
+    let generated = generate_module_token_stream(
+        tests_path("scalar_leaf_query.graphql"),
+        tests_path("scalar_subselection_schema.graphql"),
+        Some(GraphQLClientDeriveOptions {
+            field_types: Some("Place.location = crate::geo::Point<f64, f64>".to_string()),
+            ..test_options()
+        }),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains("type PlaceLocation = crate :: geo :: Point < f64 , f64 > ;"));
+    assert!(generated.contains("pub location : Option < PlaceLocation >"));
+    assert!(!generated.contains("type Coordinates"));
+}
+
+#[test]
+fn malformed_field_types_are_rejected() {
+    let generate = |field_types: &str| {
+        let result = generate_module_token_stream(
+            tests_path("scalar_leaf_query.graphql"),
+            tests_path("scalar_subselection_schema.graphql"),
+            Some(GraphQLClientDeriveOptions {
+                field_types: Some(field_types.to_string()),
+                ..test_options()
+            }),
+        );
+        match result {
+            Err(CodegenError::Validation(message)) => message,
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    };
+
+    assert_eq!(
+        generate("Place = crate::geo::Point"),
+        "Expected `Type.field = path` in field_types, got `Place = crate::geo::Point`."
+    );
+    assert_eq!(
+        generate("Location.place = crate::geo::Point"),
+        "field_types maps Location.place, but Location is not an object or interface type of the schema."
+    );
+    assert_eq!(
+        generate("Place.position = crate::geo::Point"),
+        "field_types maps Place.position, but Place has no position field."
+    );
+    assert_eq!(
+        generate("Place.name = crate::Name"),
+        "field_types maps Place.name, which is of type String. Only the fields of a custom scalar type, like JSON, can be mapped."
+    );
+    assert_eq!(
+        generate("Place.location = geo::super::Point"),
+        "The path `geo::super::Point` for the Place.location field is malformed: `super` can only appear at the start of a path."
+    );
+}
+
+#[test]
+fn empty_root_selection_is_rejected() {
+    use graphql_parser::query::{Definition, OperationDefinition};
+
+    // The parser rejects `query EmptyQuery { }`, so we empty the selection after parsing.
+    let mut query = ::graphql_parser::parse_query("query EmptyQuery { hero { name } }").unwrap();
+    if let Definition::Operation(OperationDefinition::Query(q)) = &mut query.definitions[0] {
+        q.selection_set.items.clear();
+    }
+    let operation = ::codegen::all_operations(&query).remove(0);
+    let schema = ::schema::Schema::from_sdl(include_str!("star_wars_schema.graphql")).unwrap();
+
+    let result =
+        ::codegen::response_for_query(schema, query, &operation, &test_options(), false, None);
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The EmptyQuery operation must select at least one field."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn rename_all_camel_case_replaces_per_field_renames() {
+    let generate = |rename_all_camel_case| {
+        generate_module_token_stream(
+            tests_path("rename_all_query.graphql"),
+            tests_path("rename_all_schema.graphql"),
+            Some(GraphQLClientDeriveOptions {
+                rename_all_camel_case,
+                ..test_options()
+            }),
+        )
+        .unwrap()
+        .to_string()
+    };
+    let per_field = generate(false);
+    let rename_all = generate(true);
+
+    assert!(!per_field.contains("rename_all"));
+    assert!(per_field.contains("# [ serde ( rename = \"displayName\" ) ]"));
+
+    // Every struct gets the container attribute: the response, the variables and the input object.
+    assert_eq!(
+        rename_all
+            .matches("# [ serde ( rename_all = \"camelCase\" ) ]")
+            .count(),
+        4
+    );
+    for renamed in &[
+        "displayName",
+        "avatarUrl",
+        "lastLoginAt",
+        "firstCount",
+        "createdAfter",
+    ] {
+        assert!(!rename_all.contains(&format!("rename = \"{}\"", renamed)));
+    }
+    // These do not follow the camelCase rule, so they keep their own rename.
+    assert!(rename_all.contains("# [ serde ( rename = \"userID\" ) ]"));
+    assert!(rename_all.contains("# [ serde ( rename = \"__typename\" ) ]"));
+
+    assert!(rename_all.len() < per_field.len());
+}
+
+#[test]
+fn visibility_applies_to_every_generated_type() {
+    let options = GraphQLClientDeriveOptions {
+        visibility: Some(::syn::parse_str("pub(crate)").unwrap()),
+        ..test_options()
+    };
+
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains("pub ( crate ) struct ResponseData"));
+    assert!(generated.contains("pub ( crate ) struct Variables"));
+    assert!(generated.contains("pub ( crate ) enum Episode"));
+    assert!(generated.contains("pub ( crate ) struct StarWarsQueryHero"));
+    assert!(generated.contains("pub ( crate ) enum StarWarsQueryHeroOn"));
+    assert!(!generated.contains("pub struct"));
+    assert!(!generated.contains("pub enum"));
+}
+
+#[test]
+fn optimistic_mirror_of_an_interface_keeps_the_on_enum() {
+    let options = GraphQLClientDeriveOptions {
+        optimistic_responses: true,
+        ..test_options()
+    };
+
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains(
+        "pub struct StarWarsQueryHeroOptimistic { pub name : Option < String > , pub on : Option < StarWarsQueryHeroOn > , }"
+    ));
+    assert!(generated.contains(
+        "pub struct ResponseDataOptimistic { pub hero : Option < Option < StarWarsQueryHeroOptimistic > > , }"
+    ));
+}
+
+#[test]
+fn schema_hash_changes_with_the_schema() {
+    const SCHEMA: &str = include_str!("star_wars_schema.graphql");
+    let schema_hash = |source: &str| {
+        let generated = ::generate_module_token_stream_with_schema(
+            tests_path("star_wars_query.graphql"),
+            ::schema::Schema::from_sdl(source).unwrap(),
+            Some(test_options()),
+        )
+        .unwrap()
+        .to_string();
+        let start = generated.find("SCHEMA_HASH : & 'static str = \"").unwrap() + 31;
+        generated[start..start + 64].to_string()
+    };
+
+    assert_eq!(schema_hash(SCHEMA), ::query_hash::sha256_hex(SCHEMA));
+    assert_eq!(schema_hash(SCHEMA), schema_hash(SCHEMA));
+    assert_ne!(
+        schema_hash(SCHEMA),
+        schema_hash(&format!("{}\nscalar Timestamp\n", SCHEMA))
+    );
+}
+
+#[test]
+fn directives_declared_in_the_schema_are_sent_to_the_server() {
+    let schema = format!(
+        "{}\ndirective @cacheControl(maxAge: Int) on FIELD\n",
+        include_str!("star_wars_schema.graphql")
+    );
+
+    let generated = ::generate_module_token_stream_with_schema(
+        tests_path("star_wars_server_directives_query.graphql"),
+        ::schema::Schema::from_sdl(&schema).unwrap(),
+        Some(test_options()),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains("name @cacheControl(maxAge: 30)"));
+    assert!(!generated.contains("@trim"));
+    assert!(generated.contains(
+        "# [ serde ( deserialize_with = \"::graphql_client::transforms::trim\" ) ] pub name : String"
+    ));
+
+    match ::generate_module_token_stream_with_schema(
+        tests_path("star_wars_server_directives_query.graphql"),
+        ::schema::Schema::from_sdl(include_str!("star_wars_schema.graphql")).unwrap(),
+        Some(test_options()),
+    ) {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "Unknown directive @cacheControl on the name field. The supported client directives are @trim, @lowercase, @uppercase, @catch, @adjacentlyTagged and @sensitive, and the schema declares no directive with this name."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn generate_to_file_writes_the_derive_output() {
+    let out_path = ::std::env::temp_dir().join("graphql_client_codegen_generate_to_file.rs");
+    let options = GraphQLClientDeriveOptions {
+        struct_name: Some("StarWarsQuery".to_string()),
+        ..test_options()
+    };
+
+    ::generate_to_file(
+        tests_path("star_wars_schema.graphql"),
+        tests_path("star_wars_query.graphql"),
+        options.clone(),
+        &out_path,
+    )
+    .unwrap();
+    let written = ::std::fs::read_to_string(&out_path).unwrap();
+    ::std::fs::remove_file(&out_path).unwrap();
+
+    let expected = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    )
+    .unwrap()
+    .to_string();
+    assert_eq!(written, expected);
+    assert!(written.contains("impl :: graphql_client :: GraphQLQuery for StarWarsQuery"));
+}
+
+#[test]
+fn comments_become_doc_comments_when_enabled() {
+    let generate = |comment_descriptions| {
+        generate_module_token_stream(
+            tests_path("comment_descriptions_query.graphql"),
+            tests_path("comment_descriptions_schema.graphql"),
+            Some(GraphQLClientDeriveOptions {
+                comment_descriptions,
+                ..test_options()
+            }),
+        )
+        .unwrap()
+        .to_string()
+    };
+
+    let with_descriptions = generate(true);
+    assert!(
+        with_descriptions.contains("# [ doc = \"The full name, as entered at registration.\" ]")
+    );
+    assert!(with_descriptions.contains("# [ doc = \"Can log in.\" ] ACTIVE"));
+
+    let without_descriptions = generate(false);
+    assert!(!without_descriptions.contains("doc = \"The full name"));
+}
+
+#[test]
+fn generate_to_directory_splits_the_module_into_submodules() {
+    let out_dir = ::std::env::temp_dir().join("graphql_client_codegen_generate_to_directory");
+    let options = GraphQLClientDeriveOptions {
+        struct_name: Some("StarWarsQuery".to_string()),
+        ..test_options()
+    };
+
+    ::generate_to_directory(
+        tests_path("star_wars_schema.graphql"),
+        tests_path("star_wars_query.graphql"),
+        options,
+        &out_dir,
+    )
+    .unwrap();
+    let read = |file_name: &str| ::std::fs::read_to_string(out_dir.join(file_name));
+    let module = read("mod.rs").unwrap();
+    let enums = read("enums.rs").unwrap();
+    let response = read("response.rs").unwrap();
+    let has_inputs = read("inputs.rs").is_ok();
+    ::std::fs::remove_dir_all(&out_dir).unwrap();
+
+    assert!(module.contains(
+        "mod enums { # [ allow ( unused_imports ) ] use super :: * ; include ! ( \"enums.rs\" ) ; } pub use self :: enums :: * ;"
+    ));
+    assert!(module.contains("impl :: graphql_client :: GraphQLQuery for StarWarsQuery"));
+    assert!(enums.contains("pub enum Episode"));
+    assert!(response.contains("pub struct ResponseData"));
+    // The query has no input object, so there is no empty file for them.
+    assert!(!has_inputs);
+}
+
+#[test]
+fn schemas_with_a_bom_or_in_utf16_are_decoded() {
+    let generate = |schema_file: &str| {
+        generate_module_token_stream(
+            tests_path("scalar_leaf_query.graphql"),
+            tests_path(schema_file),
+            Some(test_options()),
+        )
+        .unwrap()
+        .to_string()
+    };
+
+    let expected = generate("scalar_subselection_schema.graphql");
+    assert_eq!(generate("bom_schema.graphql"), expected);
+    assert_eq!(generate("utf16_schema.graphql"), expected);
+    assert_eq!(generate("utf16_without_bom_schema.graphql"), expected);
+}
+
+#[test]
+fn latin1_schema_of_even_length_is_an_encoding_error() {
+    let result = generate_module_token_stream(
+        tests_path("scalar_leaf_query.graphql"),
+        tests_path("latin1_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Encoding { .. }) => assert_eq!(
+            format!("{}", err),
+            format!(
+                "{} is not valid UTF-8 or UTF-16 text.",
+                tests_path("latin1_schema.graphql").display()
+            )
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn schema_in_an_unknown_encoding_is_an_encoding_error() {
+    let result = generate_module_token_stream(
+        tests_path("scalar_leaf_query.graphql"),
+        tests_path("invalid_encoding_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Encoding { .. }) => assert_eq!(
+            format!("{}", err),
+            format!(
+                "{} is not valid UTF-8 or UTF-16 text.",
+                tests_path("invalid_encoding_schema.graphql").display()
+            )
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn introspection_responses_load_with_any_nesting() {
+    let load = |json: &str| {
+        let mut schema = ::schema::Schema::from_json(json).unwrap();
+        // The hash is computed from the source, which differs between the forms.
+        schema.source_hash = None;
+        schema
+    };
+
+    let full_response = load(include_str!("introspection_full_response.json"));
+    assert_eq!(full_response.query_type, Some("Query".to_string()));
+    assert!(full_response.objects.contains_key("Query"));
+    assert!(full_response.enums.contains_key("Mood"));
+
+    for json in &[
+        include_str!("introspection_schema_field.json"),
+        include_str!("introspection_data_schema.json"),
+        include_str!("introspection_bare_schema.json"),
+    ] {
+        assert_eq!(load(json), full_response);
+    }
+}
+
+#[test]
+fn non_string_descriptions_are_dropped() {
+    let mut schema =
+        ::schema::Schema::from_json(include_str!("introspection_invalid_descriptions.json"))
+            .unwrap();
+    let mut expected =
+        ::schema::Schema::from_json(include_str!("introspection_bare_schema.json")).unwrap();
+    schema.source_hash = None;
+    expected.source_hash = None;
+
+    assert_eq!(schema, expected);
+}
+
+#[test]
+fn json_without_a_schema_is_rejected() {
+    let result = ::schema::Schema::from_json(r#"{"data": {"viewer": null}}"#);
+
+    assert!(format!("{}", result.unwrap_err())
+        .contains("expected an introspection response, with a `__schema` or a `types` field"));
+}
+
+#[test]
+fn operations_in_one_module_share_the_common_definitions() {
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_shared_variables_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    )
+    .unwrap()
+    .to_string();
+
+    assert_eq!(generated.matches("type Boolean = bool ;").count(), 1);
+    assert_eq!(generated.matches("pub enum Episode").count(), 1);
+    assert!(generated.contains("pub const DROID_NAME_OPERATION_NAME"));
+    assert!(!generated.contains("pub const OPERATION_NAME"));
+}
+
+#[test]
+fn operation_names_must_be_unique_across_globbed_files() {
+    let pattern = tests_path("star_wars_glob_collision_*_query.graphql");
+    let result = generate_module_token_stream_from_glob(
+        &pattern.to_string_lossy(),
+        tests_path("star_wars_schema.graphql"),
+        test_options(),
+    );
+
+    match result {
+        Err(CodegenError::Validation(message)) => {
+            assert!(message.starts_with("The HeroName operation is defined in both "));
+            assert!(message.contains("star_wars_glob_collision_a_query.graphql and "));
+            assert!(message.contains("star_wars_glob_collision_b_query.graphql."));
+        }
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn flattened_interfaces_cannot_select_a_field_twice() {
+    let options = GraphQLClientDeriveOptions {
+        interface_flatten: true,
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("interface_flatten_overlap_query.graphql"),
+        tests_path("interface_flatten_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(CodegenError::Validation(message)) => assert!(message.starts_with(
+            "The name field is selected both on User and in the inline fragment on Named."
+        )),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn identical_field_selections_are_merged() {
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_duplicate_fields_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    )
+    .unwrap()
+    .to_string();
+
+    assert_eq!(generated.matches("pub name : String").count(), 2);
+    assert_eq!(generated.matches("pub friends :").count(), 1);
+    assert_eq!(
+        generated
+            .matches("pub struct DuplicateFieldsQueryHeroFriends ")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn conflicting_field_selections_are_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_conflicting_fields_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The name field is selected more than once on Character, with different fields, arguments, directives or subselections. Selections of the same response key are only merged when they are identical."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn fields_selected_next_to_a_fragment_spread_and_in_the_fragment_are_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_duplicate_fragment_fields_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The name field is selected both on Character and in the HeroFields fragment. Fragment spreads are flattened into the same struct, which reads each field of the response only once, so select it in only one of them."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn fields_selected_in_two_spread_fragments_are_rejected() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_overlapping_fragments_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The name field is selected both in the HeroName fragment and in the HeroFields fragment. Fragment spreads are flattened into the same struct, which reads each field of the response only once, so select it in only one of them."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn missing_typename_error_suggests_adding_it() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_missing_typename_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::MissingTypename { .. }) => assert_eq!(
+            format!("{}", err),
+            "Missing __typename in selection for MissingTypenameHero. Add `__typename` to the selection on the Character interface: it is needed to tell the possible types apart."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+#[cfg(not(feature = "rkyv"))]
+fn rkyv_option_requires_the_feature() {
+    let options = GraphQLClientDeriveOptions {
+        rkyv: true,
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The rkyv option requires the rkyv feature of graphql_client."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+#[cfg(not(feature = "compact_str"))]
+fn compact_str_option_requires_the_feature() {
+    let options = GraphQLClientDeriveOptions {
+        compact_str: true,
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The compact_str option requires the compact_str feature of graphql_client."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+#[cfg(not(feature = "bevy"))]
+fn bevy_reflect_option_requires_the_feature() {
+    let options = GraphQLClientDeriveOptions {
+        bevy_reflect: true,
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The bevy_reflect option requires the bevy feature of graphql_client."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+#[cfg(not(feature = "sqlx"))]
+fn sqlx_from_row_option_requires_the_feature() {
+    let options = GraphQLClientDeriveOptions {
+        sqlx_from_row: true,
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The sqlx_from_row option requires the sqlx feature of graphql_client."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn field_json_option_requires_serialize() {
+    let options = GraphQLClientDeriveOptions {
+        field_json: true,
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The field_json option requires Serialize in the response_derives."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn response_trait_option_requires_serialize() {
+    let options = GraphQLClientDeriveOptions {
+        response_trait: true,
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The response_trait option requires Serialize in the response_derives."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+/// Marks the struct of the `hero` field and the `name` fields with an attribute.
+struct MarkCharacters;
+
+impl TokenTransform for MarkCharacters {
+    fn transform_type(&self, type_name: &str, tokens: TokenStream) -> TokenStream {
+        if type_name == "StarWarsQueryHero" {
+            quote!(#[doc = "marked type"] #tokens)
+        } else {
+            tokens
+        }
+    }
+
+    fn transform_field(
+        &self,
+        _type_name: &str,
+        response_key: &str,
+        tokens: TokenStream,
+    ) -> TokenStream {
+        if response_key == "name" {
+            quote!(#[doc = "marked field"] #tokens)
+        } else {
+            tokens
+        }
+    }
+}
+
+#[test]
+fn transform_hooks_post_process_types_and_fields() {
+    let options = GraphQLClientDeriveOptions {
+        transform: Some(::std::rc::Rc::new(MarkCharacters)),
+        ..test_options()
+    };
+
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(
+        generated.contains("# [ doc = \"marked type\" ] # [ derive ( Deserialize ) ] pub struct StarWarsQueryHero {"),
+        "{}",
+        generated
+    );
+    assert!(generated.contains("# [ doc = \"marked field\" ] pub name : String"));
+    assert_eq!(generated.matches("marked type").count(), 1);
+}
+
+#[test]
+fn field_excluded_by_the_tag_filter_is_rejected() {
+    let mut options = test_options();
+    options.include_tag = Some("public".to_string());
+    let result = generate_module_token_stream(
+        tests_path("tag_excluded_field_query.graphql"),
+        tests_path("tag_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::UnknownField { .. }) => assert_eq!(
+            format!("{}", err),
+            "Could not find field `supplierContract` on `Shop`. Available fields: `__typename`, `name`, `owner`."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn tag_filter_requires_tags_in_the_schema() {
+    let mut options = test_options();
+    options.include_tag = Some("public".to_string());
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The include_tag option generates the parts of the schema tagged with @tag(name: \"public\"), but the schema has no @tag directive. Introspection does not expose directives, so this needs an SDL schema."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn ord_derive_on_a_response_with_floats_is_rejected() {
+    let mut options = test_options();
+    options.additional_derives = Some("PartialEq, Eq, PartialOrd, Ord".to_string());
+    let result = generate_module_token_stream(
+        tests_path("star_wars_float_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The Eq, Ord response derives cannot be used because the response of HeightQuery holds a Float. Floats only implement PartialEq and PartialOrd."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn serde_crate_option_must_be_a_path() {
+    let options = GraphQLClientDeriveOptions {
+        serde_crate: Some("my serde".to_string()),
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The serde_crate option must be a path, like my_serde, but it is \"my serde\"."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_only_derives_are_behind_cfg_test() {
+    let options = GraphQLClientDeriveOptions {
+        additional_derives: Some("Debug".to_string()),
+        test_only_derives: Some("Debug, PartialEq".to_string()),
+        ..test_options()
+    };
+
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains(
+        "# [ derive ( Deserialize , Debug ) ] # [ cfg_attr ( test , derive ( PartialEq ) ) ] pub struct ResponseData"
+    ));
+    assert!(generated.contains(
+        "# [ derive ( Serialize , Debug ) ] # [ cfg_attr ( test , derive ( PartialEq ) ) ] pub struct Variables"
+    ));
+    assert!(generated.contains(
+        "# [ derive ( Debug ) ] # [ cfg_attr ( test , derive ( PartialEq ) ) ] pub enum Episode"
+    ));
+}
+
+#[test]
+fn serde_derives_cannot_be_test_only() {
+    let options = GraphQLClientDeriveOptions {
+        test_only_derives: Some("Debug, Serialize".to_string()),
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "Serialize cannot be in test_only_derives, the serde annotations of the generated types need it in every build."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn custom_derives_can_be_limited_to_the_response_data() {
+    let options = GraphQLClientDeriveOptions {
+        additional_derives: Some("Debug, Serialize, JsonSchema".to_string()),
+        top_level_derives_only: true,
+        ..test_options()
+    };
+
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains(
+        "# [ derive ( Deserialize , Debug , Serialize , JsonSchema ) ] pub struct ResponseData"
+    ));
+    assert!(generated
+        .contains("# [ derive ( Deserialize , Debug , Serialize ) ] pub struct StarWarsQueryHero"));
+    assert!(generated.contains("# [ derive ( Debug ) ] pub enum Episode"));
+    // On `ResponseData`, and on `Variables`, which has the variables derives.
+    assert_eq!(generated.matches("JsonSchema").count(), 2);
+}
+
+#[test]
+fn registered_persisted_query_hashes_are_generated() {
+    let options = GraphQLClientDeriveOptions {
+        persisted_query_manifest: Some(tests_path("star_wars_persisted_queries.json")),
+        ..test_options()
+    };
+
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains(
+        "pub const PERSISTED_QUERY_HASH : & 'static str = \"dd1290afe67d6bdd21d7df10c901b3ef06b66336a1c82e3e2a21ffc6909ec223\" ;"
+    ));
+}
+
+#[test]
+fn stale_persisted_query_hashes_are_rejected() {
+    let options = GraphQLClientDeriveOptions {
+        persisted_query_manifest: Some(tests_path("star_wars_stale_persisted_queries.json")),
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert!(format!("{}", err).starts_with(&format!(
+            "The StarWarsQuery operation is registered with the hash 0000000000000000000000000000000000000000000000000000000000000000 in the persisted query manifest {}, but the query hashes to dd1290afe67d6bdd21d7df10c901b3ef06b66336a1c82e3e2a21ffc6909ec223",
+            tests_path("star_wars_stale_persisted_queries.json").display()
+        ))),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn response_wrapper_option_must_be_a_path() {
+    let options = GraphQLClientDeriveOptions {
+        response_wrapper: Some("Envelope<T>".to_string()),
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The response_wrapper option must be a path, like crate::Envelope, but it is \"Envelope<T>\"."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn fields_selected_on_every_variant_get_an_accessor() {
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_common_fields_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains(
+        "pub fn name ( & self ) -> & String { match & self . on { HeroQueryHeroOn :: Human ( value ) => & value . name , HeroQueryHeroOn :: Droid ( value ) => & value . name , } }"
+    ));
+    assert!(generated.contains("pub fn detail ( & self ) -> & Option < String >"));
+    // Only on humans, on the struct itself, and with a struct for each variant.
+    assert!(!generated.contains("pub fn mass"));
+    assert!(!generated.contains("pub fn id"));
+    assert!(!generated.contains("pub fn friends"));
+}
+
+#[test]
+fn fragment_variables_must_be_declared_by_each_operation() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_fragment_variables_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The $count variable of the first argument on the friendsConnection field of the CharacterFriends fragment is not declared by the HumanFriends operation."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn fragment_variables_declared_by_the_operation_are_accepted() {
+    let options = GraphQLClientDeriveOptions {
+        operation_name: Some("HeroFriends".to_string()),
+        ..test_options()
+    };
+
+    let result = generate_module_token_stream(
+        tests_path("star_wars_fragment_variables_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(options),
+    );
+
+    assert!(result.is_ok());
+}
+
+#[test]
+fn combined_documents_cannot_have_a_schema_path_too() {
+    let result = generate_module_token_stream(
+        tests_path("combined_document.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            format!(
+                "The query document {} contains type-system definitions, but a schema is given too. Remove the schema_path to use the definitions as the schema, or move them to the schema.",
+                tests_path("combined_document.graphql").display()
+            )
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn documents_without_a_schema_path_need_type_system_definitions() {
+    let result = generate_module_token_stream_from_combined_document(
+        tests_path("star_wars_query.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            format!(
+                "The query document {} has no type-system definitions to use as the schema. Add them, or give a schema_path.",
+                tests_path("star_wars_query.graphql").display()
+            )
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn combined_documents_generate_the_operations() {
+    let generated = generate_module_token_stream_from_combined_document(
+        tests_path("combined_document.graphql"),
+        Some(test_options()),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains("pub struct ResponseData { pub greeting : String , }"));
+    assert!(generated.contains("\"query GreetingQuery {\\n  greeting\\n}\""));
+}
+
+#[test]
+fn max_identifier_length_leaves_room_for_the_hash() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(GraphQLClientDeriveOptions {
+            max_identifier_length: Some(12),
+            ..test_options()
+        }),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The max_identifier_length option must be at least 16, to leave room for the hash ending the shortened names."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn module_attributes_are_at_the_top_of_the_module() {
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(GraphQLClientDeriveOptions {
+            module_attributes: Some(
+                "#![allow(clippy::all)] #![cfg_attr(coverage, coverage(off))]".to_string(),
+            ),
+            ..test_options()
+        }),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains(
+        "# ! [ allow ( dead_code ) ] # ! [ allow ( clippy :: all ) ] # ! [ cfg_attr ( coverage , coverage ( off ) ) ] pub const QUERY"
+    ));
+}
+
+#[test]
+fn module_attributes_must_be_inner_attributes() {
+    let result = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(GraphQLClientDeriveOptions {
+            module_attributes: Some("#[allow(dead_code)]".to_string()),
+            ..test_options()
+        }),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The module_attributes option must be inner attributes, like #![allow(clippy::all)], but it is \"#[allow(dead_code)]\"."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn each_operation_gets_a_result_alias() {
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_shared_variables_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(GraphQLClientDeriveOptions {
+            result_aliases: true,
+            ..test_options()
+        }),
+    )
+    .unwrap()
+    .to_string();
+
+    for operation in &["DroidName", "HumanName", "HeroName"] {
+        assert!(generated.contains(&format!(
+            "pub type {}Result = :: graphql_client :: Response < {}ResponseData > ;",
+            operation, operation
+        )));
+    }
+}
+
+#[test]
+fn result_aliases_are_not_generated_by_default() {
+    let generated = generate_module_token_stream(
+        tests_path("star_wars_query.graphql"),
+        tests_path("star_wars_schema.graphql"),
+        Some(test_options()),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(!generated.contains("Result ="));
+}
+
+#[test]
+fn variables_and_literals_can_be_mixed_in_lists() {
+    let generated = generate_module_token_stream(
+        tests_path("list_arguments_query.graphql"),
+        tests_path("list_arguments_schema.graphql"),
+        Some(test_options()),
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated.contains("pub struct ListArgumentsShips"));
+}
+
+#[test]
+fn variables_in_lists_must_have_the_type_of_the_items() {
+    let result = generate_module_token_stream(
+        tests_path("list_arguments_mistyped_variable_query.graphql"),
+        tests_path("list_arguments_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The $length variable in the list of the filter argument on the ships field of the FilteredShips fragment is declared as String by the ListArgumentsMistypedVariable operation, but the items of the list are Int."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn nullable_variables_cannot_be_non_null_list_items() {
+    let result = generate_module_token_stream(
+        tests_path("list_arguments_nullable_variable_query.graphql"),
+        tests_path("list_arguments_schema.graphql"),
+        Some(test_options()),
+    );
+
+    match result {
+        Err(err @ CodegenError::Validation(_)) => assert_eq!(
+            format!("{}", err),
+            "The $first variable in the list of the ids argument on the ships field is declared as ID by the ListArgumentsNullableVariable operation, but the items of the list are ID!."
+        ),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn fragment_names_must_be_unique_across_globbed_files() {
+    let pattern = tests_path("star_wars_glob_fragment_*_query.graphql");
+    let result = generate_module_token_stream_from_glob(
+        &pattern.to_string_lossy(),
+        tests_path("star_wars_schema.graphql"),
+        test_options(),
+    );
+
+    match result {
+        Err(CodegenError::Validation(message)) => {
+            let a = tests_path("star_wars_glob_fragment_a_query.graphql");
+            let b = tests_path("star_wars_glob_fragment_b_query.graphql");
+            assert_eq!(
+                message,
+                format!(
+                    "The CharacterFields fragment is defined in both {a} and {b}, differently:\n{a}: fragment CharacterFields on Character {{\n  name\n}}\n{b}: fragment CharacterFields on Character {{\n  id\n  name\n}}\nFragment names must be unique across the files matched by {pattern}.",
+                    a = a.display(),
+                    b = b.display(),
+                    pattern = pattern.display()
+                )
+            );
+        }
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
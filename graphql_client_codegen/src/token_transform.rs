@@ -0,0 +1,25 @@
+//! Hooks to post-process the generated code, for custom codegen pipelines.
+
+use proc_macro2::TokenStream;
+
+/// Post-processes the generated code, set with `GraphQLClientDeriveOptions::transform`. This allows adding attributes or derives, or renaming, without forking the code generation.
+///
+/// Every method returns the tokens to emit in place of the ones it is given. The default implementations return them unchanged, so implementors only override the hook points they need.
+pub trait TokenTransform {
+    /// Called on the definition of each generated struct and enum: the response types, the enums of the schema, the input objects and the variables. `type_name` is the name of the Rust type, and `tokens` hold the definition with its derives and attributes, but not the impls of the type.
+    fn transform_type(&self, type_name: &str, tokens: TokenStream) -> TokenStream {
+        let _ = type_name;
+        tokens
+    }
+
+    /// Called on each field of a response struct, before its definition goes through [`TokenTransform::transform_type`]. `type_name` is the name of the struct, except for the fields of `ResponseData`, which get the name of the operation. `response_key` is the alias of the field if it has one, its name otherwise. Fragment spreads are not fields.
+    fn transform_field(
+        &self,
+        type_name: &str,
+        response_key: &str,
+        tokens: TokenStream,
+    ) -> TokenStream {
+        let _ = (type_name, response_key);
+        tokens
+    }
+}
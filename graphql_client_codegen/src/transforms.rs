@@ -0,0 +1,790 @@
+use error::CodegenError;
+use graphql_parser::query::{
+    Definition, Directive, Document, OperationDefinition, Selection, SelectionSet, Value,
+};
+use objects::GqlObjectField;
+use operations::Operation;
+use proc_macro2::TokenStream;
+use query::QueryContext;
+use schema::Schema;
+use selection::{Selection as SelectionTree, SelectionField, SelectionItem};
+
+/// Client directives that transform the value of a string field on deserialization. They are stripped from the query before it is sent to the server.
+pub(crate) const TRANSFORM_DIRECTIVES: &[&str] = &["trim", "lowercase", "uppercase"];
+
+/// The client directive marking a field as an error boundary: it is generated as a `Result`, holding the errors that made the field `null`.
+pub(crate) const CATCH_DIRECTIVE: &str = "catch";
+
+/// The repeatable operation directive for arbitrary client metadata, e.g. `@meta(key: "team", value: "billing")`. Its applications are collected in the `META` constant.
+pub(crate) const META_DIRECTIVE: &str = "meta";
+
+/// The operation directive listing the auth scopes the operation needs, e.g. `@requiresScopes(scopes: ["read:user"])`. The scopes are collected in the `REQUIRED_SCOPES` constant, for pre-flight checks on the client.
+pub(crate) const REQUIRES_SCOPES_DIRECTIVE: &str = "requiresScopes";
+
+/// The operation directive choosing the HTTP method a transport should send the operation with, e.g. `@httpMethod(method: "GET")` for cacheable queries. It is exposed as the `HTTP_METHOD` constant.
+pub(crate) const HTTP_METHOD_DIRECTIVE: &str = "httpMethod";
+
+/// The operation directive marking an operation, typically a mutation, as safe to retry with an idempotency key. It is exposed as the `IDEMPOTENT` constant.
+pub(crate) const IDEMPOTENT_DIRECTIVE: &str = "idempotent";
+
+/// The client directive generating the enum of a union field with adjacent tagging, for servers that wrap union payloads like `{"type": "Dog", "data": {...}}`: `pets @adjacentlyTagged(tag: "type", content: "data")`.
+pub(crate) const ADJACENTLY_TAGGED_DIRECTIVE: &str = "adjacentlyTagged";
+
+/// The client directive marking a field as sensitive, like a token or a personal detail: it is redacted or left out when the response is serialized, following the `sensitive_fields` option.
+pub(crate) const SENSITIVE_DIRECTIVE: &str = "sensitive";
+
+/// The directives defined by the spec, which we send to the server untouched.
+pub(crate) const EXECUTABLE_DIRECTIVES: &[&str] = &["skip", "include"];
+
+/// Whether the directive on a field is for the server: defined by the spec, or declared in the schema, like `@defer` or `@cacheControl`. The transform directives stay client directives even if the schema declares them, since they are stripped from the query.
+pub(crate) fn is_server_directive(name: &str, schema: &Schema) -> bool {
+    !TRANSFORM_DIRECTIVES.contains(&name)
+        && (EXECUTABLE_DIRECTIVES.contains(&name) || schema.directives.contains(name))
+}
+
+/// How the fields with the `@sensitive` client directive are serialized.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub enum SensitiveFields {
+    /// Serialize them as `"[redacted]"` (default).
+    #[default]
+    Redact,
+    /// Leave them out of the serialized output.
+    Skip,
+}
+
+/// Whether the selected field has the `@catch` directive.
+pub(crate) fn is_caught(selected: &SelectionField) -> bool {
+    selected
+        .directives
+        .iter()
+        .any(|(name, _)| name == CATCH_DIRECTIVE)
+}
+
+/// The `(tag, content)` keys of the `@adjacentlyTagged` directive on the selected field, if it has one.
+pub(crate) fn adjacent_tagging(
+    selected: &SelectionField,
+) -> Result<Option<(String, String)>, CodegenError> {
+    let arguments = match selected
+        .directives
+        .iter()
+        .find(|(name, _)| name == ADJACENTLY_TAGGED_DIRECTIVE)
+    {
+        Some((_, arguments)) => arguments,
+        None => return Ok(None),
+    };
+
+    if let Some((unknown, _)) = arguments
+        .iter()
+        .find(|(argument, _)| argument != "tag" && argument != "content")
+    {
+        Err(CodegenError::Validation(format!(
+            "Unknown argument {} of @adjacentlyTagged on the {} field. Only tag and content are allowed.",
+            unknown, selected.name
+        )))?
+    }
+
+    let argument = |name: &str| match arguments.iter().find(|(argument, _)| argument == name) {
+        Some((_, Value::String(value))) => Ok(value.clone()),
+        Some(_) => Err(CodegenError::Validation(format!(
+            "The {} argument of @adjacentlyTagged on the {} field must be a string literal.",
+            name, selected.name
+        ))),
+        None => Err(CodegenError::Validation(format!(
+            "@adjacentlyTagged on the {} field is missing the {} argument.",
+            selected.name, name
+        ))),
+    };
+
+    Ok(Some((argument("tag")?, argument("content")?)))
+}
+
+/// The serde annotation of a field with the `@sensitive` directive, replacing its value with `"[redacted]"` or leaving it out when serializing, following the `sensitive_fields` option.
+pub(crate) fn sensitive_annotation(
+    selected: &SelectionField,
+    context: &QueryContext,
+) -> TokenStream {
+    let sensitive = selected
+        .directives
+        .iter()
+        .any(|(name, _)| name == SENSITIVE_DIRECTIVE);
+    if !sensitive {
+        return quote!();
+    }
+
+    match context.sensitive_fields {
+        SensitiveFields::Redact => {
+            quote!(#[serde(serialize_with = "::graphql_client::sensitive::serialize_redacted")])
+        }
+        SensitiveFields::Skip => quote!(#[serde(skip_serializing)]),
+    }
+}
+
+/// The `deserialize_with` annotation for the transform or `@catch` directive on a selected field, if there is one.
+pub(crate) fn deserialize_with_annotation(
+    selected: &SelectionField,
+    schema_field: &GqlObjectField,
+    schema: &Schema,
+) -> Result<TokenStream, CodegenError> {
+    let mut transforms = selected
+        .directives
+        .iter()
+        .map(|(name, _)| name)
+        .filter(|name| {
+            !is_server_directive(name, schema)
+                && *name != CATCH_DIRECTIVE
+                && *name != ADJACENTLY_TAGGED_DIRECTIVE
+                && *name != SENSITIVE_DIRECTIVE
+        });
+
+    if is_caught(selected) {
+        if transforms.next().is_some() {
+            Err(CodegenError::Validation(format!(
+                "The {} field has both a transform directive and @catch. They cannot be combined.",
+                selected.name
+            )))?
+        }
+        return Ok(quote!(#[serde(deserialize_with = "::graphql_client::catch::deserialize")]));
+    }
+
+    let transform = match transforms.next() {
+        Some(transform) => transform,
+        None => return Ok(quote!()),
+    };
+
+    if !TRANSFORM_DIRECTIVES.contains(&transform.as_str()) {
+        Err(CodegenError::Validation(format!(
+            "Unknown directive @{} on the {} field. The supported client directives are @trim, @lowercase, @uppercase, @catch, @adjacentlyTagged and @sensitive, and the schema declares no directive with this name.",
+            transform, selected.name
+        )))?
+    }
+
+    if transforms.next().is_some() {
+        Err(CodegenError::Validation(format!(
+            "The {} field has more than one transform directive. Only one is allowed per field.",
+            selected.name
+        )))?
+    }
+
+    let inner_type = schema_field.type_.inner_name_string();
+    if inner_type != "String" && inner_type != "ID" {
+        Err(CodegenError::Validation(format!(
+            "The @{} directive can only be used on String and ID fields, but {} is a {}.",
+            transform, selected.name, inner_type
+        )))?
+    }
+
+    let path = format!("::graphql_client::transforms::{}", transform);
+    Ok(quote!(#[serde(deserialize_with = #path)]))
+}
+
+/// The `(key, value)` pairs of the `@meta` directives on the operation, in order.
+pub(crate) fn operation_meta(operation: &Operation) -> Result<Vec<(String, String)>, CodegenError> {
+    operation
+        .directives
+        .iter()
+        .filter(|directive| directive.name == META_DIRECTIVE)
+        .map(|directive| {
+            let argument = |name: &str| {
+                match directive.arguments.iter().find(|(argument, _)| argument == name) {
+                    Some((_, Value::String(value))) => Ok(value.clone()),
+                    Some(_) => Err(CodegenError::Validation(format!(
+                        "The {} argument of @meta on the {} operation must be a string literal.",
+                        name, operation.name
+                    ))),
+                    None => Err(CodegenError::Validation(format!(
+                        "@meta on the {} operation is missing the {} argument.",
+                        operation.name, name
+                    ))),
+                }
+            };
+
+            if let Some((unknown, _)) = directive
+                .arguments
+                .iter()
+                .find(|(argument, _)| argument != "key" && argument != "value")
+            {
+                Err(CodegenError::Validation(format!(
+                    "Unknown argument {} of @meta on the {} operation. Only key and value are allowed.",
+                    unknown, operation.name
+                )))?
+            }
+
+            Ok((argument("key")?, argument("value")?))
+        })
+        .collect()
+}
+
+/// The scopes of the `@requiresScopes` directives on the operation, in order and without duplicates.
+pub(crate) fn operation_required_scopes(operation: &Operation) -> Result<Vec<String>, CodegenError> {
+    let mut scopes: Vec<String> = Vec::new();
+
+    for directive in operation
+        .directives
+        .iter()
+        .filter(|directive| directive.name == REQUIRES_SCOPES_DIRECTIVE)
+    {
+        if let Some((unknown, _)) = directive
+            .arguments
+            .iter()
+            .find(|(argument, _)| argument != "scopes")
+        {
+            Err(CodegenError::Validation(format!(
+                "Unknown argument {} of @requiresScopes on the {} operation. Only scopes is allowed.",
+                unknown, operation.name
+            )))?
+        }
+
+        let not_a_list = || {
+            CodegenError::Validation(format!(
+                "The scopes argument of @requiresScopes on the {} operation must be a list of string literals.",
+                operation.name
+            ))
+        };
+
+        let values = match directive
+            .arguments
+            .iter()
+            .find(|(argument, _)| argument == "scopes")
+        {
+            Some((_, Value::List(values))) => values,
+            Some(_) => Err(not_a_list())?,
+            None => Err(CodegenError::Validation(format!(
+                "@requiresScopes on the {} operation is missing the scopes argument.",
+                operation.name
+            )))?,
+        };
+
+        for value in values {
+            match value {
+                Value::String(scope) => {
+                    if !scopes.contains(scope) {
+                        scopes.push(scope.clone());
+                    }
+                }
+                _ => Err(not_a_list())?,
+            }
+        }
+    }
+
+    Ok(scopes)
+}
+
+/// The variant of `graphql_client::HttpMethod` chosen by the `@httpMethod` directive on the operation, `Post` without the directive.
+pub(crate) fn operation_http_method(operation: &Operation) -> Result<&'static str, CodegenError> {
+    let mut directives = operation
+        .directives
+        .iter()
+        .filter(|directive| directive.name == HTTP_METHOD_DIRECTIVE);
+
+    let directive = match directives.next() {
+        Some(directive) => directive,
+        None => return Ok("Post"),
+    };
+
+    if directives.next().is_some() {
+        Err(CodegenError::Validation(format!(
+            "The {} operation has more than one @httpMethod directive.",
+            operation.name
+        )))?
+    }
+
+    if let Some((unknown, _)) = directive
+        .arguments
+        .iter()
+        .find(|(argument, _)| argument != "method")
+    {
+        Err(CodegenError::Validation(format!(
+            "Unknown argument {} of @httpMethod on the {} operation. Only method is allowed.",
+            unknown, operation.name
+        )))?
+    }
+
+    match directive
+        .arguments
+        .iter()
+        .find(|(argument, _)| argument == "method")
+    {
+        Some((_, Value::String(method))) if method == "GET" => Ok("Get"),
+        Some((_, Value::String(method))) if method == "POST" => Ok("Post"),
+        Some(_) => Err(CodegenError::Validation(format!(
+            "The method argument of @httpMethod on the {} operation must be either \"GET\" or \"POST\".",
+            operation.name
+        ))),
+        None => Err(CodegenError::Validation(format!(
+            "@httpMethod on the {} operation is missing the method argument.",
+            operation.name
+        ))),
+    }
+}
+
+/// Removes the client directives (transforms, `@catch`, `@adjacentlyTagged`, `@sensitive`, `@meta`, `@requiresScopes` and `@httpMethod`) from the query document, so the server never sees them. Returns `None` if the document does not contain any.
+pub(crate) fn strip_transform_directives(document: &Document) -> Option<Document> {
+    let mut stripped = document.clone();
+    let mut found = false;
+
+    for definition in &mut stripped.definitions {
+        let selection_set = match definition {
+            Definition::Operation(OperationDefinition::Query(q)) => {
+                found |= strip_operation_directives(&mut q.directives);
+                &mut q.selection_set
+            }
+            Definition::Operation(OperationDefinition::Mutation(m)) => {
+                found |= strip_operation_directives(&mut m.directives);
+                &mut m.selection_set
+            }
+            Definition::Operation(OperationDefinition::Subscription(s)) => {
+                found |= strip_operation_directives(&mut s.directives);
+                &mut s.selection_set
+            }
+            Definition::Operation(OperationDefinition::SelectionSet(s)) => s,
+            Definition::Fragment(f) => &mut f.selection_set,
+        };
+        found |= strip_selection_set(selection_set);
+    }
+
+    if found {
+        Some(stripped)
+    } else {
+        None
+    }
+}
+
+fn is_client_directive(name: &str) -> bool {
+    TRANSFORM_DIRECTIVES.contains(&name)
+        || name == CATCH_DIRECTIVE
+        || name == ADJACENTLY_TAGGED_DIRECTIVE
+        || name == SENSITIVE_DIRECTIVE
+}
+
+/// The paths, made of response keys, of the fields with the `@catch` directive in the selection, looking through fragments.
+pub(crate) fn caught_field_paths(
+    selection: &SelectionTree,
+    context: &QueryContext,
+) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    collect_caught_field_paths(
+        selection,
+        context,
+        &mut Vec::new(),
+        &mut Vec::new(),
+        &mut paths,
+    );
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+fn collect_caught_field_paths<'a>(
+    selection: &'a SelectionTree,
+    context: &'a QueryContext,
+    prefix: &mut Vec<String>,
+    visited_fragments: &mut Vec<&'a str>,
+    paths: &mut Vec<Vec<String>>,
+) {
+    for item in &selection.0 {
+        match item {
+            SelectionItem::Field(field) => {
+                prefix.push(field.alias.as_ref().unwrap_or(&field.name).clone());
+                if is_caught(field) {
+                    paths.push(prefix.clone());
+                }
+                collect_caught_field_paths(
+                    &field.fields,
+                    context,
+                    prefix,
+                    visited_fragments,
+                    paths,
+                );
+                prefix.pop();
+            }
+            SelectionItem::InlineFragment(inline) => collect_caught_field_paths(
+                &inline.fields,
+                context,
+                prefix,
+                visited_fragments,
+                paths,
+            ),
+            SelectionItem::FragmentSpread(spread) => {
+                let name = spread.fragment_name.as_str();
+                if visited_fragments.contains(&name) {
+                    continue;
+                }
+                visited_fragments.push(name);
+                if let Some(fragment) = context.fragments.get(name) {
+                    collect_caught_field_paths(
+                        &fragment.selection,
+                        context,
+                        prefix,
+                        visited_fragments,
+                        paths,
+                    );
+                }
+                visited_fragments.pop();
+            }
+        }
+    }
+}
+
+/// Whether the operation has the `@idempotent` directive, which takes no arguments.
+pub(crate) fn operation_is_idempotent(operation: &Operation) -> Result<bool, CodegenError> {
+    let directive = match operation
+        .directives
+        .iter()
+        .find(|directive| directive.name == IDEMPOTENT_DIRECTIVE)
+    {
+        Some(directive) => directive,
+        None => return Ok(false),
+    };
+
+    if !directive.arguments.is_empty() {
+        Err(CodegenError::Validation(format!(
+            "@idempotent on the {} operation does not take arguments.",
+            operation.name
+        )))?
+    }
+
+    Ok(true)
+}
+
+fn strip_operation_directives(directives: &mut Vec<Directive>) -> bool {
+    let before = directives.len();
+    directives.retain(|d| {
+        d.name != META_DIRECTIVE
+            && d.name != REQUIRES_SCOPES_DIRECTIVE
+            && d.name != HTTP_METHOD_DIRECTIVE
+            && d.name != IDEMPOTENT_DIRECTIVE
+    });
+    directives.len() != before
+}
+
+fn strip_selection_set(selection_set: &mut SelectionSet) -> bool {
+    let mut found = false;
+
+    for item in &mut selection_set.items {
+        match item {
+            Selection::Field(field) => {
+                let before = field.directives.len();
+                field.directives.retain(|d| !is_client_directive(&d.name));
+                found |= field.directives.len() != before;
+                found |= strip_selection_set(&mut field.selection_set);
+            }
+            Selection::InlineFragment(inline) => {
+                found |= strip_selection_set(&mut inline.selection_set);
+            }
+            Selection::FragmentSpread(_) => (),
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use constants::*;
+    use deprecation::DeprecationStatus;
+    use field_type::FieldType;
+    use graphql_parser;
+
+    fn selected(directives: &[&str]) -> SelectionField {
+        SelectionField {
+            alias: None,
+            name: "name".to_string(),
+            directives: directives
+                .iter()
+                .map(|d| (d.to_string(), Vec::new()))
+                .collect(),
+            arguments: Vec::new(),
+            fields: SelectionTree(Vec::new()),
+        }
+    }
+
+    fn schema_field(type_name: &str) -> GqlObjectField {
+        GqlObjectField {
+            description: None,
+            name: "name".to_string(),
+            type_: FieldType::Optional(Box::new(FieldType::Named(type_name.to_string()))),
+            deprecation: DeprecationStatus::Current,
+            arguments: Vec::new(),
+            external: false,
+        }
+    }
+
+    #[test]
+    fn trim_maps_to_the_runtime_helper() {
+        let annotation = deserialize_with_annotation(
+            &selected(&["trim"]),
+            &schema_field(&string_type()),
+            &Schema::new(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            annotation.to_string(),
+            quote!(#[serde(deserialize_with = "::graphql_client::transforms::trim")]).to_string()
+        );
+    }
+
+    #[test]
+    fn unknown_transform_directive_is_rejected() {
+        let err = deserialize_with_annotation(
+            &selected(&["reverse"]),
+            &schema_field(&string_type()),
+            &Schema::new(),
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("Unknown directive @reverse"));
+    }
+
+    #[test]
+    fn transform_on_non_string_field_is_rejected() {
+        let err = deserialize_with_annotation(
+            &selected(&["trim"]),
+            &schema_field(&float_type()),
+            &Schema::new(),
+        )
+        .unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("can only be used on String and ID fields"));
+    }
+
+    #[test]
+    fn catch_maps_to_the_runtime_helper() {
+        let annotation = deserialize_with_annotation(
+            &selected(&["catch"]),
+            &schema_field(&float_type()),
+            &Schema::new(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            annotation.to_string(),
+            quote!(#[serde(deserialize_with = "::graphql_client::catch::deserialize")]).to_string()
+        );
+    }
+
+    #[test]
+    fn directives_declared_in_the_schema_are_not_transforms() {
+        let mut schema = Schema::new();
+        schema.directives.insert("cacheControl".to_string());
+
+        let annotation = deserialize_with_annotation(
+            &selected(&["cacheControl", "trim"]),
+            &schema_field(&string_type()),
+            &schema,
+        )
+        .unwrap();
+        assert_eq!(
+            annotation.to_string(),
+            quote!(#[serde(deserialize_with = "::graphql_client::transforms::trim")]).to_string()
+        );
+
+        let annotation = deserialize_with_annotation(
+            &selected(&["catch", "cacheControl"]),
+            &schema_field(&float_type()),
+            &schema,
+        )
+        .unwrap();
+        assert_eq!(
+            annotation.to_string(),
+            quote!(#[serde(deserialize_with = "::graphql_client::catch::deserialize")]).to_string()
+        );
+
+        let err = deserialize_with_annotation(
+            &selected(&["cacheControl"]),
+            &schema_field(&string_type()),
+            &Schema::new(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Unknown directive @cacheControl"));
+    }
+
+    #[test]
+    fn catch_cannot_be_combined_with_a_transform() {
+        let err = deserialize_with_annotation(
+            &selected(&["trim", "catch"]),
+            &schema_field(&string_type()),
+            &Schema::new(),
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("cannot be combined"));
+    }
+
+    #[test]
+    fn adjacent_tagging_arguments_must_be_string_literals() {
+        let mut field = selected(&[]);
+        field.directives.push((
+            ADJACENTLY_TAGGED_DIRECTIVE.to_string(),
+            vec![
+                ("tag".to_string(), Value::String("type".to_string())),
+                ("content".to_string(), Value::String("data".to_string())),
+            ],
+        ));
+        assert_eq!(
+            adjacent_tagging(&field).unwrap(),
+            Some(("type".to_string(), "data".to_string()))
+        );
+        assert_eq!(
+            deserialize_with_annotation(&field, &schema_field(&string_type()), &Schema::new())
+                .unwrap()
+                .to_string(),
+            ""
+        );
+
+        field.directives[0].1[1].1 = Value::Boolean(true);
+        let err = adjacent_tagging(&field).unwrap_err();
+        assert!(err.to_string().contains(
+            "The content argument of @adjacentlyTagged on the name field must be a string literal."
+        ));
+    }
+
+    #[test]
+    fn transform_directives_are_stripped_from_the_query() {
+        let query = graphql_parser::parse_query(
+            "query Q { person { name @trim nickname @include(if: true) } }",
+        )
+        .unwrap();
+
+        let stripped = strip_transform_directives(&query).unwrap().to_string();
+
+        assert!(!stripped.contains("@trim"));
+        assert!(stripped.contains("@include(if: true)"));
+    }
+
+    #[test]
+    fn meta_directives_are_collected_and_stripped() {
+        let query = graphql_parser::parse_query(
+            r#"query Q @meta(key: "team", value: "billing") @meta(key: "cache", value: "none") { person { name } }"#,
+        )
+        .unwrap();
+        let operation = ::codegen::all_operations(&query).remove(0);
+
+        assert_eq!(
+            operation_meta(&operation).unwrap(),
+            vec![
+                ("team".to_string(), "billing".to_string()),
+                ("cache".to_string(), "none".to_string()),
+            ]
+        );
+        assert!(!strip_transform_directives(&query)
+            .unwrap()
+            .to_string()
+            .contains("@meta"));
+    }
+
+    #[test]
+    fn meta_arguments_must_be_string_literals() {
+        let query = graphql_parser::parse_query(
+            r#"query Q @meta(key: "ttl", value: 60) { person { name } }"#,
+        )
+        .unwrap();
+        let operation = ::codegen::all_operations(&query).remove(0);
+
+        assert_eq!(
+            operation_meta(&operation).unwrap_err().to_string(),
+            "The value argument of @meta on the Q operation must be a string literal."
+        );
+    }
+
+    #[test]
+    fn required_scopes_are_collected_and_stripped() {
+        let query = graphql_parser::parse_query(
+            r#"query Q @requiresScopes(scopes: ["read:user", "read:org"]) @requiresScopes(scopes: ["read:user"]) { person { name } }"#,
+        )
+        .unwrap();
+        let operation = ::codegen::all_operations(&query).remove(0);
+
+        assert_eq!(
+            operation_required_scopes(&operation).unwrap(),
+            vec!["read:user".to_string(), "read:org".to_string()]
+        );
+        assert!(!strip_transform_directives(&query)
+            .unwrap()
+            .to_string()
+            .contains("@requiresScopes"));
+    }
+
+    #[test]
+    fn required_scopes_must_be_a_list_of_string_literals() {
+        for directive in &[
+            r#"@requiresScopes(scopes: "read:user")"#,
+            r#"@requiresScopes(scopes: ["read:user", 1])"#,
+            r#"@requiresScopes(scopes: $scopes)"#,
+        ] {
+            let query = graphql_parser::parse_query(&format!(
+                "query Q {} {{ person {{ name }} }}",
+                directive
+            ))
+            .unwrap();
+            let operation = ::codegen::all_operations(&query).remove(0);
+
+            assert_eq!(
+                operation_required_scopes(&operation).unwrap_err().to_string(),
+                "The scopes argument of @requiresScopes on the Q operation must be a list of string literals."
+            );
+        }
+    }
+
+    #[test]
+    fn http_method_is_read_and_stripped() {
+        let query = graphql_parser::parse_query(
+            r#"query Q @httpMethod(method: "GET") { person { name } } query R { person { name } }"#,
+        )
+        .unwrap();
+        let operations = ::codegen::all_operations(&query);
+
+        assert_eq!(operation_http_method(&operations[0]).unwrap(), "Get");
+        assert_eq!(operation_http_method(&operations[1]).unwrap(), "Post");
+        assert!(!strip_transform_directives(&query)
+            .unwrap()
+            .to_string()
+            .contains("@httpMethod"));
+    }
+
+    #[test]
+    fn http_method_must_be_get_or_post() {
+        let query = graphql_parser::parse_query(
+            r#"query Q @httpMethod(method: "PUT") { person { name } }"#,
+        )
+        .unwrap();
+        let operation = ::codegen::all_operations(&query).remove(0);
+
+        assert_eq!(
+            operation_http_method(&operation).unwrap_err().to_string(),
+            "The method argument of @httpMethod on the Q operation must be either \"GET\" or \"POST\"."
+        );
+    }
+
+    #[test]
+    fn idempotent_is_read_and_stripped() {
+        let query = graphql_parser::parse_query(
+            r#"mutation M @idempotent { person { name } } query Q { person { name } }"#,
+        )
+        .unwrap();
+        let operations = ::codegen::all_operations(&query);
+
+        assert!(operation_is_idempotent(&operations[0]).unwrap());
+        assert!(!operation_is_idempotent(&operations[1]).unwrap());
+        assert!(!strip_transform_directives(&query)
+            .unwrap()
+            .to_string()
+            .contains("@idempotent"));
+    }
+
+    #[test]
+    fn idempotent_takes_no_arguments() {
+        let query =
+            graphql_parser::parse_query(r#"mutation M @idempotent(key: "id") { person { name } }"#)
+                .unwrap();
+        let operation = ::codegen::all_operations(&query).remove(0);
+
+        assert_eq!(
+            operation_is_idempotent(&operation).unwrap_err().to_string(),
+            "@idempotent on the M operation does not take arguments."
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! A machine-readable description of the generated response structs, for bindings in other languages and other tools consuming the type model.
+//!
+//! The descriptions are recorded while the fields of the structs are resolved, so they describe the code that is generated with the same options.
+
+use proc_macro2::TokenStream;
+use std::collections::BTreeMap;
+
+/// The response structs generated for a query document, sorted by name. The enums, for GraphQL enums and for the concrete types of unions and interfaces, are referenced by name in the Rust types of the fields.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TypeMap {
+    /// The response structs, including `ResponseData` and the structs of the fragments.
+    pub types: Vec<TypeDescription>,
+}
+
+/// A generated response struct.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeDescription {
+    /// The name of the Rust struct.
+    pub name: String,
+    /// The name of the GraphQL type the struct holds a selection on.
+    pub graphql_type: String,
+    /// The fields of the struct, in the order of the selection.
+    pub fields: Vec<FieldDescription>,
+}
+
+/// A field of a generated response struct.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldDescription {
+    /// The name of the Rust field.
+    pub name: String,
+    /// The key of the field in the response: the alias if there is one, the field name otherwise. `None` for the flattened fragments, whose fields are at the level of the struct.
+    pub response_key: Option<String>,
+    /// The Rust type of the field, e.g. `Option<Vec<String>>`.
+    pub rust_type: String,
+    /// The GraphQL type of the field, e.g. `[String!]`, or the type condition of a flattened fragment.
+    pub graphql_type: String,
+    /// Whether the value can be missing or null in the response, because of its type or an `@include` or `@skip` directive.
+    pub nullable: bool,
+}
+
+impl FieldDescription {
+    pub(crate) fn new(
+        name: &str,
+        response_key: Option<&str>,
+        rust_type: &TokenStream,
+        graphql_type: String,
+        nullable: bool,
+    ) -> FieldDescription {
+        FieldDescription {
+            name: name.to_string(),
+            response_key: response_key.map(|key| key.to_string()),
+            rust_type: rust_type_string(rust_type),
+            graphql_type,
+            nullable,
+        }
+    }
+}
+
+/// The type as written in Rust, without the spaces `TokenStream::to_string` puts between the tokens.
+fn rust_type_string(rust_type: &TokenStream) -> String {
+    let compact: String = rust_type
+        .to_string()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    compact.replace(",", ", ")
+}
+
+/// The type map of the structs recorded for each operation. The structs shared by several operations, like the fragments, are listed once.
+pub(crate) fn merge(recorded: Vec<BTreeMap<String, TypeDescription>>) -> TypeMap {
+    let mut types = BTreeMap::new();
+    for operation_types in recorded {
+        types.extend(operation_types);
+    }
+
+    TypeMap {
+        types: types.into_values().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rust_types_are_written_without_token_spacing() {
+        let ty = quote!(Result<Option<Vec<String>>, ::graphql_client::catch::FieldError>);
+
+        assert_eq!(
+            rust_type_string(&ty),
+            "Result<Option<Vec<String>>, ::graphql_client::catch::FieldError>"
+        );
+    }
+}
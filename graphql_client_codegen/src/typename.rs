@@ -0,0 +1,181 @@
+//! The `add_typename` option: selecting `__typename` on interfaces and unions when the query does not, since the generated code tells their possible types apart with it.
+
+use constants::TYPENAME_FIELD;
+use graphql_parser::query::{
+    Definition, Document, Field, OperationDefinition, Selection, SelectionSet, TypeCondition,
+};
+use graphql_parser::Pos;
+use schema::Schema;
+use transforms::ADJACENTLY_TAGGED_DIRECTIVE;
+
+/// Adds `__typename` to the selection sets on interfaces and unions that do not select it, in the operations and in the fragments on interfaces and unions, whether the type refinements are inline fragments or spreads of fragments on the possible types. The query sent to the server has to be rewritten with it. Returns whether the document changed.
+pub(crate) fn add_typename(document: &mut Document, schema: &Schema) -> bool {
+    let mut added = false;
+
+    for definition in &mut document.definitions {
+        match definition {
+            Definition::Operation(operation) => {
+                let (root_name, selection_set) = match operation {
+                    OperationDefinition::SelectionSet(selection_set) => {
+                        (&schema.query_type, selection_set)
+                    }
+                    OperationDefinition::Query(q) => (&schema.query_type, &mut q.selection_set),
+                    OperationDefinition::Mutation(m) => {
+                        (&schema.mutation_type, &mut m.selection_set)
+                    }
+                    OperationDefinition::Subscription(s) => {
+                        (&schema.subscription_type, &mut s.selection_set)
+                    }
+                };
+                if let Some(root_name) = root_name {
+                    added |= add_to_selection_set(selection_set, root_name, schema);
+                }
+            }
+            Definition::Fragment(fragment) => {
+                let TypeCondition::On(on) = &fragment.type_condition;
+                added |= add_to_selection_set(&mut fragment.selection_set, on, schema);
+            }
+        }
+    }
+
+    added
+}
+
+/// Adds `__typename` to a selection set on `type_name` if it is an interface or a union, and to the selection sets nested in it.
+fn add_to_selection_set(
+    selection_set: &mut SelectionSet,
+    type_name: &str,
+    schema: &Schema,
+) -> bool {
+    let is_abstract =
+        schema.interfaces.contains_key(type_name) || schema.unions.contains_key(type_name);
+    let selects_typename = selection_set.items.iter().any(|item| match item {
+        Selection::Field(field) => field.name == TYPENAME_FIELD,
+        _ => false,
+    });
+
+    let added = is_abstract && !selects_typename;
+    if added {
+        let position = selection_set.span.0;
+        selection_set
+            .items
+            .insert(0, Selection::Field(typename_field(position)));
+    }
+
+    add_to_nested_selection_sets(selection_set, type_name, schema) || added
+}
+
+/// Inline fragments are part of the selection set they are in, which already selects `__typename` if it needs it.
+fn add_to_nested_selection_sets(
+    selection_set: &mut SelectionSet,
+    type_name: &str,
+    schema: &Schema,
+) -> bool {
+    let mut added = false;
+
+    for item in &mut selection_set.items {
+        match item {
+            Selection::Field(field) => {
+                let field_type = match field_type_name(type_name, &field.name, schema) {
+                    Some(field_type) => field_type,
+                    None => continue,
+                };
+                // The enums of `@adjacentlyTagged` unions read the type name from their own tag.
+                let adjacently_tagged = field
+                    .directives
+                    .iter()
+                    .any(|directive| directive.name == ADJACENTLY_TAGGED_DIRECTIVE);
+
+                added |= if adjacently_tagged {
+                    add_to_nested_selection_sets(&mut field.selection_set, &field_type, schema)
+                } else {
+                    add_to_selection_set(&mut field.selection_set, &field_type, schema)
+                };
+            }
+            Selection::InlineFragment(inline) => {
+                let on = match &inline.type_condition {
+                    Some(TypeCondition::On(on)) => on.as_str(),
+                    None => type_name,
+                };
+                added |= add_to_nested_selection_sets(&mut inline.selection_set, on, schema);
+            }
+            Selection::FragmentSpread(_) => (),
+        }
+    }
+
+    added
+}
+
+/// The name of the type of a field, without its list and non-null wrappers. Unknown fields are reported later, when generating the code.
+fn field_type_name(type_name: &str, field_name: &str, schema: &Schema) -> Option<String> {
+    let fields = match (
+        schema.objects.get(type_name),
+        schema.interfaces.get(type_name),
+    ) {
+        (Some(object), _) => &object.fields,
+        (None, Some(iface)) => &iface.fields,
+        (None, None) => return None,
+    };
+
+    fields
+        .iter()
+        .find(|field| field.name == field_name)
+        .map(|field| field.type_.inner_name_string())
+}
+
+fn typename_field(position: Pos) -> Field {
+    Field {
+        position,
+        alias: None,
+        name: TYPENAME_FIELD.to_string(),
+        arguments: Vec::new(),
+        directives: Vec::new(),
+        selection_set: SelectionSet {
+            span: (position, position),
+            items: Vec::new(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphql_parser;
+
+    const SCHEMA: &str = r#"
+        schema { query: Query }
+        type Query { animals: [Animal!]! }
+        interface Animal { name: String! }
+        type Dog implements Animal { name: String! }
+    "#;
+
+    fn rewrite(query: &str) -> Option<String> {
+        let schema = Schema::from(graphql_parser::parse_schema(SCHEMA).unwrap());
+        let mut document = graphql_parser::parse_query(query).unwrap();
+
+        if add_typename(&mut document, &schema) {
+            Some(document.to_string())
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    fn typename_is_added_to_fragments_on_interfaces() {
+        let rewritten = rewrite(
+            "query Animals { animals { ...AnimalFields } } fragment AnimalFields on Animal { name ... on Dog { name } }",
+        )
+        .unwrap();
+
+        assert!(rewritten.contains("fragment AnimalFields on Animal {\n  __typename\n  name\n"));
+        assert!(rewritten.contains("animals {\n    __typename\n"));
+    }
+
+    #[test]
+    fn selections_with_typename_are_left_alone() {
+        assert_eq!(
+            rewrite("query Animals { animals { kind: __typename name } }"),
+            None
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use constants::*;
-use failure;
+use error::CodegenError;
+use heck::SnakeCase;
+use itertools::Itertools;
 use proc_macro2::{Ident, Span, TokenStream};
 use query::QueryContext;
 use selection::{Selection, SelectionFragmentSpread, SelectionItem};
@@ -8,23 +10,18 @@ use std::collections::BTreeSet;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct GqlUnion {
+    /// The name of the union in the schema.
+    pub name: String,
     pub description: Option<String>,
     pub variants: BTreeSet<String>,
     pub is_required: Cell<bool>,
 }
 
-#[derive(Debug, Fail)]
-#[fail(display = "UnionError")]
-enum UnionError {
-    #[fail(display = "Unknown type: {}", ty)]
-    UnknownType { ty: String },
-    #[fail(display = "Missing __typename in selection for {}", union_name)]
-    MissingTypename { union_name: String },
-}
-
-type UnionVariantResult = Result<(Vec<TokenStream>, Vec<TokenStream>, Vec<String>), failure::Error>;
+type UnionVariantResult = Result<(Vec<TokenStream>, Vec<TokenStream>, Vec<String>), CodegenError>;
 
+/// The variants of the enum for a selection on the union or interface `type_name`, their structs and their names.
 pub(crate) fn union_variants(
+    type_name: &str,
     selection: &Selection,
     query_context: &QueryContext,
     prefix: &str,
@@ -32,56 +29,83 @@ pub(crate) fn union_variants(
     let mut children_definitions = Vec::new();
     let mut used_variants = Vec::with_capacity(selection.0.len());
 
-    let variants: Result<Vec<TokenStream>, failure::Error> = selection
-        .0
-        .iter()
-        // ignore __typename
-        .filter(|item| {
-            if let SelectionItem::Field(f) = item {
-                f.name != TYPENAME_FIELD
-            } else {
-                true
+    // Fragments on the same type end up in the same variant, so we merge their selections first.
+    let mut merged_fragments: Vec<(&str, Selection)> = Vec::with_capacity(selection.0.len());
+
+    for item in &selection.0 {
+        let (on, fields, fragment_name) = match item {
+            // ignore __typename
+            SelectionItem::Field(f) if f.name == TYPENAME_FIELD => continue,
+            SelectionItem::Field(_) => Err(CodegenError::Validation(
+                "field selection on union".to_string(),
+            ))?,
+            SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name, .. }) => {
+                let fragment = query_context
+                    .fragments
+                    .get(fragment_name)
+                    .ok_or_else(|| CodegenError::UnknownFragment {
+                        name: fragment_name.to_string(),
+                    })?;
+
+                (&fragment.on, &fragment.selection, Some(fragment_name.as_str()))
             }
-        })
-        .map(|item| {
-            let (on, fields) = match item {
-                SelectionItem::Field(_) => Err(format_err!("field selection on union"))?,
-                SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name }) => {
-                    let fragment = query_context
-                        .fragments
-                        .get(fragment_name)
-                        .ok_or_else(|| format_err!("Unknown fragment: {}", &fragment_name))?;
-
-                    (&fragment.on, &fragment.selection)
+            SelectionItem::InlineFragment(frag) => (&frag.on, &frag.fields, None),
+        };
+
+        ::shared::check_fragment_spread(on, type_name, fragment_name, query_context)?;
+
+        // The `__typename` is always an object type, so the fields selected on an interface go to the variant of each object implementing it.
+        let targets: Vec<&str> = match query_context.schema.interfaces.get(on.as_str()) {
+            Some(iface) => iface.implemented_by.iter().map(|s| s.as_str()).sorted(),
+            None => vec![on.as_str()],
+        };
+
+        for target in targets {
+            match merged_fragments
+                .iter_mut()
+                .find(|(merged_on, _)| *merged_on == target)
+            {
+                Some((_, merged_fields)) => {
+                    for field in &fields.0 {
+                        if !merged_fields.0.contains(field) {
+                            merged_fields.0.push(field.clone());
+                        }
+                    }
                 }
-                SelectionItem::InlineFragment(frag) => (&frag.on, &frag.fields),
-            };
+                None => merged_fragments.push((target, fields.clone())),
+            }
+        }
+    }
+
+    let variants: Result<Vec<TokenStream>, CodegenError> = merged_fragments
+        .iter()
+        .map(|(on, fields)| {
             let variant_name = Ident::new(&on, Span::call_site());
             used_variants.push(on.to_string());
 
-            let new_prefix = format!("{}On{}", prefix, on);
+            let new_prefix = query_context.variant_type_name(prefix, on);
 
             let variant_type = Ident::new(&new_prefix, Span::call_site());
 
             let field_object_type = query_context
                 .schema
                 .objects
-                .get(on)
+                .get(*on)
                 .map(|_f| query_context.maybe_expand_field(&on, &fields, &new_prefix));
             let field_interface = query_context
                 .schema
                 .interfaces
-                .get(on)
+                .get(*on)
                 .map(|_f| query_context.maybe_expand_field(&on, &fields, &new_prefix));
             let field_union_type = query_context
                 .schema
                 .unions
-                .get(on)
+                .get(*on)
                 .map(|_f| query_context.maybe_expand_field(&on, &fields, &new_prefix));
 
             match field_object_type.or(field_interface).or(field_union_type) {
                 Some(tokens) => children_definitions.push(tokens?),
-                None => Err(UnionError::UnknownType { ty: on.to_string() })?,
+                None => Err(CodegenError::UnknownType { ty: on.to_string() })?,
             };
 
             Ok(quote! {
@@ -95,45 +119,265 @@ pub(crate) fn union_variants(
     Ok((variants, children_definitions, used_variants))
 }
 
+/// Generates `is_*` methods for all the variants of the enum, and `as_*` methods returning the inner struct for the variants with a selection.
+pub(crate) fn variant_accessors<'a>(
+    context: &QueryContext,
+    enum_name: &Ident,
+    prefix: &str,
+    selected_variants: &[String],
+    other_variants: impl Iterator<Item = &'a String>,
+) -> TokenStream {
+    let selected_accessors = selected_variants.iter().map(|variant| {
+        let variant_name = Ident::new(variant, Span::call_site());
+        let variant_type = Ident::new(
+            &context.variant_type_name(prefix, variant),
+            Span::call_site(),
+        );
+        let is_variant = Ident::new(
+            &format!("is_{}", variant.to_snake_case()),
+            Span::call_site(),
+        );
+        let as_variant = Ident::new(
+            &format!("as_{}", variant.to_snake_case()),
+            Span::call_site(),
+        );
+
+        quote! {
+            pub fn #is_variant(&self) -> bool {
+                match self {
+                    #enum_name::#variant_name(_) => true,
+                    _ => false,
+                }
+            }
+
+            pub fn #as_variant(&self) -> Option<&#variant_type> {
+                match self {
+                    #enum_name::#variant_name(inner) => Some(inner),
+                    _ => None,
+                }
+            }
+        }
+    });
+
+    let other_accessors = other_variants.map(|variant| {
+        let variant_name = Ident::new(variant, Span::call_site());
+        let is_variant = Ident::new(
+            &format!("is_{}", variant.to_snake_case()),
+            Span::call_site(),
+        );
+
+        quote! {
+            pub fn #is_variant(&self) -> bool {
+                match self {
+                    #enum_name::#variant_name => true,
+                    _ => false,
+                }
+            }
+        }
+    });
+
+    quote! {
+        impl #enum_name {
+            #(#selected_accessors)*
+            #(#other_accessors)*
+        }
+    }
+}
+
+/// Generates a `typename` method returning the name of the concrete type, which is also the `__typename` the enum is tagged with.
+pub(crate) fn typename_accessor<'a>(
+    enum_name: &Ident,
+    selected_variants: &[String],
+    other_variants: impl Iterator<Item = &'a String>,
+) -> TokenStream {
+    let selected_arms = selected_variants.iter().map(|variant| {
+        let variant_name = Ident::new(variant, Span::call_site());
+        quote!(#enum_name::#variant_name(_) => #variant)
+    });
+    let other_arms = other_variants.map(|variant| {
+        let variant_name = Ident::new(variant, Span::call_site());
+        quote!(#enum_name::#variant_name => #variant)
+    });
+
+    quote! {
+        impl #enum_name {
+            pub fn typename(&self) -> &'static str {
+                match self {
+                    #(#selected_arms,)*
+                    #(#other_arms,)*
+                }
+            }
+        }
+    }
+}
+
+/// The `Default` implementation for a union or interface enum, with the `test_defaults` option: the first variant, holding the default value of its struct if it has a selection.
+pub(crate) fn enum_default_impl(
+    context: &QueryContext,
+    enum_name: &Ident,
+    prefix: &str,
+    selected_variants: &[String],
+    first_other_variant: Option<&String>,
+) -> TokenStream {
+    let default_variant = match (selected_variants.first(), first_other_variant) {
+        (Some(variant), _) => {
+            let variant_name = Ident::new(variant, Span::call_site());
+            let variant_type = Ident::new(
+                &context.variant_type_name(prefix, variant),
+                Span::call_site(),
+            );
+            quote!(#enum_name::#variant_name(#variant_type::default()))
+        }
+        (None, Some(variant)) => {
+            let variant_name = Ident::new(variant, Span::call_site());
+            quote!(#enum_name::#variant_name)
+        }
+        (None, None) => return quote!(),
+    };
+
+    quote! {
+        impl Default for #enum_name {
+            fn default() -> Self {
+                #default_variant
+            }
+        }
+    }
+}
+
 impl GqlUnion {
     pub(crate) fn response_for_selection(
         &self,
         query_context: &QueryContext,
         selection: &Selection,
         prefix: &str,
-    ) -> Result<TokenStream, failure::Error> {
-        let struct_name = Ident::new(prefix, Span::call_site());
-        let derives = query_context.response_derives();
+    ) -> Result<TokenStream, CodegenError> {
+        let typename_field =
+            selection
+                .extract_typename()
+                .ok_or_else(|| CodegenError::MissingTypename {
+                    selection_name: prefix.into(),
+                    type_name: self.name.clone(),
+                    kind: "union",
+                })?;
+        // `__typename` may be aliased, the enum is tagged by the key it has in the response.
+        let typename_key = typename_field
+            .alias
+            .as_ref()
+            .unwrap_or(&typename_field.name);
+
+        self.enum_for_selection(
+            query_context,
+            selection,
+            prefix,
+            quote!(#[serde(tag = #typename_key)]),
+        )
+    }
 
-        let typename_field = selection.extract_typename();
+    /// Like `response_for_selection`, for a field with the `@adjacentlyTagged` directive: the type name is under the `tag` key and the selected fields under the `content` key, so `__typename` does not have to be selected.
+    pub(crate) fn adjacently_tagged_response_for_selection(
+        &self,
+        query_context: &QueryContext,
+        selection: &Selection,
+        prefix: &str,
+        tag: &str,
+        content: &str,
+    ) -> Result<TokenStream, CodegenError> {
+        self.enum_for_selection(
+            query_context,
+            selection,
+            prefix,
+            quote!(#[serde(tag = #tag, content = #content)]),
+        )
+    }
 
-        if typename_field.is_none() {
-            Err(UnionError::MissingTypename {
-                union_name: prefix.into(),
-            })?;
-        }
+    fn enum_for_selection(
+        &self,
+        query_context: &QueryContext,
+        selection: &Selection,
+        prefix: &str,
+        tagging: TokenStream,
+    ) -> Result<TokenStream, CodegenError> {
+        let struct_name = Ident::new(prefix, Span::call_site());
+        let derives = query_context.response_derives();
+        let visibility = query_context.type_visibility();
 
         let (mut variants, children_definitions, used_variants) =
-            union_variants(selection, query_context, prefix)?;
+            union_variants(&self.name, selection, query_context, prefix)?;
 
-        variants.extend(
+        let unused_variants = || {
             self.variants
                 .iter()
                 .filter(|v| used_variants.iter().find(|a| a == v).is_none())
-                .map(|v| {
-                    let v = Ident::new(v, Span::call_site());
-                    quote!(#v)
-                }),
+        };
+
+        variants.extend(unused_variants().map(|v| {
+            let v = Ident::new(v, Span::call_site());
+            quote!(#v)
+        }));
+
+        let accessors = if query_context.variant_accessors {
+            variant_accessors(
+                query_context,
+                &struct_name,
+                prefix,
+                &used_variants,
+                unused_variants(),
+            )
+        } else {
+            quote!()
+        };
+        let typename_accessor = typename_accessor(&struct_name, &used_variants, unused_variants());
+        let default_impl = if query_context.test_defaults {
+            enum_default_impl(
+                query_context,
+                &struct_name,
+                prefix,
+                &used_variants,
+                unused_variants().next(),
+            )
+        } else {
+            quote!()
+        };
+
+        let collectors = ::collectors::enum_collectors(
+            query_context,
+            &struct_name,
+            &used_variants,
+            unused_variants().next().is_some(),
+        );
+
+        let estimated_size = ::estimated_size::enum_impl(
+            query_context,
+            &struct_name,
+            &used_variants,
+            unused_variants().next().is_some(),
+        );
+
+        let definition = query_context.transform_type(
+            prefix,
+            quote! {
+                #derives
+                #tagging
+                #visibility enum #struct_name {
+                    #(#variants),*
+                }
+            },
         );
 
         Ok(quote! {
             #(#children_definitions)*
 
-            #derives
-            #[serde(tag = "__typename")]
-            pub enum #struct_name {
-                #(#variants),*
-            }
+            #definition
+
+            #typename_accessor
+
+            #accessors
+
+            #default_impl
+
+            #collectors
+
+            #estimated_size
         })
     }
 }
@@ -154,6 +398,8 @@ mod tests {
                 fields: Selection(vec![SelectionItem::Field(SelectionField {
                     alias: None,
                     name: "firstName".to_string(),
+                    directives: Vec::new(),
+                    arguments: Vec::new(),
                     fields: Selection(vec![]),
                 })]),
             }),
@@ -162,6 +408,8 @@ mod tests {
                 fields: Selection(vec![SelectionItem::Field(SelectionField {
                     alias: None,
                     name: "title".to_string(),
+                    directives: Vec::new(),
+                    arguments: Vec::new(),
                     fields: Selection(vec![]),
                 })]),
             }),
@@ -170,6 +418,7 @@ mod tests {
         let selection = Selection(fields);
         let prefix = "Meow";
         let union = GqlUnion {
+            name: "Pet".to_string(),
             description: None,
             variants: BTreeSet::new(),
             is_required: false.into(),
@@ -186,6 +435,8 @@ mod tests {
                         name: "firstName".to_string(),
                         type_: FieldType::Named("String".to_string()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
@@ -193,12 +444,16 @@ mod tests {
                         type_: FieldType::Named("String".to_string()),
 
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
                         name: "createdAt".to_string(),
                         type_: FieldType::Named("Date".to_string()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                 ],
                 is_required: false.into(),
@@ -216,12 +471,16 @@ mod tests {
                         name: "title".to_string(),
                         type_: FieldType::Named("String".to_string()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
                         name: "created_at".to_string(),
                         type_: FieldType::Named("Date".to_string()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                 ],
                 is_required: false.into(),
@@ -234,7 +493,7 @@ mod tests {
 
         assert_eq!(
             format!("{}", result.unwrap_err()),
-            "Missing __typename in selection for Meow"
+            "Missing __typename in selection for Meow. Add `__typename` to the selection on the Pet union: it is needed to tell the possible types apart."
         );
     }
 
@@ -244,6 +503,8 @@ mod tests {
             SelectionItem::Field(SelectionField {
                 alias: None,
                 name: "__typename".to_string(),
+                directives: Vec::new(),
+                arguments: Vec::new(),
                 fields: Selection(vec![]),
             }),
             SelectionItem::InlineFragment(SelectionInlineFragment {
@@ -251,6 +512,8 @@ mod tests {
                 fields: Selection(vec![SelectionItem::Field(SelectionField {
                     alias: None,
                     name: "firstName".to_string(),
+                    directives: Vec::new(),
+                    arguments: Vec::new(),
                     fields: Selection(vec![]),
                 })]),
             }),
@@ -259,6 +522,8 @@ mod tests {
                 fields: Selection(vec![SelectionItem::Field(SelectionField {
                     alias: None,
                     name: "title".to_string(),
+                    directives: Vec::new(),
+                    arguments: Vec::new(),
                     fields: Selection(vec![]),
                 })]),
             }),
@@ -267,10 +532,18 @@ mod tests {
         let selection = Selection(fields);
         let prefix = "Meow";
         let union = GqlUnion {
+            name: "Pet".to_string(),
             description: None,
-            variants: BTreeSet::new(),
+            variants: ["User", "Organization"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
             is_required: false.into(),
         };
+        context
+            .schema
+            .unions
+            .insert("Pet".to_string(), union.clone());
 
         let result = union.response_for_selection(&context, &selection, &prefix);
 
@@ -287,24 +560,32 @@ mod tests {
                         name: "__typename".to_string(),
                         type_: FieldType::Named(string_type()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
                         name: "firstName".to_string(),
                         type_: FieldType::Named(string_type()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
                         name: "lastName".to_string(),
                         type_: FieldType::Named(string_type()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
                         name: "createdAt".to_string(),
                         type_: FieldType::Named("Date".to_string()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                 ],
                 is_required: false.into(),
@@ -322,18 +603,24 @@ mod tests {
                         name: "__typename".to_string(),
                         type_: FieldType::Named(string_type()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
                         name: "title".to_string(),
                         type_: FieldType::Named("String".to_string()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                     GqlObjectField {
                         description: None,
                         name: "createdAt".to_string(),
                         type_: FieldType::Named("Date".to_string()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
+                        external: false,
                     },
                 ],
                 is_required: false.into(),
@@ -355,7 +642,9 @@ mod tests {
                 "pub struct MeowOnOrganization { pub title : String , } ",
                 "# [ derive ( Deserialize ) ] ",
                 "# [ serde ( tag = \"__typename\" ) ] ",
-                "pub enum Meow { User ( MeowOnUser ) , Organization ( MeowOnOrganization ) }",
+                "pub enum Meow { User ( MeowOnUser ) , Organization ( MeowOnOrganization ) } ",
+                "impl Meow { pub fn typename ( & self ) -> & 'static str { match self { ",
+                "Meow :: User ( _ ) => \"User\" , Meow :: Organization ( _ ) => \"Organization\" , } } }",
             ].into_iter()
                 .collect::<String>(),
         );
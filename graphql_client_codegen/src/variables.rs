@@ -1,10 +1,11 @@
+use enums::ENUMS_PREFIX;
 use field_type::FieldType;
 use graphql_parser;
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Ident, Literal, Span, TokenStream};
 use query::QueryContext;
 use std::collections::BTreeMap;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Variable {
     pub name: String,
     pub ty: FieldType,
@@ -12,11 +13,42 @@ pub struct Variable {
 }
 
 impl Variable {
+    /// Whether the variable is an input object, generated as its `Patch` type because of the `input_patches` option.
+    pub(crate) fn is_patch(&self, context: &QueryContext) -> bool {
+        context.input_patches
+            && context
+                .schema
+                .inputs
+                .contains_key(&self.ty.inner_name_string())
+    }
+
+    /// The Rust type of the variable in the `Variables` struct.
+    pub(crate) fn rust_type(&self, context: &QueryContext) -> TokenStream {
+        if self.is_patch(context) {
+            let patch_name = format!("{}Patch", self.ty.inner_name_string());
+            self.ty.to_rust(context, &patch_name)
+        } else {
+            self.ty.to_rust(context, "")
+        }
+    }
+
+    /// Whether the variable must be given a value: it is non-null and has no default value. The default values of patches are full input objects, so they do not count.
+    pub(crate) fn is_required(&self, context: &QueryContext) -> bool {
+        let has_default = self.default.is_some() && !self.is_patch(context);
+        !self.ty.is_optional() && !has_default
+    }
+
+    /// The name of the function returning the default value of the variable, generated on the `Variables` struct if the variable has a default value and is not a patch.
+    pub(crate) fn default_value_constructor_name(&self) -> Ident {
+        Ident::new(&format!("default_{}", self.name), Span::call_site())
+    }
+
     pub(crate) fn generate_default_value_constructor(&self, context: &QueryContext) -> TokenStream {
-        context.schema.require(&self.ty.inner_name_string());
         match &self.default {
+            // The default value literal is a full input object, not a patch.
+            Some(_) if self.is_patch(context) => quote!(),
             Some(default) => {
-                let fn_name = Ident::new(&format!("default_{}", self.name), Span::call_site());
+                let fn_name = self.default_value_constructor_name();
                 let ty = self.ty.to_rust(context, "");
                 let value = graphql_parser_value_to_literal(
                     default,
@@ -62,15 +94,29 @@ fn graphql_parser_value_to_literal(
                 quote!(false)
             }
         }
+        Value::String(s) if context.compact_str && ty.inner_name_string() == "String" => {
+            quote!(::compact_str::CompactString::from(#s))
+        }
         Value::String(s) => quote!(#s.to_string()),
         Value::Variable(_) => panic!("variable in variable"),
         Value::Null => panic!("null as default value"),
-        Value::Float(f) => quote!(#f),
+        // Unsuffixed, so the literal has the type of the `Float` alias.
+        Value::Float(f) => {
+            let f = Literal::f64_unsuffixed(*f);
+            quote!(#f)
+        }
         Value::Int(i) => {
             let i = i.as_i64();
             quote!(#i)
         }
-        Value::Enum(en) => quote!(#en),
+        Value::Enum(en) => {
+            let enum_name = Ident::new(
+                &format!("{}{}", ENUMS_PREFIX, ty.inner_name_string()),
+                Span::call_site(),
+            );
+            let variant = Ident::new(en, Span::call_site());
+            quote!(#enum_name::#variant)
+        }
         Value::List(inner) => {
             let elements = inner
                 .iter()
@@ -107,7 +153,7 @@ fn render_object_literal(
         .fields
         .iter()
         .map(|(name, field)| {
-            let field_name = Ident::new(&name, Span::call_site());
+            let field_name = ::shared::field_ident(&name);
             let provided_value = object.get(name);
             match provided_value {
                 Some(default_value) => {
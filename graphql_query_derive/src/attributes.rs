@@ -1,8 +1,12 @@
 use failure;
 use graphql_client_codegen::deprecation::DeprecationStrategy;
+use graphql_client_codegen::{EnumUnknowns, FloatType, SensitiveFields};
 use syn;
 
 const DEPRECATION_ERROR: &str = "deprecated must be one of 'allow', 'deny', or 'warn'";
+const ENUM_UNKNOWNS_ERROR: &str = "enum_unknowns must be either 'fallback' or 'default'";
+const FLOAT_TYPE_ERROR: &str = "float_type must be either 'f64' or 'f32'";
+const SENSITIVE_FIELDS_ERROR: &str = "sensitive_fields must be either 'redact' or 'skip'";
 
 /// The `graphql` attribute as a `syn::Path`.
 fn path_to_match() -> syn::Path {
@@ -36,6 +40,49 @@ pub fn extract_attr(ast: &syn::DeriveInput, attr: &str) -> Result<String, failur
     Err(format_err!("attribute not found"))?
 }
 
+/// Extract a boolean flag specified in the `graphql` attribute, e.g. `variant_accessors = "true"`. Absent flags are `false`.
+pub fn extract_flag(ast: &syn::DeriveInput, attr: &str) -> Result<bool, failure::Error> {
+    match extract_attr(ast, attr) {
+        Ok(value) => match value.to_lowercase().as_str() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(format_err!("{} must be either \"true\" or \"false\"", attr)),
+        },
+        Err(_) => Ok(false),
+    }
+}
+
+/// Extract the `with_variables` flag. The generated `new` returns the struct itself, so the flag is only allowed on unit structs.
+pub fn extract_with_variables(ast: &syn::DeriveInput) -> Result<bool, failure::Error> {
+    let with_variables = extract_flag(ast, "with_variables")?;
+    let is_unit_struct = match &ast.data {
+        syn::Data::Struct(data) => data.fields == syn::Fields::Unit,
+        _ => false,
+    };
+
+    if with_variables && !is_unit_struct {
+        Err(format_err!(
+            "with_variables generates a `new` method returning {} itself, so it can only be used on a unit struct, like `pub struct {};`",
+            ast.ident,
+            ast.ident
+        ))?
+    }
+
+    Ok(with_variables)
+}
+
+/// Extract the visibility of the generated types, e.g. `visibility = "pub(crate)"`. `None` when absent.
+pub fn extract_visibility(
+    ast: &syn::DeriveInput,
+) -> Result<Option<syn::Visibility>, failure::Error> {
+    match extract_attr(ast, "visibility") {
+        Ok(value) => syn::parse_str(&value)
+            .map(Some)
+            .map_err(|_| format_err!("visibility must be a Rust visibility, like \"pub(crate)\"")),
+        Err(_) => Ok(None),
+    }
+}
+
 /// Get the deprecation from a struct attribute in the derive case.
 pub fn extract_deprecation_strategy(
     ast: &syn::DeriveInput,
@@ -48,10 +95,97 @@ pub fn extract_deprecation_strategy(
     }
 }
 
+/// Extract what the generated enums do with unknown values, e.g. `enum_unknowns = "default"`. `fallback` when absent.
+pub fn extract_enum_unknowns(ast: &syn::DeriveInput) -> Result<EnumUnknowns, failure::Error> {
+    match extract_attr(ast, "enum_unknowns") {
+        Ok(value) => match value.to_lowercase().as_str() {
+            "fallback" => Ok(EnumUnknowns::Fallback),
+            "default" => Ok(EnumUnknowns::Default),
+            _ => Err(format_err!("{}", ENUM_UNKNOWNS_ERROR)),
+        },
+        Err(_) => Ok(EnumUnknowns::default()),
+    }
+}
+
+/// Extract how the `@sensitive` fields are serialized, e.g. `sensitive_fields = "skip"`. Redacted when absent.
+pub fn extract_sensitive_fields(ast: &syn::DeriveInput) -> Result<SensitiveFields, failure::Error> {
+    match extract_attr(ast, "sensitive_fields") {
+        Ok(value) => match value.to_lowercase().as_str() {
+            "redact" => Ok(SensitiveFields::Redact),
+            "skip" => Ok(SensitiveFields::Skip),
+            _ => Err(format_err!("{}", SENSITIVE_FIELDS_ERROR)),
+        },
+        Err(_) => Ok(SensitiveFields::default()),
+    }
+}
+
+/// Extract the longest name of the generated types, e.g. `max_identifier_length = "40"`. `None` when absent.
+pub fn extract_max_identifier_length(
+    ast: &syn::DeriveInput,
+) -> Result<Option<usize>, failure::Error> {
+    match extract_attr(ast, "max_identifier_length") {
+        Ok(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| format_err!("max_identifier_length must be a number, like \"40\"")),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Extract the Rust type of the `Float` scalar, e.g. `float_type = "f32"`. `f64` when absent.
+pub fn extract_float_type(ast: &syn::DeriveInput) -> Result<FloatType, failure::Error> {
+    match extract_attr(ast, "float_type") {
+        Ok(value) => match value.as_str() {
+            "f64" => Ok(FloatType::F64),
+            "f32" => Ok(FloatType::F32),
+            _ => Err(format_err!("{}", FLOAT_TYPE_ERROR)),
+        },
+        Err(_) => Ok(FloatType::default()),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_with_variables_on_a_unit_struct() {
+        let input = "
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = \"x\",
+            query_path = \"x\",
+            with_variables = \"true\",
+        )]
+        struct MyQuery;
+        ";
+        let parsed = syn::parse_str(input).unwrap();
+        assert!(extract_with_variables(&parsed).unwrap());
+    }
+
+    #[test]
+    fn test_with_variables_on_a_struct_with_fields() {
+        let input = "
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = \"x\",
+            query_path = \"x\",
+            with_variables = \"true\",
+        )]
+        struct MyQuery {
+            client: String,
+        }
+        ";
+        let parsed = syn::parse_str(input).unwrap();
+        match extract_with_variables(&parsed) {
+            Ok(_) => panic!("with_variables is only allowed on unit structs"),
+            Err(e) => assert_eq!(
+                format!("{}", e),
+                "with_variables generates a `new` method returning MyQuery itself, so it can only be used on a unit struct, like `pub struct MyQuery;`"
+            ),
+        };
+    }
+
     #[test]
     fn test_deprecation_strategy() {
         let input = "
@@ -105,4 +239,89 @@ mod test {
             Err(e) => assert_eq!(&format!("{}", e), DEPRECATION_ERROR),
         };
     }
+
+    #[test]
+    fn test_visibility() {
+        let input = "
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = \"x\",
+            query_path = \"x\",
+            visibility = \"pub(crate)\",
+        )]
+        struct MyQuery;
+        ";
+        let parsed = syn::parse_str(input).unwrap();
+        let expected: syn::Visibility = syn::parse_str("pub(crate)").unwrap();
+        assert_eq!(extract_visibility(&parsed).unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn test_enum_unknowns() {
+        let input = "
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = \"x\",
+            query_path = \"x\",
+            enum_unknowns = \"default\",
+        )]
+        struct MyQuery;
+        ";
+        let parsed = syn::parse_str(input).unwrap();
+        assert_eq!(
+            extract_enum_unknowns(&parsed).unwrap(),
+            EnumUnknowns::Default
+        );
+    }
+
+    #[test]
+    fn test_invalid_enum_unknowns() {
+        let input = "
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = \"x\",
+            query_path = \"x\",
+            enum_unknowns = \"other\",
+        )]
+        struct MyQuery;
+        ";
+        let parsed = syn::parse_str(input).unwrap();
+        match extract_enum_unknowns(&parsed) {
+            Ok(_) => panic!("parsed unexpectedly"),
+            Err(e) => assert_eq!(&format!("{}", e), ENUM_UNKNOWNS_ERROR),
+        };
+    }
+
+    #[test]
+    fn test_float_type() {
+        let input = "
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = \"x\",
+            query_path = \"x\",
+            float_type = \"f32\",
+        )]
+        struct MyQuery;
+        ";
+        let parsed = syn::parse_str(input).unwrap();
+        assert_eq!(extract_float_type(&parsed).unwrap(), FloatType::F32);
+    }
+
+    #[test]
+    fn test_invalid_float_type() {
+        let input = "
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = \"x\",
+            query_path = \"x\",
+            float_type = \"f16\",
+        )]
+        struct MyQuery;
+        ";
+        let parsed = syn::parse_str(input).unwrap();
+        match extract_float_type(&parsed) {
+            Ok(_) => panic!("parsed unexpectedly"),
+            Err(e) => assert_eq!(&format!("{}", e), FLOAT_TYPE_ERROR),
+        };
+    }
 }
@@ -17,34 +17,131 @@ use proc_macro2::TokenStream;
 pub fn graphql_query_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = TokenStream::from(input);
     let ast = syn::parse2(input).expect("Derive input is well formed");
-    let (query_path, schema_path) = build_query_and_schema_path(&ast);
+    let schema_path = build_schema_path(&ast);
     let options = build_graphql_client_derive_options(&ast);
-    let gen = generate_module_token_stream(query_path, schema_path, Some(options)).unwrap();
+    let gen = match attributes::extract_attr(&ast, "query_path_glob") {
+        // All the operations of the matching files are generated, each with its own struct.
+        Ok(query_path_glob) => {
+            let query_path_glob = format!("{}/{}", cargo_manifest_dir(), query_path_glob);
+            let options = GraphQLClientDeriveOptions {
+                operation_name: None,
+                struct_name: None,
+                ..options
+            };
+            let schema_path = schema_path
+                .ok_or_else(|| {
+                    format_err!("The schema_path attribute is required with query_path_glob")
+                })
+                .unwrap();
+            generate_module_token_stream_from_glob(&query_path_glob, schema_path, options).unwrap()
+        }
+        Err(_) => {
+            let query_path = build_query_path(&ast);
+            match schema_path {
+                Some(schema_path) => {
+                    generate_module_token_stream(query_path, schema_path, Some(options)).unwrap()
+                }
+                // The query document holds the schema definitions too.
+                None => {
+                    generate_module_token_stream_from_combined_document(query_path, Some(options))
+                        .unwrap()
+                }
+            }
+        }
+    };
     gen.into()
 }
 
-fn build_query_and_schema_path(
-    input: &syn::DeriveInput,
-) -> (std::path::PathBuf, std::path::PathBuf) {
-    let cargo_manifest_dir =
-        ::std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR env variable is defined");
+fn cargo_manifest_dir() -> String {
+    ::std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR env variable is defined")
+}
 
+fn build_query_path(input: &syn::DeriveInput) -> std::path::PathBuf {
     let query_path = attributes::extract_attr(input, "query_path")
         .context("Extracting query path")
         .unwrap();
-    let query_path = format!("{}/{}", cargo_manifest_dir, query_path);
-    let query_path = ::std::path::Path::new(&query_path).to_path_buf();
-    let schema_path = attributes::extract_attr(input, "schema_path")
-        .context("Extracting schema path")
-        .unwrap();
-    let schema_path = ::std::path::Path::new(&cargo_manifest_dir).join(schema_path);
-    (query_path, schema_path)
+    let query_path = format!("{}/{}", cargo_manifest_dir(), query_path);
+    ::std::path::Path::new(&query_path).to_path_buf()
+}
+
+fn build_schema_path(input: &syn::DeriveInput) -> Option<std::path::PathBuf> {
+    let cargo_manifest_dir = cargo_manifest_dir();
+    match attributes::extract_attr(input, "config_path") {
+        // The schema path comes from the graphql-config file.
+        Ok(config_path) => {
+            let config_path = ::std::path::Path::new(&cargo_manifest_dir).join(config_path);
+            let project = attributes::extract_attr(input, "project").ok();
+            let schema = graphql_config::load_project(&config_path, project.as_deref())
+                .unwrap()
+                .schema;
+            Some(schema)
+        }
+        Err(_) => attributes::extract_attr(input, "schema_path")
+            .ok()
+            .map(|schema_path| ::std::path::Path::new(&cargo_manifest_dir).join(schema_path)),
+    }
 }
 
 fn build_graphql_client_derive_options(input: &syn::DeriveInput) -> GraphQLClientDeriveOptions {
     let response_derives = attributes::extract_attr(input, "response_derives").ok();
     // The user can determine what to do about deprecations.
     let deprecation_strategy = attributes::extract_deprecation_strategy(input).unwrap_or_default();
+    let variant_accessors = attributes::extract_flag(input, "variant_accessors").unwrap();
+    let root_field_accessor = attributes::extract_flag(input, "root_field_accessor").unwrap();
+    let list_iterators = attributes::extract_flag(input, "list_iterators").unwrap();
+    let rename_all_camel_case = attributes::extract_flag(input, "rename_all_camel_case").unwrap();
+    let input_patches = attributes::extract_flag(input, "input_patches").unwrap();
+    let comment_descriptions = attributes::extract_flag(input, "comment_descriptions").unwrap();
+    let with_variables = attributes::extract_with_variables(input).unwrap();
+    let test_defaults = attributes::extract_flag(input, "test_defaults").unwrap();
+    let enum_all_variants = attributes::extract_flag(input, "enum_all_variants").unwrap();
+    let optimistic_responses = attributes::extract_flag(input, "optimistic_responses").unwrap();
+    let field_accessors = attributes::extract_flag(input, "field_accessors").unwrap();
+    let conditional_fields_report =
+        attributes::extract_flag(input, "conditional_fields_report").unwrap();
+    let variables_try_from = attributes::extract_flag(input, "variables_try_from").unwrap();
+    let interface_flatten = attributes::extract_flag(input, "interface_flatten").unwrap();
+    let enum_unknowns = attributes::extract_enum_unknowns(input).unwrap();
+    let rkyv = attributes::extract_flag(input, "rkyv").unwrap();
+    let field_json = attributes::extract_flag(input, "field_json").unwrap();
+    let strip_typename = attributes::extract_flag(input, "strip_typename").unwrap();
+    let visibility = attributes::extract_visibility(input).unwrap();
+    let float_type = attributes::extract_float_type(input).unwrap();
+    let qualified_paths = attributes::extract_flag(input, "qualified_paths").unwrap();
+    let scalar_paths = attributes::extract_attr(input, "scalar_paths").ok();
+    let variables_schema = attributes::extract_flag(input, "variables_schema").unwrap();
+    let skip_external_fields = attributes::extract_flag(input, "skip_external_fields").unwrap();
+    let variables_builder = attributes::extract_flag(input, "variables_builder").unwrap();
+    let variables_constructor = attributes::extract_flag(input, "variables_constructor").unwrap();
+    let raw_response = attributes::extract_flag(input, "raw_response").unwrap();
+    let cache_keys = attributes::extract_flag(input, "cache_keys").unwrap();
+    let compact_str = attributes::extract_flag(input, "compact_str").unwrap();
+    let bevy_reflect = attributes::extract_flag(input, "bevy_reflect").unwrap();
+    let variables_as_pairs = attributes::extract_flag(input, "variables_as_pairs").unwrap();
+    let include_tag = attributes::extract_attr(input, "include_tag").ok();
+    let scalar_collectors = attributes::extract_flag(input, "scalar_collectors").unwrap();
+    let add_typename = attributes::extract_flag(input, "add_typename").unwrap();
+    let sqlx_from_row = attributes::extract_flag(input, "sqlx_from_row").unwrap();
+    let empty_lists = attributes::extract_flag(input, "empty_lists").unwrap();
+    let unknown_fields = attributes::extract_flag(input, "unknown_fields").unwrap();
+    let serde_crate = attributes::extract_attr(input, "serde_crate").ok();
+    let estimated_size = attributes::extract_flag(input, "estimated_size").unwrap();
+    let enum_value_methods = attributes::extract_attr(input, "enum_value_methods").ok();
+    let response_wrapper = attributes::extract_attr(input, "response_wrapper").ok();
+    let persisted_query_manifest = attributes::extract_attr(input, "persisted_query_manifest")
+        .ok()
+        .map(|path| ::std::path::Path::new(&cargo_manifest_dir()).join(path));
+    let test_only_derives = attributes::extract_attr(input, "test_only_derives").ok();
+    let field_types = attributes::extract_attr(input, "field_types").ok();
+    let response_trait = attributes::extract_flag(input, "response_trait").unwrap();
+    let max_identifier_length = attributes::extract_max_identifier_length(input).unwrap();
+    let case_insensitive_enums = attributes::extract_flag(input, "case_insensitive_enums").unwrap();
+    let module_attributes = attributes::extract_attr(input, "module_attributes").ok();
+    let result_aliases = attributes::extract_flag(input, "result_aliases").unwrap();
+    let sensitive_fields = attributes::extract_sensitive_fields(input).unwrap();
+    // `derives_on_nested` is on unless set to "false".
+    let top_level_derives_only = attributes::extract_attr(input, "derives_on_nested").is_ok()
+        && !attributes::extract_flag(input, "derives_on_nested").unwrap();
 
     let selected_operation_name = attributes::extract_attr(input, "selected_operation")
         .context("Extracting selected operation name");
@@ -61,5 +158,60 @@ fn build_graphql_client_derive_options(input: &syn::DeriveInput) -> GraphQLClien
         additional_derives: response_derives,
         deprecation_strategy: Some(deprecation_strategy),
         module_visibility: input.clone().vis,
+        variant_accessors,
+        // The derive only generates code for one operation.
+        shared_variables: false,
+        root_field_accessor,
+        list_iterators,
+        rename_all_camel_case,
+        input_patches,
+        comment_descriptions,
+        with_variables,
+        test_defaults,
+        enum_all_variants,
+        optimistic_responses,
+        field_accessors,
+        variables_try_from,
+        interface_flatten,
+        enum_unknowns,
+        rkyv,
+        field_json,
+        strip_typename,
+        visibility,
+        // Closures cannot be given in attributes, the hooks are only for the programmatic API.
+        transform: None,
+        float_type,
+        qualified_paths,
+        scalar_paths,
+        variables_schema,
+        skip_external_fields,
+        variables_builder,
+        compact_str,
+        bevy_reflect,
+        variables_as_pairs,
+        include_tag,
+        scalar_collectors,
+        add_typename,
+        sqlx_from_row,
+        empty_lists,
+        unknown_fields,
+        serde_crate,
+        estimated_size,
+        enum_value_methods,
+        response_wrapper,
+        top_level_derives_only,
+        persisted_query_manifest,
+        test_only_derives,
+        conditional_fields_report,
+        field_types,
+        response_trait,
+        max_identifier_length,
+        case_insensitive_enums,
+        module_attributes,
+        result_aliases,
+        sensitive_fields,
+        variables_constructor,
+        raw_response,
+        cache_keys,
     }
 }